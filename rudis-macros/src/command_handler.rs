@@ -78,7 +78,7 @@ fn generate_fields_from_parser(
                 let field_name = field.ident.as_ref().unwrap();
                 let field_type = &field.ty;
 
-                let (flag_opt, aliases) = parse_arg_attributes(&field)?;
+                let (flag_opt, aliases) = parse_arg_attributes(field)?;
 
                 if let Some(flag_name) = flag_opt {
                     // flag field (must be bool)
@@ -155,7 +155,7 @@ fn generate_fields_from_parser(
             });
 
             // build while-loop match
-            let loop_block = if match_arms.len() > 0 {
+            let loop_block = if !match_arms.is_empty() {
                 quote! {
                     while parser.has_next() {
                         let k: String = parser.next()?;
@@ -216,10 +216,10 @@ fn generate_fields_from_parser(
 }
 
 fn is_option_type(ty: &syn::Type) -> bool {
-    if let syn::Type::Path(type_path) = ty {
-        if let Some(segment) = type_path.path.segments.last() {
-            return segment.ident == "Option";
-        }
+    if let syn::Type::Path(type_path) = ty
+        && let Some(segment) = type_path.path.segments.last()
+    {
+        return segment.ident == "Option";
     }
     false
 }
@@ -280,10 +280,12 @@ fn parse_arg_attributes(
 
 fn calculate_required_args_count(fields: &Fields) -> usize {
     match fields {
+        // flags are optional as well, they are told apart by the `arg` attribute
         Fields::Named(named_fields) => named_fields
             .named
             .iter()
             .filter(|field| !is_option_type(&field.ty))
+            .filter(|field| !field.attrs.iter().any(|attr| attr.path().is_ident("arg")))
             .count(),
         Fields::Unnamed(unnamed_fields) => unnamed_fields
             .unnamed
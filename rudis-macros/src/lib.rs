@@ -12,10 +12,11 @@ mod command_handler;
 /// registry automatically.
 ///
 /// # Example
-/// ```rust
-/// use rudis_macros::register;
+/// ```rust,ignore
+/// use rudis_macros::Command;
 ///
-/// #[register("GET")]
+/// #[derive(Command)]
+/// #[command("GET")]
 /// struct GetCommand {
 ///    key: String,
 /// }
@@ -65,14 +66,14 @@ mod command_handler;
 /// - If the struct is not a struct, it will return a compile error.
 /// - If the struct does not have the `command` attribute, it will return a compile error.
 /// - If the number of fields in the struct does not match the number of arguments in the Frame,
-///  it will return a runtime error.
+///   it will return a runtime error.
 /// - If the conversion from `Frame` to the field type fails, it will return a runtime error.
 /// - The field types must implement the `TryFrom<Frame>` trait, otherwise it will return a compile error.
 ///
 /// # Notes
 /// - This macro requires the `ctor` crate to be included in the dependencies.
 /// - This macro assumes that the command executor will implement the `CommandExecutor` trait with an
-/// `execute` method that takes `self` and a `Context` and returns a `CommandResult`.
+///   `execute` method that takes `self` and a `Context` and returns a `CommandResult`.
 /// - This macro is designed to work with the existing command registration system in `rudis`.
 #[proc_macro_derive(Command, attributes(command, arg))]
 pub fn command(input: TokenStream) -> TokenStream {
@@ -81,10 +82,10 @@ pub fn command(input: TokenStream) -> TokenStream {
 
     let mut command_name: Option<String> = None;
     for attr in &input_ast.attrs {
-        if attr.path().is_ident("command") {
-            if let Ok(lit) = attr.parse_args::<LitStr>() {
-                command_name = Some(lit.value());
-            }
+        if attr.path().is_ident("command")
+            && let Ok(lit) = attr.parse_args::<LitStr>()
+        {
+            command_name = Some(lit.value());
         }
     }
     let command_name = command_name.unwrap_or_else(|| struct_name.to_string().to_uppercase());
@@ -0,0 +1,183 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use once_cell::sync::Lazy;
+
+use crate::context::Context;
+
+/// Clients blocked by `BLPOP` and co until one of their keys is pushed to
+pub static BLOCKED: Lazy<BlockedClients> = Lazy::new(BlockedClients::new);
+
+/// The clients waiting for a key in the order they blocked
+type Waiters = VecDeque<Arc<Context>>;
+
+/// How `CLIENT UNBLOCK` ends the wait of a client
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum UnblockReason {
+    /// As if the timeout elapsed
+    Timeout,
+    /// With the `UNBLOCKED` error
+    Error,
+}
+
+pub struct BlockedClients {
+    /// Keyed by database index and key
+    keys: Mutex<HashMap<(usize, String), Waiters>>,
+    /// Checked before taking the lock, most writes happen without any blocked client
+    clients: AtomicUsize,
+}
+
+impl Default for BlockedClients {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BlockedClients {
+    pub fn new() -> Self {
+        Self {
+            keys: Mutex::new(HashMap::new()),
+            clients: AtomicUsize::new(0),
+        }
+    }
+
+    /// Queue the client for `keys` of database `db`, until the returned guard is dropped
+    pub fn block(&self, ctx: &Arc<Context>, db: usize, keys: &[String]) -> BlockGuard<'_> {
+        let mut waiters = self.keys.lock().unwrap();
+        for key in keys {
+            waiters
+                .entry((db, key.clone()))
+                .or_default()
+                .push_back(ctx.clone());
+        }
+        ctx.set_blocked(true);
+        self.clients.fetch_add(1, Ordering::Relaxed);
+        BlockGuard {
+            blocked: self,
+            ctx: ctx.clone(),
+            db,
+            keys: keys.to_vec(),
+        }
+    }
+
+    /// Wake the client which waits the longest for `key`.
+    ///
+    /// It serves itself and hands over to the next one by dropping its guard, so the clients
+    /// are served in the order they blocked.
+    pub fn signal(&self, db: usize, key: &str) {
+        if self.clients.load(Ordering::Relaxed) == 0 {
+            return;
+        }
+        // the map is keyed by owned strings, the lookup needs one too
+        if let Some(ctx) = self
+            .keys
+            .lock()
+            .unwrap()
+            .get(&(db, key.to_string()))
+            .and_then(|waiters| waiters.front())
+        {
+            ctx.wake();
+        }
+    }
+
+    /// Wake the first client of every key of database `db`, e.g. once `SWAPDB` replaced it
+    pub fn signal_db(&self, db: usize) {
+        if self.clients.load(Ordering::Relaxed) == 0 {
+            return;
+        }
+        for ((key_db, _), waiters) in self.keys.lock().unwrap().iter() {
+            if *key_db == db
+                && let Some(ctx) = waiters.front()
+            {
+                ctx.wake();
+            }
+        }
+    }
+
+    /// Count of blocked clients
+    pub fn len(&self) -> usize {
+        self.clients.load(Ordering::Relaxed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Unqueues a blocked client however its command ended
+pub struct BlockGuard<'a> {
+    blocked: &'a BlockedClients,
+    ctx: Arc<Context>,
+    db: usize,
+    keys: Vec<String>,
+}
+
+impl Drop for BlockGuard<'_> {
+    fn drop(&mut self) {
+        {
+            let mut waiters = self.blocked.keys.lock().unwrap();
+            for key in &self.keys {
+                let entry = (self.db, key.clone());
+                if let Some(queue) = waiters.get_mut(&entry) {
+                    queue.retain(|ctx| ctx.id != self.ctx.id);
+                    if queue.is_empty() {
+                        waiters.remove(&entry);
+                    }
+                }
+            }
+        }
+        self.ctx.set_blocked(false);
+        self.blocked.clients.fetch_sub(1, Ordering::Relaxed);
+        // a push may be left for the next client, whether this one was served or not
+        for key in &self.keys {
+            self.blocked.signal(self.db, key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, time::Duration};
+
+    use crate::{
+        blocked::{BlockedClients, UnblockReason},
+        context::Context,
+        storage::database::Databases,
+    };
+
+    #[tokio::test]
+    async fn test_block() {
+        let blocked = BlockedClients::new();
+        let dbs = Arc::new(Databases::new(1));
+        let ctx = || Context::test_client_of(dbs.clone());
+        let (first, second) = (ctx(), ctx());
+        let keys = ["list".to_string()];
+        let first_guard = blocked.block(&first, 0, &keys);
+        let second_guard = blocked.block(&second, 0, &keys);
+        assert_eq!(blocked.len(), 2);
+        assert!(first.is_blocked());
+
+        // only the client which blocked first is woken
+        blocked.signal(0, "list");
+        blocked.signal(1, "list");
+        first.woken().await;
+        let pending = tokio::time::timeout(Duration::from_millis(10), second.woken());
+        assert!(pending.await.is_err());
+
+        // it hands over to the next client once it leaves
+        drop(first_guard);
+        assert!(!first.is_blocked());
+        second.woken().await;
+
+        assert!(second.unblock(UnblockReason::Timeout));
+        assert_eq!(second.take_unblock(), Some(UnblockReason::Timeout));
+        drop(second_guard);
+        assert!(blocked.is_empty());
+        assert!(!second.unblock(UnblockReason::Error));
+    }
+}
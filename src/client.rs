@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+
+use crate::{context::Context, pubsub::PUBSUB};
+
+/// Every connected client, keyed by client id
+pub static CLIENT_REGISTRY: Lazy<DashMap<usize, Arc<Context>>> = Lazy::new(DashMap::new);
+
+/// Add a client to the registry, it is removed when the returned guard is dropped
+pub fn register_client(ctx: Arc<Context>) -> ClientGuard {
+    CLIENT_REGISTRY.insert(ctx.id, ctx.clone());
+    ClientGuard { ctx }
+}
+
+/// Snapshot of the connected clients ordered by id
+pub fn clients() -> Vec<Arc<Context>> {
+    let mut clients: Vec<_> = CLIENT_REGISTRY
+        .iter()
+        .map(|entry| entry.value().clone())
+        .collect();
+    clients.sort_by_key(|ctx| ctx.id);
+    clients
+}
+
+/// Unregisters a client, so it disappears from `CLIENT LIST` and stops receiving pub/sub
+/// messages however the connection ended
+pub struct ClientGuard {
+    ctx: Arc<Context>,
+}
+
+impl Drop for ClientGuard {
+    fn drop(&mut self) {
+        CLIENT_REGISTRY.remove(&self.ctx.id);
+        PUBSUB.remove_client(&self.ctx);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        client::{CLIENT_REGISTRY, clients, register_client},
+        context::Context,
+        storage::database::Databases,
+    };
+
+    #[test]
+    fn test_register_client() {
+        let dbs = Arc::new(Databases::new(1));
+        let first = Context::next_id();
+        let second = Context::next_id();
+        assert!(second > first);
+
+        let guard = register_client(Arc::new(Context::new(
+            second,
+            dbs.clone(),
+            "127.0.0.1:5000".to_string(),
+            "127.0.0.1:6379".to_string(),
+        )));
+        let _other = register_client(Arc::new(Context::new(
+            first,
+            dbs,
+            "127.0.0.1:5001".to_string(),
+            "127.0.0.1:6379".to_string(),
+        )));
+        let ids: Vec<_> = clients()
+            .iter()
+            .map(|ctx| ctx.id)
+            .filter(|&id| id == first || id == second)
+            .collect();
+        assert_eq!(ids, vec![first, second]);
+
+        drop(guard);
+        assert!(!CLIENT_REGISTRY.contains_key(&second));
+        assert!(CLIENT_REGISTRY.contains_key(&first));
+    }
+}
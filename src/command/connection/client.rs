@@ -0,0 +1,478 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    blocked::UnblockReason,
+    client::{CLIENT_REGISTRY, clients},
+    command::{
+        CommandExecutor, connection::hello::is_valid_client_name, error::CommandError,
+        parser::Parser, registry::CommandResult,
+    },
+    context::Context,
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `CLIENT <subcommand> [arguments]`
+#[derive(PartialEq, Eq, Debug)]
+enum Client {
+    Id,
+    Info,
+    /// `CLIENT LIST [TYPE type] [ID id [id ...]]`
+    List {
+        client_type: Option<String>,
+        ids: Vec<usize>,
+    },
+    GetName,
+    SetName(String),
+    /// `CLIENT SETINFO LIB-NAME|LIB-VER value`
+    SetInfo(LibAttr, String),
+    /// `CLIENT KILL ip:port`
+    KillAddr(String),
+    /// `CLIENT KILL [ID id] [ADDR ip:port] [LADDR ip:port] [USER username] [SKIPME yes|no]`
+    Kill(KillFilter),
+    /// `CLIENT UNBLOCK client-id [TIMEOUT|ERROR]`
+    Unblock(usize, UnblockReason),
+}
+
+#[derive(PartialEq, Eq, Debug)]
+enum LibAttr {
+    Name,
+    Version,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+struct KillFilter {
+    id: Option<usize>,
+    addr: Option<String>,
+    laddr: Option<String>,
+    user: Option<String>,
+    skipme: bool,
+}
+
+impl KillFilter {
+    fn matches(&self, ctx: &Context, current: &Context) -> bool {
+        !(self.skipme && ctx.id == current.id)
+            && self.id.is_none_or(|id| id == ctx.id)
+            && self.addr.as_ref().is_none_or(|addr| *addr == ctx.addr)
+            && self.laddr.as_ref().is_none_or(|laddr| *laddr == ctx.laddr)
+            // there is no ACL yet, every client is authenticated as the default user
+            && self.user.as_ref().is_none_or(|user| user == "default")
+    }
+}
+
+impl TryFrom<Parser> for Client {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let subcommand = parser.next::<String>()?.to_ascii_uppercase();
+        let client = match subcommand.as_str() {
+            "ID" => Client::Id,
+            "INFO" => Client::Info,
+            "GETNAME" => Client::GetName,
+            "SETNAME" => Client::SetName(parser.next()?),
+            "LIST" => {
+                let mut client_type = None;
+                let mut ids = Vec::new();
+                while parser.has_next() {
+                    let option: String = parser.next()?;
+                    match option.to_ascii_uppercase().as_str() {
+                        "TYPE" => {
+                            let value: String = parser.next()?;
+                            let value = value.to_ascii_lowercase();
+                            if !matches!(value.as_str(), "normal" | "master" | "replica" | "pubsub")
+                            {
+                                return Err(CommandError::InvalidArgumentFormat(
+                                    "type".to_string(),
+                                ));
+                            }
+                            client_type = Some(value);
+                        }
+                        "ID" => {
+                            while parser.has_next() {
+                                ids.push(parse_client_id(&mut parser)?);
+                            }
+                        }
+                        _ => return Err(CommandError::SyntaxError),
+                    }
+                }
+                Client::List { client_type, ids }
+            }
+            "SETINFO" => {
+                let attr: String = parser.next()?;
+                let attr = match attr.to_ascii_uppercase().as_str() {
+                    "LIB-NAME" => LibAttr::Name,
+                    "LIB-VER" => LibAttr::Version,
+                    _ => return Err(CommandError::SyntaxError),
+                };
+                Client::SetInfo(attr, parser.next()?)
+            }
+            "UNBLOCK" => {
+                let id = parse_client_id(&mut parser)?;
+                let reason = if parser.has_next() {
+                    let reason: String = parser.next()?;
+                    match reason.to_ascii_uppercase().as_str() {
+                        "TIMEOUT" => UnblockReason::Timeout,
+                        "ERROR" => UnblockReason::Error,
+                        _ => return Err(CommandError::InvalidUnblockReason),
+                    }
+                } else {
+                    UnblockReason::Timeout
+                };
+                Client::Unblock(id, reason)
+            }
+            "KILL" if parser.len() == 3 => Client::KillAddr(parser.next()?),
+            "KILL" => {
+                let mut filter = KillFilter {
+                    id: None,
+                    addr: None,
+                    laddr: None,
+                    user: None,
+                    skipme: true,
+                };
+                if !parser.has_next() {
+                    return Err(CommandError::SyntaxError);
+                }
+                while parser.has_next() {
+                    let option: String = parser.next()?;
+                    match option.to_ascii_uppercase().as_str() {
+                        "ID" => filter.id = Some(parse_client_id(&mut parser)?),
+                        "ADDR" => filter.addr = Some(parser.next()?),
+                        "LADDR" => filter.laddr = Some(parser.next()?),
+                        "USER" => {
+                            let user: String = parser.next()?;
+                            // there is no ACL yet, `default` is the only user
+                            if user != "default" {
+                                return Err(CommandError::NoSuchUser(user));
+                            }
+                            filter.user = Some(user);
+                        }
+                        "SKIPME" => {
+                            let value: String = parser.next()?;
+                            filter.skipme = match value.to_ascii_lowercase().as_str() {
+                                "yes" => true,
+                                "no" => false,
+                                _ => return Err(CommandError::SyntaxError),
+                            };
+                        }
+                        _ => return Err(CommandError::SyntaxError),
+                    }
+                }
+                Client::Kill(filter)
+            }
+            _ => {
+                return Err(CommandError::UnknownSubcommand(
+                    subcommand,
+                    "CLIENT".to_string(),
+                ));
+            }
+        };
+        if parser.has_next() {
+            return Err(CommandError::UnknownSubcommand(
+                subcommand,
+                "CLIENT".to_string(),
+            ));
+        }
+        Ok(client)
+    }
+}
+
+fn parse_client_id(parser: &mut Parser) -> Result<usize, CommandError> {
+    parser
+        .next::<i64>()
+        .ok()
+        .and_then(|id| usize::try_from(id).ok())
+        .filter(|&id| id > 0)
+        .ok_or_else(|| CommandError::InvalidArgumentFormat("client-id".to_string()))
+}
+
+fn text(s: String) -> Frame {
+    Frame::Verbatim("txt".to_string(), s.into_bytes())
+}
+
+#[async_trait]
+impl CommandExecutor for Client {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        match self {
+            Client::Id => Ok(Frame::Integer(ctx.id as i64)),
+            Client::Info => Ok(text(ctx.client_info() + "\n")),
+            Client::List { client_type, ids } => {
+                let mut list = String::new();
+                for client in clients() {
+                    let is_type = |t: &String| match t.as_str() {
+                        "normal" => !client.is_subscribed(),
+                        "pubsub" => client.is_subscribed(),
+                        _ => false,
+                    };
+                    if !client_type.as_ref().is_none_or(is_type)
+                        || !(ids.is_empty() || ids.contains(&client.id))
+                    {
+                        continue;
+                    }
+                    list.push_str(&client.client_info());
+                    list.push('\n');
+                }
+                Ok(text(list))
+            }
+            Client::GetName => Ok(Frame::BulkString(ctx.name().map(String::into_bytes))),
+            Client::SetName(name) => {
+                if !is_valid_client_name(&name) {
+                    return Err(CommandError::InvalidClientName);
+                }
+                ctx.set_name(Some(name).filter(|name| !name.is_empty()));
+                Ok(Frame::SimpleString("OK".to_string()))
+            }
+            Client::SetInfo(attr, value) => {
+                if !is_valid_client_name(&value) {
+                    return Err(CommandError::InvalidArgumentFormat(match attr {
+                        LibAttr::Name => "lib-name".to_string(),
+                        LibAttr::Version => "lib-ver".to_string(),
+                    }));
+                }
+                let value = Some(value).filter(|value| !value.is_empty());
+                match attr {
+                    LibAttr::Name => ctx.set_lib_name(value),
+                    LibAttr::Version => ctx.set_lib_ver(value),
+                }
+                Ok(Frame::SimpleString("OK".to_string()))
+            }
+            Client::KillAddr(addr) => {
+                let target = CLIENT_REGISTRY
+                    .iter()
+                    .find(|entry| entry.value().addr == addr)
+                    .map(|entry| entry.value().clone())
+                    .ok_or(CommandError::NoSuchClient)?;
+                log::info!("ctx {} killed client {} ({})", ctx.id, target.id, addr);
+                target.kill();
+                Ok(Frame::SimpleString("OK".to_string()))
+            }
+            Client::Kill(filter) => {
+                let mut killed = 0;
+                for client in clients() {
+                    if filter.matches(&client, &ctx) {
+                        log::info!(
+                            "ctx {} killed client {} ({})",
+                            ctx.id,
+                            client.id,
+                            client.addr
+                        );
+                        client.kill();
+                        killed += 1;
+                    }
+                }
+                Ok(Frame::Integer(killed))
+            }
+            Client::Unblock(id, reason) => {
+                let unblocked = CLIENT_REGISTRY
+                    .get(&id)
+                    .is_some_and(|client| client.unblock(reason));
+                Ok(Frame::Integer(unblocked as i64))
+            }
+        }
+    }
+}
+
+async fn client(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: Client = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("CLIENT", client);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        blocked::{BLOCKED, UnblockReason},
+        client::register_client,
+        command::{
+            CommandExecutor,
+            connection::client::{Client, KillFilter, LibAttr},
+            parser::Parser,
+        },
+        context::Context,
+        protocol::Frame,
+    };
+
+    #[test]
+    fn test_try_from_parser_to_client() {
+        assert_eq!(
+            Client::try_from(Parser::from_args(&["CLIENT", "id"])).unwrap(),
+            Client::Id
+        );
+        assert_eq!(
+            Client::try_from(Parser::from_args(&[
+                "CLIENT", "LIST", "TYPE", "normal", "ID", "1", "2"
+            ]))
+            .unwrap(),
+            Client::List {
+                client_type: Some("normal".to_string()),
+                ids: vec![1, 2]
+            }
+        );
+        assert_eq!(
+            Client::try_from(Parser::from_args(&["CLIENT", "SETINFO", "lib-ver", "1.0"])).unwrap(),
+            Client::SetInfo(LibAttr::Version, "1.0".to_string())
+        );
+        assert_eq!(
+            Client::try_from(Parser::from_args(&["CLIENT", "UNBLOCK", "7", "error"])).unwrap(),
+            Client::Unblock(7, UnblockReason::Error)
+        );
+        assert_eq!(
+            Client::try_from(Parser::from_args(&["CLIENT", "KILL", "127.0.0.1:5000"])).unwrap(),
+            Client::KillAddr("127.0.0.1:5000".to_string())
+        );
+        assert_eq!(
+            Client::try_from(Parser::from_args(&[
+                "CLIENT", "KILL", "ID", "7", "SKIPME", "no"
+            ]))
+            .unwrap(),
+            Client::Kill(KillFilter {
+                id: Some(7),
+                addr: None,
+                laddr: None,
+                user: None,
+                skipme: false
+            })
+        );
+    }
+
+    #[test]
+    fn test_try_from_parser_to_client_err() {
+        assert!(Client::try_from(Parser::from_args(&["CLIENT", "FOO"])).is_err());
+        assert!(Client::try_from(Parser::from_args(&["CLIENT", "ID", "extra"])).is_err());
+        assert!(
+            Client::try_from(Parser::from_args(&["CLIENT", "LIST", "TYPE", "unknown"])).is_err()
+        );
+        assert!(Client::try_from(Parser::from_args(&["CLIENT", "UNBLOCK", "7", "NOW"])).is_err());
+        assert!(
+            Client::try_from(Parser::from_args(&[
+                "CLIENT", "KILL", "ID", "0", "SKIPME", "no"
+            ]))
+            .is_err()
+        );
+        assert!(
+            Client::try_from(Parser::from_args(&[
+                "CLIENT", "KILL", "SKIPME", "maybe", "ID", "1"
+            ]))
+            .is_err()
+        );
+        assert_eq!(
+            Client::try_from(Parser::from_args(&["CLIENT", "KILL", "USER", "alice"]))
+                .unwrap_err()
+                .to_string(),
+            "No such user 'alice'"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_client_setname_and_info() {
+        let ctx = Context::test_client(1);
+        let _guard = register_client(ctx.clone());
+
+        let reply = Client::GetName.execute(ctx.clone()).await.unwrap();
+        assert_eq!(reply, Frame::BulkString(None));
+        assert!(
+            Client::SetName("bad name".to_string())
+                .execute(ctx.clone())
+                .await
+                .is_err()
+        );
+        Client::SetName("worker".to_string())
+            .execute(ctx.clone())
+            .await
+            .unwrap();
+        Client::SetInfo(LibAttr::Name, "redis-py".to_string())
+            .execute(ctx.clone())
+            .await
+            .unwrap();
+
+        let Frame::Verbatim(_, info) = Client::Info.execute(ctx.clone()).await.unwrap() else {
+            panic!("expect a verbatim string");
+        };
+        let info = String::from_utf8(info).unwrap();
+        assert!(info.starts_with(&format!("id={} addr={} ", ctx.id, ctx.addr)));
+        assert!(info.contains(" name=worker "));
+        assert!(info.contains(" lib-name=redis-py "));
+
+        let Frame::Verbatim(_, list) = Client::List {
+            client_type: None,
+            ids: vec![ctx.id],
+        }
+        .execute(ctx.clone())
+        .await
+        .unwrap() else {
+            panic!("expect a verbatim string");
+        };
+        assert_eq!(list, info.into_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_client_kill() {
+        let me = Context::test_client(1);
+        let other = Context::test_client(1);
+        let _me_guard = register_client(me.clone());
+        let _other_guard = register_client(other.clone());
+
+        let kill = |filter| Client::Kill(filter).execute(me.clone());
+        let reply = kill(KillFilter {
+            id: None,
+            addr: Some(me.addr.clone()),
+            laddr: None,
+            user: None,
+            skipme: true,
+        })
+        .await
+        .unwrap();
+        assert_eq!(reply, Frame::Integer(0));
+        assert!(!me.is_killed());
+
+        let reply = kill(KillFilter {
+            id: Some(other.id),
+            addr: None,
+            laddr: None,
+            user: Some("default".to_string()),
+            skipme: true,
+        })
+        .await
+        .unwrap();
+        assert_eq!(reply, Frame::Integer(1));
+        assert!(other.is_killed());
+        other.killed().await;
+
+        assert!(
+            Client::KillAddr("127.0.0.1:1".to_string())
+                .execute(me.clone())
+                .await
+                .is_err()
+        );
+        Client::KillAddr(me.addr.clone())
+            .execute(me.clone())
+            .await
+            .unwrap();
+        assert!(me.is_killed());
+    }
+
+    #[tokio::test]
+    async fn test_client_unblock() {
+        let me = Context::test_client(1);
+        let blocked = Context::test_client(1);
+        let _guard = register_client(blocked.clone());
+        let unblock = |reason| Client::Unblock(blocked.id, reason).execute(me.clone());
+        assert_eq!(
+            unblock(UnblockReason::Timeout).await.unwrap(),
+            Frame::Integer(0)
+        );
+
+        let block_guard = BLOCKED.block(&blocked, 0, &["list".to_string()]);
+        assert!(blocked.client_info().contains(" flags=b "));
+        assert_eq!(
+            unblock(UnblockReason::Error).await.unwrap(),
+            Frame::Integer(1)
+        );
+        blocked.woken().await;
+        assert_eq!(blocked.take_unblock(), Some(UnblockReason::Error));
+        drop(block_guard);
+        assert!(blocked.client_info().contains(" flags=N "));
+    }
+}
@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, parser::Parser, registry::CommandResult},
+    context::Context,
+    protocol::{Frame, ProtocolVersion},
+    register_redis_command,
+};
+
+/// `HELLO [protover [AUTH username password] [SETNAME clientname]]`
+#[derive(PartialEq, Eq, Debug)]
+struct Hello {
+    protover: Option<i64>,
+    auth: Option<(String, String)>,
+    setname: Option<String>,
+}
+
+impl TryFrom<Parser> for Hello {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let mut hello = Hello {
+            protover: None,
+            auth: None,
+            setname: None,
+        };
+        if !parser.has_next() {
+            return Ok(hello);
+        }
+        hello.protover = Some(
+            parser
+                .next::<i64>()
+                .map_err(|_| CommandError::InvalidArgumentFormat("protover".to_string()))?,
+        );
+        while parser.has_next() {
+            let option: String = parser.next()?;
+            match option.to_ascii_uppercase().as_str() {
+                "AUTH" => hello.auth = Some(parser.next_pair()?),
+                "SETNAME" => hello.setname = Some(parser.next()?),
+                _ => return Err(CommandError::SyntaxError),
+            }
+        }
+        Ok(hello)
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for Hello {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let version = match self.protover {
+            Some(protover) => ProtocolVersion::try_from(protover)
+                .map_err(|_| CommandError::UnsupportedProtocol)?,
+            None => ctx.protocol_version(),
+        };
+        // there is no ACL yet, the default user accepts any password
+        if let Some((username, _)) = &self.auth
+            && username != "default"
+        {
+            return Err(CommandError::WrongPass);
+        }
+        if let Some(name) = &self.setname
+            && !is_valid_client_name(name)
+        {
+            return Err(CommandError::InvalidClientName);
+        }
+
+        ctx.set_protocol_version(version);
+        if let Some(name) = self.setname {
+            ctx.set_name(Some(name).filter(|name| !name.is_empty()));
+        }
+        log::debug!("ctx {} switched to RESP{}", ctx.id, version as u8);
+
+        let field = |name: &str| Frame::BulkString(Some(name.as_bytes().to_vec()));
+        Ok(Frame::Map(vec![
+            (field("server"), field("rudis")),
+            (field("version"), field(env!("CARGO_PKG_VERSION"))),
+            (field("proto"), Frame::Integer(version as i64)),
+            (field("id"), Frame::Integer(ctx.id as i64)),
+            (field("mode"), field("standalone")),
+            (field("role"), field("master")),
+            (field("modules"), Frame::Array(Some(vec![]))),
+        ]))
+    }
+}
+
+/// Client names are printed in `CLIENT LIST`, so they can't contain spaces or control chars.
+pub(crate) fn is_valid_client_name(name: &str) -> bool {
+    name.bytes().all(|b| (b'!'..=b'~').contains(&b))
+}
+
+async fn hello(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: Hello = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("HELLO", hello);
+
+#[cfg(test)]
+mod test {
+    use crate::command::{connection::hello::Hello, parser::Parser};
+
+    #[test]
+    fn test_try_from_parser_to_hello_ok() {
+        let hello: Hello = Parser::from_args(&["HELLO"]).try_into().unwrap();
+        assert_eq!(
+            hello,
+            Hello {
+                protover: None,
+                auth: None,
+                setname: None
+            }
+        );
+
+        let hello: Hello =
+            Parser::from_args(&["HELLO", "3", "AUTH", "default", "pass", "SETNAME", "worker"])
+                .try_into()
+                .unwrap();
+        assert_eq!(
+            hello,
+            Hello {
+                protover: Some(3),
+                auth: Some(("default".to_string(), "pass".to_string())),
+                setname: Some("worker".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_try_from_parser_to_hello_on_unknown_option() {
+        assert!(Hello::try_from(Parser::from_args(&["HELLO", "3", "FOO"])).is_err());
+        assert!(Hello::try_from(Parser::from_args(&["HELLO", "three"])).is_err());
+    }
+}
@@ -1 +1,5 @@
+mod client;
+mod hello;
 mod ping;
+mod quit;
+mod select;
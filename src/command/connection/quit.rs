@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, registry::CommandResult},
+    context::Context,
+    protocol::Frame,
+};
+
+/// `QUIT`, the connection is closed once `+OK` has been sent
+#[derive(PartialEq, Eq, Command, Debug)]
+struct Quit;
+
+#[async_trait]
+impl CommandExecutor for Quit {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        log::debug!("ctx {} quit", ctx.id);
+        ctx.kill();
+        Ok(Frame::SimpleString("OK".to_string()))
+    }
+}
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, registry::CommandResult},
+    context::Context,
+    protocol::Frame,
+};
+
+/// `SELECT index`
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("SELECT")]
+struct Select {
+    index: i64,
+}
+
+#[async_trait]
+impl CommandExecutor for Select {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let selected = usize::try_from(self.index).is_ok_and(|index| ctx.select(index));
+        if !selected {
+            return Err(CommandError::DbIndexOutOfRange);
+        }
+        log::debug!("ctx {} selected db {}", ctx.id, self.index);
+        Ok(Frame::SimpleString("OK".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, connection::select::Select},
+        context::Context,
+        object::redis_object::RedisObject,
+    };
+
+    #[tokio::test]
+    async fn test_select() {
+        let ctx = Context::test_client(16);
+        ctx.db().set(
+            "k".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+
+        Select { index: 15 }.execute(ctx.clone()).await.unwrap();
+        assert_eq!(ctx.db_index(), 15);
+        assert!(!ctx.db().contains_key("k"));
+
+        assert!(Select { index: 16 }.execute(ctx.clone()).await.is_err());
+        assert!(Select { index: -1 }.execute(ctx.clone()).await.is_err());
+        assert_eq!(ctx.db_index(), 15);
+    }
+}
@@ -11,13 +11,150 @@ pub enum CommandError {
 
     #[error("Wrong number of arguments for `{0}` command, expect {1} value(s)")]
     InvalidArgumentNumber(String, usize),
-    
+
     #[error("Wrong format for `{0}` argument")]
     InvalidArgumentFormat(String),
-    
+
     #[error("Operation against a key holding the wrong kind of value")]
     WrongType,
 
+    #[error("Syntax error")]
+    SyntaxError,
+
+    #[error("NOPROTO unsupported protocol version")]
+    UnsupportedProtocol,
+
+    #[error("WRONGPASS invalid username-password pair or user is disabled.")]
+    WrongPass,
+
+    #[error("Client names cannot contain spaces, newlines or special characters.")]
+    InvalidClientName,
+
+    #[error("Unknown subcommand '{0}'. Try {1} HELP.")]
+    UnknownSubcommand(String, String),
+
+    #[error("CLIENT UNBLOCK reason should be TIMEOUT or ERROR")]
+    InvalidUnblockReason,
+
+    #[error("No such client")]
+    NoSuchClient,
+
+    #[error("No such user '{0}'")]
+    NoSuchUser(String),
+
+    #[error("No shutdown in progress.")]
+    NoShutdownInProgress,
+
+    #[error("DB index is out of range")]
+    DbIndexOutOfRange,
+
+    #[error("Source and destination objects are the same")]
+    SameObject,
+
+    #[error("no such key")]
+    NoSuchKey,
+
+    #[error("index out of range")]
+    IndexOutOfRange,
+
+    #[error("value is out of range, must be positive")]
+    NotPositive,
+
+    #[error("value is out of range")]
+    OutOfRange,
+
+    #[error(
+        "RANK can't be zero: use 1 to start from the first match, 2 from the second ... or use negative to start from the end of the list"
+    )]
+    ZeroRank,
+
+    #[error("{0} can't be negative")]
+    NegativeOption(String),
+
+    #[error("{0} should be greater than 0")]
+    NotGreaterThanZero(String),
+
+    #[error("value is not a valid float")]
+    NotFloat,
+
+    #[error("hash value is not an integer")]
+    HashValueNotInteger,
+
+    #[error("hash value is not a float")]
+    HashValueNotFloat,
+
+    #[error("increment or decrement would overflow")]
+    IncrementOverflow,
+
+    #[error("increment would produce NaN or Infinity")]
+    NanOrInfinity,
+
+    #[error("resulting score is not a number (NaN)")]
+    ScoreNaN,
+
+    #[error("INCR option supports a single increment-element pair")]
+    IncrSinglePair,
+
+    #[error("min or max is not a float")]
+    MinMaxNotFloat,
+
+    #[error("min or max not valid string range item")]
+    InvalidLexRange,
+
+    #[error("syntax error, LIMIT is only supported in combination with either BYSCORE or BYLEX")]
+    LimitWithoutBy,
+
+    #[error("syntax error, WITHSCORES not supported in combination with BYLEX")]
+    WithScoresByLex,
+
+    #[error("Mandatory argument FIELDS is missing or not at the right position")]
+    MissingFields,
+
+    #[error("The `numfields` parameter must match the number of arguments")]
+    NumFieldsMismatch,
+
+    #[error("timeout is not a float or out of range")]
+    InvalidTimeout,
+
+    #[error("timeout is negative")]
+    NegativeTimeout,
+
+    #[error("UNBLOCKED client unblocked via CLIENT UNBLOCK")]
+    Unblocked,
+
+    #[error("invalid expire time in '{0}' command")]
+    InvalidExpireTime(String),
+
+    #[error("Unsupported option {0}")]
+    UnsupportedOption(String),
+
+    #[error("{0} options at the same time are not compatible")]
+    IncompatibleOptions(String),
+
+    #[error("invalid cursor")]
+    InvalidCursor,
+
+    #[error(
+        "Can't execute '{0}': only (P|S)SUBSCRIBE / (P|S)UNSUBSCRIBE / PING / QUIT / RESET are allowed in this context"
+    )]
+    SubscribedContext(String),
+
+    #[error("OOM command not allowed when used memory > 'maxmemory'.")]
+    OutOfMemory,
+
+    #[error(
+        "An LFU maxmemory policy is not selected, access frequency not tracked. Please note that when switching between policies at runtime LRU and LFU data will take some time to adjust."
+    )]
+    FreqNotTracked,
+
+    #[error(
+        "An LFU maxmemory policy is selected, idle time not tracked. Please note that when switching between policies at runtime LRU and LFU data will take some time to adjust."
+    )]
+    IdleTimeNotTracked,
+
+    #[error("unknown type name '{0}'")]
+    UnknownTypeName(String),
+
     #[error("Super huge value(length: {0}) for `{1}` command")]
     SuperHugeString(usize, String),
 
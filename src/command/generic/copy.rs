@@ -0,0 +1,128 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, parser::Parser, registry::CommandResult},
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `COPY source destination [DB destination-db] [REPLACE]`
+#[derive(PartialEq, Eq, Debug)]
+struct Copy {
+    source: String,
+    destination: String,
+    db: Option<i64>,
+    replace: bool,
+}
+
+impl TryFrom<Parser> for Copy {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let source = parser.next()?;
+        let destination = parser.next()?;
+        let mut db = None;
+        let mut replace = false;
+        while parser.has_next() {
+            let option: String = parser.next()?;
+            match option.to_ascii_uppercase().as_str() {
+                "DB" => db = Some(parser.next()?),
+                "REPLACE" => replace = true,
+                _ => return Err(CommandError::SyntaxError),
+            }
+        }
+        Ok(Copy {
+            source,
+            destination,
+            db,
+            replace,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for Copy {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let source = ctx.db();
+        let target = match self.db {
+            Some(index) => usize::try_from(index)
+                .ok()
+                .and_then(|index| ctx.dbs.get(index))
+                .ok_or(CommandError::DbIndexOutOfRange)?,
+            None => source.clone(),
+        };
+        if Arc::ptr_eq(&source, &target) && self.source == self.destination {
+            return Err(CommandError::SameObject);
+        }
+        let destination = self.destination.clone();
+        let copied = source.copy_to(&self.source, &target, self.destination, self.replace);
+        if copied {
+            notify_keyspace_event(notify::GENERIC, "copy_to", &destination, target.id());
+        }
+        Ok(Frame::Integer(copied as i64))
+    }
+}
+
+async fn copy(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: Copy = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("COPY", copy);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, generic::copy::Copy, parser::Parser},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[test]
+    fn test_try_from_parser_to_copy() {
+        assert_eq!(
+            Copy::try_from(Parser::from_args(&["COPY", "a", "b", "replace", "DB", "3"])).unwrap(),
+            Copy {
+                source: "a".to_string(),
+                destination: "b".to_string(),
+                db: Some(3),
+                replace: true,
+            }
+        );
+        assert!(Copy::try_from(Parser::from_args(&["COPY", "a"])).is_err());
+        assert!(Copy::try_from(Parser::from_args(&["COPY", "a", "b", "DB"])).is_err());
+        assert!(Copy::try_from(Parser::from_args(&["COPY", "a", "b", "NX"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_copy() {
+        let ctx = Context::test_client(2);
+        ctx.db().set(
+            "a".to_string(),
+            RedisObject::new_string(b"1".to_vec()),
+            None,
+        );
+        let copy = |destination: &str, db, replace| {
+            Copy {
+                source: "a".to_string(),
+                destination: destination.to_string(),
+                db,
+                replace,
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(copy("b", None, false).await.unwrap(), Frame::Integer(1));
+        assert_eq!(copy("b", None, false).await.unwrap(), Frame::Integer(0));
+        assert_eq!(copy("b", None, true).await.unwrap(), Frame::Integer(1));
+        assert_eq!(copy("a", Some(1), false).await.unwrap(), Frame::Integer(1));
+        assert!(ctx.dbs.get(1).unwrap().contains_key("a"));
+        assert!(copy("a", None, true).await.is_err());
+        assert!(copy("a", Some(0), true).await.is_err());
+        assert!(copy("c", Some(2), false).await.is_err());
+    }
+}
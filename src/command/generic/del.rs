@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, parser::Parser, registry::CommandResult},
+    config::get_server_config,
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `DEL key [key ...]`
+#[derive(PartialEq, Eq, Debug)]
+struct Del {
+    keys: Vec<String>,
+}
+
+impl TryFrom<Parser> for Del {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        Ok(Del {
+            keys: parser.remaining()?,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for Del {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let lazy = get_server_config().lazyfree_lazy_user_del;
+        let deleted = self
+            .keys
+            .iter()
+            .filter(|key| db.delete(key, lazy))
+            .inspect(|key| notify_keyspace_event(notify::GENERIC, "del", key, db.id()))
+            .count();
+        log::debug!("ctx {} deleted {} key(s)", ctx.id, deleted);
+        Ok(Frame::Integer(deleted as i64))
+    }
+}
+
+async fn del(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: Del = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("DEL", del);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, generic::del::Del, parser::Parser},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_del() {
+        assert!(Del::try_from(Parser::from_args(&["DEL"])).is_err());
+        let cmd = Del::try_from(Parser::from_args(&["DEL", "a", "b", "a", "c"])).unwrap();
+
+        let ctx = Context::test_client(1);
+        for key in ["a", "b", "d"] {
+            ctx.db().set(
+                key.to_string(),
+                RedisObject::new_string(b"v".to_vec()),
+                None,
+            );
+        }
+        assert_eq!(cmd.execute(ctx.clone()).await.unwrap(), Frame::Integer(2));
+        assert_eq!(ctx.db().len(), 1);
+    }
+}
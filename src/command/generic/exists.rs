@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, parser::Parser, registry::CommandResult},
+    context::Context,
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `EXISTS key [key ...]`, a key given several times is counted as many times
+#[derive(PartialEq, Eq, Debug)]
+struct Exists {
+    keys: Vec<String>,
+}
+
+impl TryFrom<Parser> for Exists {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        Ok(Exists {
+            keys: parser.remaining()?,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for Exists {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let count = self.keys.iter().filter(|key| db.contains_key(key)).count();
+        Ok(Frame::Integer(count as i64))
+    }
+}
+
+async fn exists(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: Exists = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("EXISTS", exists);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, generic::exists::Exists},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_exists() {
+        let ctx = Context::test_client(1);
+        ctx.db().set(
+            "a".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+        let keys = ["a", "b", "a"].iter().map(|key| key.to_string()).collect();
+        assert_eq!(
+            Exists { keys }.execute(ctx).await.unwrap(),
+            Frame::Integer(2)
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        option::{ExpireCondition, ExpireKind},
+        parser::Parser,
+        registry::CommandResult,
+    },
+    config::get_server_config,
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+    register_redis_command,
+    util::unix_millis,
+};
+
+/// Latest expire time of a key in unix milliseconds, the same as of a field
+const MAX_EXPIRE: i64 = (1 << 48) - 1;
+
+/// `EXPIRE key seconds [NX | XX | GT | LT]` and `PEXPIRE`, `EXPIREAT`, `PEXPIREAT`.
+///
+/// A time in the past deletes the key.
+#[derive(PartialEq, Eq, Debug)]
+struct Expire {
+    kind: ExpireKind,
+    key: String,
+    time: i64,
+    condition: ExpireCondition,
+}
+
+impl Expire {
+    fn parse(kind: ExpireKind, mut parser: Parser) -> Result<Self, CommandError> {
+        let key = parser.next()?;
+        let time = parser.next()?;
+        let mut condition = ExpireCondition::default();
+        while parser.has_next() {
+            let option: String = parser.next()?;
+            if !condition.parse_option(&option) {
+                return Err(CommandError::UnsupportedOption(option));
+            }
+        }
+        condition.check()?;
+        Ok(Expire {
+            kind,
+            key,
+            time,
+            condition,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for Expire {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let now = SystemTime::now();
+        let at = self
+            .kind
+            .unix_millis(self.time, now)
+            .filter(|at| *at <= MAX_EXPIRE)
+            .ok_or_else(|| CommandError::InvalidExpireTime(self.kind.command().to_string()))?;
+        let db = ctx.db();
+        if !db.contains_key(&self.key) {
+            return Ok(Frame::Integer(0));
+        }
+        let current = db.expire_time(&self.key).map(unix_millis);
+        if !self.condition.allows(current, at) {
+            return Ok(Frame::Integer(0));
+        }
+        if at <= unix_millis(now) {
+            log::debug!(
+                "ctx {} expired {} by {}",
+                ctx.id,
+                &self.key,
+                self.kind.command()
+            );
+            db.delete(&self.key, get_server_config().lazyfree_lazy_expire);
+            notify_keyspace_event(notify::GENERIC, "del", &self.key, db.id());
+            return Ok(Frame::Integer(1));
+        }
+        let updated = db.set_expire(&self.key, UNIX_EPOCH + Duration::from_millis(at as u64));
+        if updated {
+            notify_keyspace_event(notify::GENERIC, "expire", &self.key, db.id());
+        }
+        Ok(Frame::Integer(updated as i64))
+    }
+}
+
+async fn expire(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    Expire::parse(ExpireKind::Seconds, parser)?
+        .execute(ctx)
+        .await
+}
+
+async fn pexpire(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    Expire::parse(ExpireKind::Millis, parser)?
+        .execute(ctx)
+        .await
+}
+
+async fn expireat(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    Expire::parse(ExpireKind::UnixSeconds, parser)?
+        .execute(ctx)
+        .await
+}
+
+async fn pexpireat(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    Expire::parse(ExpireKind::UnixMillis, parser)?
+        .execute(ctx)
+        .await
+}
+
+register_redis_command!("EXPIRE", expire);
+register_redis_command!("PEXPIRE", pexpire);
+register_redis_command!("EXPIREAT", expireat);
+register_redis_command!("PEXPIREAT", pexpireat);
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, SystemTime};
+
+    use crate::{
+        command::{
+            CommandExecutor,
+            error::CommandError,
+            generic::expire::Expire,
+            option::{ExpireCondition, ExpireKind},
+            parser::Parser,
+        },
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+        util::unix_millis,
+    };
+
+    fn expire(kind: ExpireKind, key: &str, time: i64, condition: ExpireCondition) -> Expire {
+        Expire {
+            kind,
+            key: key.to_string(),
+            time,
+            condition,
+        }
+    }
+
+    #[test]
+    fn test_parse_expire() {
+        let cmd = Expire::parse(
+            ExpireKind::Seconds,
+            Parser::from_args(&["EXPIRE", "k", "10", "gt"]),
+        );
+        assert_eq!(
+            cmd.unwrap(),
+            expire(
+                ExpireKind::Seconds,
+                "k",
+                10,
+                ExpireCondition {
+                    gt: true,
+                    ..Default::default()
+                }
+            )
+        );
+        let parse = |args: &[&str]| Expire::parse(ExpireKind::Millis, Parser::from_args(args));
+        assert!(parse(&["PEXPIRE", "k"]).is_err());
+        assert!(parse(&["PEXPIRE", "k", "ten"]).is_err());
+        assert!(parse(&["PEXPIRE", "k", "10", "NX", "XX"]).is_err());
+        assert!(parse(&["PEXPIRE", "k", "10", "GT", "LT"]).is_err());
+        assert!(parse(&["PEXPIRE", "k", "10", "KEEPTTL"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_expire_conditions() {
+        let ctx = Context::test_client(1);
+        let db = ctx.db();
+        db.set(
+            "k".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+        let condition = |option: &str| {
+            let mut condition = ExpireCondition::default();
+            match option {
+                "NX" => condition.nx = true,
+                "XX" => condition.xx = true,
+                "GT" => condition.gt = true,
+                "LT" => condition.lt = true,
+                _ => {}
+            }
+            condition
+        };
+        let run = |kind, time, option: &str| {
+            expire(kind, "k", time, condition(option)).execute(ctx.clone())
+        };
+
+        // no TTL: XX and GT fail, NX and LT succeed
+        assert_eq!(
+            run(ExpireKind::Seconds, 100, "XX").await.unwrap(),
+            Frame::Integer(0)
+        );
+        assert_eq!(
+            run(ExpireKind::Seconds, 100, "GT").await.unwrap(),
+            Frame::Integer(0)
+        );
+        assert!(db.expire_time("k").is_none());
+        assert_eq!(
+            run(ExpireKind::Seconds, 100, "NX").await.unwrap(),
+            Frame::Integer(1)
+        );
+        assert_eq!(
+            run(ExpireKind::Seconds, 50, "NX").await.unwrap(),
+            Frame::Integer(0)
+        );
+        assert_eq!(
+            run(ExpireKind::Seconds, 50, "GT").await.unwrap(),
+            Frame::Integer(0)
+        );
+        assert_eq!(
+            run(ExpireKind::Seconds, 200, "GT").await.unwrap(),
+            Frame::Integer(1)
+        );
+        assert_eq!(
+            run(ExpireKind::Millis, 300_000, "LT").await.unwrap(),
+            Frame::Integer(0)
+        );
+        assert_eq!(
+            run(ExpireKind::Millis, 1000, "LT").await.unwrap(),
+            Frame::Integer(1)
+        );
+
+        let at = unix_millis(SystemTime::now()) + 60_000;
+        assert_eq!(
+            run(ExpireKind::UnixMillis, at, "").await.unwrap(),
+            Frame::Integer(1)
+        );
+        let remaining = db
+            .expire_time("k")
+            .unwrap()
+            .duration_since(SystemTime::now());
+        assert!(remaining.unwrap() > Duration::from_secs(59));
+
+        assert!(run(ExpireKind::Seconds, i64::MAX, "").await.is_err());
+        for (kind, time) in [
+            (ExpireKind::UnixMillis, i64::MAX),
+            (ExpireKind::UnixSeconds, i64::MAX / 1000),
+        ] {
+            assert!(matches!(
+                run(kind, time, "").await,
+                Err(CommandError::InvalidExpireTime(_))
+            ));
+        }
+        assert!(db.expire_time("k").is_some());
+        // a time in the past deletes the key
+        assert_eq!(
+            run(ExpireKind::UnixSeconds, 1, "").await.unwrap(),
+            Frame::Integer(1)
+        );
+        assert!(!db.contains_key("k"));
+        assert_eq!(
+            run(ExpireKind::Seconds, 100, "").await.unwrap(),
+            Frame::Integer(0)
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, registry::CommandResult},
+    context::Context,
+    protocol::Frame,
+};
+
+/// `KEYS pattern`, walks the whole keyspace
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("KEYS")]
+struct Keys {
+    pattern: String,
+}
+
+#[async_trait]
+impl CommandExecutor for Keys {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let keys = ctx
+            .db()
+            .keys(&self.pattern)
+            .into_iter()
+            .map(|key| Frame::BulkString(Some(key.into_bytes())))
+            .collect();
+        Ok(Frame::Array(Some(keys)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, generic::keys::Keys},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_keys() {
+        let ctx = Context::test_client(1);
+        for key in ["hello", "hallo", "world"] {
+            ctx.db().set(
+                key.to_string(),
+                RedisObject::new_string(b"v".to_vec()),
+                None,
+            );
+        }
+        let keys = |pattern: &str| {
+            Keys {
+                pattern: pattern.to_string(),
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(
+            keys("h[^a]llo").await.unwrap(),
+            Frame::Array(Some(vec![Frame::BulkString(Some(b"hello".to_vec()))]))
+        );
+        let Frame::Array(Some(all)) = keys("*o*").await.unwrap() else {
+            panic!("KEYS replies an array");
+        };
+        assert_eq!(all.len(), 3);
+        assert_eq!(keys("x*").await.unwrap(), Frame::Array(Some(vec![])));
+    }
+}
@@ -0,0 +1,16 @@
+mod copy;
+mod del;
+mod exists;
+mod expire;
+mod keys;
+mod r#move;
+mod object;
+mod persist;
+mod randomkey;
+mod rename;
+mod renamenx;
+mod scan;
+mod touch;
+mod ttl;
+mod r#type;
+mod unlink;
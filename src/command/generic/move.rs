@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, registry::CommandResult},
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+};
+
+/// `MOVE key db`, the key keeps its TTL in the target database
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("MOVE")]
+struct Move {
+    key: String,
+    db: i64,
+}
+
+#[async_trait]
+impl CommandExecutor for Move {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let target = usize::try_from(self.db)
+            .ok()
+            .and_then(|index| ctx.dbs.get(index))
+            .ok_or(CommandError::DbIndexOutOfRange)?;
+        let source = ctx.db();
+        if Arc::ptr_eq(&source, &target) {
+            return Err(CommandError::SameObject);
+        }
+        if target.contains_key(&self.key) {
+            return Ok(Frame::Integer(0));
+        }
+        let Some((value, expire)) = source.remove(&self.key) else {
+            return Ok(Frame::Integer(0));
+        };
+        // the key may have been added to the target meanwhile, it goes back to the source then
+        if let Err(value) = target.insert_if_absent(self.key.clone(), value, expire) {
+            // unless the source got a new value as well, which is the latest write
+            let _ = source.insert_if_absent(self.key, value, expire);
+            return Ok(Frame::Integer(0));
+        }
+        log::debug!(
+            "ctx {} moved {} from db {} to db {}",
+            ctx.id,
+            &self.key,
+            source.id(),
+            target.id()
+        );
+        notify_keyspace_event(notify::GENERIC, "move_from", &self.key, source.id());
+        notify_keyspace_event(notify::GENERIC, "move_to", &self.key, target.id());
+        Ok(Frame::Integer(1))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, generic::r#move::Move},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_move() {
+        let ctx = Context::test_client(2);
+        let value = || RedisObject::new_string(b"v".to_vec());
+        ctx.db().set("a".to_string(), value(), None);
+        ctx.db().set("b".to_string(), value(), None);
+        ctx.dbs.get(1).unwrap().set("b".to_string(), value(), None);
+
+        let move_key = |key: &str, db| {
+            Move {
+                key: key.to_string(),
+                db,
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(move_key("a", 1).await.unwrap(), Frame::Integer(1));
+        assert!(!ctx.db().contains_key("a"));
+        assert!(ctx.dbs.get(1).unwrap().contains_key("a"));
+        // the key exists in the target database
+        assert_eq!(move_key("b", 1).await.unwrap(), Frame::Integer(0));
+        assert!(ctx.db().contains_key("b"));
+        assert_eq!(move_key("missing", 1).await.unwrap(), Frame::Integer(0));
+        assert!(move_key("b", 0).await.is_err());
+        assert!(move_key("b", 2).await.is_err());
+    }
+}
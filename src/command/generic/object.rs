@@ -0,0 +1,182 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, parser::Parser, registry::CommandResult},
+    config::get_server_config,
+    context::Context,
+    protocol::Frame,
+    register_redis_command,
+    storage::evict::{LRU_CLOCK_RESOLUTION, estimate_idle_time, lfu_decr_and_return},
+};
+
+const HELP: [&str; 15] = [
+    "OBJECT <subcommand> [<arg> [value] [opt] ...]. Subcommands are:",
+    "ENCODING <key>",
+    "    Return the kind of internal representation used in order to store the value",
+    "    associated with a <key>.",
+    "FREQ <key>",
+    "    Return the access frequency index of the <key>. The returned integer is",
+    "    proportional to the logarithm of the recent access frequency of the key.",
+    "IDLETIME <key>",
+    "    Return the idle time of the <key>, that is the approximated number of",
+    "    seconds elapsed since the last access to the key.",
+    "REFCOUNT <key>",
+    "    Return the number of references of the value associated with the specified",
+    "    <key>.",
+    "HELP",
+    "    Print this help.",
+];
+
+/// `OBJECT <subcommand> key`, inspecting a key doesn't count as an access
+#[derive(PartialEq, Eq, Debug)]
+enum Object {
+    Encoding(String),
+    /// The LFU counter, only tracked under the LFU policies
+    Freq(String),
+    /// Seconds since the last access, not tracked under the LFU policies
+    IdleTime(String),
+    RefCount(String),
+    Help,
+}
+
+impl TryFrom<Parser> for Object {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let subcommand = parser.next::<String>()?.to_ascii_uppercase();
+        let object = match subcommand.as_str() {
+            "ENCODING" => Object::Encoding(parser.next()?),
+            "FREQ" => Object::Freq(parser.next()?),
+            "IDLETIME" => Object::IdleTime(parser.next()?),
+            "REFCOUNT" => Object::RefCount(parser.next()?),
+            "HELP" => Object::Help,
+            _ => {
+                return Err(CommandError::UnknownSubcommand(
+                    subcommand,
+                    "OBJECT".to_string(),
+                ));
+            }
+        };
+        if parser.has_next() {
+            return Err(CommandError::UnknownSubcommand(
+                subcommand,
+                "OBJECT".to_string(),
+            ));
+        }
+        Ok(object)
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for Object {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let lfu = get_server_config().maxmemory_policy.is_lfu();
+        let db = ctx.db();
+        let reply = match self {
+            Object::Encoding(key) => db.peek_with(&key, |value| {
+                Frame::BulkString(Some(value.ptr.encoding().as_bytes().to_vec()))
+            }),
+            Object::Freq(key) => {
+                if !lfu {
+                    return Err(CommandError::FreqNotTracked);
+                }
+                db.peek_with(&key, |value| {
+                    Frame::Integer(lfu_decr_and_return(value.header.lru()) as i64)
+                })
+            }
+            Object::IdleTime(key) => {
+                if lfu {
+                    return Err(CommandError::IdleTimeNotTracked);
+                }
+                db.peek_with(&key, |value| {
+                    let idle = estimate_idle_time(value.header.lru()) / LRU_CLOCK_RESOLUTION;
+                    Frame::Integer(idle as i64)
+                })
+            }
+            Object::RefCount(key) => {
+                db.peek_with(
+                    &key,
+                    |value| Frame::Integer(value.header.ref_count() as i64),
+                )
+            }
+            Object::Help => {
+                return Ok(Frame::Array(Some(
+                    HELP.iter()
+                        .map(|line| Frame::SimpleString(line.to_string()))
+                        .collect(),
+                )));
+            }
+        };
+        Ok(reply.unwrap_or(Frame::Null))
+    }
+}
+
+async fn object(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: Object = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("OBJECT", object);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, generic::object::Object, parser::Parser},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+        storage::evict::lru_clock,
+    };
+
+    #[tokio::test]
+    async fn test_object() {
+        assert_eq!(
+            Object::try_from(Parser::from_args(&["OBJECT", "encoding", "k"])).unwrap(),
+            Object::Encoding("k".to_string())
+        );
+        assert!(Object::try_from(Parser::from_args(&["OBJECT", "FOO", "k"])).is_err());
+        assert!(Object::try_from(Parser::from_args(&["OBJECT", "FREQ"])).is_err());
+        assert!(
+            Object::try_from(Parser::from_args(&["OBJECT", "REFCOUNT", "k", "extra"])).is_err()
+        );
+
+        let ctx = Context::test_client(1);
+        let mut value = RedisObject::new_string(b"v".repeat(100));
+        value.header.set_lru(lru_clock() - 30);
+        ctx.db().set("k".to_string(), value, None);
+        let run = |args: &[&str]| {
+            Object::try_from(Parser::from_args(args))
+                .unwrap()
+                .execute(ctx.clone())
+        };
+
+        assert_eq!(
+            run(&["OBJECT", "ENCODING", "k"]).await.unwrap(),
+            Frame::BulkString(Some(b"raw".to_vec()))
+        );
+        assert_eq!(
+            run(&["OBJECT", "REFCOUNT", "k"]).await.unwrap(),
+            Frame::Integer(1)
+        );
+        // looking at the key doesn't reset its idle time
+        for _ in 0..2 {
+            let Frame::Integer(idle) = run(&["OBJECT", "IDLETIME", "k"]).await.unwrap() else {
+                panic!("IDLETIME replies an integer");
+            };
+            assert!((30..=31).contains(&idle));
+        }
+        assert!(run(&["OBJECT", "FREQ", "k"]).await.is_err());
+        assert_eq!(
+            run(&["OBJECT", "ENCODING", "missing"]).await.unwrap(),
+            Frame::Null
+        );
+
+        ctx.db().get("k");
+        assert_eq!(
+            run(&["OBJECT", "IDLETIME", "k"]).await.unwrap(),
+            Frame::Integer(0)
+        );
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, registry::CommandResult},
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+};
+
+/// `PERSIST key`, 1 when the expire time was removed
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("PERSIST")]
+struct Persist {
+    key: String,
+}
+
+#[async_trait]
+impl CommandExecutor for Persist {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let persisted = db.persist(&self.key);
+        if persisted {
+            notify_keyspace_event(notify::GENERIC, "persist", &self.key, db.id());
+        }
+        Ok(Frame::Integer(persisted as i64))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::{
+        command::{CommandExecutor, generic::persist::Persist},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_persist() {
+        let ctx = Context::test_client(1);
+        ctx.db().set(
+            "k".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            Some(Duration::from_secs(60)),
+        );
+        let persist = |key: &str| {
+            Persist {
+                key: key.to_string(),
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(persist("k").await.unwrap(), Frame::Integer(1));
+        assert!(ctx.db().expire_time("k").is_none());
+        assert!(ctx.db().contains_key("k"));
+        assert_eq!(persist("k").await.unwrap(), Frame::Integer(0));
+        assert_eq!(persist("missing").await.unwrap(), Frame::Integer(0));
+    }
+}
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, registry::CommandResult},
+    context::Context,
+    protocol::Frame,
+};
+
+/// `RANDOMKEY`, nil when the database is empty
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("RANDOMKEY")]
+struct RandomKey;
+
+#[async_trait]
+impl CommandExecutor for RandomKey {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        match ctx.db().random_key() {
+            Some(key) => Ok(Frame::BulkString(Some(key.into_bytes()))),
+            None => Ok(Frame::Null),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, generic::randomkey::RandomKey},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_randomkey() {
+        let ctx = Context::test_client(1);
+        assert_eq!(RandomKey.execute(ctx.clone()).await.unwrap(), Frame::Null);
+        ctx.db().set(
+            "a".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+        assert_eq!(
+            RandomKey.execute(ctx).await.unwrap(),
+            Frame::BulkString(Some(b"a".to_vec()))
+        );
+    }
+}
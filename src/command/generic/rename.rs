@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, registry::CommandResult},
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+};
+
+/// `RENAME key newkey`, an existing `newkey` is overwritten
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("RENAME")]
+struct Rename {
+    key: String,
+    new_key: String,
+}
+
+#[async_trait]
+impl CommandExecutor for Rename {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let new_key = self.new_key.clone();
+        db.rename(&self.key, self.new_key, false)
+            .ok_or(CommandError::NoSuchKey)?;
+        log::debug!("ctx {} renamed {}", ctx.id, &self.key);
+        if self.key != new_key {
+            notify_keyspace_event(notify::GENERIC, "rename_from", &self.key, db.id());
+            notify_keyspace_event(notify::GENERIC, "rename_to", &new_key, db.id());
+        }
+        Ok(Frame::SimpleString("OK".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, generic::rename::Rename},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_rename() {
+        let ctx = Context::test_client(1);
+        ctx.db().set(
+            "a".to_string(),
+            RedisObject::new_string(b"1".to_vec()),
+            None,
+        );
+        ctx.db().set(
+            "b".to_string(),
+            RedisObject::new_string(b"2".to_vec()),
+            None,
+        );
+        let rename = |key: &str, new_key: &str| {
+            Rename {
+                key: key.to_string(),
+                new_key: new_key.to_string(),
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(
+            rename("a", "b").await.unwrap(),
+            Frame::SimpleString("OK".to_string())
+        );
+        assert_eq!(ctx.db().len(), 1);
+        assert_eq!(
+            ctx.db().get("b").unwrap(),
+            RedisObject::new_string(b"1".to_vec())
+        );
+        assert_eq!(
+            rename("a", "c").await.unwrap_err().to_string(),
+            "no such key"
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, registry::CommandResult},
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+};
+
+/// `RENAMENX key newkey`, renames only when `newkey` doesn't exist
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("RENAMENX")]
+struct RenameNx {
+    key: String,
+    new_key: String,
+}
+
+#[async_trait]
+impl CommandExecutor for RenameNx {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let new_key = self.new_key.clone();
+        let renamed = db
+            .rename(&self.key, self.new_key, true)
+            .ok_or(CommandError::NoSuchKey)?;
+        if renamed && self.key != new_key {
+            notify_keyspace_event(notify::GENERIC, "rename_from", &self.key, db.id());
+            notify_keyspace_event(notify::GENERIC, "rename_to", &new_key, db.id());
+        }
+        Ok(Frame::Integer(renamed as i64))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, generic::renamenx::RenameNx},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_renamenx() {
+        let ctx = Context::test_client(1);
+        ctx.db().set(
+            "a".to_string(),
+            RedisObject::new_string(b"1".to_vec()),
+            None,
+        );
+        ctx.db().set(
+            "b".to_string(),
+            RedisObject::new_string(b"2".to_vec()),
+            None,
+        );
+        let renamenx = |key: &str, new_key: &str| {
+            RenameNx {
+                key: key.to_string(),
+                new_key: new_key.to_string(),
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(renamenx("a", "b").await.unwrap(), Frame::Integer(0));
+        assert_eq!(renamenx("a", "a").await.unwrap(), Frame::Integer(0));
+        assert_eq!(renamenx("a", "c").await.unwrap(), Frame::Integer(1));
+        assert!(ctx.db().contains_key("c"));
+        assert!(renamenx("a", "d").await.is_err());
+    }
+}
@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        option::{ScanArgs, scan_reply},
+        parser::Parser,
+        registry::CommandResult,
+    },
+    context::Context,
+    register_redis_command,
+};
+
+/// `SCAN cursor [MATCH pattern] [COUNT count] [TYPE type]`
+#[derive(PartialEq, Eq, Debug)]
+struct Scan {
+    args: ScanArgs,
+}
+
+impl TryFrom<Parser> for Scan {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        Ok(Scan {
+            args: ScanArgs::parse(&mut parser, Some("TYPE"))?,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for Scan {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let args = self.args;
+        let (cursor, keys) = ctx.db().scan(
+            args.cursor,
+            args.count,
+            args.pattern.as_deref(),
+            args.obj_type,
+        );
+        let keys = keys.into_iter().map(String::into_bytes).collect();
+        Ok(scan_reply(cursor, keys))
+    }
+}
+
+async fn scan(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: Scan = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("SCAN", scan);
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::{
+        command::{CommandExecutor, generic::scan::Scan, parser::Parser},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[test]
+    fn test_try_from_parser_to_scan_err() {
+        assert!(Scan::try_from(Parser::from_args(&["SCAN"])).is_err());
+        assert!(Scan::try_from(Parser::from_args(&["SCAN", "-1"])).is_err());
+        assert!(Scan::try_from(Parser::from_args(&["SCAN", "0", "COUNT", "0"])).is_err());
+        assert!(Scan::try_from(Parser::from_args(&["SCAN", "0", "TYPE", "stream"])).is_err());
+        assert!(Scan::try_from(Parser::from_args(&["SCAN", "0", "NOVALUES"])).is_err());
+        assert!(Scan::try_from(Parser::from_args(&["SCAN", "0", "MATCH"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scan() {
+        let ctx = Context::test_client(1);
+        for i in 0..100 {
+            ctx.db().set(
+                format!("key:{}", i),
+                RedisObject::new_string(b"v".to_vec()),
+                None,
+            );
+        }
+        ctx.db().set(
+            "other".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+
+        let mut seen = HashSet::new();
+        let mut cursor = "0".to_string();
+        loop {
+            let args = [
+                "SCAN", &cursor, "MATCH", "key:*", "COUNT", "15", "TYPE", "string",
+            ];
+            let cmd = Scan::try_from(Parser::from_args(&args)).unwrap();
+            let Frame::Array(Some(reply)) = cmd.execute(ctx.clone()).await.unwrap() else {
+                panic!("SCAN replies an array");
+            };
+            let [Frame::BulkString(Some(next)), Frame::Array(Some(keys))] = &reply[..] else {
+                panic!("SCAN replies a cursor and keys");
+            };
+            seen.extend(keys.iter().map(|key| match key {
+                Frame::BulkString(Some(key)) => key.clone(),
+                _ => panic!("SCAN replies keys as bulk strings"),
+            }));
+            cursor = String::from_utf8(next.clone()).unwrap();
+            if cursor == "0" {
+                break;
+            }
+        }
+        assert_eq!(seen.len(), 100);
+        assert!(!seen.contains(b"other".as_slice()));
+
+        let cmd = Scan::try_from(Parser::from_args(&["SCAN", "0", "TYPE", "hash"])).unwrap();
+        let Frame::Array(Some(reply)) = cmd.execute(ctx).await.unwrap() else {
+            panic!("SCAN replies an array");
+        };
+        assert_eq!(reply[1], Frame::Array(Some(vec![])));
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, parser::Parser, registry::CommandResult},
+    context::Context,
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `TOUCH key [key ...]`, returns the count of existing keys
+#[derive(PartialEq, Eq, Debug)]
+struct Touch {
+    keys: Vec<String>,
+}
+
+impl TryFrom<Parser> for Touch {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        Ok(Touch {
+            keys: parser.remaining()?,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for Touch {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let count = self.keys.iter().filter(|key| db.touch(key)).count();
+        Ok(Frame::Integer(count as i64))
+    }
+}
+
+async fn touch(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: Touch = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("TOUCH", touch);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, generic::touch::Touch},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_touch() {
+        let ctx = Context::test_client(1);
+        ctx.db().set(
+            "a".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+        let keys = ["a", "b", "a"].iter().map(|key| key.to_string()).collect();
+        assert_eq!(
+            Touch { keys }.execute(ctx).await.unwrap(),
+            Frame::Integer(2)
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor, error::CommandError, option::TtlKind, parser::Parser,
+        registry::CommandResult,
+    },
+    context::Context,
+    protocol::Frame,
+    register_redis_command,
+    util::unix_millis,
+};
+
+/// `TTL key`, `PTTL`, `EXPIRETIME` and `PEXPIRETIME`.
+///
+/// -2 when the key doesn't exist, -1 when it has no expire time.
+#[derive(PartialEq, Eq, Debug)]
+struct Ttl {
+    kind: TtlKind,
+    key: String,
+}
+
+impl Ttl {
+    fn parse(kind: TtlKind, mut parser: Parser) -> Result<Self, CommandError> {
+        Ok(Ttl {
+            kind,
+            key: parser.next()?,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for Ttl {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        if !db.contains_key(&self.key) {
+            return Ok(Frame::Integer(-2));
+        }
+        let Some(at) = db.expire_time(&self.key).map(unix_millis) else {
+            return Ok(Frame::Integer(-1));
+        };
+        Ok(Frame::Integer(self.kind.reply(at, SystemTime::now())))
+    }
+}
+
+async fn ttl(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    Ttl::parse(TtlKind::Seconds, parser)?.execute(ctx).await
+}
+
+async fn pttl(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    Ttl::parse(TtlKind::Millis, parser)?.execute(ctx).await
+}
+
+async fn expiretime(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    Ttl::parse(TtlKind::UnixSeconds, parser)?.execute(ctx).await
+}
+
+async fn pexpiretime(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    Ttl::parse(TtlKind::UnixMillis, parser)?.execute(ctx).await
+}
+
+register_redis_command!("TTL", ttl);
+register_redis_command!("PTTL", pttl);
+register_redis_command!("EXPIRETIME", expiretime);
+register_redis_command!("PEXPIRETIME", pexpiretime);
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use crate::{
+        command::{CommandExecutor, generic::ttl::Ttl, option::TtlKind},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_ttl() {
+        let ctx = Context::test_client(1);
+        let db = ctx.db();
+        db.set(
+            "a".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+        db.set(
+            "b".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            Some(Duration::from_secs(100)),
+        );
+        let at = UNIX_EPOCH + Duration::from_millis(4_102_444_800_123);
+        db.set(
+            "c".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+        db.set_expire("c", at);
+        for (key, millis) in [("d", 4_102_444_800_999), ("e", i64::MAX as u64)] {
+            db.set(
+                key.to_string(),
+                RedisObject::new_string(b"v".to_vec()),
+                None,
+            );
+            db.set_expire(key, UNIX_EPOCH + Duration::from_millis(millis));
+        }
+        let ttl = |kind, key: &str| {
+            Ttl {
+                kind,
+                key: key.to_string(),
+            }
+            .execute(ctx.clone())
+        };
+
+        assert_eq!(
+            ttl(TtlKind::Seconds, "missing").await.unwrap(),
+            Frame::Integer(-2)
+        );
+        assert_eq!(
+            ttl(TtlKind::UnixMillis, "missing").await.unwrap(),
+            Frame::Integer(-2)
+        );
+        assert_eq!(
+            ttl(TtlKind::Seconds, "a").await.unwrap(),
+            Frame::Integer(-1)
+        );
+        assert_eq!(
+            ttl(TtlKind::UnixSeconds, "a").await.unwrap(),
+            Frame::Integer(-1)
+        );
+        assert_eq!(
+            ttl(TtlKind::Seconds, "b").await.unwrap(),
+            Frame::Integer(100)
+        );
+        let Frame::Integer(millis) = ttl(TtlKind::Millis, "b").await.unwrap() else {
+            panic!("PTTL replies an integer");
+        };
+        assert!(millis > 99_000 && millis <= 100_000);
+        assert_eq!(
+            ttl(TtlKind::UnixMillis, "c").await.unwrap(),
+            Frame::Integer(4_102_444_800_123)
+        );
+        assert_eq!(
+            ttl(TtlKind::UnixSeconds, "c").await.unwrap(),
+            Frame::Integer(4_102_444_800)
+        );
+        assert!(SystemTime::now() < at);
+
+        // a unix time is truncated, not rounded up
+        assert_eq!(
+            ttl(TtlKind::UnixSeconds, "d").await.unwrap(),
+            Frame::Integer(4_102_444_800)
+        );
+        assert_eq!(
+            ttl(TtlKind::UnixSeconds, "e").await.unwrap(),
+            Frame::Integer(i64::MAX / 1000)
+        );
+        let Frame::Integer(seconds) = ttl(TtlKind::Seconds, "e").await.unwrap() else {
+            panic!("TTL replies an integer");
+        };
+        assert!(seconds > 0);
+    }
+}
@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, registry::CommandResult},
+    context::Context,
+    protocol::Frame,
+};
+
+/// `TYPE key`, `none` when the key doesn't exist
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("TYPE")]
+struct Type {
+    key: String,
+}
+
+#[async_trait]
+impl CommandExecutor for Type {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let name = match ctx.db().key_type(&self.key) {
+            Some(obj_type) => obj_type.to_string(),
+            None => "none".to_string(),
+        };
+        Ok(Frame::SimpleString(name))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, generic::r#type::Type},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_type() {
+        let ctx = Context::test_client(1);
+        ctx.db().set(
+            "a".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+        let key_type = |key: &str| {
+            Type {
+                key: key.to_string(),
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(
+            key_type("a").await.unwrap(),
+            Frame::SimpleString("string".to_string())
+        );
+        assert_eq!(
+            key_type("b").await.unwrap(),
+            Frame::SimpleString("none".to_string())
+        );
+    }
+}
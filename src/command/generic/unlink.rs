@@ -0,0 +1,76 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, parser::Parser, registry::CommandResult},
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `UNLINK key [key ...]`, like `DEL` but large values are always dropped on the lazy free thread
+#[derive(PartialEq, Eq, Debug)]
+struct Unlink {
+    keys: Vec<String>,
+}
+
+impl TryFrom<Parser> for Unlink {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        Ok(Unlink {
+            keys: parser.remaining()?,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for Unlink {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let deleted = self
+            .keys
+            .iter()
+            .filter(|key| db.delete(key, true))
+            .inspect(|key| notify_keyspace_event(notify::GENERIC, "del", key, db.id()))
+            .count();
+        log::debug!("ctx {} unlinked {} key(s)", ctx.id, deleted);
+        Ok(Frame::Integer(deleted as i64))
+    }
+}
+
+async fn unlink(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: Unlink = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("UNLINK", unlink);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, generic::unlink::Unlink, parser::Parser},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_unlink() {
+        assert!(Unlink::try_from(Parser::from_args(&["UNLINK"])).is_err());
+        let cmd = Unlink::try_from(Parser::from_args(&["UNLINK", "a", "b", "a", "c"])).unwrap();
+
+        let ctx = Context::test_client(1);
+        for key in ["a", "b", "d"] {
+            ctx.db().set(
+                key.to_string(),
+                RedisObject::new_string(b"v".to_vec()),
+                None,
+            );
+        }
+        assert_eq!(cmd.execute(ctx.clone()).await.unwrap(), Frame::Integer(2));
+        assert_eq!(ctx.db().len(), 1);
+    }
+}
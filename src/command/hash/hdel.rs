@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor, error::CommandError, hash::as_hash_mut, parser::Parser,
+        registry::CommandResult,
+    },
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `HDEL key field [field ...]`, replies the count of removed fields. The key is deleted
+/// with its last field.
+#[derive(PartialEq, Eq, Debug)]
+struct HDel {
+    key: String,
+    fields: Vec<Vec<u8>>,
+}
+
+impl TryFrom<Parser> for HDel {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        Ok(HDel {
+            key: parser.next()?,
+            fields: parser.remaining()?,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for HDel {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let id = db.id();
+        let removed = db
+            .modify(&self.key, |value| {
+                let hash = as_hash_mut(value)?;
+                let removed = self
+                    .fields
+                    .iter()
+                    .filter(|field| hash.hash_remove(field))
+                    .count();
+                if removed > 0 {
+                    notify_keyspace_event(notify::HASH, "hdel", &self.key, id);
+                }
+                Ok::<_, CommandError>(removed)
+            })
+            .transpose()?
+            .unwrap_or(0);
+        Ok(Frame::Integer(removed as i64))
+    }
+}
+
+async fn hdel(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: HDel = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("HDEL", hdel);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, hash::hdel::HDel},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_hdel() {
+        let ctx = Context::test_client(1);
+        let mut hash = RedisObject::new_hash();
+        hash.ptr.hash_set(b"a", b"1");
+        hash.ptr.hash_set(b"b", b"2");
+        ctx.db().insert("h".to_string(), hash, None);
+        let hdel = |fields: &[&str]| {
+            HDel {
+                key: "h".to_string(),
+                fields: fields.iter().map(|f| f.as_bytes().to_vec()).collect(),
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(hdel(&["a", "a", "c"]).await.unwrap(), Frame::Integer(1));
+        assert!(ctx.db().contains_key("h"));
+        assert_eq!(hdel(&["b"]).await.unwrap(), Frame::Integer(1));
+        assert!(!ctx.db().contains_key("h"));
+        assert_eq!(hdel(&["b"]).await.unwrap(), Frame::Integer(0));
+    }
+}
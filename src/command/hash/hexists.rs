@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, hash::as_hash, registry::CommandResult},
+    context::Context,
+    protocol::Frame,
+};
+
+/// `HEXISTS key field`
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("HEXISTS")]
+struct HExists {
+    key: String,
+    field: Vec<u8>,
+}
+
+#[async_trait]
+impl CommandExecutor for HExists {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let exists = ctx
+            .db()
+            .get_with(&self.key, |value| {
+                as_hash(value).map(|hash| hash.hash_get(&self.field).is_some())
+            })
+            .transpose()?
+            .unwrap_or(false);
+        Ok(Frame::Integer(exists as i64))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, hash::hexists::HExists},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_hexists() {
+        let ctx = Context::test_client(1);
+        let mut hash = RedisObject::new_hash();
+        hash.ptr.hash_set(b"a", b"1");
+        ctx.db().insert("h".to_string(), hash, None);
+        let hexists = |key: &str, field: &str| {
+            HExists {
+                key: key.to_string(),
+                field: field.as_bytes().to_vec(),
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(hexists("h", "a").await.unwrap(), Frame::Integer(1));
+        assert_eq!(hexists("h", "b").await.unwrap(), Frame::Integer(0));
+        assert_eq!(hexists("missing", "a").await.unwrap(), Frame::Integer(0));
+    }
+}
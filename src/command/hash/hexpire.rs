@@ -0,0 +1,242 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        hash::{MAX_FIELD_EXPIRE, as_hash_mut, expire_field, parse_fields},
+        option::{ExpireCondition, ExpireKind},
+        parser::Parser,
+        registry::CommandResult,
+    },
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+    register_redis_command,
+    util::unix_millis,
+};
+
+/// `HEXPIRE key seconds [NX | XX | GT | LT] FIELDS numfields field [field ...]` and
+/// `HPEXPIRE`, `HEXPIREAT`, `HPEXPIREAT`.
+///
+/// Replies for every field -2 when it doesn't exist, 0 when the condition isn't met, 1 when
+/// the expire time is set and 2 when the time passed already and the field is deleted.
+#[derive(PartialEq, Eq, Debug)]
+struct HExpire {
+    kind: ExpireKind,
+    key: String,
+    time: i64,
+    condition: ExpireCondition,
+    fields: Vec<Vec<u8>>,
+}
+
+impl HExpire {
+    fn parse(kind: ExpireKind, mut parser: Parser) -> Result<Self, CommandError> {
+        let key = parser.next()?;
+        let time = parser.next()?;
+        let mut condition = ExpireCondition::default();
+        let fields = parse_fields(&mut parser, false, |option, _| {
+            if !condition.parse_option(option) {
+                return Err(CommandError::MissingFields);
+            }
+            Ok(())
+        })?;
+        condition.check()?;
+        Ok(HExpire {
+            kind,
+            key,
+            time,
+            condition,
+            fields,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for HExpire {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let now = SystemTime::now();
+        let at = self
+            .kind
+            .unix_millis(self.time, now)
+            .filter(|at| self.time >= 0 && *at <= MAX_FIELD_EXPIRE)
+            .ok_or_else(|| CommandError::InvalidExpireTime(format!("h{}", self.kind.command())))?;
+        let now = unix_millis(now);
+        let db = ctx.db();
+        let id = db.id();
+        let replies = db
+            .modify(&self.key, |value| {
+                let hash = as_hash_mut(value)?;
+                let (mut updated, mut deleted) = (false, false);
+                let replies = self
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        if hash.hash_get(field).is_none() {
+                            return Frame::Integer(-2);
+                        }
+                        if !self.condition.allows(hash.hash_expire_time(field), at) {
+                            return Frame::Integer(0);
+                        }
+                        if expire_field(hash, field, at, now) {
+                            deleted = true;
+                            Frame::Integer(2)
+                        } else {
+                            updated = true;
+                            Frame::Integer(1)
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                if updated {
+                    notify_keyspace_event(notify::HASH, "hexpire", &self.key, id);
+                }
+                if deleted {
+                    notify_keyspace_event(notify::HASH, "hdel", &self.key, id);
+                }
+                Ok::<_, CommandError>(replies)
+            })
+            .transpose()?
+            .unwrap_or_else(|| vec![Frame::Integer(-2); self.fields.len()]);
+        Ok(Frame::Array(Some(replies)))
+    }
+}
+
+async fn hexpire(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    HExpire::parse(ExpireKind::Seconds, parser)?
+        .execute(ctx)
+        .await
+}
+
+async fn hpexpire(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    HExpire::parse(ExpireKind::Millis, parser)?
+        .execute(ctx)
+        .await
+}
+
+async fn hexpireat(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    HExpire::parse(ExpireKind::UnixSeconds, parser)?
+        .execute(ctx)
+        .await
+}
+
+async fn hpexpireat(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    HExpire::parse(ExpireKind::UnixMillis, parser)?
+        .execute(ctx)
+        .await
+}
+
+register_redis_command!("HEXPIRE", hexpire);
+register_redis_command!("HPEXPIRE", hpexpire);
+register_redis_command!("HEXPIREAT", hexpireat);
+register_redis_command!("HPEXPIREAT", hpexpireat);
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::{
+        command::{
+            CommandExecutor,
+            error::CommandError,
+            hash::hexpire::HExpire,
+            option::{ExpireCondition, ExpireKind},
+            parser::Parser,
+        },
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[test]
+    fn test_parse_hexpire() {
+        let parse = |args: &[&str]| HExpire::parse(ExpireKind::Seconds, Parser::from_args(args));
+        assert_eq!(
+            parse(&["HEXPIRE", "h", "10", "NX", "FIELDS", "2", "a", "b"]).unwrap(),
+            HExpire {
+                kind: ExpireKind::Seconds,
+                key: "h".to_string(),
+                time: 10,
+                condition: ExpireCondition {
+                    nx: true,
+                    ..Default::default()
+                },
+                fields: vec![b"a".to_vec(), b"b".to_vec()],
+            }
+        );
+        assert!(matches!(
+            parse(&["HEXPIRE", "h", "10", "a"]),
+            Err(CommandError::MissingFields)
+        ));
+        assert!(matches!(
+            parse(&["HEXPIRE", "h", "10", "FIELDS", "0", "a"]),
+            Err(CommandError::NotGreaterThanZero(_))
+        ));
+        assert!(matches!(
+            parse(&["HEXPIRE", "h", "10", "FIELDS", "2", "a"]),
+            Err(CommandError::NumFieldsMismatch)
+        ));
+        assert!(parse(&["HEXPIRE", "h", "10", "NX", "XX", "FIELDS", "1", "a"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_hexpire() {
+        let ctx = Context::test_client(1);
+        let mut hash = RedisObject::new_hash();
+        hash.ptr.hash_set(b"a", b"1");
+        hash.ptr.hash_set(b"b", b"2");
+        ctx.db().insert("h".to_string(), hash, None);
+        let hexpire = |key: &str, time, condition, fields: &[&str]| {
+            HExpire {
+                kind: ExpireKind::Millis,
+                key: key.to_string(),
+                time,
+                condition,
+                fields: fields
+                    .iter()
+                    .map(|field| field.as_bytes().to_vec())
+                    .collect(),
+            }
+            .execute(ctx.clone())
+        };
+        let replies = |replies: &[i64]| {
+            Frame::Array(Some(replies.iter().map(|&n| Frame::Integer(n)).collect()))
+        };
+        let gt = ExpireCondition {
+            gt: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            hexpire("h", 60_000, gt, &["a"]).await.unwrap(),
+            replies(&[0])
+        );
+        assert_eq!(
+            hexpire("h", 20, Default::default(), &["a", "c"])
+                .await
+                .unwrap(),
+            replies(&[1, -2])
+        );
+        assert_eq!(
+            hexpire("missing", 20, Default::default(), &["a", "b"])
+                .await
+                .unwrap(),
+            replies(&[-2, -2])
+        );
+        assert!(hexpire("h", -1, Default::default(), &["a"]).await.is_err());
+
+        // the field is reclaimed once its time passed
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(
+            ctx.db()
+                .get_with("h", |value| value.ptr.hash_get(b"a").is_none())
+                .unwrap()
+        );
+        assert_eq!(
+            hexpire("h", 0, Default::default(), &["b"]).await.unwrap(),
+            replies(&[2])
+        );
+        assert!(!ctx.db().contains_key("h"));
+    }
+}
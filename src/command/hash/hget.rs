@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, hash::as_hash, registry::CommandResult},
+    context::Context,
+    protocol::Frame,
+};
+
+/// `HGET key field`
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("HGET")]
+struct HGet {
+    key: String,
+    field: Vec<u8>,
+}
+
+#[async_trait]
+impl CommandExecutor for HGet {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let value = ctx
+            .db()
+            .get_with(&self.key, |value| {
+                as_hash(value).map(|hash| hash.hash_get(&self.field).map(<[u8]>::to_vec))
+            })
+            .transpose()?
+            .flatten();
+        Ok(value.map_or(Frame::Null, |value| Frame::BulkString(Some(value))))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, hash::hget::HGet},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_hget() {
+        let ctx = Context::test_client(1);
+        let mut hash = RedisObject::new_hash();
+        hash.ptr.hash_set(b"\x00f", b"\xffv");
+        ctx.db().insert("h".to_string(), hash, None);
+        let hget = |key: &str, field: &[u8]| {
+            HGet {
+                key: key.to_string(),
+                field: field.to_vec(),
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(
+            hget("h", b"\x00f").await.unwrap(),
+            Frame::BulkString(Some(b"\xffv".to_vec()))
+        );
+        assert_eq!(hget("h", b"g").await.unwrap(), Frame::Null);
+        assert_eq!(hget("missing", b"f").await.unwrap(), Frame::Null);
+    }
+}
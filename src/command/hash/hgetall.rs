@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor, error::CommandError, hash::as_hash, parser::Parser,
+        registry::CommandResult,
+    },
+    context::Context,
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// What `HGETALL`, `HKEYS` and `HVALS` reply of every field
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum Part {
+    All,
+    Fields,
+    Values,
+}
+
+/// `HGETALL key`, `HKEYS key` and `HVALS key`
+#[derive(PartialEq, Eq, Debug)]
+struct HGetAll {
+    key: String,
+    part: Part,
+}
+
+impl HGetAll {
+    fn parse(part: Part, mut parser: Parser) -> Result<Self, CommandError> {
+        let key = parser.next()?;
+        if parser.has_next() {
+            return Err(CommandError::SyntaxError);
+        }
+        Ok(HGetAll { key, part })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for HGetAll {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let bulk = |data: &[u8]| Frame::BulkString(Some(data.to_vec()));
+        let reply = ctx
+            .db()
+            .get_with(&self.key, |value| {
+                let hash = as_hash(value)?.hash_iter();
+                Ok::<_, CommandError>(match self.part {
+                    Part::All => Frame::Map(
+                        hash.map(|(field, value)| (bulk(field), bulk(value)))
+                            .collect(),
+                    ),
+                    Part::Fields => {
+                        Frame::Array(Some(hash.map(|(field, _)| bulk(field)).collect()))
+                    }
+                    Part::Values => {
+                        Frame::Array(Some(hash.map(|(_, value)| bulk(value)).collect()))
+                    }
+                })
+            })
+            .transpose()?;
+        Ok(reply.unwrap_or(match self.part {
+            Part::All => Frame::Map(Vec::new()),
+            _ => Frame::Array(Some(Vec::new())),
+        }))
+    }
+}
+
+async fn hgetall(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    HGetAll::parse(Part::All, parser)?.execute(ctx).await
+}
+
+async fn hkeys(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    HGetAll::parse(Part::Fields, parser)?.execute(ctx).await
+}
+
+async fn hvals(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    HGetAll::parse(Part::Values, parser)?.execute(ctx).await
+}
+
+register_redis_command!("HGETALL", hgetall);
+register_redis_command!("HKEYS", hkeys);
+register_redis_command!("HVALS", hvals);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{
+            CommandExecutor,
+            hash::hgetall::{HGetAll, Part},
+        },
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_hgetall() {
+        let ctx = Context::test_client(1);
+        let mut hash = RedisObject::new_hash();
+        hash.ptr.hash_set(b"a", b"1");
+        hash.ptr.hash_set(b"b", b"2");
+        ctx.db().insert("h".to_string(), hash, None);
+        let hgetall = |key: &str, part| {
+            HGetAll {
+                key: key.to_string(),
+                part,
+            }
+            .execute(ctx.clone())
+        };
+        let bulk = |s: &str| Frame::BulkString(Some(s.as_bytes().to_vec()));
+        assert_eq!(
+            hgetall("h", Part::All).await.unwrap(),
+            Frame::Map(vec![(bulk("a"), bulk("1")), (bulk("b"), bulk("2"))])
+        );
+        assert_eq!(
+            hgetall("h", Part::Fields).await.unwrap(),
+            Frame::Array(Some(vec![bulk("a"), bulk("b")]))
+        );
+        assert_eq!(
+            hgetall("h", Part::Values).await.unwrap(),
+            Frame::Array(Some(vec![bulk("1"), bulk("2")]))
+        );
+        assert_eq!(
+            hgetall("missing", Part::All).await.unwrap(),
+            Frame::Map(vec![])
+        );
+    }
+}
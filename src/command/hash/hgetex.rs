@@ -0,0 +1,187 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        hash::{FieldExpiration, as_hash_mut, expire_field, parse_fields},
+        parser::Parser,
+        registry::CommandResult,
+    },
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+    register_redis_command,
+    util::unix_millis,
+};
+
+/// `HGETEX key [EX seconds | PX milliseconds | EXAT unix-time-seconds |
+/// PXAT unix-time-milliseconds | PERSIST] FIELDS numfields field [field ...]`.
+///
+/// Replies the values like `HMGET`, the fields which exist get the expire time.
+#[derive(PartialEq, Eq, Debug)]
+struct HGetEx {
+    key: String,
+    expiration: FieldExpiration,
+    fields: Vec<Vec<u8>>,
+}
+
+impl TryFrom<Parser> for HGetEx {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let key = parser.next()?;
+        let mut expiration = None;
+        let fields = parse_fields(&mut parser, false, |option, parser| {
+            let parsed = if option.eq_ignore_ascii_case("PERSIST") {
+                FieldExpiration::Persist
+            } else {
+                FieldExpiration::parse(option, parser)?.ok_or(CommandError::SyntaxError)?
+            };
+            match expiration.replace(parsed) {
+                Some(_) => Err(CommandError::SyntaxError),
+                None => Ok(()),
+            }
+        })?;
+        Ok(HGetEx {
+            key,
+            expiration: expiration.unwrap_or(FieldExpiration::Keep),
+            fields,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for HGetEx {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let now = SystemTime::now();
+        let at = self.expiration.unix_millis("hgetex", now)?;
+        let now = unix_millis(now);
+        let db = ctx.db();
+        let id = db.id();
+        let values = db
+            .modify(&self.key, |value| {
+                let hash = as_hash_mut(value)?;
+                let (mut updated, mut deleted) = (false, false);
+                let values = self
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        let value = hash.hash_get(field).map(<[u8]>::to_vec);
+                        if value.is_some() {
+                            match at {
+                                Some(at) if expire_field(hash, field, at, now) => deleted = true,
+                                Some(_) => updated = true,
+                                None if self.expiration == FieldExpiration::Persist => {
+                                    updated |= hash.hash_persist(field)
+                                }
+                                None => {}
+                            }
+                        }
+                        Frame::BulkString(value)
+                    })
+                    .collect::<Vec<_>>();
+                if updated {
+                    let event = match self.expiration {
+                        FieldExpiration::Persist => "hpersist",
+                        _ => "hexpire",
+                    };
+                    notify_keyspace_event(notify::HASH, event, &self.key, id);
+                }
+                if deleted {
+                    notify_keyspace_event(notify::HASH, "hdel", &self.key, id);
+                }
+                Ok::<_, CommandError>(values)
+            })
+            .transpose()?
+            .unwrap_or_else(|| vec![Frame::BulkString(None); self.fields.len()]);
+        Ok(Frame::Array(Some(values)))
+    }
+}
+
+async fn hgetex(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: HGetEx = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("HGETEX", hgetex);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{
+            CommandExecutor,
+            error::CommandError,
+            hash::{FieldExpiration, hgetex::HGetEx},
+            option::Expiration,
+            parser::Parser,
+        },
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_hgetex() {
+        let parse = |args: &[&str]| HGetEx::try_from(Parser::from_args(args));
+        assert_eq!(
+            parse(&["HGETEX", "h", "px", "100", "FIELDS", "1", "a"]).unwrap(),
+            HGetEx {
+                key: "h".to_string(),
+                expiration: FieldExpiration::Expire(Expiration::PX(100)),
+                fields: vec![b"a".to_vec()],
+            }
+        );
+        assert!(matches!(
+            parse(&["HGETEX", "h", "EX", "1", "PERSIST", "FIELDS", "1", "a"]),
+            Err(CommandError::SyntaxError)
+        ));
+
+        let ctx = Context::test_client(1);
+        let mut hash = RedisObject::new_hash();
+        hash.ptr.hash_set(b"a", b"1");
+        hash.ptr.hash_set(b"b", b"2");
+        ctx.db().insert("h".to_string(), hash, None);
+        let hgetex = |args: &[&str]| parse(args).unwrap().execute(ctx.clone());
+        let expire_time = |field: &[u8]| {
+            ctx.db()
+                .peek_with("h", |value| value.ptr.hash_expire_time(field))
+                .flatten()
+        };
+
+        assert_eq!(
+            hgetex(&["HGETEX", "h", "EX", "100", "FIELDS", "2", "a", "c"])
+                .await
+                .unwrap(),
+            Frame::Array(Some(vec![
+                Frame::BulkString(Some(b"1".to_vec())),
+                Frame::BulkString(None)
+            ]))
+        );
+        assert!(expire_time(b"a").is_some());
+        assert!(expire_time(b"b").is_none());
+        hgetex(&["HGETEX", "h", "PERSIST", "FIELDS", "1", "a"])
+            .await
+            .unwrap();
+        assert!(expire_time(b"a").is_none());
+        assert!(
+            hgetex(&["HGETEX", "h", "EX", "0", "FIELDS", "1", "a"])
+                .await
+                .is_err()
+        );
+
+        // a time which passed deletes the fields after reading them
+        assert_eq!(
+            hgetex(&["HGETEX", "h", "PXAT", "1", "FIELDS", "2", "a", "b"])
+                .await
+                .unwrap(),
+            Frame::Array(Some(vec![
+                Frame::BulkString(Some(b"1".to_vec())),
+                Frame::BulkString(Some(b"2".to_vec()))
+            ]))
+        );
+        assert!(!ctx.db().contains_key("h"));
+    }
+}
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, hash::as_hash_mut, registry::CommandResult},
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    object::redis_object::RedisObject,
+    protocol::Frame,
+};
+
+/// `HINCRBY key field increment`, a missing field counts as 0
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("HINCRBY")]
+struct HIncrBy {
+    key: String,
+    field: Vec<u8>,
+    increment: i64,
+}
+
+#[async_trait]
+impl CommandExecutor for HIncrBy {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let id = db.id();
+        let value = db.modify_or_insert(&self.key, RedisObject::new_hash, |value| {
+            let hash = as_hash_mut(value)?;
+            let current = match hash.hash_get(&self.field) {
+                Some(current) => std::str::from_utf8(current)
+                    .ok()
+                    .and_then(|current| current.parse::<i64>().ok())
+                    .ok_or(CommandError::HashValueNotInteger)?,
+                None => 0,
+            };
+            let value = current
+                .checked_add(self.increment)
+                .ok_or(CommandError::IncrementOverflow)?;
+            hash.hash_set(&self.field, value.to_string().as_bytes());
+            notify_keyspace_event(notify::HASH, "hincrby", &self.key, id);
+            Ok::<_, CommandError>(value)
+        })?;
+        Ok(Frame::Integer(value))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, error::CommandError, hash::hincrby::HIncrBy},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_hincrby() {
+        let ctx = Context::test_client(1);
+        let hincrby = |field: &str, increment| {
+            HIncrBy {
+                key: "h".to_string(),
+                field: field.as_bytes().to_vec(),
+                increment,
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(hincrby("n", 5).await.unwrap(), Frame::Integer(5));
+        assert_eq!(hincrby("n", -7).await.unwrap(), Frame::Integer(-2));
+        assert!(matches!(
+            hincrby("n", i64::MIN).await,
+            Err(CommandError::IncrementOverflow)
+        ));
+
+        let mut hash = RedisObject::new_hash();
+        hash.ptr.hash_set(b"s", b"abc");
+        ctx.db().insert("h".to_string(), hash, None);
+        assert!(matches!(
+            hincrby("s", 1).await,
+            Err(CommandError::HashValueNotInteger)
+        ));
+    }
+}
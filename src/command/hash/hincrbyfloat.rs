@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, hash::as_hash_mut, registry::CommandResult},
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    object::redis_object::RedisObject,
+    protocol::Frame,
+};
+
+/// `HINCRBYFLOAT key field increment`, replies the new value as a bulk string
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("HINCRBYFLOAT")]
+struct HIncrByFloat {
+    key: String,
+    field: Vec<u8>,
+    increment: String,
+}
+
+fn parse_float(data: &[u8]) -> Option<f64> {
+    std::str::from_utf8(data)
+        .ok()?
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+}
+
+#[async_trait]
+impl CommandExecutor for HIncrByFloat {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let increment = parse_float(self.increment.as_bytes()).ok_or(CommandError::NotFloat)?;
+        let db = ctx.db();
+        let id = db.id();
+        let value = db.modify_or_insert(&self.key, RedisObject::new_hash, |value| {
+            let hash = as_hash_mut(value)?;
+            let current = match hash.hash_get(&self.field) {
+                Some(current) => parse_float(current).ok_or(CommandError::HashValueNotFloat)?,
+                None => 0.0,
+            };
+            let value = current + increment;
+            if !value.is_finite() {
+                return Err(CommandError::NanOrInfinity);
+            }
+            let value = value.to_string();
+            hash.hash_set(&self.field, value.as_bytes());
+            notify_keyspace_event(notify::HASH, "hincrbyfloat", &self.key, id);
+            Ok(value)
+        })?;
+        Ok(Frame::BulkString(Some(value.into_bytes())))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, error::CommandError, hash::hincrbyfloat::HIncrByFloat},
+        context::Context,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_hincrbyfloat() {
+        let ctx = Context::test_client(1);
+        let hincrbyfloat = |increment: &str| {
+            HIncrByFloat {
+                key: "h".to_string(),
+                field: b"f".to_vec(),
+                increment: increment.to_string(),
+            }
+            .execute(ctx.clone())
+        };
+        let bulk = |s: &str| Frame::BulkString(Some(s.as_bytes().to_vec()));
+        assert_eq!(hincrbyfloat("10.5").await.unwrap(), bulk("10.5"));
+        assert_eq!(hincrbyfloat("-0.5").await.unwrap(), bulk("10"));
+        assert!(matches!(
+            hincrbyfloat("abc").await,
+            Err(CommandError::NotFloat)
+        ));
+        assert!(matches!(
+            hincrbyfloat("inf").await,
+            Err(CommandError::NotFloat)
+        ));
+        assert!(hincrbyfloat("1.7e308").await.is_ok());
+        assert!(matches!(
+            hincrbyfloat("1.7e308").await,
+            Err(CommandError::NanOrInfinity)
+        ));
+    }
+}
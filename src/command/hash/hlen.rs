@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, hash::as_hash, registry::CommandResult},
+    context::Context,
+    protocol::Frame,
+};
+
+/// `HLEN key`, 0 when the key doesn't exist
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("HLEN")]
+struct HLen {
+    key: String,
+}
+
+#[async_trait]
+impl CommandExecutor for HLen {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let len = ctx
+            .db()
+            .get_with(&self.key, |value| {
+                as_hash(value).map(|hash| hash.hash_len())
+            })
+            .transpose()?
+            .unwrap_or(0);
+        Ok(Frame::Integer(len as i64))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, hash::hlen::HLen},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_hlen() {
+        let ctx = Context::test_client(1);
+        let mut hash = RedisObject::new_hash();
+        hash.ptr.hash_set(b"a", b"1");
+        hash.ptr.hash_set(b"b", b"2");
+        ctx.db().insert("h".to_string(), hash, None);
+        let hlen = |key: &str| {
+            HLen {
+                key: key.to_string(),
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(hlen("h").await.unwrap(), Frame::Integer(2));
+        assert_eq!(hlen("missing").await.unwrap(), Frame::Integer(0));
+    }
+}
@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor, error::CommandError, hash::as_hash, parser::Parser,
+        registry::CommandResult,
+    },
+    context::Context,
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `HMGET key field [field ...]`, a missing field is replied as nil
+#[derive(PartialEq, Eq, Debug)]
+struct HMGet {
+    key: String,
+    fields: Vec<Vec<u8>>,
+}
+
+impl TryFrom<Parser> for HMGet {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        Ok(HMGet {
+            key: parser.next()?,
+            fields: parser.remaining()?,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for HMGet {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let values = ctx
+            .db()
+            .get_with(&self.key, |value| {
+                let hash = as_hash(value)?;
+                Ok::<_, CommandError>(
+                    self.fields
+                        .iter()
+                        .map(|field| Frame::BulkString(hash.hash_get(field).map(<[u8]>::to_vec)))
+                        .collect(),
+                )
+            })
+            .transpose()?
+            .unwrap_or_else(|| vec![Frame::BulkString(None); self.fields.len()]);
+        Ok(Frame::Array(Some(values)))
+    }
+}
+
+async fn hmget(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: HMGet = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("HMGET", hmget);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, hash::hmget::HMGet},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_hmget() {
+        let ctx = Context::test_client(1);
+        let mut hash = RedisObject::new_hash();
+        hash.ptr.hash_set(b"a", b"1");
+        ctx.db().insert("h".to_string(), hash, None);
+        let hmget = |key: &str| {
+            HMGet {
+                key: key.to_string(),
+                fields: vec![b"a".to_vec(), b"b".to_vec()],
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(
+            hmget("h").await.unwrap(),
+            Frame::Array(Some(vec![
+                Frame::BulkString(Some(b"1".to_vec())),
+                Frame::BulkString(None)
+            ]))
+        );
+        assert_eq!(
+            hmget("missing").await.unwrap(),
+            Frame::Array(Some(vec![Frame::BulkString(None), Frame::BulkString(None)]))
+        );
+    }
+}
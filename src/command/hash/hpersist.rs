@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        hash::{as_hash_mut, parse_fields},
+        parser::Parser,
+        registry::CommandResult,
+    },
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `HPERSIST key FIELDS numfields field [field ...]`.
+///
+/// Replies for every field -2 when it doesn't exist, -1 when it has no expire time and 1 when
+/// the expire time is removed.
+#[derive(PartialEq, Eq, Debug)]
+struct HPersist {
+    key: String,
+    fields: Vec<Vec<u8>>,
+}
+
+impl TryFrom<Parser> for HPersist {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let key = parser.next()?;
+        let fields = parse_fields(&mut parser, false, |_, _| Err(CommandError::MissingFields))?;
+        Ok(HPersist { key, fields })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for HPersist {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let id = db.id();
+        let replies = db
+            .modify(&self.key, |value| {
+                let hash = as_hash_mut(value)?;
+                let replies: Vec<Frame> = self
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        if hash.hash_persist(field) {
+                            1
+                        } else if hash.hash_get(field).is_some() {
+                            -1
+                        } else {
+                            -2
+                        }
+                    })
+                    .map(Frame::Integer)
+                    .collect();
+                if replies.contains(&Frame::Integer(1)) {
+                    notify_keyspace_event(notify::HASH, "hpersist", &self.key, id);
+                }
+                Ok::<_, CommandError>(replies)
+            })
+            .transpose()?
+            .unwrap_or_else(|| vec![Frame::Integer(-2); self.fields.len()]);
+        Ok(Frame::Array(Some(replies)))
+    }
+}
+
+async fn hpersist(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: HPersist = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("HPERSIST", hpersist);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, hash::hpersist::HPersist},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_hpersist() {
+        let ctx = Context::test_client(1);
+        let mut hash = RedisObject::new_hash();
+        hash.ptr.hash_set(b"a", b"1");
+        hash.ptr.hash_set(b"b", b"2");
+        hash.ptr.hash_set_expire(b"a", 4_102_444_800_000);
+        ctx.db().insert("h".to_string(), hash, None);
+        let hpersist = || {
+            HPersist {
+                key: "h".to_string(),
+                fields: vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()],
+            }
+            .execute(ctx.clone())
+        };
+        let replies = |replies: &[i64]| {
+            Frame::Array(Some(replies.iter().map(|&n| Frame::Integer(n)).collect()))
+        };
+
+        assert_eq!(hpersist().await.unwrap(), replies(&[1, -1, -2]));
+        assert_eq!(hpersist().await.unwrap(), replies(&[-1, -1, -2]));
+        assert_eq!(
+            ctx.db().peek_with("h", |value| value.ptr.encoding()),
+            Some("listpack")
+        );
+    }
+}
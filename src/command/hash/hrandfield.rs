@@ -0,0 +1,202 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor, error::CommandError, hash::as_hash, parser::Parser, random_picks,
+        registry::CommandResult,
+    },
+    context::Context,
+    protocol::{Frame, ProtocolVersion},
+    register_redis_command,
+};
+
+/// `HRANDFIELD key [count [WITHVALUES]]`.
+///
+/// A positive count replies distinct fields, a negative one may repeat them.
+#[derive(PartialEq, Eq, Debug)]
+struct HRandField {
+    key: String,
+    count: Option<i64>,
+    with_values: bool,
+}
+
+impl TryFrom<Parser> for HRandField {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let key = parser.next()?;
+        let count = if parser.has_next() {
+            Some(parser.next()?)
+        } else {
+            None
+        };
+        let with_values = if parser.has_next() {
+            let option: String = parser.next()?;
+            if !option.eq_ignore_ascii_case("WITHVALUES") {
+                return Err(CommandError::SyntaxError);
+            }
+            true
+        } else {
+            false
+        };
+        if parser.has_next() {
+            return Err(CommandError::SyntaxError);
+        }
+        // a negative count is negated, twice as many frames are replied with the values
+        let min = if with_values {
+            -(i64::MAX / 2)
+        } else {
+            -i64::MAX
+        };
+        if count.is_some_and(|count: i64| count < min) {
+            return Err(CommandError::OutOfRange);
+        }
+        Ok(HRandField {
+            key,
+            count,
+            with_values,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for HRandField {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let bulk = |data: &[u8]| Frame::BulkString(Some(data.to_vec()));
+        let picked = ctx
+            .db()
+            .get_with(&self.key, |value| {
+                let hash = as_hash(value)?;
+                // without a count a single field is picked
+                let count = self.count.unwrap_or(1);
+                let picked = random_picks(hash.hash_iter(), hash.hash_len(), count)
+                    .into_iter()
+                    .map(|(field, value)| (bulk(field), bulk(value)))
+                    .collect::<Vec<_>>();
+                Ok::<_, CommandError>(picked)
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        if self.count.is_none() {
+            return Ok(picked
+                .into_iter()
+                .next()
+                .map_or(Frame::Null, |(field, _)| field));
+        }
+        let frames = if !self.with_values {
+            picked.into_iter().map(|(field, _)| field).collect()
+        } else if ctx.protocol_version() == ProtocolVersion::Resp3 {
+            picked
+                .into_iter()
+                .map(|(field, value)| Frame::Array(Some(vec![field, value])))
+                .collect()
+        } else {
+            picked
+                .into_iter()
+                .flat_map(|(field, value)| [field, value])
+                .collect()
+        };
+        Ok(Frame::Array(Some(frames)))
+    }
+}
+
+async fn hrandfield(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: HRandField = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("HRANDFIELD", hrandfield);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{
+            CommandExecutor, error::CommandError, hash::hrandfield::HRandField, parser::Parser,
+        },
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[test]
+    fn test_hrandfield_count_range() {
+        let parse = |args: &[&str]| HRandField::try_from(Parser::from_args(args));
+        assert!(matches!(
+            parse(&["HRANDFIELD", "h", "-9223372036854775808"]),
+            Err(CommandError::OutOfRange)
+        ));
+        assert!(parse(&["HRANDFIELD", "h", "-9223372036854775807"]).is_ok());
+        assert!(matches!(
+            parse(&["HRANDFIELD", "h", "-4611686018427387904", "WITHVALUES"]),
+            Err(CommandError::OutOfRange)
+        ));
+        assert!(parse(&["HRANDFIELD", "h", "-4611686018427387903", "WITHVALUES"]).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_hrandfield() {
+        let ctx = Context::test_client(1);
+        let mut hash = RedisObject::new_hash();
+        hash.ptr.hash_set(b"a", b"1");
+        hash.ptr.hash_set(b"b", b"2");
+        hash.ptr.hash_set(b"c", b"3");
+        ctx.db().insert("h".to_string(), hash, None);
+        let hrandfield = |key: &str, count, with_values| {
+            HRandField {
+                key: key.to_string(),
+                count,
+                with_values,
+            }
+            .execute(ctx.clone())
+        };
+        let bulk = |s: &str| Frame::BulkString(Some(s.as_bytes().to_vec()));
+        let fields = |frame: Frame| match frame {
+            Frame::Array(Some(frames)) => frames,
+            frame => panic!("unexpected reply {:?}", frame),
+        };
+
+        assert!(matches!(
+            hrandfield("h", None, false).await.unwrap(),
+            Frame::BulkString(Some(_))
+        ));
+        assert_eq!(
+            hrandfield("missing", None, false).await.unwrap(),
+            Frame::Null
+        );
+        assert_eq!(
+            hrandfield("missing", Some(3), false).await.unwrap(),
+            Frame::Array(Some(vec![]))
+        );
+        assert_eq!(
+            hrandfield("h", Some(0), false).await.unwrap(),
+            Frame::Array(Some(vec![]))
+        );
+
+        let mut distinct = fields(hrandfield("h", Some(5), false).await.unwrap());
+        distinct.sort_by_key(|frame| format!("{:?}", frame));
+        assert_eq!(distinct, [bulk("a"), bulk("b"), bulk("c")]);
+        assert_eq!(
+            fields(hrandfield("h", Some(2), false).await.unwrap()).len(),
+            2
+        );
+        assert_eq!(
+            fields(hrandfield("h", Some(-10), false).await.unwrap()).len(),
+            10
+        );
+
+        // RESP2 replies the values flattened after their fields
+        let pairs = fields(hrandfield("h", Some(3), true).await.unwrap());
+        assert_eq!(pairs.len(), 6);
+        for pair in pairs.chunks(2) {
+            let (Frame::BulkString(Some(field)), Frame::BulkString(Some(value))) =
+                (&pair[0], &pair[1])
+            else {
+                panic!("unexpected pair {:?}", pair);
+            };
+            assert_eq!(value[0] - b'0', field[0] - b'a' + 1);
+        }
+    }
+}
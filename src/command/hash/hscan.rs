@@ -0,0 +1,140 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        option::{ScanArgs, scan_reply},
+        parser::Parser,
+        registry::CommandResult,
+    },
+    context::Context,
+    object::redis_object::ObjectType,
+    register_redis_command,
+};
+
+/// `HSCAN key cursor [MATCH pattern] [COUNT count] [NOVALUES]`
+#[derive(PartialEq, Eq, Debug)]
+struct HScan {
+    key: String,
+    args: ScanArgs,
+}
+
+impl TryFrom<Parser> for HScan {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let key = parser.next()?;
+        Ok(HScan {
+            key,
+            args: ScanArgs::parse(&mut parser, Some("NOVALUES"))?,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for HScan {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let args = self.args;
+        let page = ctx.db().get_with(&self.key, |value| {
+            if value.header.obj_type() != ObjectType::Hash {
+                return Err(CommandError::WrongType);
+            }
+            let (cursor, page) = value.ptr.hash_scan(args.cursor, args.count);
+            let mut elements = Vec::new();
+            for (field, value) in page.into_iter().filter(|(field, _)| args.matches(field)) {
+                elements.push(field.to_vec());
+                if !args.no_values {
+                    elements.push(value.to_vec());
+                }
+            }
+            Ok((cursor, elements))
+        });
+        let (cursor, elements) = page.transpose()?.unwrap_or_default();
+        Ok(scan_reply(cursor, elements))
+    }
+}
+
+async fn hscan(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: HScan = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("HSCAN", hscan);
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        command::{CommandExecutor, hash::hscan::HScan, parser::Parser},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    async fn hscan(ctx: &Arc<Context>, args: &[&str]) -> (String, Vec<Frame>) {
+        let cmd = HScan::try_from(Parser::from_args(args)).unwrap();
+        let Frame::Array(Some(reply)) = cmd.execute(ctx.clone()).await.unwrap() else {
+            panic!("HSCAN replies an array");
+        };
+        let [
+            Frame::BulkString(Some(cursor)),
+            Frame::Array(Some(elements)),
+        ] = &reply[..]
+        else {
+            panic!("HSCAN replies a cursor and elements");
+        };
+        (String::from_utf8(cursor.clone()).unwrap(), elements.clone())
+    }
+
+    #[tokio::test]
+    async fn test_hscan() {
+        let ctx = Context::test_client(1);
+        let mut hash = RedisObject::new_hash();
+        for i in 0..50 {
+            hash.ptr
+                .hash_set(format!("f{}", i).as_bytes(), format!("v{}", i).as_bytes());
+        }
+        ctx.db().insert("h".to_string(), hash, None);
+        ctx.db().set(
+            "s".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+
+        // a listpack is returned whole
+        let (cursor, elements) = hscan(&ctx, &["HSCAN", "h", "0", "COUNT", "8"]).await;
+        assert_eq!((cursor.as_str(), elements.len()), ("0", 100));
+        assert!(elements.contains(&Frame::BulkString(Some(b"v49".to_vec()))));
+
+        let mut table = RedisObject::new_hash();
+        for i in 0..500 {
+            table.ptr.hash_set(format!("f{}", i).as_bytes(), b"v");
+        }
+        assert_eq!(table.ptr.encoding(), "hashtable");
+        ctx.db().insert("table".to_string(), table, None);
+        let mut elements = Vec::new();
+        let mut cursor = "0".to_string();
+        let mut pages = 0;
+        loop {
+            let (next, page) = hscan(&ctx, &["HSCAN", "table", &cursor, "COUNT", "8"]).await;
+            elements.extend(page);
+            pages += 1;
+            cursor = next;
+            if cursor == "0" {
+                break;
+            }
+        }
+        assert_eq!(elements.len(), 1000);
+        assert!(pages >= 500 / 9);
+
+        let (cursor, page) = hscan(&ctx, &["HSCAN", "h", "0", "MATCH", "f1?", "NOVALUES"]).await;
+        assert_eq!((cursor.as_str(), page.len()), ("0", 10));
+        assert_eq!(hscan(&ctx, &["HSCAN", "missing", "0"]).await.1, vec![]);
+        let cmd = HScan::try_from(Parser::from_args(&["HSCAN", "s", "0"])).unwrap();
+        assert!(cmd.execute(ctx).await.is_err());
+    }
+}
@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor, error::CommandError, hash::as_hash_mut, parser::Parser,
+        registry::CommandResult,
+    },
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    object::redis_object::RedisObject,
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `HSET key field value [field value ...]`, replies the count of new fields.
+///
+/// `HMSET` is the same, it replies OK.
+#[derive(PartialEq, Eq, Debug)]
+struct HSet {
+    key: String,
+    pairs: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl TryFrom<Parser> for HSet {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let key = parser.next()?;
+        let args: Vec<Vec<u8>> = parser.remaining()?;
+        if !args.len().is_multiple_of(2) {
+            return Err(CommandError::InvalidArgumentNumber(
+                "hset".to_string(),
+                args.len() + 3,
+            ));
+        }
+        let mut args = args.into_iter();
+        let mut pairs = Vec::with_capacity(args.len() / 2);
+        while let (Some(field), Some(value)) = (args.next(), args.next()) {
+            pairs.push((field, value));
+        }
+        Ok(HSet { key, pairs })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for HSet {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let id = db.id();
+        let added = db.modify_or_insert(&self.key, RedisObject::new_hash, |value| {
+            let hash = as_hash_mut(value)?;
+            let added = self
+                .pairs
+                .iter()
+                .filter(|(field, value)| hash.hash_set(field, value))
+                .count();
+            notify_keyspace_event(notify::HASH, "hset", &self.key, id);
+            Ok::<_, CommandError>(added)
+        })?;
+        Ok(Frame::Integer(added as i64))
+    }
+}
+
+async fn hset(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: HSet = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+async fn hmset(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: HSet = parser.try_into()?;
+    cmd.execute(ctx).await?;
+    Ok(Frame::SimpleString("OK".to_string()))
+}
+
+register_redis_command!("HSET", hset);
+register_redis_command!("HMSET", hmset);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, hash::hset::HSet, parser::Parser},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_hset() {
+        assert!(HSet::try_from(Parser::from_args(&["HSET", "h", "f"])).is_err());
+        assert!(HSet::try_from(Parser::from_args(&["HSET", "h", "f", "v", "g"])).is_err());
+
+        let ctx = Context::test_client(1);
+        let hset = |args: &[&str]| {
+            HSet::try_from(Parser::from_args(args))
+                .unwrap()
+                .execute(ctx.clone())
+        };
+        assert_eq!(
+            hset(&["HSET", "h", "a", "1", "b", "2"]).await.unwrap(),
+            Frame::Integer(2)
+        );
+        assert_eq!(
+            hset(&["HSET", "h", "a", "3", "c", "4"]).await.unwrap(),
+            Frame::Integer(1)
+        );
+        let value = ctx
+            .db()
+            .get_with("h", |value| value.ptr.hash_get(b"a").map(<[u8]>::to_vec))
+            .flatten();
+        assert_eq!(value, Some(b"3".to_vec()));
+        let used = ctx.db().used_memory();
+        assert_eq!(ctx.db().memory_usage("h"), Some(used));
+
+        ctx.db().set(
+            "s".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+        assert!(hset(&["HSET", "s", "a", "1"]).await.is_err());
+    }
+}
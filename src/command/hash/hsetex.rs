@@ -0,0 +1,204 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        hash::{FieldExpiration, as_hash_mut, expire_field, parse_fields},
+        parser::Parser,
+        registry::CommandResult,
+    },
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    object::redis_object::RedisObject,
+    protocol::Frame,
+    register_redis_command,
+    util::unix_millis,
+};
+
+/// `HSETEX key [FNX | FXX] [EX seconds | PX milliseconds | EXAT unix-time-seconds |
+/// PXAT unix-time-milliseconds | KEEPTTL] FIELDS numfields field value [field value ...]`.
+///
+/// Replies 1 when the fields are set, 0 when `FNX` or `FXX` isn't met by all of them. The
+/// fields lose their expire time unless one is given or `KEEPTTL`.
+#[derive(PartialEq, Eq, Debug)]
+struct HSetEx {
+    key: String,
+    /// `FNX`, set the fields only when none of them exists
+    fnx: bool,
+    /// `FXX`, set the fields only when all of them exist
+    fxx: bool,
+    expiration: FieldExpiration,
+    pairs: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl TryFrom<Parser> for HSetEx {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let key = parser.next()?;
+        let (mut fnx, mut fxx) = (false, false);
+        let mut expiration = None;
+        let args = parse_fields(&mut parser, true, |option, parser| {
+            let parsed = match option.to_ascii_uppercase().as_str() {
+                "FNX" if !fxx => {
+                    fnx = true;
+                    return Ok(());
+                }
+                "FXX" if !fnx => {
+                    fxx = true;
+                    return Ok(());
+                }
+                "KEEPTTL" => FieldExpiration::Keep,
+                _ => FieldExpiration::parse(option, parser)?.ok_or(CommandError::SyntaxError)?,
+            };
+            match expiration.replace(parsed) {
+                Some(_) => Err(CommandError::SyntaxError),
+                None => Ok(()),
+            }
+        })?;
+        let mut args = args.into_iter();
+        let mut pairs = Vec::with_capacity(args.len() / 2);
+        while let (Some(field), Some(value)) = (args.next(), args.next()) {
+            pairs.push((field, value));
+        }
+        Ok(HSetEx {
+            key,
+            fnx,
+            fxx,
+            expiration: expiration.unwrap_or(FieldExpiration::Persist),
+            pairs,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for HSetEx {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let now = SystemTime::now();
+        let at = self.expiration.unix_millis("hsetex", now)?;
+        let now = unix_millis(now);
+        let db = ctx.db();
+        let id = db.id();
+        let set = |value: &mut RedisObject| {
+            let hash = as_hash_mut(value)?;
+            let exist = self
+                .pairs
+                .iter()
+                .filter(|(field, _)| hash.hash_get(field).is_some())
+                .count();
+            if (self.fnx && exist > 0) || (self.fxx && exist < self.pairs.len()) {
+                return Ok(0);
+            }
+            let mut deleted = false;
+            for (field, value) in self.pairs.iter() {
+                let keep = match self.expiration {
+                    FieldExpiration::Keep => hash.hash_expire_time(field),
+                    _ => None,
+                };
+                hash.hash_set(field, value);
+                if let Some(at) = keep.or(at) {
+                    deleted |= expire_field(hash, field, at, now);
+                }
+            }
+            notify_keyspace_event(notify::HASH, "hset", &self.key, id);
+            if at.is_some() && !deleted {
+                notify_keyspace_event(notify::HASH, "hexpire", &self.key, id);
+            }
+            if deleted {
+                notify_keyspace_event(notify::HASH, "hdel", &self.key, id);
+            }
+            Ok::<_, CommandError>(1)
+        };
+        // a missing key has none of the fields
+        let set = if self.fxx {
+            db.modify(&self.key, set).transpose()?.unwrap_or(0)
+        } else {
+            db.modify_or_insert(&self.key, RedisObject::new_hash, set)?
+        };
+        Ok(Frame::Integer(set))
+    }
+}
+
+async fn hsetex(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: HSetEx = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("HSETEX", hsetex);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, error::CommandError, hash::hsetex::HSetEx, parser::Parser},
+        context::Context,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_hsetex() {
+        let ctx = Context::test_client(1);
+        let hsetex = |args: &[&str]| {
+            let cmd = HSetEx::try_from(Parser::from_args(args));
+            let ctx = ctx.clone();
+            async move { cmd?.execute(ctx).await }
+        };
+        let expire_time = |field: &[u8]| {
+            ctx.db()
+                .peek_with("h", |value| value.ptr.hash_expire_time(field))
+                .flatten()
+        };
+
+        assert!(matches!(
+            hsetex(&["HSETEX", "h", "FNX", "FXX", "FIELDS", "1", "a", "1"]).await,
+            Err(CommandError::SyntaxError)
+        ));
+        assert!(matches!(
+            hsetex(&["HSETEX", "h", "FIELDS", "2", "a", "1"]).await,
+            Err(CommandError::NumFieldsMismatch)
+        ));
+        assert_eq!(
+            hsetex(&["HSETEX", "h", "FXX", "FIELDS", "1", "a", "1"])
+                .await
+                .unwrap(),
+            Frame::Integer(0)
+        );
+        assert!(!ctx.db().contains_key("h"));
+
+        assert_eq!(
+            hsetex(&[
+                "HSETEX", "h", "FNX", "EX", "100", "FIELDS", "2", "a", "1", "b", "2"
+            ])
+            .await
+            .unwrap(),
+            Frame::Integer(1)
+        );
+        let at = expire_time(b"a").unwrap();
+        assert_eq!(
+            hsetex(&["HSETEX", "h", "FNX", "FIELDS", "2", "a", "1", "c", "3"])
+                .await
+                .unwrap(),
+            Frame::Integer(0)
+        );
+        assert_eq!(
+            hsetex(&["HSETEX", "h", "FXX", "KEEPTTL", "FIELDS", "1", "a", "x"])
+                .await
+                .unwrap(),
+            Frame::Integer(1)
+        );
+        assert_eq!(expire_time(b"a"), Some(at));
+        // without an option the fields lose their expire time, like by HSET
+        hsetex(&["HSETEX", "h", "FIELDS", "1", "b", "y"])
+            .await
+            .unwrap();
+        assert_eq!(expire_time(b"b"), None);
+        assert_eq!(
+            ctx.db()
+                .get_with("h", |value| value.ptr.hash_get(b"a").map(<[u8]>::to_vec))
+                .flatten(),
+            Some(b"x".to_vec())
+        );
+    }
+}
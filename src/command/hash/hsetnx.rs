@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, hash::as_hash_mut, registry::CommandResult},
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    object::redis_object::RedisObject,
+    protocol::Frame,
+};
+
+/// `HSETNX key field value`, only sets a field which doesn't exist
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("HSETNX")]
+struct HSetNx {
+    key: String,
+    field: Vec<u8>,
+    value: Vec<u8>,
+}
+
+#[async_trait]
+impl CommandExecutor for HSetNx {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let id = db.id();
+        let set = db.modify_or_insert(&self.key, RedisObject::new_hash, |value| {
+            let hash = as_hash_mut(value)?;
+            if hash.hash_get(&self.field).is_some() {
+                return Ok(false);
+            }
+            hash.hash_set(&self.field, &self.value);
+            notify_keyspace_event(notify::HASH, "hset", &self.key, id);
+            Ok::<_, CommandError>(true)
+        })?;
+        Ok(Frame::Integer(set as i64))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, hash::hsetnx::HSetNx},
+        context::Context,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_hsetnx() {
+        let ctx = Context::test_client(1);
+        let hsetnx = |value: &str| {
+            HSetNx {
+                key: "h".to_string(),
+                field: b"f".to_vec(),
+                value: value.as_bytes().to_vec(),
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(hsetnx("1").await.unwrap(), Frame::Integer(1));
+        assert_eq!(hsetnx("2").await.unwrap(), Frame::Integer(0));
+        let value = ctx
+            .db()
+            .get_with("h", |value| value.ptr.hash_get(b"f").map(<[u8]>::to_vec))
+            .flatten();
+        assert_eq!(value, Some(b"1".to_vec()));
+    }
+}
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, hash::as_hash, registry::CommandResult},
+    context::Context,
+    protocol::Frame,
+};
+
+/// `HSTRLEN key field`, 0 when the field doesn't exist
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("HSTRLEN")]
+struct HStrLen {
+    key: String,
+    field: Vec<u8>,
+}
+
+#[async_trait]
+impl CommandExecutor for HStrLen {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let len = ctx
+            .db()
+            .get_with(&self.key, |value| {
+                as_hash(value).map(|hash| hash.hash_get(&self.field).map_or(0, <[u8]>::len))
+            })
+            .transpose()?
+            .unwrap_or(0);
+        Ok(Frame::Integer(len as i64))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, hash::hstrlen::HStrLen},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_hstrlen() {
+        let ctx = Context::test_client(1);
+        let mut hash = RedisObject::new_hash();
+        hash.ptr.hash_set(b"a", b"hello");
+        ctx.db().insert("h".to_string(), hash, None);
+        let hstrlen = |field: &str| {
+            HStrLen {
+                key: "h".to_string(),
+                field: field.as_bytes().to_vec(),
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(hstrlen("a").await.unwrap(), Frame::Integer(5));
+        assert_eq!(hstrlen("b").await.unwrap(), Frame::Integer(0));
+    }
+}
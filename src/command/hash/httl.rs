@@ -0,0 +1,130 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        hash::{as_hash, parse_fields},
+        option::TtlKind,
+        parser::Parser,
+        registry::CommandResult,
+    },
+    context::Context,
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `HTTL key FIELDS numfields field [field ...]` and `HPTTL`, `HEXPIRETIME`, `HPEXPIRETIME`.
+///
+/// Replies for every field -2 when it doesn't exist and -1 when it has no expire time.
+#[derive(PartialEq, Eq, Debug)]
+struct HTtl {
+    kind: TtlKind,
+    key: String,
+    fields: Vec<Vec<u8>>,
+}
+
+impl HTtl {
+    fn parse(kind: TtlKind, mut parser: Parser) -> Result<Self, CommandError> {
+        let key = parser.next()?;
+        let fields = parse_fields(&mut parser, false, |_, _| Err(CommandError::MissingFields))?;
+        Ok(HTtl { kind, key, fields })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for HTtl {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let now = SystemTime::now();
+        let replies = ctx
+            .db()
+            .get_with(&self.key, |value| {
+                let hash = as_hash(value)?;
+                Ok::<_, CommandError>(
+                    self.fields
+                        .iter()
+                        .map(|field| match hash.hash_expire_time(field) {
+                            Some(at) => self.kind.reply(at, now),
+                            None if hash.hash_get(field).is_some() => -1,
+                            None => -2,
+                        })
+                        .map(Frame::Integer)
+                        .collect(),
+                )
+            })
+            .transpose()?
+            .unwrap_or_else(|| vec![Frame::Integer(-2); self.fields.len()]);
+        Ok(Frame::Array(Some(replies)))
+    }
+}
+
+async fn httl(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    HTtl::parse(TtlKind::Seconds, parser)?.execute(ctx).await
+}
+
+async fn hpttl(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    HTtl::parse(TtlKind::Millis, parser)?.execute(ctx).await
+}
+
+async fn hexpiretime(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    HTtl::parse(TtlKind::UnixSeconds, parser)?
+        .execute(ctx)
+        .await
+}
+
+async fn hpexpiretime(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    HTtl::parse(TtlKind::UnixMillis, parser)?.execute(ctx).await
+}
+
+register_redis_command!("HTTL", httl);
+register_redis_command!("HPTTL", hpttl);
+register_redis_command!("HEXPIRETIME", hexpiretime);
+register_redis_command!("HPEXPIRETIME", hpexpiretime);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, hash::httl::HTtl, option::TtlKind},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+        util::unix_millis,
+    };
+
+    #[tokio::test]
+    async fn test_httl() {
+        let ctx = Context::test_client(1);
+        let mut hash = RedisObject::new_hash();
+        hash.ptr.hash_set(b"a", b"1");
+        hash.ptr.hash_set(b"b", b"2");
+        let at = unix_millis(std::time::SystemTime::now()) + 100_000;
+        hash.ptr.hash_set_expire(b"a", at);
+        ctx.db().insert("h".to_string(), hash, None);
+        let httl = |kind, key: &str| {
+            HTtl {
+                kind,
+                key: key.to_string(),
+                fields: vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()],
+            }
+            .execute(ctx.clone())
+        };
+        let replies = |replies: &[i64]| {
+            Frame::Array(Some(replies.iter().map(|&n| Frame::Integer(n)).collect()))
+        };
+
+        assert_eq!(
+            httl(TtlKind::Seconds, "h").await.unwrap(),
+            replies(&[100, -1, -2])
+        );
+        assert_eq!(
+            httl(TtlKind::UnixMillis, "h").await.unwrap(),
+            replies(&[at, -1, -2])
+        );
+        assert_eq!(
+            httl(TtlKind::Millis, "missing").await.unwrap(),
+            replies(&[-2, -2, -2])
+        );
+    }
+}
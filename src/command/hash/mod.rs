@@ -0,0 +1,122 @@
+use std::time::SystemTime;
+
+use crate::{
+    command::{error::CommandError, option::Expiration, parser::Parser},
+    object::redis_object::{ObjectType, RedisObject, RedisValue},
+};
+
+mod hdel;
+mod hexists;
+mod hexpire;
+mod hget;
+mod hgetall;
+mod hgetex;
+mod hincrby;
+mod hincrbyfloat;
+mod hlen;
+mod hmget;
+mod hpersist;
+mod hrandfield;
+mod hscan;
+mod hset;
+mod hsetex;
+mod hsetnx;
+mod hstrlen;
+mod httl;
+
+/// Latest expire time of a field in unix milliseconds, 2^48 - 1 as in redis
+const MAX_FIELD_EXPIRE: i64 = (1 << 48) - 1;
+
+/// What `HGETEX` and `HSETEX` do with the expire time of the fields
+#[derive(PartialEq, Eq, Debug)]
+enum FieldExpiration {
+    /// `KEEPTTL`, or `HGETEX` without any option
+    Keep,
+    /// `PERSIST`, or `HSETEX` without any option
+    Persist,
+    Expire(Expiration),
+}
+
+impl FieldExpiration {
+    /// Read `EX`, `PX`, `EXAT` or `PXAT` and its time, `None` when `option` isn't one of them
+    fn parse(option: &str, parser: &mut Parser) -> Result<Option<Self>, CommandError> {
+        let option = option.to_ascii_uppercase();
+        if !matches!(option.as_str(), "EX" | "PX" | "EXAT" | "PXAT") {
+            return Ok(None);
+        }
+        let time: String = parser.next()?;
+        Ok(Some(FieldExpiration::Expire(Expiration::try_from((
+            option, time,
+        ))?)))
+    }
+
+    /// The expire time in unix milliseconds, `None` unless the fields get one
+    fn unix_millis(&self, command: &str, now: SystemTime) -> Result<Option<i64>, CommandError> {
+        let FieldExpiration::Expire(expiration) = self else {
+            return Ok(None);
+        };
+        match expiration {
+            Expiration::EX(0) | Expiration::PX(0) => None,
+            expiration => expiration.unix_millis(now),
+        }
+        .filter(|at| (0..=MAX_FIELD_EXPIRE).contains(at))
+        .map(Some)
+        .ok_or_else(|| CommandError::InvalidExpireTime(command.to_string()))
+    }
+}
+
+fn as_hash(value: &RedisObject) -> Result<&RedisValue, CommandError> {
+    match value.header.obj_type() {
+        ObjectType::Hash => Ok(&value.ptr),
+        _ => Err(CommandError::WrongType),
+    }
+}
+
+/// Read the options before `FIELDS numfields field [field ...]` by `option`, then the
+/// fields. `pairs` reads a value after every field.
+fn parse_fields(
+    parser: &mut Parser,
+    pairs: bool,
+    mut option: impl FnMut(&str, &mut Parser) -> Result<(), CommandError>,
+) -> Result<Vec<Vec<u8>>, CommandError> {
+    loop {
+        if !parser.has_next() {
+            return Err(CommandError::MissingFields);
+        }
+        let name: String = parser.next()?;
+        if name.eq_ignore_ascii_case("FIELDS") {
+            break;
+        }
+        option(&name, parser)?;
+    }
+    let count: i64 = parser.next()?;
+    if count <= 0 {
+        return Err(CommandError::NotGreaterThanZero(
+            "Parameter `numFields`".to_string(),
+        ));
+    }
+    let args: Vec<Vec<u8>> = parser.remaining()?;
+    let expected = if pairs { count * 2 } else { count };
+    if args.len() as i64 != expected {
+        return Err(CommandError::NumFieldsMismatch);
+    }
+    Ok(args)
+}
+
+/// Give the existing `field` the expire time `at`, a time which passed deletes the field
+/// instead. Returns true when it was deleted.
+fn expire_field(hash: &mut RedisValue, field: &[u8], at: i64, now: i64) -> bool {
+    if at <= now {
+        hash.hash_remove(field);
+        return true;
+    }
+    hash.hash_set_expire(field, at);
+    false
+}
+
+fn as_hash_mut(value: &mut RedisObject) -> Result<&mut RedisValue, CommandError> {
+    match value.header.obj_type() {
+        ObjectType::Hash => Ok(&mut value.ptr),
+        _ => Err(CommandError::WrongType),
+    }
+}
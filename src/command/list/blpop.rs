@@ -0,0 +1,140 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        list::{End, block_for_keys, parse_timeout, pop_first},
+        parser::Parser,
+        registry::CommandResult,
+    },
+    context::Context,
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `BLPOP key [key ...] timeout` and `BRPOP`, pops from the first non empty list or blocks
+/// until one of the keys is pushed to. Replies the key and the element.
+#[derive(PartialEq, Eq, Debug)]
+struct BPop {
+    end: End,
+    keys: Vec<String>,
+    timeout: Option<Duration>,
+}
+
+impl BPop {
+    fn parse(end: End, mut parser: Parser) -> Result<Self, CommandError> {
+        // at least a key and the timeout
+        let mut keys = vec![parser.next::<String>()?];
+        keys.extend(parser.remaining::<String>()?);
+        let timeout = keys.pop().expect("the timeout was parsed");
+        Ok(BPop {
+            end,
+            keys,
+            timeout: parse_timeout(&timeout)?,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for BPop {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let served = block_for_keys(&ctx, &self.keys, self.timeout, || {
+            pop_first(&ctx, &self.keys, self.end, 1)
+        })
+        .await?;
+        Ok(match served {
+            Some((key, mut popped)) => Frame::Array(Some(vec![
+                Frame::BulkString(Some(key.into_bytes())),
+                Frame::BulkString(popped.pop()),
+            ])),
+            None => Frame::Array(None),
+        })
+    }
+}
+
+async fn blpop(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    BPop::parse(End::Left, parser)?.execute(ctx).await
+}
+
+async fn brpop(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    BPop::parse(End::Right, parser)?.execute(ctx).await
+}
+
+register_redis_command!("BLPOP", blpop);
+register_redis_command!("BRPOP", brpop);
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, time::Duration};
+
+    use crate::{
+        command::{
+            CommandExecutor,
+            list::{End, as_list_mut, blpop::BPop},
+            parser::Parser,
+        },
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+        storage::database::Databases,
+    };
+
+    #[tokio::test]
+    async fn test_bpop() {
+        assert_eq!(
+            BPop::parse(End::Left, Parser::from_args(&["BLPOP", "a", "b", "1.5"])).unwrap(),
+            BPop {
+                end: End::Left,
+                keys: vec!["a".to_string(), "b".to_string()],
+                timeout: Some(Duration::from_millis(1500)),
+            }
+        );
+        assert!(BPop::parse(End::Left, Parser::from_args(&["BLPOP", "0"])).is_err());
+        assert!(BPop::parse(End::Left, Parser::from_args(&["BLPOP", "a", "-1"])).is_err());
+        assert!(BPop::parse(End::Left, Parser::from_args(&["BLPOP", "a", "1e20"])).is_err());
+
+        let dbs = Arc::new(Databases::new(1));
+        let ctx = || Context::test_client_of(dbs.clone());
+        let (first, second, pusher) = (ctx(), ctx(), ctx());
+        let brpop = |ctx: &Arc<Context>, timeout| {
+            BPop {
+                end: End::Right,
+                keys: vec!["empty".to_string(), "jobs".to_string()],
+                timeout,
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(
+            brpop(&first, Some(Duration::from_millis(10)))
+                .await
+                .unwrap(),
+            Frame::Array(None)
+        );
+
+        // the clients are served in the order they blocked
+        let first_pop = tokio::spawn(brpop(&first, None));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let second_pop = tokio::spawn(brpop(&second, None));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(first.is_blocked() && second.is_blocked());
+        pusher
+            .db()
+            .modify_or_insert("jobs", RedisObject::new_list, |value| {
+                let list = as_list_mut(value).unwrap();
+                list.push_back(b"1");
+                list.push_back(b"2");
+            });
+        let reply = |element: &str| {
+            Frame::Array(Some(vec![
+                Frame::BulkString(Some(b"jobs".to_vec())),
+                Frame::BulkString(Some(element.as_bytes().to_vec())),
+            ]))
+        };
+        assert_eq!(first_pop.await.unwrap().unwrap(), reply("2"));
+        assert_eq!(second_pop.await.unwrap().unwrap(), reply("1"));
+        assert!(!pusher.db().contains_key("jobs"));
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        list::{as_list, list_index},
+        registry::CommandResult,
+    },
+    context::Context,
+    protocol::Frame,
+};
+
+/// `LINDEX key index`, a negative index counts from the tail
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("LINDEX")]
+struct LIndex {
+    key: String,
+    index: i64,
+}
+
+#[async_trait]
+impl CommandExecutor for LIndex {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let element = ctx
+            .db()
+            .get_with(&self.key, |value| {
+                let list = as_list(value)?;
+                Ok::<_, CommandError>(
+                    list_index(self.index, list.len()).and_then(|index| list.get(index)),
+                )
+            })
+            .transpose()?
+            .flatten();
+        Ok(element.map_or(Frame::Null, |element| Frame::BulkString(Some(element))))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{
+            CommandExecutor,
+            list::{as_list_mut, lindex::LIndex},
+        },
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_lindex() {
+        let ctx = Context::test_client(1);
+        ctx.db()
+            .modify_or_insert("list", RedisObject::new_list, |value| {
+                let list = as_list_mut(value).unwrap();
+                list.push_back(b"a");
+                list.push_back(b"b");
+            });
+        let lindex = |index: i64| {
+            LIndex {
+                key: "list".to_string(),
+                index,
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(
+            lindex(-1).await.unwrap(),
+            Frame::BulkString(Some(b"b".to_vec()))
+        );
+        assert_eq!(
+            lindex(0).await.unwrap(),
+            Frame::BulkString(Some(b"a".to_vec()))
+        );
+        assert_eq!(lindex(2).await.unwrap(), Frame::Null);
+        assert_eq!(lindex(-3).await.unwrap(), Frame::Null);
+    }
+}
@@ -0,0 +1,139 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor, error::CommandError, list::as_list_mut, parser::Parser,
+        registry::CommandResult,
+    },
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `LINSERT key BEFORE | AFTER pivot element`
+#[derive(PartialEq, Eq, Debug)]
+struct LInsert {
+    key: String,
+    after: bool,
+    pivot: Vec<u8>,
+    element: Vec<u8>,
+}
+
+impl TryFrom<Parser> for LInsert {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let key = parser.next()?;
+        let position: String = parser.next()?;
+        let after = match position.to_ascii_uppercase().as_str() {
+            "BEFORE" => false,
+            "AFTER" => true,
+            _ => return Err(CommandError::SyntaxError),
+        };
+        let pivot = parser.next()?;
+        let element = parser.next()?;
+        if parser.has_next() {
+            return Err(CommandError::SyntaxError);
+        }
+        Ok(LInsert {
+            key,
+            after,
+            pivot,
+            element,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for LInsert {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let id = db.id();
+        // -1 when the pivot isn't found, 0 when the key doesn't exist
+        let len = db
+            .modify(&self.key, |value| {
+                let list = as_list_mut(value)?;
+                let Some(pivot) = list.iter().position(|element| element == self.pivot) else {
+                    return Ok(-1);
+                };
+                list.insert(pivot + self.after as usize, &self.element);
+                notify_keyspace_event(notify::LIST, "linsert", &self.key, id);
+                Ok::<_, CommandError>(list.len() as i64)
+            })
+            .transpose()?
+            .unwrap_or(0);
+        Ok(Frame::Integer(len))
+    }
+}
+
+async fn linsert(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: LInsert = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("LINSERT", linsert);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{
+            CommandExecutor,
+            list::{as_list, as_list_mut, linsert::LInsert},
+            parser::Parser,
+        },
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_linsert() {
+        assert!(
+            LInsert::try_from(Parser::from_args(&["LINSERT", "list", "MIDDLE", "a", "b"])).is_err()
+        );
+        let ctx = Context::test_client(1);
+        let linsert = |args: &[&str]| {
+            LInsert::try_from(Parser::from_args(args))
+                .unwrap()
+                .execute(ctx.clone())
+        };
+        assert_eq!(
+            linsert(&["LINSERT", "list", "BEFORE", "a", "b"])
+                .await
+                .unwrap(),
+            Frame::Integer(0)
+        );
+        ctx.db()
+            .modify_or_insert("list", RedisObject::new_list, |value| {
+                let list = as_list_mut(value).unwrap();
+                list.push_back(b"a");
+                list.push_back(b"c");
+            });
+        assert_eq!(
+            linsert(&["LINSERT", "list", "after", "a", "b"])
+                .await
+                .unwrap(),
+            Frame::Integer(3)
+        );
+        assert_eq!(
+            linsert(&["LINSERT", "list", "BEFORE", "a", "_"])
+                .await
+                .unwrap(),
+            Frame::Integer(4)
+        );
+        assert_eq!(
+            linsert(&["LINSERT", "list", "BEFORE", "z", "_"])
+                .await
+                .unwrap(),
+            Frame::Integer(-1)
+        );
+        let elements = ctx
+            .db()
+            .get_with("list", |value| as_list(value).unwrap().range(0, 3))
+            .unwrap();
+        assert_eq!(elements, [&b"_"[..], b"a", b"b", b"c"]);
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, list::as_list, registry::CommandResult},
+    context::Context,
+    protocol::Frame,
+};
+
+/// `LLEN key`, 0 when the key doesn't exist
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("LLEN")]
+struct LLen {
+    key: String,
+}
+
+#[async_trait]
+impl CommandExecutor for LLen {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let len = ctx
+            .db()
+            .get_with(&self.key, |value| as_list(value).map(|list| list.len()))
+            .transpose()?
+            .unwrap_or(0);
+        Ok(Frame::Integer(len as i64))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{
+            CommandExecutor,
+            list::{as_list_mut, llen::LLen},
+        },
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_llen() {
+        let ctx = Context::test_client(1);
+        let llen = |key: &str| {
+            LLen {
+                key: key.to_string(),
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(llen("list").await.unwrap(), Frame::Integer(0));
+        ctx.db()
+            .modify_or_insert("list", RedisObject::new_list, |value| {
+                let list = as_list_mut(value).unwrap();
+                list.push_back(b"a");
+                list.push_back(b"b");
+            });
+        assert_eq!(llen("list").await.unwrap(), Frame::Integer(2));
+        ctx.db().set(
+            "string".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+        assert!(llen("string").await.is_err());
+    }
+}
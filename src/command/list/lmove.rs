@@ -0,0 +1,220 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        list::{End, as_list_mut, block_for_keys, parse_timeout},
+        parser::Parser,
+        registry::CommandResult,
+    },
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    object::redis_object::{ObjectType, RedisObject},
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `LMOVE source destination LEFT | RIGHT LEFT | RIGHT`, pops an element from `source` and
+/// pushes it to `destination`. `BLMOVE` takes a timeout as well and blocks until `source` is
+/// pushed to.
+#[derive(PartialEq, Eq, Debug)]
+struct LMove {
+    source: String,
+    destination: String,
+    from: End,
+    to: End,
+    /// `None` for `LMOVE`, which doesn't block
+    block: Option<Option<Duration>>,
+}
+
+impl LMove {
+    fn parse(blocking: bool, mut parser: Parser) -> Result<Self, CommandError> {
+        let source = parser.next()?;
+        let destination = parser.next()?;
+        let from = End::parse(&mut parser)?;
+        let to = End::parse(&mut parser)?;
+        let block = if blocking {
+            Some(parse_timeout(&parser.next::<String>()?)?)
+        } else {
+            None
+        };
+        if parser.has_next() {
+            return Err(CommandError::SyntaxError);
+        }
+        Ok(LMove {
+            source,
+            destination,
+            from,
+            to,
+            block,
+        })
+    }
+
+    /// Move an element, `None` when `source` doesn't exist
+    fn move_element(&self, ctx: &Context) -> Result<Option<Vec<u8>>, CommandError> {
+        let db = ctx.db();
+        let id = db.id();
+        // nothing is popped when the destination can't take it
+        if db
+            .key_type(&self.destination)
+            .is_some_and(|obj_type| obj_type != ObjectType::List)
+        {
+            return Err(CommandError::WrongType);
+        }
+        let popped = db
+            .modify(&self.source, |value| {
+                let list = as_list_mut(value)?;
+                let popped = self.from.pop(list);
+                if let Some(element) = &popped {
+                    notify_keyspace_event(notify::LIST, self.from.pop_event(), &self.source, id);
+                    // a rotation stays in the same list
+                    if self.source == self.destination {
+                        self.to.push(list, element);
+                        notify_keyspace_event(notify::LIST, self.to.push_event(), &self.source, id);
+                    }
+                }
+                Ok::<_, CommandError>(popped)
+            })
+            .transpose()?
+            .flatten();
+        let Some(element) = popped else {
+            return Ok(None);
+        };
+        if self.source != self.destination {
+            db.modify_or_insert(&self.destination, RedisObject::new_list, |value| {
+                let list = as_list_mut(value)?;
+                self.to.push(list, &element);
+                notify_keyspace_event(notify::LIST, self.to.push_event(), &self.destination, id);
+                Ok::<_, CommandError>(())
+            })?;
+        }
+        Ok(Some(element))
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for LMove {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let element = match self.block {
+            Some(timeout) => {
+                let keys = [self.source.clone()];
+                block_for_keys(&ctx, &keys, timeout, || self.move_element(&ctx)).await?
+            }
+            None => self.move_element(&ctx)?,
+        };
+        Ok(element.map_or(Frame::Null, |element| Frame::BulkString(Some(element))))
+    }
+}
+
+async fn lmove(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    LMove::parse(false, parser)?.execute(ctx).await
+}
+
+async fn blmove(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    LMove::parse(true, parser)?.execute(ctx).await
+}
+
+register_redis_command!("LMOVE", lmove);
+register_redis_command!("BLMOVE", blmove);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{
+            CommandExecutor,
+            list::{as_list, as_list_mut, lmove::LMove},
+            parser::Parser,
+        },
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_lmove() {
+        assert!(
+            LMove::parse(false, Parser::from_args(&["LMOVE", "a", "b", "LEFT", "UP"])).is_err()
+        );
+        assert!(
+            LMove::parse(
+                false,
+                Parser::from_args(&["LMOVE", "a", "b", "LEFT", "LEFT", "0"])
+            )
+            .is_err()
+        );
+        assert!(
+            LMove::parse(
+                true,
+                Parser::from_args(&["BLMOVE", "a", "b", "LEFT", "LEFT"])
+            )
+            .is_err()
+        );
+        let ctx = Context::test_client(1);
+        let lmove = |args: &[&str]| {
+            LMove::parse(args[0] == "BLMOVE", Parser::from_args(args))
+                .unwrap()
+                .execute(ctx.clone())
+        };
+        let elements = |key: &str| {
+            ctx.db()
+                .get_with(key, |value| {
+                    let list = as_list(value).unwrap();
+                    list.range(0, list.len() - 1)
+                })
+                .unwrap_or_default()
+        };
+        let bulk = |s: &str| Frame::BulkString(Some(s.as_bytes().to_vec()));
+        assert_eq!(
+            lmove(&["LMOVE", "src", "dst", "LEFT", "RIGHT"])
+                .await
+                .unwrap(),
+            Frame::Null
+        );
+        ctx.db()
+            .modify_or_insert("src", RedisObject::new_list, |value| {
+                let list = as_list_mut(value).unwrap();
+                for element in ["a", "b", "c"] {
+                    list.push_back(element.as_bytes());
+                }
+            });
+        assert_eq!(
+            lmove(&["LMOVE", "src", "src", "right", "left"])
+                .await
+                .unwrap(),
+            bulk("c")
+        );
+        assert_eq!(elements("src"), [&b"c"[..], b"a", b"b"]);
+        assert_eq!(
+            lmove(&["LMOVE", "src", "dst", "LEFT", "RIGHT"])
+                .await
+                .unwrap(),
+            bulk("c")
+        );
+        assert_eq!(
+            lmove(&["LMOVE", "src", "dst", "LEFT", "LEFT"])
+                .await
+                .unwrap(),
+            bulk("a")
+        );
+        assert_eq!(elements("dst"), [&b"a"[..], b"c"]);
+
+        ctx.db().set(
+            "string".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+        assert!(
+            lmove(&["LMOVE", "src", "string", "LEFT", "LEFT"])
+                .await
+                .is_err()
+        );
+        assert_eq!(elements("src"), [&b"b"[..]]);
+        lmove(&["LMOVE", "src", "dst", "LEFT", "LEFT"])
+            .await
+            .unwrap();
+        assert!(!ctx.db().contains_key("src"));
+    }
+}
@@ -0,0 +1,174 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        list::{End, block_for_keys, parse_timeout, pop_first},
+        parser::Parser,
+        registry::CommandResult,
+    },
+    context::Context,
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `LMPOP numkeys key [key ...] LEFT | RIGHT [COUNT count]` and
+/// `BLMPOP timeout numkeys key [key ...] LEFT | RIGHT [COUNT count]`.
+///
+/// Pops up to `count` elements from the first non empty list, replies the key and the elements.
+#[derive(PartialEq, Eq, Debug)]
+struct MPop {
+    keys: Vec<String>,
+    end: End,
+    count: usize,
+    /// `None` for `LMPOP`, which doesn't block
+    block: Option<Option<Duration>>,
+}
+
+impl MPop {
+    fn parse(blocking: bool, mut parser: Parser) -> Result<Self, CommandError> {
+        let block = if blocking {
+            Some(parse_timeout(&parser.next::<String>()?)?)
+        } else {
+            None
+        };
+        let numkeys = usize::try_from(parser.next::<i64>()?)
+            .ok()
+            .filter(|&numkeys| numkeys > 0)
+            .ok_or_else(|| CommandError::NotGreaterThanZero("numkeys".to_string()))?;
+        let keys = (0..numkeys)
+            .map(|_| parser.next())
+            .collect::<Result<_, _>>()?;
+        let end = End::parse(&mut parser)?;
+        let mut count = 1;
+        if parser.has_next() {
+            let option: String = parser.next()?;
+            if !option.eq_ignore_ascii_case("COUNT") {
+                return Err(CommandError::SyntaxError);
+            }
+            count = usize::try_from(parser.next::<i64>()?)
+                .ok()
+                .filter(|&count| count > 0)
+                .ok_or_else(|| CommandError::NotGreaterThanZero("count".to_string()))?;
+        }
+        if parser.has_next() {
+            return Err(CommandError::SyntaxError);
+        }
+        Ok(MPop {
+            keys,
+            end,
+            count,
+            block,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for MPop {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let pop = || pop_first(&ctx, &self.keys, self.end, self.count);
+        let served = match self.block {
+            Some(timeout) => block_for_keys(&ctx, &self.keys, timeout, pop).await?,
+            None => pop()?,
+        };
+        Ok(match served {
+            Some((key, popped)) => Frame::Array(Some(vec![
+                Frame::BulkString(Some(key.into_bytes())),
+                Frame::Array(Some(
+                    popped
+                        .into_iter()
+                        .map(|element| Frame::BulkString(Some(element)))
+                        .collect(),
+                )),
+            ])),
+            None => Frame::Array(None),
+        })
+    }
+}
+
+async fn lmpop(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    MPop::parse(false, parser)?.execute(ctx).await
+}
+
+async fn blmpop(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    MPop::parse(true, parser)?.execute(ctx).await
+}
+
+register_redis_command!("LMPOP", lmpop);
+register_redis_command!("BLMPOP", blmpop);
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::{
+        command::{
+            CommandExecutor,
+            list::{End, as_list_mut, lmpop::MPop},
+            parser::Parser,
+        },
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_mpop() {
+        assert_eq!(
+            MPop::parse(
+                true,
+                Parser::from_args(&["BLMPOP", "0", "2", "a", "b", "RIGHT", "COUNT", "3"])
+            )
+            .unwrap(),
+            MPop {
+                keys: vec!["a".to_string(), "b".to_string()],
+                end: End::Right,
+                count: 3,
+                block: Some(None),
+            }
+        );
+        assert!(MPop::parse(false, Parser::from_args(&["LMPOP", "0", "LEFT"])).is_err());
+        assert!(MPop::parse(false, Parser::from_args(&["LMPOP", "2", "a", "LEFT"])).is_err());
+        assert!(
+            MPop::parse(
+                false,
+                Parser::from_args(&["LMPOP", "1", "a", "LEFT", "COUNT", "0"])
+            )
+            .is_err()
+        );
+
+        let ctx = Context::test_client(1);
+        let mpop = |block| {
+            MPop {
+                keys: vec!["a".to_string(), "b".to_string()],
+                end: End::Left,
+                count: 2,
+                block,
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(mpop(None).await.unwrap(), Frame::Array(None));
+        assert_eq!(
+            mpop(Some(Some(Duration::from_millis(10)))).await.unwrap(),
+            Frame::Array(None)
+        );
+        ctx.db()
+            .modify_or_insert("b", RedisObject::new_list, |value| {
+                let list = as_list_mut(value).unwrap();
+                for element in ["1", "2", "3"] {
+                    list.push_back(element.as_bytes());
+                }
+            });
+        let bulk = |s: &str| Frame::BulkString(Some(s.as_bytes().to_vec()));
+        assert_eq!(
+            mpop(Some(None)).await.unwrap(),
+            Frame::Array(Some(vec![
+                bulk("b"),
+                Frame::Array(Some(vec![bulk("1"), bulk("2")]))
+            ]))
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        list::{End, as_list_mut},
+        parser::Parser,
+        registry::CommandResult,
+    },
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `LPOP key [count]` and `RPOP`. Without `count` the element is replied on its own,
+/// otherwise an array of up to `count` elements.
+#[derive(PartialEq, Eq, Debug)]
+struct Pop {
+    end: End,
+    key: String,
+    count: Option<usize>,
+}
+
+impl Pop {
+    fn parse(end: End, mut parser: Parser) -> Result<Self, CommandError> {
+        let key = parser.next()?;
+        let count = if parser.has_next() {
+            let count =
+                usize::try_from(parser.next::<i64>()?).map_err(|_| CommandError::NotPositive)?;
+            Some(count)
+        } else {
+            None
+        };
+        if parser.has_next() {
+            return Err(CommandError::SyntaxError);
+        }
+        Ok(Pop { end, key, count })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for Pop {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let id = db.id();
+        let popped = db
+            .modify(&self.key, |value| {
+                let list = as_list_mut(value)?;
+                let popped: Vec<Vec<u8>> = (0..self.count.unwrap_or(1))
+                    .map_while(|_| self.end.pop(list))
+                    .collect();
+                if !popped.is_empty() {
+                    notify_keyspace_event(notify::LIST, self.end.pop_event(), &self.key, id);
+                }
+                Ok::<_, CommandError>(popped)
+            })
+            .transpose()?;
+        Ok(match (popped, self.count) {
+            (None, Some(_)) => Frame::Array(None),
+            (None, None) => Frame::Null,
+            (Some(popped), Some(_)) => Frame::Array(Some(
+                popped
+                    .into_iter()
+                    .map(|element| Frame::BulkString(Some(element)))
+                    .collect(),
+            )),
+            (Some(mut popped), None) => Frame::BulkString(popped.pop()),
+        })
+    }
+}
+
+async fn lpop(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    Pop::parse(End::Left, parser)?.execute(ctx).await
+}
+
+async fn rpop(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    Pop::parse(End::Right, parser)?.execute(ctx).await
+}
+
+register_redis_command!("LPOP", lpop);
+register_redis_command!("RPOP", rpop);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{
+            CommandExecutor,
+            list::{End, as_list_mut, lpop::Pop},
+            parser::Parser,
+        },
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_pop() {
+        assert!(Pop::parse(End::Left, Parser::from_args(&["LPOP", "list", "-1"])).is_err());
+        assert!(Pop::parse(End::Left, Parser::from_args(&["LPOP", "list", "1", "2"])).is_err());
+
+        let ctx = Context::test_client(1);
+        let pop = |end: End, count: Option<usize>| {
+            Pop {
+                end,
+                key: "list".to_string(),
+                count,
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(pop(End::Left, None).await.unwrap(), Frame::Null);
+        assert_eq!(pop(End::Left, Some(2)).await.unwrap(), Frame::Array(None));
+
+        ctx.db()
+            .modify_or_insert("list", RedisObject::new_list, |value| {
+                let list = as_list_mut(value).unwrap();
+                for element in ["a", "b", "c", "d"] {
+                    list.push_back(element.as_bytes());
+                }
+            });
+        let bulk = |s: &str| Frame::BulkString(Some(s.as_bytes().to_vec()));
+        assert_eq!(pop(End::Left, None).await.unwrap(), bulk("a"));
+        assert_eq!(pop(End::Right, None).await.unwrap(), bulk("d"));
+        assert_eq!(
+            pop(End::Left, Some(0)).await.unwrap(),
+            Frame::Array(Some(vec![]))
+        );
+        assert_eq!(
+            pop(End::Right, Some(5)).await.unwrap(),
+            Frame::Array(Some(vec![bulk("c"), bulk("b")]))
+        );
+        // the emptied list is deleted
+        assert!(!ctx.db().contains_key("list"));
+        assert_eq!(ctx.db().used_memory(), 0);
+    }
+}
@@ -0,0 +1,190 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor, error::CommandError, list::as_list, parser::Parser,
+        registry::CommandResult,
+    },
+    context::Context,
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `LPOS key element [RANK rank] [COUNT num-matches] [MAXLEN len]`
+#[derive(PartialEq, Eq, Debug)]
+struct LPos {
+    key: String,
+    element: Vec<u8>,
+    /// The match to start from, negative ranks scan from the tail
+    rank: i64,
+    /// Matches to return, 0 is all of them. Without it a single index is replied.
+    count: Option<usize>,
+    /// Elements compared at most, 0 is the whole list
+    maxlen: usize,
+}
+
+impl TryFrom<Parser> for LPos {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let key = parser.next()?;
+        let element = parser.next()?;
+        let mut lpos = LPos {
+            key,
+            element,
+            rank: 1,
+            count: None,
+            maxlen: 0,
+        };
+        while parser.has_next() {
+            let option: String = parser.next()?;
+            match option.to_ascii_uppercase().as_str() {
+                "RANK" => {
+                    lpos.rank = parser.next()?;
+                    if lpos.rank == 0 {
+                        return Err(CommandError::ZeroRank);
+                    }
+                }
+                "COUNT" => {
+                    let count = usize::try_from(parser.next::<i64>()?)
+                        .map_err(|_| CommandError::NegativeOption("COUNT".to_string()))?;
+                    lpos.count = Some(count);
+                }
+                "MAXLEN" => {
+                    lpos.maxlen = usize::try_from(parser.next::<i64>()?)
+                        .map_err(|_| CommandError::NegativeOption("MAXLEN".to_string()))?;
+                }
+                _ => return Err(CommandError::SyntaxError),
+            }
+        }
+        Ok(lpos)
+    }
+}
+
+impl LPos {
+    /// Indexes of the matches, from the head whatever the scan direction
+    fn matches(&self, elements: impl Iterator<Item = (usize, Vec<u8>)>) -> Vec<usize> {
+        let maxlen = if self.maxlen == 0 {
+            usize::MAX
+        } else {
+            self.maxlen
+        };
+        let count = match self.count {
+            Some(0) => usize::MAX,
+            Some(count) => count,
+            None => 1,
+        };
+        elements
+            .take(maxlen)
+            .filter(|(_, element)| *element == self.element)
+            .skip(self.rank.unsigned_abs() as usize - 1)
+            .take(count)
+            .map(|(index, _)| index)
+            .collect()
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for LPos {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let matches = ctx
+            .db()
+            .get_with(&self.key, |value| {
+                let list = as_list(value)?;
+                Ok::<_, CommandError>(if self.rank > 0 {
+                    self.matches(list.iter().enumerate())
+                } else {
+                    self.matches((0..list.len()).rev().zip(list.iter().rev()))
+                })
+            })
+            .transpose()?
+            .unwrap_or_default();
+        Ok(match self.count {
+            Some(_) => Frame::Array(Some(
+                matches
+                    .into_iter()
+                    .map(|index| Frame::Integer(index as i64))
+                    .collect(),
+            )),
+            None => matches
+                .first()
+                .map_or(Frame::Null, |&index| Frame::Integer(index as i64)),
+        })
+    }
+}
+
+async fn lpos(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: LPos = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("LPOS", lpos);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{
+            CommandExecutor,
+            list::{as_list_mut, lpos::LPos},
+            parser::Parser,
+        },
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_lpos() {
+        assert!(LPos::try_from(Parser::from_args(&["LPOS", "list", "a", "RANK", "0"])).is_err());
+        assert!(LPos::try_from(Parser::from_args(&["LPOS", "list", "a", "COUNT", "-1"])).is_err());
+        assert!(LPos::try_from(Parser::from_args(&["LPOS", "list", "a", "MAXLEN"])).is_err());
+
+        let ctx = Context::test_client(1);
+        ctx.db()
+            .modify_or_insert("list", RedisObject::new_list, |value| {
+                let list = as_list_mut(value).unwrap();
+                for element in ["a", "b", "c", "1", "2", "3", "c", "c"] {
+                    list.push_back(element.as_bytes());
+                }
+            });
+        let lpos = |args: &[&str]| {
+            LPos::try_from(Parser::from_args(args))
+                .unwrap()
+                .execute(ctx.clone())
+        };
+        let indexes = |indexes: &[i64]| {
+            Frame::Array(Some(indexes.iter().map(|&i| Frame::Integer(i)).collect()))
+        };
+        assert_eq!(
+            lpos(&["LPOS", "list", "c"]).await.unwrap(),
+            Frame::Integer(2)
+        );
+        assert_eq!(
+            lpos(&["LPOS", "list", "c", "RANK", "2"]).await.unwrap(),
+            Frame::Integer(6)
+        );
+        assert_eq!(
+            lpos(&["LPOS", "list", "c", "RANK", "-1", "COUNT", "2"])
+                .await
+                .unwrap(),
+            indexes(&[7, 6])
+        );
+        assert_eq!(
+            lpos(&["LPOS", "list", "c", "COUNT", "0"]).await.unwrap(),
+            indexes(&[2, 6, 7])
+        );
+        assert_eq!(
+            lpos(&["LPOS", "list", "c", "COUNT", "0", "MAXLEN", "7"])
+                .await
+                .unwrap(),
+            indexes(&[2, 6])
+        );
+        assert_eq!(lpos(&["LPOS", "list", "z"]).await.unwrap(), Frame::Null);
+        assert_eq!(
+            lpos(&["LPOS", "missing", "a", "COUNT", "1"]).await.unwrap(),
+            indexes(&[])
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        list::{End, as_list_mut},
+        parser::Parser,
+        registry::CommandResult,
+    },
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    object::redis_object::RedisObject,
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `LPUSH key element [element ...]` and `RPUSH`, `LPUSHX`, `RPUSHX`, replies the length of
+/// the list. The `X` variants only push to an existing list.
+#[derive(PartialEq, Eq, Debug)]
+struct Push {
+    end: End,
+    only_existing: bool,
+    key: String,
+    elements: Vec<Vec<u8>>,
+}
+
+impl Push {
+    fn parse(end: End, only_existing: bool, mut parser: Parser) -> Result<Self, CommandError> {
+        Ok(Push {
+            end,
+            only_existing,
+            key: parser.next()?,
+            elements: parser.remaining()?,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for Push {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let id = db.id();
+        let push = |value: &mut RedisObject| {
+            let list = as_list_mut(value)?;
+            for element in &self.elements {
+                self.end.push(list, element);
+            }
+            notify_keyspace_event(notify::LIST, self.end.push_event(), &self.key, id);
+            Ok::<_, CommandError>(list.len())
+        };
+        let len = if self.only_existing {
+            db.modify(&self.key, push).transpose()?.unwrap_or(0)
+        } else {
+            db.modify_or_insert(&self.key, RedisObject::new_list, push)?
+        };
+        Ok(Frame::Integer(len as i64))
+    }
+}
+
+async fn lpush(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    Push::parse(End::Left, false, parser)?.execute(ctx).await
+}
+
+async fn rpush(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    Push::parse(End::Right, false, parser)?.execute(ctx).await
+}
+
+async fn lpushx(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    Push::parse(End::Left, true, parser)?.execute(ctx).await
+}
+
+async fn rpushx(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    Push::parse(End::Right, true, parser)?.execute(ctx).await
+}
+
+register_redis_command!("LPUSH", lpush);
+register_redis_command!("RPUSH", rpush);
+register_redis_command!("LPUSHX", lpushx);
+register_redis_command!("RPUSHX", rpushx);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{
+            CommandExecutor,
+            list::{End, lpush::Push},
+        },
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_push() {
+        let ctx = Context::test_client(1);
+        let push = |end: End, only_existing: bool, key: &str, elements: &[&str]| {
+            Push {
+                end,
+                only_existing,
+                key: key.to_string(),
+                elements: elements.iter().map(|e| e.as_bytes().to_vec()).collect(),
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(
+            push(End::Left, true, "list", &["a"]).await.unwrap(),
+            Frame::Integer(0)
+        );
+        assert!(!ctx.db().contains_key("list"));
+        assert_eq!(
+            push(End::Left, false, "list", &["b", "a"]).await.unwrap(),
+            Frame::Integer(2)
+        );
+        assert_eq!(
+            push(End::Right, true, "list", &["c"]).await.unwrap(),
+            Frame::Integer(3)
+        );
+        let list = ctx.db().get("list").unwrap();
+        let bulk = |s: &str| Frame::BulkString(Some(s.as_bytes().to_vec()));
+        assert_eq!(
+            Frame::from(list),
+            Frame::Array(Some(vec![bulk("a"), bulk("b"), bulk("c")]))
+        );
+        let usage = ctx.db().memory_usage("list").unwrap();
+        assert_eq!(ctx.db().used_memory(), usage);
+
+        ctx.db().set(
+            "string".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+        assert!(push(End::Left, false, "string", &["a"]).await.is_err());
+    }
+}
@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        list::{as_list, list_range},
+        registry::CommandResult,
+    },
+    context::Context,
+    protocol::Frame,
+};
+
+/// `LRANGE key start stop`, both ends included and negative indexes count from the tail
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("LRANGE")]
+struct LRange {
+    key: String,
+    start: i64,
+    stop: i64,
+}
+
+#[async_trait]
+impl CommandExecutor for LRange {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let elements = ctx
+            .db()
+            .get_with(&self.key, |value| {
+                let list = as_list(value)?;
+                Ok::<_, CommandError>(match list_range(self.start, self.stop, list.len()) {
+                    Some((start, stop)) => list.range(start, stop),
+                    None => Vec::new(),
+                })
+            })
+            .transpose()?
+            .unwrap_or_default();
+        Ok(Frame::Array(Some(
+            elements
+                .into_iter()
+                .map(|element| Frame::BulkString(Some(element)))
+                .collect(),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{
+            CommandExecutor,
+            list::{as_list_mut, lrange::LRange},
+        },
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_lrange() {
+        let ctx = Context::test_client(1);
+        ctx.db()
+            .modify_or_insert("list", RedisObject::new_list, |value| {
+                let list = as_list_mut(value).unwrap();
+                for i in 0..5 {
+                    list.push_back(i.to_string().as_bytes());
+                }
+            });
+        let lrange = |key: &str, start: i64, stop: i64| {
+            LRange {
+                key: key.to_string(),
+                start,
+                stop,
+            }
+            .execute(ctx.clone())
+        };
+        let array = |elements: &[&str]| {
+            Frame::Array(Some(
+                elements
+                    .iter()
+                    .map(|e| Frame::BulkString(Some(e.as_bytes().to_vec())))
+                    .collect(),
+            ))
+        };
+        assert_eq!(lrange("list", 1, 2).await.unwrap(), array(&["1", "2"]));
+        assert_eq!(lrange("list", -2, 100).await.unwrap(), array(&["3", "4"]));
+        assert_eq!(lrange("list", 3, 1).await.unwrap(), array(&[]));
+        assert_eq!(lrange("missing", 0, -1).await.unwrap(), array(&[]));
+    }
+}
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, list::as_list_mut, registry::CommandResult},
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+};
+
+/// `LREM key count element`, removes the first `count` occurrences from the head, or from the
+/// tail when `count` is negative. 0 removes all of them.
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("LREM")]
+struct LRem {
+    key: String,
+    count: i64,
+    element: Vec<u8>,
+}
+
+#[async_trait]
+impl CommandExecutor for LRem {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let id = db.id();
+        let removed = db
+            .modify(&self.key, |value| {
+                let list = as_list_mut(value)?;
+                let removed = list.remove_matching(
+                    &self.element,
+                    self.count.unsigned_abs() as usize,
+                    self.count < 0,
+                );
+                if removed > 0 {
+                    notify_keyspace_event(notify::LIST, "lrem", &self.key, id);
+                }
+                Ok::<_, CommandError>(removed)
+            })
+            .transpose()?
+            .unwrap_or(0);
+        Ok(Frame::Integer(removed as i64))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{
+            CommandExecutor,
+            list::{as_list, as_list_mut, lrem::LRem},
+        },
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_lrem() {
+        let ctx = Context::test_client(1);
+        ctx.db()
+            .modify_or_insert("list", RedisObject::new_list, |value| {
+                let list = as_list_mut(value).unwrap();
+                for element in ["a", "b", "a", "c", "a", "b"] {
+                    list.push_back(element.as_bytes());
+                }
+            });
+        let lrem = |count: i64, element: &str| {
+            LRem {
+                key: "list".to_string(),
+                count,
+                element: element.as_bytes().to_vec(),
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(lrem(-2, "a").await.unwrap(), Frame::Integer(2));
+        assert_eq!(lrem(1, "b").await.unwrap(), Frame::Integer(1));
+        assert_eq!(lrem(1, "z").await.unwrap(), Frame::Integer(0));
+        let elements = ctx
+            .db()
+            .get_with("list", |value| as_list(value).unwrap().range(0, 2))
+            .unwrap();
+        assert_eq!(elements, [&b"a"[..], b"c", b"b"]);
+        assert_eq!(lrem(0, "a").await.unwrap(), Frame::Integer(1));
+        assert_eq!(lrem(0, "b").await.unwrap(), Frame::Integer(1));
+        assert_eq!(lrem(0, "c").await.unwrap(), Frame::Integer(1));
+        assert!(!ctx.db().contains_key("list"));
+        assert_eq!(lrem(0, "c").await.unwrap(), Frame::Integer(0));
+    }
+}
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        list::{as_list_mut, list_index},
+        registry::CommandResult,
+    },
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+};
+
+/// `LSET key index element`
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("LSET")]
+struct LSet {
+    key: String,
+    index: i64,
+    element: Vec<u8>,
+}
+
+#[async_trait]
+impl CommandExecutor for LSet {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let id = db.id();
+        db.modify(&self.key, |value| {
+            let list = as_list_mut(value)?;
+            let index = list_index(self.index, list.len()).ok_or(CommandError::IndexOutOfRange)?;
+            list.set(index, &self.element);
+            notify_keyspace_event(notify::LIST, "lset", &self.key, id);
+            Ok(())
+        })
+        .unwrap_or(Err(CommandError::NoSuchKey))?;
+        Ok(Frame::SimpleString("OK".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{
+            CommandExecutor,
+            list::{as_list, as_list_mut, lset::LSet},
+        },
+        context::Context,
+        object::redis_object::RedisObject,
+    };
+
+    #[tokio::test]
+    async fn test_lset() {
+        let ctx = Context::test_client(1);
+        let lset = |index: i64, element: &str| {
+            LSet {
+                key: "list".to_string(),
+                index,
+                element: element.as_bytes().to_vec(),
+            }
+            .execute(ctx.clone())
+        };
+        assert!(lset(0, "x").await.is_err());
+        ctx.db()
+            .modify_or_insert("list", RedisObject::new_list, |value| {
+                let list = as_list_mut(value).unwrap();
+                list.push_back(b"a");
+                list.push_back(b"b");
+            });
+        assert!(lset(-1, "c").await.is_ok());
+        assert!(lset(0, "long element").await.is_ok());
+        assert!(lset(2, "x").await.is_err());
+        let elements = ctx
+            .db()
+            .get_with("list", |value| as_list(value).unwrap().range(0, 1))
+            .unwrap();
+        assert_eq!(elements, [b"long element".to_vec(), b"c".to_vec()]);
+    }
+}
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        list::{as_list_mut, list_range},
+        registry::CommandResult,
+    },
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+};
+
+/// `LTRIM key start stop`, an empty range deletes the list
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("LTRIM")]
+struct LTrim {
+    key: String,
+    start: i64,
+    stop: i64,
+}
+
+#[async_trait]
+impl CommandExecutor for LTrim {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let id = db.id();
+        db.modify(&self.key, |value| {
+            let list = as_list_mut(value)?;
+            let (start, stop) = list_range(self.start, self.stop, list.len()).unwrap_or((1, 0));
+            list.trim(start, stop);
+            notify_keyspace_event(notify::LIST, "ltrim", &self.key, id);
+            Ok::<_, CommandError>(())
+        })
+        .transpose()?;
+        Ok(Frame::SimpleString("OK".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{
+            CommandExecutor,
+            list::{as_list, as_list_mut, ltrim::LTrim},
+        },
+        context::Context,
+        object::redis_object::RedisObject,
+    };
+
+    #[tokio::test]
+    async fn test_ltrim() {
+        let ctx = Context::test_client(1);
+        ctx.db()
+            .modify_or_insert("list", RedisObject::new_list, |value| {
+                let list = as_list_mut(value).unwrap();
+                for i in 0..1000 {
+                    list.push_back(i.to_string().as_bytes());
+                }
+            });
+        let ltrim = |start: i64, stop: i64| {
+            LTrim {
+                key: "list".to_string(),
+                start,
+                stop,
+            }
+            .execute(ctx.clone())
+        };
+        ltrim(100, -101).await.unwrap();
+        let (len, first, last) = ctx
+            .db()
+            .get_with("list", |value| {
+                let list = as_list(value).unwrap();
+                (list.len(), list.get(0), list.get(list.len() - 1))
+            })
+            .unwrap();
+        assert_eq!(len, 800);
+        assert_eq!(first, Some(b"100".to_vec()));
+        assert_eq!(last, Some(b"899".to_vec()));
+
+        ltrim(5, 1).await.unwrap();
+        assert!(!ctx.db().contains_key("list"));
+        assert_eq!(ctx.db().used_memory(), 0);
+        assert!(ltrim(0, -1).await.is_ok());
+    }
+}
@@ -0,0 +1,230 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::time::Instant;
+
+use crate::{
+    blocked::{BLOCKED, UnblockReason},
+    command::{error::CommandError, parser::Parser},
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    object::{
+        encoding::quicklist::Quicklist,
+        redis_object::{RedisObject, RedisValue},
+    },
+};
+
+mod blpop;
+mod lindex;
+mod linsert;
+mod llen;
+mod lmove;
+mod lmpop;
+mod lpop;
+mod lpos;
+mod lpush;
+mod lrange;
+mod lrem;
+mod lset;
+mod ltrim;
+
+/// The end of a list elements are pushed to or popped from
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum End {
+    Left,
+    Right,
+}
+
+impl End {
+    /// `LEFT | RIGHT`
+    fn parse(parser: &mut Parser) -> Result<Self, CommandError> {
+        let end: String = parser.next()?;
+        match end.to_ascii_uppercase().as_str() {
+            "LEFT" => Ok(End::Left),
+            "RIGHT" => Ok(End::Right),
+            _ => Err(CommandError::SyntaxError),
+        }
+    }
+
+    fn push(self, list: &mut Quicklist, element: &[u8]) {
+        match self {
+            End::Left => list.push_front(element),
+            End::Right => list.push_back(element),
+        }
+    }
+
+    fn pop(self, list: &mut Quicklist) -> Option<Vec<u8>> {
+        match self {
+            End::Left => list.pop_front(),
+            End::Right => list.pop_back(),
+        }
+    }
+
+    /// Keyspace event of a push to this end
+    fn push_event(self) -> &'static str {
+        match self {
+            End::Left => "lpush",
+            End::Right => "rpush",
+        }
+    }
+
+    fn pop_event(self) -> &'static str {
+        match self {
+            End::Left => "lpop",
+            End::Right => "rpop",
+        }
+    }
+}
+
+fn as_list(value: &RedisObject) -> Result<&Quicklist, CommandError> {
+    match &value.ptr {
+        RedisValue::QuickList(list) => Ok(list),
+        _ => Err(CommandError::WrongType),
+    }
+}
+
+fn as_list_mut(value: &mut RedisObject) -> Result<&mut Quicklist, CommandError> {
+    match &mut value.ptr {
+        RedisValue::QuickList(list) => Ok(list),
+        _ => Err(CommandError::WrongType),
+    }
+}
+
+/// The position of `index`, which counts from the tail when negative
+fn list_index(index: i64, len: usize) -> Option<usize> {
+    let index = if index < 0 {
+        index.checked_add(len as i64)?
+    } else {
+        index
+    };
+    usize::try_from(index).ok().filter(|&index| index < len)
+}
+
+/// The inclusive range of `start stop` in a list of `len`, `None` when it's empty.
+///
+/// Negative indexes count from the tail and the range is clamped to the list.
+fn list_range(start: i64, stop: i64, len: usize) -> Option<(usize, usize)> {
+    let len = len as i64;
+    let start = if start < 0 {
+        (len + start).max(0)
+    } else {
+        start
+    };
+    let stop = if stop < 0 {
+        len + stop
+    } else {
+        stop.min(len - 1)
+    };
+    if start > stop || start >= len {
+        return None;
+    }
+    Some((start as usize, stop as usize))
+}
+
+/// A key and the elements popped from it
+type Popped = (String, Vec<Vec<u8>>);
+
+/// Pop up to `count` elements from the first of `keys` holding a list
+fn pop_first(
+    ctx: &Context,
+    keys: &[String],
+    end: End,
+    count: usize,
+) -> Result<Option<Popped>, CommandError> {
+    let db = ctx.db();
+    for key in keys {
+        let popped = db
+            .modify(key, |value| {
+                let list = as_list_mut(value)?;
+                let popped: Vec<Vec<u8>> = (0..count).map_while(|_| end.pop(list)).collect();
+                if !popped.is_empty() {
+                    notify_keyspace_event(notify::LIST, end.pop_event(), key, db.id());
+                }
+                Ok::<_, CommandError>(popped)
+            })
+            .transpose()?;
+        if let Some(popped) = popped.filter(|popped| !popped.is_empty()) {
+            return Ok(Some((key.clone(), popped)));
+        }
+    }
+    Ok(None)
+}
+
+/// The timeout of a blocking command in seconds, 0 is `None` and blocks forever
+fn parse_timeout(timeout: &str) -> Result<Option<Duration>, CommandError> {
+    let timeout: f64 = timeout.parse().map_err(|_| CommandError::InvalidTimeout)?;
+    if !timeout.is_finite() {
+        return Err(CommandError::InvalidTimeout);
+    }
+    if timeout < 0.0 {
+        return Err(CommandError::NegativeTimeout);
+    }
+    if timeout == 0.0 {
+        return Ok(None);
+    }
+    Duration::try_from_secs_f64(timeout)
+        .map(Some)
+        .map_err(|_| CommandError::InvalidTimeout)
+}
+
+/// Serve the client by `serve`, which returns `None` while none of `keys` can be served.
+///
+/// The client is blocked until one of `keys` is pushed to, `None` once `timeout` elapsed.
+async fn block_for_keys<T>(
+    ctx: &Arc<Context>,
+    keys: &[String],
+    timeout: Option<Duration>,
+    mut serve: impl FnMut() -> Result<Option<T>, CommandError>,
+) -> Result<Option<T>, CommandError> {
+    if let Some(served) = serve()? {
+        return Ok(Some(served));
+    }
+    // a deadline too far off to be represented never comes
+    let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+    let _guard = BLOCKED.block(ctx, ctx.db_index(), keys);
+    loop {
+        // a push may have come before the client was queued
+        if let Some(served) = serve()? {
+            return Ok(Some(served));
+        }
+        tokio::select! {
+            _ = ctx.woken() => {}
+            _ = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now)),
+                if deadline.is_some() => return Ok(None),
+            // the reply isn't sent anyway
+            _ = ctx.killed() => return Ok(None),
+        }
+        match ctx.take_unblock() {
+            Some(UnblockReason::Timeout) => return Ok(None),
+            Some(UnblockReason::Error) => return Err(CommandError::Unblocked),
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::command::list::{list_index, list_range, parse_timeout};
+
+    #[test]
+    fn test_list_range() {
+        assert_eq!(list_index(-1, 3), Some(2));
+        assert_eq!(list_index(3, 3), None);
+        assert_eq!(list_index(-4, 3), None);
+        assert_eq!(list_range(0, -1, 3), Some((0, 2)));
+        assert_eq!(list_range(-100, 100, 3), Some((0, 2)));
+        assert_eq!(list_range(2, 1, 3), None);
+        assert_eq!(list_range(5, 10, 3), None);
+        assert_eq!(list_range(0, -4, 3), None);
+        assert_eq!(list_range(0, 0, 0), None);
+
+        assert_eq!(parse_timeout("0").unwrap(), None);
+        assert_eq!(
+            parse_timeout("0.5").unwrap(),
+            Some(std::time::Duration::from_millis(500))
+        );
+        assert!(parse_timeout("-1").is_err());
+        assert!(parse_timeout("inf").is_err());
+        assert!(parse_timeout("soon").is_err());
+        assert!(parse_timeout("1e20").is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use std::{fmt::Debug, sync::Arc};
 
 use async_trait::async_trait;
+use rand::{Rng, seq::SliceRandom};
 
 use crate::{
     command::{
@@ -9,16 +10,46 @@ use crate::{
         registry::{COMMAND_REGISTRY, CommandHandler},
     },
     context::Context,
-    protocol::Frame,
+    protocol::{Frame, ProtocolVersion},
+    storage::evict::{EvictResult, perform_evictions},
 };
 
 pub mod connection;
 pub mod error;
+pub mod generic;
+pub mod hash;
+pub mod list;
 pub mod parser;
+pub mod pubsub;
 pub mod registry;
+pub mod server;
+pub mod set;
 pub mod string;
+pub mod zset;
 mod option;
 
+/// Commands which may grow the used memory, refused when it can't be brought below
+/// `maxmemory`. The `denyoom` flag of redis.
+const DENY_OOM_COMMANDS: [&str; 24] = [
+    "blmove", "copy", "getset", "hincrby", "hincrbyfloat", "hmset", "hset", "hsetex", "hsetnx",
+    "linsert", "lmove", "lpush", "lpushx", "lset", "rpush", "rpushx", "sadd", "sdiffstore",
+    "set", "sinterstore", "smove", "sunionstore", "zadd", "zincrby",
+];
+
+/// Commands which may block the client until a key is pushed to
+const BLOCKING_COMMANDS: [&str; 4] = ["blmove", "blmpop", "blpop", "brpop"];
+
+/// Commands a RESP2 client may send once it subscribed to a channel or pattern
+const SUBSCRIBED_COMMANDS: [&str; 7] = [
+    "ping",
+    "psubscribe",
+    "punsubscribe",
+    "quit",
+    "reset",
+    "subscribe",
+    "unsubscribe",
+];
+
 #[async_trait]
 pub trait CommandExecutor: Send + Sync {
     async fn execute(self, ctx: Arc<Context>) -> Result<Frame, CommandError>;
@@ -26,6 +57,7 @@ pub trait CommandExecutor: Send + Sync {
 
 #[derive(Debug)]
 pub struct Command {
+    name: String,
     handler: CommandHandler,
     parser: Parser,
 }
@@ -36,19 +68,83 @@ impl Command {
         let name_upper = parser.next::<String>()?.to_ascii_uppercase();
         let reg = COMMAND_REGISTRY.read().await;
         if let Some(&handler) = reg.get(name_upper.as_str()) {
-            Ok(Command { handler, parser })
+            Ok(Command {
+                name: name_upper.to_ascii_lowercase(),
+                handler,
+                parser,
+            })
         } else {
-            Err(CommandError::InvalidCommand(name_upper.into()))
+            Err(CommandError::InvalidCommand(name_upper))
         }
     }
+
+    /// Lowercase command name, as shown in the `cmd` field of `CLIENT LIST`
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The connection watches for the client to go away while it's blocked
+    pub fn may_block(&self) -> bool {
+        BLOCKING_COMMANDS.contains(&self.name.as_str())
+    }
+
+    fn deny_oom(&self) -> bool {
+        DENY_OOM_COMMANDS.contains(&self.name.as_str())
+    }
 }
 
 #[async_trait]
 impl CommandExecutor for Command {
     async fn execute(self, ctx: Arc<Context>) -> Result<Frame, CommandError> {
+        // RESP3 clients receive messages as pushes, so they can keep sending any command
+        if ctx.protocol_version() == ProtocolVersion::Resp2
+            && ctx.is_subscribed()
+            && !SUBSCRIBED_COMMANDS.contains(&self.name.as_str())
+        {
+            return Ok(Frame::Error(
+                CommandError::SubscribedContext(self.name).to_string(),
+            ));
+        }
+        if perform_evictions(&ctx.dbs) == EvictResult::Fail && self.deny_oom() {
+            return Ok(Frame::Error(CommandError::OutOfMemory.to_string()));
+        }
         match (self.handler)(ctx, self.parser).await {
             Ok(result) => Ok(result),
             Err(error) => Ok(Frame::Error(error.to_string())),
         }
     }
 }
+
+/// Random items of `HRANDFIELD` and `SRANDMEMBER`, out of the `len` ones `items` yields.
+///
+/// A positive `count` picks distinct items, at most `len`, a negative one picks `-count` items
+/// which may repeat. The items are walked once, they aren't collected to be indexed.
+pub fn random_picks<T: Clone>(items: impl Iterator<Item = T>, len: usize, count: i64) -> Vec<T> {
+    if len == 0 {
+        return Vec::new();
+    }
+    let mut rng = rand::rng();
+    let mut indexes = if count >= 0 {
+        rand::seq::index::sample(&mut rng, len, (count as usize).min(len)).into_vec()
+    } else {
+        let mut indexes = Vec::new();
+        for _ in 0..count.unsigned_abs() {
+            indexes.push(rng.random_range(0..len));
+        }
+        indexes
+    };
+    indexes.sort_unstable();
+    let mut picks = Vec::with_capacity(indexes.len());
+    let mut indexes = indexes.into_iter().peekable();
+    for (index, item) in items.enumerate() {
+        while indexes.next_if_eq(&index).is_some() {
+            picks.push(item.clone());
+        }
+        if indexes.peek().is_none() {
+            break;
+        }
+    }
+    // the walk picked them in the order of `items`
+    picks.shuffle(&mut rng);
+    picks
+}
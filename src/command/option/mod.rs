@@ -1,9 +1,17 @@
-use crate::command::error::CommandError;
+use std::time::SystemTime;
 
+use crate::{
+    command::{error::CommandError, parser::Parser},
+    object::redis_object::ObjectType,
+    protocol::Frame,
+    util::{string_match, unix_millis},
+};
+
+#[allow(clippy::upper_case_acronyms)]
 #[derive(PartialEq, Eq, Debug)]
 pub(super) enum Expiration {
-    EX(u64), // seconds
-    PX(u64), // milliseconds
+    EX(u64),   // seconds
+    PX(u64),   // milliseconds
     EXAT(u64), // absolute timestamp in seconds
     PXAT(u64), // absolute timestamp in milliseconds
 }
@@ -14,13 +22,216 @@ impl TryFrom<(String, String)> for Expiration {
     fn try_from(pair: (String, String)) -> Result<Self, Self::Error> {
         let (k, v_str) = pair;
         let key = k.to_uppercase();
-        let v = v_str.parse::<u64>().map_err(|e| CommandError::ParseIntError(e))?;
+        let v = v_str.parse::<u64>().map_err(CommandError::ParseIntError)?;
         match key.as_str() {
             "EX" => Ok(Expiration::EX(v)),
             "PX" => Ok(Expiration::PX(v)),
             "EXAT" => Ok(Expiration::EXAT(v)),
             "PXAT" => Ok(Expiration::PXAT(v)),
-            _ => Err(CommandError::InvalidCommandFormat(format!("Unknown expiration type {}", k))),
+            _ => Err(CommandError::InvalidCommandFormat(format!(
+                "Unknown expiration type {}",
+                k
+            ))),
+        }
+    }
+}
+
+impl Expiration {
+    /// Absolute expire time in unix milliseconds, `None` on overflow
+    pub fn unix_millis(&self, now: SystemTime) -> Option<i64> {
+        let (kind, time) = match *self {
+            Expiration::EX(time) => (ExpireKind::Seconds, time),
+            Expiration::PX(time) => (ExpireKind::Millis, time),
+            Expiration::EXAT(time) => (ExpireKind::UnixSeconds, time),
+            Expiration::PXAT(time) => (ExpireKind::UnixMillis, time),
+        };
+        kind.unix_millis(i64::try_from(time).ok()?, now)
+    }
+}
+
+/// How the time argument of the `EXPIRE` family is read, `HEXPIRE` reads it the same way
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub(super) enum ExpireKind {
+    /// `EXPIRE`, seconds from now
+    Seconds,
+    /// `PEXPIRE`, milliseconds from now
+    Millis,
+    /// `EXPIREAT`, unix time in seconds
+    UnixSeconds,
+    /// `PEXPIREAT`, unix time in milliseconds
+    UnixMillis,
+}
+
+impl ExpireKind {
+    pub fn command(&self) -> &'static str {
+        match self {
+            ExpireKind::Seconds => "expire",
+            ExpireKind::Millis => "pexpire",
+            ExpireKind::UnixSeconds => "expireat",
+            ExpireKind::UnixMillis => "pexpireat",
         }
     }
+
+    /// Absolute expire time in unix milliseconds, `None` on overflow
+    pub fn unix_millis(&self, time: i64, now: SystemTime) -> Option<i64> {
+        let millis = match self {
+            ExpireKind::Seconds | ExpireKind::UnixSeconds => time.checked_mul(1000)?,
+            ExpireKind::Millis | ExpireKind::UnixMillis => time,
+        };
+        match self {
+            ExpireKind::Seconds | ExpireKind::Millis => millis.checked_add(unix_millis(now)),
+            ExpireKind::UnixSeconds | ExpireKind::UnixMillis => Some(millis),
+        }
+    }
+}
+
+/// What the `TTL` family replies, the `HTTL` family replies the same of each field
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub(super) enum TtlKind {
+    /// `TTL`, remaining seconds
+    Seconds,
+    /// `PTTL`, remaining milliseconds
+    Millis,
+    /// `EXPIRETIME`, unix time in seconds
+    UnixSeconds,
+    /// `PEXPIRETIME`, unix time in milliseconds
+    UnixMillis,
+}
+
+impl TtlKind {
+    /// The reply of the expire time `at` in unix milliseconds
+    pub fn reply(&self, at: i64, now: SystemTime) -> i64 {
+        let millis = match self {
+            TtlKind::Seconds | TtlKind::Millis => at.saturating_sub(unix_millis(now)),
+            TtlKind::UnixSeconds | TtlKind::UnixMillis => at,
+        }
+        .max(0);
+        match self {
+            // a TTL is rounded, a unix time truncated
+            TtlKind::Seconds => millis.saturating_add(500) / 1000,
+            TtlKind::UnixSeconds => millis / 1000,
+            TtlKind::Millis | TtlKind::UnixMillis => millis,
+        }
+    }
+}
+
+/// `NX | XX | GT | LT`, a key or field without TTL counts as an infinite TTL for `GT` and `LT`
+#[derive(PartialEq, Eq, Debug, Default, Clone, Copy)]
+pub(super) struct ExpireCondition {
+    pub nx: bool,
+    pub xx: bool,
+    pub gt: bool,
+    pub lt: bool,
+}
+
+impl ExpireCondition {
+    /// Set the flag named by `option`, false when it isn't one
+    pub fn parse_option(&mut self, option: &str) -> bool {
+        match option.to_ascii_uppercase().as_str() {
+            "NX" => self.nx = true,
+            "XX" => self.xx = true,
+            "GT" => self.gt = true,
+            "LT" => self.lt = true,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Refuse the flags which contradict each other
+    pub fn check(&self) -> Result<(), CommandError> {
+        if self.nx && (self.xx || self.gt || self.lt) {
+            return Err(CommandError::IncompatibleOptions(
+                "NX and XX, GT or LT".to_string(),
+            ));
+        }
+        if self.gt && self.lt {
+            return Err(CommandError::IncompatibleOptions("GT and LT".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Whether the expire time `at` may replace the current one
+    pub fn allows(&self, current: Option<i64>, at: i64) -> bool {
+        match current {
+            None => !(self.xx || self.gt),
+            Some(current) => !(self.nx || (self.gt && at <= current) || (self.lt && at >= current)),
+        }
+    }
+}
+
+/// Keys or members visited by one call of the `SCAN` family unless `COUNT` is given
+const DEFAULT_SCAN_COUNT: usize = 10;
+
+/// `cursor [MATCH pattern] [COUNT count]` of the `SCAN` family, plus `TYPE type` of `SCAN`,
+/// `NOVALUES` of `HSCAN` or `NOSCORES` of `ZSCAN`
+#[derive(PartialEq, Eq, Debug)]
+pub(super) struct ScanArgs {
+    pub cursor: u64,
+    /// `None` when every element matches
+    pub pattern: Option<String>,
+    pub count: usize,
+    pub obj_type: Option<ObjectType>,
+    /// Only the fields of `HSCAN` or the members of `ZSCAN` are replied
+    pub no_values: bool,
+}
+
+impl ScanArgs {
+    /// `extra` is the command specific option: `TYPE`, `NOVALUES` or `NOSCORES`
+    pub fn parse(parser: &mut Parser, extra: Option<&str>) -> Result<Self, CommandError> {
+        let cursor: String = parser.next()?;
+        let cursor = cursor.parse().map_err(|_| CommandError::InvalidCursor)?;
+        let mut args = ScanArgs {
+            cursor,
+            pattern: None,
+            count: DEFAULT_SCAN_COUNT,
+            obj_type: None,
+            no_values: false,
+        };
+        while parser.has_next() {
+            let option: String = parser.next()?;
+            let option = option.to_ascii_uppercase();
+            match option.as_str() {
+                "MATCH" => {
+                    let pattern: String = parser.next()?;
+                    args.pattern = (pattern != "*").then_some(pattern);
+                }
+                "COUNT" => {
+                    let count: i64 = parser.next()?;
+                    if count < 1 {
+                        return Err(CommandError::SyntaxError);
+                    }
+                    args.count = count as usize;
+                }
+                "TYPE" if extra == Some("TYPE") => {
+                    let name: String = parser.next()?;
+                    args.obj_type = Some(
+                        ObjectType::from_name(&name).ok_or(CommandError::UnknownTypeName(name))?,
+                    );
+                }
+                option if extra == Some(option) => args.no_values = true,
+                _ => return Err(CommandError::SyntaxError),
+            }
+        }
+        Ok(args)
+    }
+
+    /// Whether an element is returned, the elements of collections may be binary
+    pub fn matches(&self, member: &[u8]) -> bool {
+        self.pattern
+            .as_ref()
+            .is_none_or(|pattern| string_match(pattern.as_bytes(), member, false))
+    }
+}
+
+/// The `[cursor, [element ...]]` reply of the `SCAN` family
+pub(super) fn scan_reply(cursor: u64, elements: Vec<Vec<u8>>) -> Frame {
+    Frame::Array(Some(vec![
+        Frame::BulkString(Some(cursor.to_string().into_bytes())),
+        Frame::Array(Some(
+            elements
+                .into_iter()
+                .map(|element| Frame::BulkString(Some(element)))
+                .collect(),
+        )),
+    ]))
 }
@@ -14,7 +14,7 @@ impl Parser {
                 let cmd = match &parts[0] {
                     Frame::SimpleString(s) => s.clone(),
                     Frame::BulkString(Some(data)) => String::from_utf8(data.clone())
-                        .map_err(|e| CommandError::FromUtf8Error(e))?,
+                        .map_err(CommandError::FromUtf8Error)?,
                     _ => return Err(CommandError::WrongType),
                 };
 
@@ -35,6 +35,10 @@ impl Parser {
         self.parts.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
     pub fn next_pair<T>(&mut self) -> Result<(String, T), CommandError>
     where
         T: TryFrom<Frame, Error = CommandError>,
@@ -43,7 +47,8 @@ impl Parser {
         let value: T = self.next()?;
         Ok((key, value))
     }
-    
+
+    #[allow(clippy::should_implement_trait)]
     pub fn next<T>(&mut self) -> Result<T, CommandError>
     where
         T: TryFrom<Frame, Error = CommandError>,
@@ -59,11 +64,37 @@ impl Parser {
         part.try_into()
     }
 
+    /// Collect the remaining arguments, at least one is required
+    pub fn remaining<T>(&mut self) -> Result<Vec<T>, CommandError>
+    where
+        T: TryFrom<Frame, Error = CommandError>,
+    {
+        let mut values = vec![self.next()?];
+        while self.has_next() {
+            values.push(self.next()?);
+        }
+        Ok(values)
+    }
+
     pub fn has_next(&self) -> bool {
         self.cursor < self.parts.len()
     }
 }
 
+#[cfg(test)]
+impl Parser {
+    /// A parser of `args` positioned after the command name, as `Command::parse` leaves it
+    pub fn from_args(args: &[&str]) -> Self {
+        let parts = args
+            .iter()
+            .map(|arg| Frame::BulkString(Some(arg.as_bytes().to_vec())))
+            .collect();
+        let mut parser = Parser::new(Frame::Array(Some(parts))).unwrap();
+        parser.next::<String>().unwrap();
+        parser
+    }
+}
+
 impl TryFrom<Frame> for String {
     type Error = CommandError;
 
@@ -71,7 +102,7 @@ impl TryFrom<Frame> for String {
         match value {
             Frame::SimpleString(s) => Ok(s),
             Frame::BulkString(Some(bytes)) => {
-                String::from_utf8(bytes).map_err(|e| CommandError::FromUtf8Error(e))
+                String::from_utf8(bytes).map_err(CommandError::FromUtf8Error)
             }
             _ => Err(CommandError::WrongType),
         }
@@ -85,8 +116,8 @@ impl TryFrom<Frame> for i64 {
         match value {
             Frame::Integer(num) => Ok(num),
             Frame::BulkString(Some(bytes)) => {
-                let s = String::from_utf8(bytes).map_err(|e| CommandError::FromUtf8Error(e))?;
-                s.parse().map_err(|e| CommandError::ParseIntError(e))
+                let s = String::from_utf8(bytes).map_err(CommandError::FromUtf8Error)?;
+                s.parse().map_err(CommandError::ParseIntError)
             }
             _ => Err(CommandError::WrongType),
         }
@@ -0,0 +1,3 @@
+mod publish;
+mod subscribe;
+mod unsubscribe;
@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, registry::CommandResult},
+    context::Context,
+    protocol::Frame,
+    pubsub::PUBSUB,
+};
+
+/// `PUBLISH channel message`, replies the count of clients which received it
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("PUBLISH")]
+struct Publish {
+    channel: String,
+    message: Vec<u8>,
+}
+
+#[async_trait]
+impl CommandExecutor for Publish {
+    async fn execute(self, _ctx: Arc<Context>) -> CommandResult {
+        let receivers = PUBSUB.publish(&self.channel, &self.message);
+        Ok(Frame::Integer(receivers as i64))
+    }
+}
@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, parser::Parser, registry::CommandResult},
+    context::Context,
+    protocol::Frame,
+    pubsub::PUBSUB,
+    register_redis_command,
+};
+
+/// `SUBSCRIBE channel [channel ...]` and `PSUBSCRIBE pattern [pattern ...]`.
+///
+/// Each channel is confirmed by its own push, so the command has no reply of its own.
+#[derive(PartialEq, Eq, Debug)]
+struct Subscribe {
+    pattern: bool,
+    channels: Vec<String>,
+}
+
+impl Subscribe {
+    fn parse(pattern: bool, mut parser: Parser) -> Result<Self, CommandError> {
+        Ok(Subscribe {
+            pattern,
+            channels: parser.remaining()?,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for Subscribe {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let kind = if self.pattern {
+            "psubscribe"
+        } else {
+            "subscribe"
+        };
+        for channel in self.channels {
+            if self.pattern {
+                PUBSUB.psubscribe(&ctx, &channel);
+            } else {
+                PUBSUB.subscribe(&ctx, &channel);
+            }
+            let count = ctx.subscriptions().len();
+            ctx.push(Frame::Push(vec![
+                Frame::BulkString(Some(kind.as_bytes().to_vec())),
+                Frame::BulkString(Some(channel.into_bytes())),
+                Frame::Integer(count as i64),
+            ]));
+        }
+        ctx.skip_reply();
+        Ok(Frame::Null)
+    }
+}
+
+async fn subscribe(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    Subscribe::parse(false, parser)?.execute(ctx).await
+}
+
+async fn psubscribe(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    Subscribe::parse(true, parser)?.execute(ctx).await
+}
+
+register_redis_command!("SUBSCRIBE", subscribe);
+register_redis_command!("PSUBSCRIBE", psubscribe);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, pubsub::subscribe::Subscribe},
+        context::Context,
+        protocol::Frame,
+        pubsub::PUBSUB,
+    };
+
+    fn bulk(data: &str) -> Frame {
+        Frame::BulkString(Some(data.as_bytes().to_vec()))
+    }
+
+    #[tokio::test]
+    async fn test_subscribe() {
+        let ctx = Context::test_client(1);
+        let mut pushes = ctx.take_push_receiver().unwrap();
+        Subscribe {
+            pattern: false,
+            channels: vec!["test-sub-a".to_string(), "test-sub-a".to_string()],
+        }
+        .execute(ctx.clone())
+        .await
+        .unwrap();
+        Subscribe {
+            pattern: true,
+            channels: vec!["test-sub-*".to_string()],
+        }
+        .execute(ctx.clone())
+        .await
+        .unwrap();
+        assert!(ctx.take_skip_reply());
+        assert!(ctx.is_subscribed());
+
+        for (kind, channel, count) in [
+            ("subscribe", "test-sub-a", 1),
+            ("subscribe", "test-sub-a", 1),
+            ("psubscribe", "test-sub-*", 2),
+        ] {
+            assert_eq!(
+                pushes.try_recv().unwrap(),
+                Frame::Push(vec![bulk(kind), bulk(channel), Frame::Integer(count)])
+            );
+        }
+        assert_eq!(PUBSUB.publish("test-sub-a", b"hello"), 2);
+        PUBSUB.remove_client(&ctx);
+    }
+}
@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, parser::Parser, registry::CommandResult},
+    context::Context,
+    protocol::Frame,
+    pubsub::PUBSUB,
+    register_redis_command,
+};
+
+/// `UNSUBSCRIBE [channel ...]` and `PUNSUBSCRIBE [pattern ...]`, without arguments the client
+/// leaves all of its channels or patterns.
+#[derive(PartialEq, Eq, Debug)]
+struct Unsubscribe {
+    pattern: bool,
+    channels: Vec<String>,
+}
+
+impl Unsubscribe {
+    fn parse(pattern: bool, mut parser: Parser) -> Result<Self, CommandError> {
+        let mut channels = Vec::new();
+        while parser.has_next() {
+            channels.push(parser.next()?);
+        }
+        Ok(Unsubscribe { pattern, channels })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for Unsubscribe {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let kind = if self.pattern {
+            "punsubscribe"
+        } else {
+            "unsubscribe"
+        };
+        let confirm = |channel: Frame| {
+            let count = ctx.subscriptions().len();
+            ctx.push(Frame::Push(vec![
+                Frame::BulkString(Some(kind.as_bytes().to_vec())),
+                channel,
+                Frame::Integer(count as i64),
+            ]));
+        };
+        let mut channels = self.channels;
+        if channels.is_empty() {
+            let subscriptions = ctx.subscriptions();
+            let all = if self.pattern {
+                &subscriptions.patterns
+            } else {
+                &subscriptions.channels
+            };
+            channels = all.iter().cloned().collect();
+            drop(subscriptions);
+            if channels.is_empty() {
+                confirm(Frame::Null);
+            }
+        }
+        for channel in channels {
+            if self.pattern {
+                PUBSUB.punsubscribe(&ctx, &channel);
+            } else {
+                PUBSUB.unsubscribe(&ctx, &channel);
+            }
+            confirm(Frame::BulkString(Some(channel.into_bytes())));
+        }
+        ctx.skip_reply();
+        Ok(Frame::Null)
+    }
+}
+
+async fn unsubscribe(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    Unsubscribe::parse(false, parser)?.execute(ctx).await
+}
+
+async fn punsubscribe(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    Unsubscribe::parse(true, parser)?.execute(ctx).await
+}
+
+register_redis_command!("UNSUBSCRIBE", unsubscribe);
+register_redis_command!("PUNSUBSCRIBE", punsubscribe);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, pubsub::unsubscribe::Unsubscribe},
+        context::Context,
+        protocol::Frame,
+        pubsub::PUBSUB,
+    };
+
+    fn bulk(data: &str) -> Frame {
+        Frame::BulkString(Some(data.as_bytes().to_vec()))
+    }
+
+    #[tokio::test]
+    async fn test_unsubscribe() {
+        let ctx = Context::test_client(1);
+        let mut pushes = ctx.take_push_receiver().unwrap();
+        PUBSUB.subscribe(&ctx, "test-unsub-a");
+        PUBSUB.psubscribe(&ctx, "test-unsub-*");
+
+        let unsubscribe = |pattern: bool, channels: &[&str]| {
+            Unsubscribe {
+                pattern,
+                channels: channels.iter().map(|c| c.to_string()).collect(),
+            }
+            .execute(ctx.clone())
+        };
+        unsubscribe(false, &[]).await.unwrap();
+        unsubscribe(false, &[]).await.unwrap();
+        unsubscribe(true, &["test-unsub-*"]).await.unwrap();
+        assert!(ctx.take_skip_reply());
+        assert!(!ctx.is_subscribed());
+
+        for (kind, channel, count) in [
+            ("unsubscribe", bulk("test-unsub-a"), 1),
+            ("unsubscribe", Frame::Null, 1),
+            ("punsubscribe", bulk("test-unsub-*"), 0),
+        ] {
+            assert_eq!(
+                pushes.try_recv().unwrap(),
+                Frame::Push(vec![bulk(kind), channel, Frame::Integer(count)])
+            );
+        }
+        assert_eq!(PUBSUB.publish("test-unsub-a", b"hello"), 0);
+    }
+}
@@ -34,26 +34,33 @@ pub fn en_register_queue(cmd: &str, handler: CommandHandler) {
 
 /// Register all redis commands to COMMAND_REGISTRY
 pub async fn do_register() {
+    // lock the registry first, so a concurrent caller can't see it before it's filled
+    let mut map = COMMAND_REGISTRY.write().await;
     let futures = {
         let mut locked = PENDING_REGISTRATIONS.lock().unwrap();
         std::mem::take(&mut *locked)
     };
-    let mut map = COMMAND_REGISTRY.write().await;
     for (cmd, handler) in futures {
         map.insert(cmd, handler);
     }
     log::debug!("All redis commands are registered.")
 }
 
-/// Automatically register the redis command handler during the module init phrase
+/// Automatically register the redis command handler during the module init phrase.
+///
+/// Used by commands which parse their arguments by hand instead of deriving `Command`,
+/// `$handler` is an `async fn(Arc<Context>, Parser) -> CommandResult`.
 #[macro_export]
 macro_rules! register_redis_command {
     ($cmd_name:literal, $handler:path) => {
         ::paste::item! {
-            #[ctor::ctor]
+            #[::ctor::ctor]
             fn [<__register_command_ $cmd_name:lower>]() {
-                fn wrapper(ctx: Arc<Context>, args: Vec<Frame>) -> std::pin::Pin<Box<dyn Future<Output = Result<Frame, CommandError>> + Send>> {
-                    Box::pin($handler(ctx, args))
+                fn wrapper(
+                    ctx: ::std::sync::Arc<$crate::context::Context>,
+                    parser: $crate::command::parser::Parser,
+                ) -> $crate::command::registry::CommandFuture {
+                    ::std::boxed::Box::pin($handler(ctx, parser))
                 }
                 $crate::command::registry::en_register_queue($cmd_name, wrapper);
             }
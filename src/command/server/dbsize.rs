@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, registry::CommandResult},
+    context::Context,
+    protocol::Frame,
+};
+
+/// `DBSIZE`, expired keys count until they are deleted
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("DBSIZE")]
+struct DbSize;
+
+#[async_trait]
+impl CommandExecutor for DbSize {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        Ok(Frame::Integer(ctx.db().len() as i64))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, server::dbsize::DbSize},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_dbsize() {
+        let ctx = Context::test_client(2);
+        ctx.db().set(
+            "a".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+        assert_eq!(
+            DbSize.execute(ctx.clone()).await.unwrap(),
+            Frame::Integer(1)
+        );
+        ctx.select(1);
+        assert_eq!(DbSize.execute(ctx).await.unwrap(), Frame::Integer(0));
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, registry::CommandResult},
+    context::Context,
+    protocol::Frame,
+};
+
+/// `FLUSHALL [ASYNC|SYNC]`
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("FLUSHALL")]
+struct FlushAll {
+    #[arg(alias = "ASYNC")]
+    lazy: bool,
+
+    #[arg(alias = "SYNC")]
+    sync: bool,
+}
+
+#[async_trait]
+impl CommandExecutor for FlushAll {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        if self.lazy && self.sync {
+            return Err(CommandError::SyntaxError);
+        }
+        log::info!("ctx {} flushed all databases", ctx.id);
+        for db in ctx.dbs.all() {
+            if self.lazy {
+                db.clear_async();
+            } else {
+                db.clear();
+            }
+        }
+        Ok(Frame::SimpleString("OK".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, server::flushall::FlushAll},
+        context::Context,
+        object::redis_object::RedisObject,
+    };
+
+    #[tokio::test]
+    async fn test_flushall() {
+        let ctx = Context::test_client(2);
+        for db in ctx.dbs.all() {
+            db.set(
+                "k".to_string(),
+                RedisObject::new_string(b"v".to_vec()),
+                None,
+            );
+        }
+
+        let flushall = FlushAll {
+            lazy: true,
+            sync: false,
+        };
+        flushall.execute(ctx.clone()).await.unwrap();
+        assert!(ctx.dbs.all().iter().all(|db| db.is_empty()));
+    }
+}
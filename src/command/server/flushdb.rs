@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, registry::CommandResult},
+    context::Context,
+    protocol::Frame,
+};
+
+/// `FLUSHDB [ASYNC|SYNC]`
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("FLUSHDB")]
+struct FlushDb {
+    #[arg(alias = "ASYNC")]
+    lazy: bool,
+
+    #[arg(alias = "SYNC")]
+    sync: bool,
+}
+
+#[async_trait]
+impl CommandExecutor for FlushDb {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        if self.lazy && self.sync {
+            return Err(CommandError::SyntaxError);
+        }
+        let db = ctx.db();
+        log::info!("ctx {} flushed db {}", ctx.id, db.id());
+        if self.lazy {
+            db.clear_async();
+        } else {
+            db.clear();
+        }
+        Ok(Frame::SimpleString("OK".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, parser::Parser, server::flushdb::FlushDb},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[test]
+    fn test_try_from_parser_to_flushdb() {
+        let parts = vec![Frame::BulkString(Some(b"async".to_vec()))];
+        let flushdb: FlushDb = Parser::new(Frame::Array(Some(parts)))
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            flushdb,
+            FlushDb {
+                lazy: true,
+                sync: false
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_flushdb() {
+        let ctx = Context::test_client(2);
+        let value = || RedisObject::new_string(b"v".to_vec());
+        ctx.db().set("k".to_string(), value(), None);
+        ctx.dbs.get(1).unwrap().set("k".to_string(), value(), None);
+
+        let flushdb = FlushDb {
+            lazy: false,
+            sync: false,
+        };
+        flushdb.execute(ctx.clone()).await.unwrap();
+        assert!(ctx.db().is_empty());
+        assert!(!ctx.dbs.get(1).unwrap().is_empty());
+
+        assert!(ctx.select(1));
+        let flushdb = FlushDb {
+            lazy: true,
+            sync: false,
+        };
+        flushdb.execute(ctx.clone()).await.unwrap();
+        assert!(ctx.db().is_empty());
+        assert_eq!(ctx.db().used_memory(), 0);
+    }
+}
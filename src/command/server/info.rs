@@ -0,0 +1,184 @@
+use std::{fmt::Write, sync::Arc, sync::atomic::Ordering};
+
+use async_trait::async_trait;
+
+use crate::{
+    blocked::BLOCKED,
+    client::CLIENT_REGISTRY,
+    command::{CommandExecutor, error::CommandError, parser::Parser, registry::CommandResult},
+    config::get_server_config,
+    context::Context,
+    memory::{startup_memory, update_peak_memory, used_memory},
+    protocol::Frame,
+    pubsub::PUBSUB,
+    register_redis_command,
+    stats::STATS,
+    storage::lazyfree::{freed_objects, pending_objects},
+};
+
+/// Sections printed when `INFO` is called without arguments
+const DEFAULT_SECTIONS: [&str; 5] = ["server", "clients", "memory", "stats", "keyspace"];
+
+/// `INFO [section [section ...]]`
+#[derive(PartialEq, Eq, Debug)]
+struct Info {
+    sections: Vec<String>,
+}
+
+impl TryFrom<Parser> for Info {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let mut sections = Vec::new();
+        while parser.has_next() {
+            let section: String = parser.next()?;
+            match section.to_ascii_lowercase().as_str() {
+                "default" | "all" | "everything" => {
+                    sections.extend(DEFAULT_SECTIONS.iter().map(|s| s.to_string()))
+                }
+                section => sections.push(section.to_string()),
+            }
+        }
+        if sections.is_empty() {
+            sections.extend(DEFAULT_SECTIONS.iter().map(|s| s.to_string()));
+        }
+        Ok(Info { sections })
+    }
+}
+
+impl Info {
+    fn write_section(&self, out: &mut String, section: &str, ctx: &Context) -> std::fmt::Result {
+        let config = get_server_config();
+        match section {
+            "server" => {
+                writeln!(out, "# Server\r")?;
+                writeln!(out, "redis_version:{}\r", env!("CARGO_PKG_VERSION"))?;
+                writeln!(out, "redis_mode:standalone\r")?;
+                writeln!(out, "process_id:{}\r", std::process::id())?;
+                writeln!(out, "tcp_port:{}\r", config.port)?;
+                writeln!(
+                    out,
+                    "uptime_in_seconds:{}\r",
+                    STATS.started.elapsed().as_secs()
+                )?;
+                writeln!(out, "hz:{}\r", config.hz)?;
+            }
+            "clients" => {
+                writeln!(out, "# Clients\r")?;
+                writeln!(out, "connected_clients:{}\r", CLIENT_REGISTRY.len())?;
+                writeln!(out, "maxclients:{}\r", config.maxclients)?;
+                writeln!(out, "blocked_clients:{}\r", BLOCKED.len())?;
+            }
+            "memory" => {
+                writeln!(out, "# Memory\r")?;
+                let used = used_memory();
+                let dataset: usize = ctx.dbs.all().iter().map(|db| db.used_memory()).sum();
+                writeln!(out, "used_memory:{}\r", used)?;
+                writeln!(out, "used_memory_peak:{}\r", update_peak_memory())?;
+                writeln!(out, "used_memory_startup:{}\r", startup_memory())?;
+                writeln!(out, "used_memory_dataset:{}\r", dataset)?;
+                writeln!(out, "maxmemory:{}\r", config.maxmemory)?;
+                writeln!(out, "maxmemory_policy:{}\r", config.maxmemory_policy)?;
+                writeln!(out, "lazyfree_pending_objects:{}\r", pending_objects())?;
+                writeln!(out, "lazyfreed_objects:{}\r", freed_objects())?;
+            }
+            "stats" => {
+                writeln!(out, "# Stats\r")?;
+                writeln!(
+                    out,
+                    "expired_keys:{}\r",
+                    STATS.expired_keys.load(Ordering::Relaxed)
+                )?;
+                writeln!(
+                    out,
+                    "expired_subkeys:{}\r",
+                    STATS.expired_subkeys.load(Ordering::Relaxed)
+                )?;
+                writeln!(
+                    out,
+                    "expired_time_cap_reached_count:{}\r",
+                    STATS.expired_time_cap_reached_count.load(Ordering::Relaxed)
+                )?;
+                writeln!(
+                    out,
+                    "expire_cycle_cpu_milliseconds:{}\r",
+                    STATS.expire_cycle_cpu_microseconds.load(Ordering::Relaxed) / 1000
+                )?;
+                writeln!(
+                    out,
+                    "evicted_keys:{}\r",
+                    STATS.evicted_keys.load(Ordering::Relaxed)
+                )?;
+                writeln!(out, "pubsub_channels:{}\r", PUBSUB.channels_len())?;
+                writeln!(out, "pubsub_patterns:{}\r", PUBSUB.patterns_len())?;
+            }
+            "keyspace" => {
+                writeln!(out, "# Keyspace\r")?;
+                for db in ctx.dbs.all().iter().filter(|db| !db.is_empty()) {
+                    writeln!(
+                        out,
+                        "db{}:keys={},expires={},avg_ttl=0\r",
+                        db.id(),
+                        db.len(),
+                        db.expires_len()
+                    )?;
+                }
+            }
+            // unknown sections are ignored like redis does
+            _ => return Ok(()),
+        }
+        writeln!(out, "\r")
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for Info {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let mut out = String::new();
+        for section in self.sections.iter() {
+            self.write_section(&mut out, section, &ctx)
+                .expect("writing to a String never fails");
+        }
+        // no blank line after the last section
+        out.truncate(out.trim_end().len());
+        Ok(Frame::Verbatim("txt".to_string(), out.into_bytes()))
+    }
+}
+
+async fn info(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: Info = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("INFO", info);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, server::info::Info},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_info_sections() {
+        let ctx = Context::test_client(4);
+        ctx.dbs.get(3).unwrap().set(
+            "k".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+
+        let info = Info {
+            sections: vec!["stats".to_string(), "keyspace".to_string()],
+        };
+        let Frame::Verbatim(_, out) = info.execute(ctx).await.unwrap() else {
+            panic!("expect a verbatim string");
+        };
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("# Stats\r\nexpired_keys:"));
+        assert!(!out.contains("# Server"));
+        assert!(out.ends_with("# Keyspace\r\ndb3:keys=1,expires=0,avg_ttl=0"));
+    }
+}
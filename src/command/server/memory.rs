@@ -0,0 +1,300 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, parser::Parser, registry::CommandResult},
+    config::{MaxmemoryPolicy, get_server_config},
+    context::Context,
+    memory::{startup_memory, update_peak_memory, used_memory},
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `MEMORY DOCTOR` doesn't report anything below this much used memory
+const DOCTOR_MIN_MEMORY: usize = 5 << 20;
+
+const HELP: [&str; 10] = [
+    "MEMORY <subcommand> [<arg> [value] [opt] ...]. Subcommands are:",
+    "DOCTOR",
+    "    Return memory problems reports.",
+    "STATS",
+    "    Return information about the memory usage of the server.",
+    "USAGE <key> [SAMPLES <count>]",
+    "    Return memory in bytes used by <key> and its value. Nested values are",
+    "    sampled up to <count> times (default: 5, 0 means sample all).",
+    "HELP",
+    "    Print this help.",
+];
+
+/// `MEMORY <subcommand> [arguments]`
+#[derive(PartialEq, Eq, Debug)]
+enum Memory {
+    /// `MEMORY USAGE key [SAMPLES count]`, the key isn't counted as accessed. The size of a
+    /// collection is kept up to date, so the count is checked but nothing is sampled.
+    Usage {
+        key: String,
+    },
+    Stats,
+    Doctor,
+    Help,
+}
+
+impl TryFrom<Parser> for Memory {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let subcommand = parser.next::<String>()?.to_ascii_uppercase();
+        let memory = match subcommand.as_str() {
+            "USAGE" => {
+                let key = parser.next()?;
+                while parser.has_next() {
+                    let option: String = parser.next()?;
+                    match option.to_ascii_uppercase().as_str() {
+                        "SAMPLES" => {
+                            usize::try_from(parser.next::<i64>()?)
+                                .map_err(|_| CommandError::SyntaxError)?;
+                        }
+                        _ => return Err(CommandError::SyntaxError),
+                    }
+                }
+                Memory::Usage { key }
+            }
+            "STATS" => Memory::Stats,
+            "DOCTOR" => Memory::Doctor,
+            "HELP" => Memory::Help,
+            _ => {
+                return Err(CommandError::UnknownSubcommand(
+                    subcommand,
+                    "MEMORY".to_string(),
+                ));
+            }
+        };
+        if parser.has_next() {
+            return Err(CommandError::UnknownSubcommand(
+                subcommand,
+                "MEMORY".to_string(),
+            ));
+        }
+        Ok(memory)
+    }
+}
+
+/// Problems found by `MEMORY DOCTOR`, the used memory doesn't count the startup memory
+fn diagnose(used: usize, peak: usize, maxmemory: usize, policy: MaxmemoryPolicy) -> String {
+    if used < DOCTOR_MIN_MEMORY {
+        return "Hi Sam, this instance is empty or is using very little memory, my issues \
+            detector can't be used in these conditions. Please, leave for your mission on \
+            Earth and fill it with some data. The new Sam and I will be back to our \
+            programming as soon as I finished rebooting."
+            .to_string();
+    }
+    let mut issues = Vec::new();
+    if peak / 3 * 2 > used {
+        issues.push(
+            "Peak memory: In the past this instance used more than 150% the memory that is \
+            currently using. The allocator is normally not able to release memory after a \
+            peak, so the process may hold more memory than reported here until it is filled \
+            with more data again. If the peak was only occasional, the only way to reclaim \
+            the memory is to restart the instance.",
+        );
+    }
+    if maxmemory > 0 && policy == MaxmemoryPolicy::NoEviction && used > maxmemory / 10 * 8 {
+        issues.push(
+            "Near maxmemory: This instance uses more than 80% of 'maxmemory' and the \
+            'noeviction' policy is selected, so write commands will fail with an OOM error \
+            once the limit is reached. Consider raising 'maxmemory' or an eviction policy \
+            if this instance is used as a cache.",
+        );
+    }
+    if issues.is_empty() {
+        return "Hi Sam, I can't find any memory issue in your instance. I can only account \
+            for what occurs on this base."
+            .to_string();
+    }
+    let mut report =
+        "Sam, I detected a few issues in this Redis instance memory implants:\n\n".to_string();
+    for issue in issues {
+        report.push_str(" * ");
+        report.push_str(issue);
+        report.push_str("\n\n");
+    }
+    report.push_str("I'm here to keep you safe, Sam. I want to help you.\n");
+    report
+}
+
+fn field(name: &str) -> Frame {
+    Frame::BulkString(Some(name.as_bytes().to_vec()))
+}
+
+#[async_trait]
+impl CommandExecutor for Memory {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        match self {
+            Memory::Usage { key } => Ok(ctx
+                .db()
+                .memory_usage(&key)
+                .map_or(Frame::Null, |usage| Frame::Integer(usage as i64))),
+            Memory::Stats => {
+                let total = used_memory();
+                let peak = update_peak_memory();
+                let startup = startup_memory();
+                let mut stats = vec![
+                    (field("peak.allocated"), Frame::Integer(peak as i64)),
+                    (field("total.allocated"), Frame::Integer(total as i64)),
+                    (field("startup.allocated"), Frame::Integer(startup as i64)),
+                ];
+                let mut overhead = startup;
+                let mut keys = 0;
+                for db in ctx.dbs.all().iter().filter(|db| !db.is_empty()) {
+                    let (main, expires) = db.overhead();
+                    overhead += main + expires;
+                    keys += db.len();
+                    stats.push((
+                        field(&format!("db.{}", db.id())),
+                        Frame::Map(vec![
+                            (
+                                field("overhead.hashtable.main"),
+                                Frame::Integer(main as i64),
+                            ),
+                            (
+                                field("overhead.hashtable.expires"),
+                                Frame::Integer(expires as i64),
+                            ),
+                        ]),
+                    ));
+                }
+                let net = total.saturating_sub(startup);
+                let dataset = total.saturating_sub(overhead);
+                let percentage = |part: usize, whole: usize| match whole {
+                    0 => 0.0,
+                    whole => part as f64 * 100.0 / whole as f64,
+                };
+                stats.extend([
+                    (field("overhead.total"), Frame::Integer(overhead as i64)),
+                    (field("keys.count"), Frame::Integer(keys as i64)),
+                    (
+                        field("keys.bytes-per-key"),
+                        Frame::Integer(net.checked_div(keys).unwrap_or(0) as i64),
+                    ),
+                    (field("dataset.bytes"), Frame::Integer(dataset as i64)),
+                    (
+                        field("dataset.percentage"),
+                        Frame::Double(percentage(dataset, net)),
+                    ),
+                    (
+                        field("peak.percentage"),
+                        Frame::Double(percentage(total, peak)),
+                    ),
+                ]);
+                Ok(Frame::Map(stats))
+            }
+            Memory::Doctor => {
+                let config = get_server_config();
+                let report = diagnose(
+                    used_memory().saturating_sub(startup_memory()),
+                    update_peak_memory().saturating_sub(startup_memory()),
+                    config.maxmemory,
+                    config.maxmemory_policy,
+                );
+                Ok(Frame::Verbatim("txt".to_string(), report.into_bytes()))
+            }
+            Memory::Help => Ok(Frame::Array(Some(
+                HELP.iter()
+                    .map(|line| Frame::SimpleString(line.to_string()))
+                    .collect(),
+            ))),
+        }
+    }
+}
+
+async fn memory(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: Memory = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("MEMORY", memory);
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::{
+        command::{
+            CommandExecutor,
+            parser::Parser,
+            server::memory::{Memory, diagnose},
+        },
+        config::MaxmemoryPolicy,
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+        storage::expires,
+    };
+
+    #[tokio::test]
+    async fn test_memory_usage() {
+        assert_eq!(
+            Memory::try_from(Parser::from_args(&["MEMORY", "usage", "k", "SAMPLES", "0"])).unwrap(),
+            Memory::Usage {
+                key: "k".to_string()
+            }
+        );
+        assert!(
+            Memory::try_from(Parser::from_args(&[
+                "MEMORY", "USAGE", "k", "SAMPLES", "-1"
+            ]))
+            .is_err()
+        );
+        assert!(Memory::try_from(Parser::from_args(&["MEMORY", "USAGE", "k", "FOO"])).is_err());
+        assert!(Memory::try_from(Parser::from_args(&["MEMORY", "PURGE"])).is_err());
+
+        let ctx = Context::test_client(1);
+        let usage = |key: &str| {
+            Memory::Usage {
+                key: key.to_string(),
+            }
+            .execute(ctx.clone())
+        };
+        ctx.db().set(
+            "k".to_string(),
+            RedisObject::new_string(vec![b'x'; 1000]),
+            None,
+        );
+        let Frame::Integer(persistent) = usage("k").await.unwrap() else {
+            panic!("USAGE replies an integer");
+        };
+        assert!(persistent > 1000);
+        assert_eq!(ctx.db().used_memory(), persistent as usize);
+
+        ctx.db()
+            .set_expire("k", std::time::SystemTime::now() + Duration::from_secs(60));
+        assert_eq!(
+            usage("k").await.unwrap(),
+            Frame::Integer(persistent + expires::entry_size("k") as i64)
+        );
+        assert_eq!(usage("missing").await.unwrap(), Frame::Null);
+
+        let Frame::Map(stats) = Memory::Stats.execute(ctx.clone()).await.unwrap() else {
+            panic!("STATS replies a map");
+        };
+        assert!(stats.contains(&(
+            Frame::BulkString(Some(b"keys.count".to_vec())),
+            Frame::Integer(1)
+        )));
+    }
+
+    #[test]
+    fn test_memory_doctor() {
+        let policy = MaxmemoryPolicy::NoEviction;
+        assert!(diagnose(1 << 20, 1 << 20, 0, policy).contains("very little memory"));
+        assert!(diagnose(10 << 20, 10 << 20, 0, policy).contains("can't find any"));
+        let report = diagnose(10 << 20, 30 << 20, 11 << 20, policy);
+        assert!(report.contains("Peak memory"));
+        assert!(report.contains("Near maxmemory"));
+        assert!(
+            !diagnose(10 << 20, 10 << 20, 11 << 20, MaxmemoryPolicy::AllKeysLru)
+                .contains("Near maxmemory")
+        );
+    }
+}
@@ -0,0 +1,7 @@
+mod dbsize;
+mod flushall;
+mod flushdb;
+mod info;
+mod memory;
+mod shutdown;
+mod swapdb;
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, parser::Parser, registry::CommandResult},
+    context::Context,
+    protocol::Frame,
+    register_redis_command,
+    shutdown::{SHUTDOWN, ShutdownFlags},
+};
+
+/// `SHUTDOWN [NOSAVE|SAVE] [NOW] [FORCE] [ABORT]`
+#[derive(PartialEq, Eq, Debug)]
+enum Shutdown {
+    Start(ShutdownFlags),
+    Abort,
+}
+
+impl TryFrom<Parser> for Shutdown {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let mut flags = ShutdownFlags::default();
+        let mut abort = false;
+        let mut count = 0;
+        while parser.has_next() {
+            let option: String = parser.next()?;
+            match option.to_ascii_uppercase().as_str() {
+                "SAVE" if flags.save.is_none() => flags.save = Some(true),
+                "NOSAVE" if flags.save.is_none() => flags.save = Some(false),
+                "NOW" => flags.now = true,
+                "FORCE" => flags.force = true,
+                "ABORT" => abort = true,
+                _ => return Err(CommandError::SyntaxError),
+            }
+            count += 1;
+        }
+        match abort {
+            // ABORT can't be combined with other flags
+            true if count > 1 => Err(CommandError::SyntaxError),
+            true => Ok(Shutdown::Abort),
+            false => Ok(Shutdown::Start(flags)),
+        }
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for Shutdown {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        match self {
+            Shutdown::Abort => {
+                if !SHUTDOWN.abort() {
+                    return Err(CommandError::NoShutdownInProgress);
+                }
+                log::warn!("ctx {} aborted the shutdown", ctx.id);
+                Ok(Frame::SimpleString("OK".to_string()))
+            }
+            Shutdown::Start(flags) => {
+                log::warn!("ctx {} requested shutdown: {:?}", ctx.id, flags);
+                SHUTDOWN.request(flags);
+                // a successful shutdown has no reply, the connection is just closed
+                ctx.kill_without_reply();
+                Ok(Frame::Null)
+            }
+        }
+    }
+}
+
+async fn shutdown(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: Shutdown = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("SHUTDOWN", shutdown);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{parser::Parser, server::shutdown::Shutdown},
+        shutdown::ShutdownFlags,
+    };
+
+    #[test]
+    fn test_try_from_parser_to_shutdown() {
+        assert_eq!(
+            Shutdown::try_from(Parser::from_args(&["SHUTDOWN"])).unwrap(),
+            Shutdown::Start(ShutdownFlags::default())
+        );
+        assert_eq!(
+            Shutdown::try_from(Parser::from_args(&["SHUTDOWN", "nosave", "NOW", "FORCE"])).unwrap(),
+            Shutdown::Start(ShutdownFlags {
+                save: Some(false),
+                now: true,
+                force: true
+            })
+        );
+        assert_eq!(
+            Shutdown::try_from(Parser::from_args(&["SHUTDOWN", "ABORT"])).unwrap(),
+            Shutdown::Abort
+        );
+    }
+
+    #[test]
+    fn test_try_from_parser_to_shutdown_err() {
+        assert!(Shutdown::try_from(Parser::from_args(&["SHUTDOWN", "SAVE", "NOSAVE"])).is_err());
+        assert!(Shutdown::try_from(Parser::from_args(&["SHUTDOWN", "ABORT", "NOW"])).is_err());
+        assert!(Shutdown::try_from(Parser::from_args(&["SHUTDOWN", "LATER"])).is_err());
+    }
+}
@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, error::CommandError, registry::CommandResult},
+    context::Context,
+    protocol::Frame,
+};
+
+/// `SWAPDB index1 index2`, clients which selected one of them see the other one afterwards
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("SWAPDB")]
+struct SwapDb {
+    index1: i64,
+    index2: i64,
+}
+
+#[async_trait]
+impl CommandExecutor for SwapDb {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let (Ok(a), Ok(b)) = (usize::try_from(self.index1), usize::try_from(self.index2)) else {
+            return Err(CommandError::DbIndexOutOfRange);
+        };
+        if !ctx.dbs.swap(a, b) {
+            return Err(CommandError::DbIndexOutOfRange);
+        }
+        log::info!("ctx {} swapped db {} and db {}", ctx.id, a, b);
+        Ok(Frame::SimpleString("OK".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        command::{CommandExecutor, server::swapdb::SwapDb},
+        context::Context,
+        object::redis_object::RedisObject,
+        storage::database::Databases,
+    };
+
+    #[tokio::test]
+    async fn test_swapdb() {
+        let dbs = Arc::new(Databases::new(2));
+        let first = Context::test_client_of(dbs.clone());
+        let second = Context::test_client_of(dbs.clone());
+        second.select(1);
+        first.db().set(
+            "k".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+
+        SwapDb {
+            index1: 0,
+            index2: 1,
+        }
+        .execute(first.clone())
+        .await
+        .unwrap();
+        assert!(!first.db().contains_key("k"));
+        assert!(second.db().contains_key("k"));
+        assert_eq!(second.db().id(), 1);
+
+        let out_of_range = SwapDb {
+            index1: 0,
+            index2: 2,
+        };
+        assert!(out_of_range.execute(first).await.is_err());
+    }
+}
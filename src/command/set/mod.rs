@@ -0,0 +1,58 @@
+use std::{borrow::Cow, collections::HashSet};
+
+use crate::{
+    command::error::CommandError,
+    object::redis_object::{ObjectType, RedisObject, RedisValue},
+    storage::database::Database,
+};
+
+mod sadd;
+mod scard;
+mod sinter;
+mod sintercard;
+mod sismember;
+mod smembers;
+mod smove;
+mod spop;
+mod srandmember;
+mod srem;
+mod sscan;
+
+fn as_set(value: &RedisObject) -> Result<&RedisValue, CommandError> {
+    match value.header.obj_type() {
+        ObjectType::Set => Ok(&value.ptr),
+        _ => Err(CommandError::WrongType),
+    }
+}
+
+fn as_set_mut(value: &mut RedisObject) -> Result<&mut RedisValue, CommandError> {
+    match value.header.obj_type() {
+        ObjectType::Set => Ok(&mut value.ptr),
+        _ => Err(CommandError::WrongType),
+    }
+}
+
+/// Members of the sets at `keys`, `None` for a key which doesn't exist. Every key is read
+/// apart, so no two shards are locked at once.
+fn read_sets(
+    db: &Database,
+    keys: &[String],
+) -> Result<Vec<Option<HashSet<Vec<u8>>>>, CommandError> {
+    keys.iter()
+        .map(|key| {
+            db.get_with(key, |value| {
+                Ok(as_set(value)?.set_iter().map(Cow::into_owned).collect())
+            })
+            .transpose()
+        })
+        .collect()
+}
+
+/// A set holding `members`
+fn new_set(members: impl IntoIterator<Item = Vec<u8>>) -> RedisObject {
+    let mut set = RedisObject::new_set();
+    for member in members {
+        set.ptr.set_add(&member);
+    }
+    set
+}
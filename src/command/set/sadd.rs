@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor, error::CommandError, parser::Parser, registry::CommandResult,
+        set::as_set_mut,
+    },
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    object::redis_object::RedisObject,
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `SADD key member [member ...]`, replies the count of new members
+#[derive(PartialEq, Eq, Debug)]
+struct SAdd {
+    key: String,
+    members: Vec<Vec<u8>>,
+}
+
+impl TryFrom<Parser> for SAdd {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        Ok(SAdd {
+            key: parser.next()?,
+            members: parser.remaining()?,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for SAdd {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let id = db.id();
+        let added = db.modify_or_insert(&self.key, RedisObject::new_set, |value| {
+            let set = as_set_mut(value)?;
+            let added = self
+                .members
+                .iter()
+                .filter(|member| set.set_add(member))
+                .count();
+            if added > 0 {
+                notify_keyspace_event(notify::SET, "sadd", &self.key, id);
+            }
+            Ok::<_, CommandError>(added)
+        })?;
+        Ok(Frame::Integer(added as i64))
+    }
+}
+
+async fn sadd(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: SAdd = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("SADD", sadd);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, parser::Parser, set::sadd::SAdd},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_sadd() {
+        assert!(SAdd::try_from(Parser::from_args(&["SADD", "s"])).is_err());
+
+        let ctx = Context::test_client(1);
+        let sadd = |args: &[&str]| {
+            SAdd::try_from(Parser::from_args(args))
+                .unwrap()
+                .execute(ctx.clone())
+        };
+        assert_eq!(
+            sadd(&["SADD", "s", "1", "2", "1"]).await.unwrap(),
+            Frame::Integer(2)
+        );
+        let encoding = |key: &str| ctx.db().get_with(key, |value| value.ptr.encoding());
+        assert_eq!(encoding("s"), Some("intset"));
+        assert_eq!(
+            sadd(&["SADD", "s", "2", "a"]).await.unwrap(),
+            Frame::Integer(1)
+        );
+        assert_eq!(encoding("s"), Some("listpack"));
+        let used = ctx.db().used_memory();
+        assert_eq!(ctx.db().memory_usage("s"), Some(used));
+
+        ctx.db().set(
+            "str".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+        assert!(sadd(&["SADD", "str", "a"]).await.is_err());
+    }
+}
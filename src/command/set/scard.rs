@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, registry::CommandResult, set::as_set},
+    context::Context,
+    protocol::Frame,
+};
+
+/// `SCARD key`, 0 when the key doesn't exist
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("SCARD")]
+struct SCard {
+    key: String,
+}
+
+#[async_trait]
+impl CommandExecutor for SCard {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let len = ctx
+            .db()
+            .get_with(&self.key, |value| as_set(value).map(|set| set.set_len()))
+            .transpose()?
+            .unwrap_or(0);
+        Ok(Frame::Integer(len as i64))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, set::scard::SCard},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_scard() {
+        let ctx = Context::test_client(1);
+        let mut set = RedisObject::new_set();
+        set.ptr.set_add(b"1");
+        set.ptr.set_add(b"x");
+        ctx.db().insert("s".to_string(), set, None);
+        ctx.db().set(
+            "str".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+        let scard = |key: &str| {
+            SCard {
+                key: key.to_string(),
+            }
+            .execute(ctx.clone())
+        };
+
+        assert_eq!(scard("s").await.unwrap(), Frame::Integer(2));
+        assert_eq!(scard("missing").await.unwrap(), Frame::Integer(0));
+        assert!(scard("str").await.is_err());
+    }
+}
@@ -0,0 +1,272 @@
+use std::{collections::HashSet, sync::Arc};
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        parser::Parser,
+        registry::CommandResult,
+        set::{new_set, read_sets},
+    },
+    config::get_server_config,
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+    register_redis_command,
+};
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum Operation {
+    Inter,
+    Union,
+    Diff,
+}
+
+impl Operation {
+    /// Combine the sets read by `read_sets`, a missing key is an empty set
+    fn apply(self, sets: Vec<Option<HashSet<Vec<u8>>>>) -> HashSet<Vec<u8>> {
+        match self {
+            Operation::Inter => {
+                let Some(mut sets) = sets.into_iter().collect::<Option<Vec<_>>>() else {
+                    return HashSet::new();
+                };
+                // the smallest set is walked, the others are looked up
+                sets.sort_by_key(HashSet::len);
+                let mut sets = sets.into_iter();
+                let mut result = sets.next().unwrap_or_default();
+                for set in sets {
+                    result.retain(|member| set.contains(member));
+                }
+                result
+            }
+            Operation::Union => sets.into_iter().flatten().flatten().collect(),
+            Operation::Diff => {
+                let mut sets = sets.into_iter();
+                let mut result = sets.next().flatten().unwrap_or_default();
+                for set in sets.flatten() {
+                    result.retain(|member| !set.contains(member));
+                }
+                result
+            }
+        }
+    }
+
+    fn store_event(self) -> &'static str {
+        match self {
+            Operation::Inter => "sinterstore",
+            Operation::Union => "sunionstore",
+            Operation::Diff => "sdiffstore",
+        }
+    }
+}
+
+/// `SINTER key [key ...]`, `SUNION` and `SDIFF` reply the members of the intersection,
+/// union or difference of the sets.
+///
+/// `SINTERSTORE destination key [key ...]` and the other `STORE` variants store them at
+/// `destination` instead and reply their count, an empty result deletes `destination`.
+#[derive(PartialEq, Eq, Debug)]
+struct SetOperation {
+    operation: Operation,
+    destination: Option<String>,
+    keys: Vec<String>,
+}
+
+impl SetOperation {
+    fn parse(operation: Operation, store: bool, mut parser: Parser) -> Result<Self, CommandError> {
+        let destination = if store { Some(parser.next()?) } else { None };
+        Ok(SetOperation {
+            operation,
+            destination,
+            keys: parser.remaining()?,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for SetOperation {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let members = self.operation.apply(read_sets(&db, &self.keys)?);
+        let Some(destination) = self.destination else {
+            return Ok(Frame::Set(
+                members
+                    .into_iter()
+                    .map(|member| Frame::BulkString(Some(member)))
+                    .collect(),
+            ));
+        };
+        let len = members.len();
+        if members.is_empty() {
+            if db.delete(&destination, get_server_config().lazyfree_lazy_server_del) {
+                notify_keyspace_event(notify::GENERIC, "del", &destination, db.id());
+            }
+        } else {
+            db.set(destination.clone(), new_set(members), None);
+            notify_keyspace_event(
+                notify::SET,
+                self.operation.store_event(),
+                &destination,
+                db.id(),
+            );
+        }
+        Ok(Frame::Integer(len as i64))
+    }
+}
+
+async fn sinter(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    SetOperation::parse(Operation::Inter, false, parser)?
+        .execute(ctx)
+        .await
+}
+
+async fn sunion(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    SetOperation::parse(Operation::Union, false, parser)?
+        .execute(ctx)
+        .await
+}
+
+async fn sdiff(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    SetOperation::parse(Operation::Diff, false, parser)?
+        .execute(ctx)
+        .await
+}
+
+async fn sinterstore(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    SetOperation::parse(Operation::Inter, true, parser)?
+        .execute(ctx)
+        .await
+}
+
+async fn sunionstore(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    SetOperation::parse(Operation::Union, true, parser)?
+        .execute(ctx)
+        .await
+}
+
+async fn sdiffstore(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    SetOperation::parse(Operation::Diff, true, parser)?
+        .execute(ctx)
+        .await
+}
+
+register_redis_command!("SINTER", sinter);
+register_redis_command!("SUNION", sunion);
+register_redis_command!("SDIFF", sdiff);
+register_redis_command!("SINTERSTORE", sinterstore);
+register_redis_command!("SUNIONSTORE", sunionstore);
+register_redis_command!("SDIFFSTORE", sdiffstore);
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::{
+        command::{
+            CommandExecutor,
+            parser::Parser,
+            set::sinter::{Operation, SetOperation},
+        },
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_set_operations() {
+        assert!(
+            SetOperation::parse(
+                Operation::Inter,
+                true,
+                Parser::from_args(&["SINTERSTORE", "d"])
+            )
+            .is_err()
+        );
+
+        let ctx = Context::test_client(1);
+        for (key, members) in [("a", ["1", "2", "3"]), ("b", ["2", "3", "x"])] {
+            let mut set = RedisObject::new_set();
+            for member in members {
+                set.ptr.set_add(member.as_bytes());
+            }
+            ctx.db().insert(key.to_string(), set, None);
+        }
+        ctx.db().set(
+            "str".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+        let run = |operation, store, args: &[&str]| {
+            SetOperation::parse(operation, store, Parser::from_args(args))
+                .unwrap()
+                .execute(ctx.clone())
+        };
+        let members = |frame: Frame| {
+            let Frame::Set(frames) = frame else {
+                panic!("unexpected reply {:?}", frame);
+            };
+            let mut members: Vec<String> = frames
+                .into_iter()
+                .map(|frame| match frame {
+                    Frame::BulkString(Some(member)) => String::from_utf8(member).unwrap(),
+                    frame => panic!("unexpected member {:?}", frame),
+                })
+                .collect();
+            members.sort();
+            members
+        };
+
+        let inter = run(Operation::Inter, false, &["SINTER", "a", "b"]);
+        assert_eq!(members(inter.await.unwrap()), ["2", "3"]);
+        let union = run(Operation::Union, false, &["SUNION", "a", "b", "missing"]);
+        assert_eq!(members(union.await.unwrap()), ["1", "2", "3", "x"]);
+        let diff = run(Operation::Diff, false, &["SDIFF", "b", "missing", "a"]);
+        assert_eq!(members(diff.await.unwrap()), ["x"]);
+        // a missing key is an empty set
+        let inter = run(Operation::Inter, false, &["SINTER", "a", "missing"]);
+        assert!(members(inter.await.unwrap()).is_empty());
+        assert!(
+            run(Operation::Union, false, &["SUNION", "a", "str"])
+                .await
+                .is_err()
+        );
+
+        // the destination is overwritten, its expire time is dropped
+        ctx.db().set(
+            "d".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            Some(Duration::from_secs(100)),
+        );
+        assert_eq!(
+            run(Operation::Union, true, &["SUNIONSTORE", "d", "a", "b"])
+                .await
+                .unwrap(),
+            Frame::Integer(4)
+        );
+        assert_eq!(
+            ctx.db().get_with("d", |value| value.ptr.encoding()),
+            Some("listpack")
+        );
+        assert_eq!(ctx.db().expire_time("d"), None);
+        assert_eq!(
+            run(Operation::Inter, true, &["SINTERSTORE", "d", "a", "b"])
+                .await
+                .unwrap(),
+            Frame::Integer(2)
+        );
+        assert_eq!(
+            ctx.db().get_with("d", |value| value.ptr.encoding()),
+            Some("intset")
+        );
+        // an empty result deletes the destination
+        assert_eq!(
+            run(Operation::Diff, true, &["SDIFFSTORE", "d", "a", "a"])
+                .await
+                .unwrap(),
+            Frame::Integer(0)
+        );
+        assert!(!ctx.db().contains_key("d"));
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor, error::CommandError, parser::Parser, registry::CommandResult,
+        set::read_sets,
+    },
+    context::Context,
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `SINTERCARD numkeys key [key ...] [LIMIT limit]`, replies the count of members of the
+/// intersection. It stops counting at `limit`, 0 means no limit.
+#[derive(PartialEq, Eq, Debug)]
+struct SInterCard {
+    keys: Vec<String>,
+    limit: usize,
+}
+
+impl TryFrom<Parser> for SInterCard {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let numkeys = usize::try_from(parser.next::<i64>()?)
+            .ok()
+            .filter(|&numkeys| numkeys > 0)
+            .ok_or_else(|| CommandError::NotGreaterThanZero("numkeys".to_string()))?;
+        let keys = (0..numkeys)
+            .map(|_| parser.next())
+            .collect::<Result<_, _>>()?;
+        let mut limit = 0;
+        if parser.has_next() {
+            let option: String = parser.next()?;
+            if !option.eq_ignore_ascii_case("LIMIT") {
+                return Err(CommandError::SyntaxError);
+            }
+            limit = usize::try_from(parser.next::<i64>()?)
+                .map_err(|_| CommandError::NegativeOption("LIMIT".to_string()))?;
+        }
+        if parser.has_next() {
+            return Err(CommandError::SyntaxError);
+        }
+        Ok(SInterCard { keys, limit })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for SInterCard {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let Some(mut sets) = read_sets(&ctx.db(), &self.keys)?
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+        else {
+            return Ok(Frame::Integer(0));
+        };
+        sets.sort_by_key(|set| set.len());
+        let limit = if self.limit == 0 {
+            usize::MAX
+        } else {
+            self.limit
+        };
+        let count = sets[0]
+            .iter()
+            .filter(|member| sets[1..].iter().all(|set| set.contains(*member)))
+            .take(limit)
+            .count();
+        Ok(Frame::Integer(count as i64))
+    }
+}
+
+async fn sintercard(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: SInterCard = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("SINTERCARD", sintercard);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, parser::Parser, set::sintercard::SInterCard},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_sintercard() {
+        assert!(SInterCard::try_from(Parser::from_args(&["SINTERCARD", "0", "a"])).is_err());
+        assert!(SInterCard::try_from(Parser::from_args(&["SINTERCARD", "2", "a"])).is_err());
+        assert!(
+            SInterCard::try_from(Parser::from_args(&["SINTERCARD", "1", "a", "LIMIT", "-1"]))
+                .is_err()
+        );
+        assert!(SInterCard::try_from(Parser::from_args(&["SINTERCARD", "1", "a", "b"])).is_err());
+
+        let ctx = Context::test_client(1);
+        for (key, range) in [("a", 0..10), ("b", 5..20)] {
+            let mut set = RedisObject::new_set();
+            for i in range {
+                set.ptr.set_add(i.to_string().as_bytes());
+            }
+            ctx.db().insert(key.to_string(), set, None);
+        }
+        let sintercard = |args: &[&str]| {
+            SInterCard::try_from(Parser::from_args(args))
+                .unwrap()
+                .execute(ctx.clone())
+        };
+
+        assert_eq!(
+            sintercard(&["SINTERCARD", "2", "a", "b"]).await.unwrap(),
+            Frame::Integer(5)
+        );
+        assert_eq!(
+            sintercard(&["SINTERCARD", "2", "a", "b", "LIMIT", "3"])
+                .await
+                .unwrap(),
+            Frame::Integer(3)
+        );
+        assert_eq!(
+            sintercard(&["SINTERCARD", "2", "a", "b", "limit", "0"])
+                .await
+                .unwrap(),
+            Frame::Integer(5)
+        );
+        assert_eq!(
+            sintercard(&["SINTERCARD", "2", "a", "missing"])
+                .await
+                .unwrap(),
+            Frame::Integer(0)
+        );
+    }
+}
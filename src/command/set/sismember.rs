@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor, error::CommandError, parser::Parser, registry::CommandResult, set::as_set,
+    },
+    context::Context,
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `SISMEMBER key member` and `SMISMEMBER key member [member ...]`, the latter replies an
+/// array with one answer per member
+#[derive(PartialEq, Eq, Debug)]
+struct SIsMember {
+    key: String,
+    members: Vec<Vec<u8>>,
+    multi: bool,
+}
+
+impl SIsMember {
+    fn parse(multi: bool, mut parser: Parser) -> Result<Self, CommandError> {
+        let key = parser.next()?;
+        let members = if multi {
+            parser.remaining()?
+        } else {
+            vec![parser.next()?]
+        };
+        if parser.has_next() {
+            return Err(CommandError::SyntaxError);
+        }
+        Ok(SIsMember {
+            key,
+            members,
+            multi,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for SIsMember {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let found = ctx
+            .db()
+            .get_with(&self.key, |value| {
+                let set = as_set(value)?;
+                Ok::<_, CommandError>(
+                    self.members
+                        .iter()
+                        .map(|member| set.set_contains(member))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .transpose()?
+            .unwrap_or_else(|| vec![false; self.members.len()]);
+        let mut frames = found.into_iter().map(|found| Frame::Integer(found as i64));
+        if self.multi {
+            Ok(Frame::Array(Some(frames.collect())))
+        } else {
+            Ok(frames.next().unwrap_or(Frame::Integer(0)))
+        }
+    }
+}
+
+async fn sismember(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    SIsMember::parse(false, parser)?.execute(ctx).await
+}
+
+async fn smismember(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    SIsMember::parse(true, parser)?.execute(ctx).await
+}
+
+register_redis_command!("SISMEMBER", sismember);
+register_redis_command!("SMISMEMBER", smismember);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, parser::Parser, set::sismember::SIsMember},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_sismember() {
+        assert!(SIsMember::parse(false, Parser::from_args(&["SISMEMBER", "s", "a", "b"])).is_err());
+        assert!(SIsMember::parse(true, Parser::from_args(&["SMISMEMBER", "s"])).is_err());
+
+        let ctx = Context::test_client(1);
+        let mut set = RedisObject::new_set();
+        set.ptr.set_add(b"a");
+        set.ptr.set_add(b"10");
+        ctx.db().insert("s".to_string(), set, None);
+        let sismember = |multi, args: &[&str]| {
+            SIsMember::parse(multi, Parser::from_args(args))
+                .unwrap()
+                .execute(ctx.clone())
+        };
+
+        assert_eq!(
+            sismember(false, &["SISMEMBER", "s", "10"]).await.unwrap(),
+            Frame::Integer(1)
+        );
+        assert_eq!(
+            sismember(false, &["SISMEMBER", "missing", "a"])
+                .await
+                .unwrap(),
+            Frame::Integer(0)
+        );
+        assert_eq!(
+            sismember(true, &["SMISMEMBER", "s", "a", "b", "10"])
+                .await
+                .unwrap(),
+            Frame::Array(Some(vec![
+                Frame::Integer(1),
+                Frame::Integer(0),
+                Frame::Integer(1)
+            ]))
+        );
+        assert_eq!(
+            sismember(true, &["SMISMEMBER", "missing", "a", "b"])
+                .await
+                .unwrap(),
+            Frame::Array(Some(vec![Frame::Integer(0), Frame::Integer(0)]))
+        );
+    }
+}
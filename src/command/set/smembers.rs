@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, registry::CommandResult, set::as_set},
+    context::Context,
+    protocol::Frame,
+};
+
+/// `SMEMBERS key`, an empty set when the key doesn't exist
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("SMEMBERS")]
+struct SMembers {
+    key: String,
+}
+
+#[async_trait]
+impl CommandExecutor for SMembers {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let members = ctx
+            .db()
+            .get_with(&self.key, |value| {
+                as_set(value).map(|set| {
+                    set.set_iter()
+                        .map(|member| Frame::BulkString(Some(member.into_owned())))
+                        .collect()
+                })
+            })
+            .transpose()?
+            .unwrap_or_default();
+        Ok(Frame::Set(members))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, set::smembers::SMembers},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_smembers() {
+        let ctx = Context::test_client(1);
+        let mut set = RedisObject::new_set();
+        for member in [b"3", b"1", b"2"] {
+            set.ptr.set_add(member);
+        }
+        ctx.db().insert("s".to_string(), set, None);
+        let smembers = |key: &str| {
+            SMembers {
+                key: key.to_string(),
+            }
+            .execute(ctx.clone())
+        };
+        let bulk = |s: &str| Frame::BulkString(Some(s.as_bytes().to_vec()));
+
+        // an intset is sorted
+        assert_eq!(
+            smembers("s").await.unwrap(),
+            Frame::Set(vec![bulk("1"), bulk("2"), bulk("3")])
+        );
+        assert_eq!(smembers("missing").await.unwrap(), Frame::Set(vec![]));
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        registry::CommandResult,
+        set::{as_set, as_set_mut},
+    },
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    object::redis_object::{ObjectType, RedisObject},
+    protocol::Frame,
+};
+
+/// `SMOVE source destination member`, replies 1 when `member` was moved and 0 when it isn't
+/// a member of `source`
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("SMOVE")]
+struct SMove {
+    source: String,
+    destination: String,
+    member: Vec<u8>,
+}
+
+#[async_trait]
+impl CommandExecutor for SMove {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let id = db.id();
+        // nothing is removed when the destination can't take it
+        if db
+            .key_type(&self.destination)
+            .is_some_and(|obj_type| obj_type != ObjectType::Set)
+        {
+            return Err(CommandError::WrongType);
+        }
+        if self.source == self.destination {
+            let found = db
+                .get_with(&self.source, |value| {
+                    as_set(value).map(|set| set.set_contains(&self.member))
+                })
+                .transpose()?
+                .unwrap_or(false);
+            return Ok(Frame::Integer(found as i64));
+        }
+        let removed = db
+            .modify(&self.source, |value| {
+                let removed = as_set_mut(value)?.set_remove(&self.member);
+                if removed {
+                    notify_keyspace_event(notify::SET, "srem", &self.source, id);
+                }
+                Ok::<_, CommandError>(removed)
+            })
+            .transpose()?
+            .unwrap_or(false);
+        if !removed {
+            return Ok(Frame::Integer(0));
+        }
+        let add = |key: &str| {
+            db.modify_or_insert(key, RedisObject::new_set, |value| {
+                if as_set_mut(value)?.set_add(&self.member) {
+                    notify_keyspace_event(notify::SET, "sadd", key, id);
+                }
+                Ok::<_, CommandError>(())
+            })
+        };
+        // the destination may have changed type since the check, the member goes back
+        if let Err(err) = add(&self.destination) {
+            let _ = add(&self.source);
+            return Err(err);
+        }
+        Ok(Frame::Integer(1))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, set::smove::SMove},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_smove() {
+        let ctx = Context::test_client(1);
+        let mut set = RedisObject::new_set();
+        set.ptr.set_add(b"a");
+        set.ptr.set_add(b"b");
+        ctx.db().insert("src".to_string(), set, None);
+        ctx.db().set(
+            "str".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+        let smove = |source: &str, destination: &str, member: &str| {
+            SMove {
+                source: source.to_string(),
+                destination: destination.to_string(),
+                member: member.as_bytes().to_vec(),
+            }
+            .execute(ctx.clone())
+        };
+        let contains = |key: &str, member: &str| {
+            ctx.db()
+                .get_with(key, |value| value.ptr.set_contains(member.as_bytes()))
+                .unwrap_or(false)
+        };
+
+        assert_eq!(smove("src", "dst", "a").await.unwrap(), Frame::Integer(1));
+        assert!(contains("dst", "a") && !contains("src", "a"));
+        assert_eq!(smove("src", "dst", "a").await.unwrap(), Frame::Integer(0));
+        assert_eq!(smove("src", "src", "b").await.unwrap(), Frame::Integer(1));
+        assert_eq!(
+            smove("missing", "dst", "b").await.unwrap(),
+            Frame::Integer(0)
+        );
+
+        // a destination of another type keeps the member in the source
+        assert!(smove("src", "str", "b").await.is_err());
+        assert!(contains("src", "b"));
+        assert!(smove("str", "dst", "b").await.is_err());
+
+        // the last member deletes the source
+        assert_eq!(smove("src", "dst", "b").await.unwrap(), Frame::Integer(1));
+        assert!(!ctx.db().contains_key("src"));
+        assert_eq!(
+            ctx.db().get_with("dst", |value| value.ptr.set_len()),
+            Some(2)
+        );
+    }
+}
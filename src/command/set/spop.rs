@@ -0,0 +1,138 @@
+use std::{borrow::Cow, sync::Arc};
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor, error::CommandError, parser::Parser, registry::CommandResult,
+        set::as_set_mut,
+    },
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `SPOP key [count]`, removes and replies random members. The key is deleted with its last
+/// member.
+#[derive(PartialEq, Eq, Debug)]
+struct SPop {
+    key: String,
+    count: Option<usize>,
+}
+
+impl TryFrom<Parser> for SPop {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let key = parser.next()?;
+        let count = if parser.has_next() {
+            let count: i64 = parser.next()?;
+            Some(usize::try_from(count).map_err(|_| CommandError::NotPositive)?)
+        } else {
+            None
+        };
+        if parser.has_next() {
+            return Err(CommandError::SyntaxError);
+        }
+        Ok(SPop { key, count })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for SPop {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let id = db.id();
+        let popped = db
+            .modify(&self.key, |value| {
+                let set = as_set_mut(value)?;
+                let members: Vec<Vec<u8>> = set.set_iter().map(Cow::into_owned).collect();
+                let count = self.count.unwrap_or(1).min(members.len());
+                let popped: Vec<Vec<u8>> =
+                    rand::seq::index::sample(&mut rand::rng(), members.len(), count)
+                        .into_iter()
+                        .map(|index| members[index].clone())
+                        .collect();
+                for member in popped.iter() {
+                    set.set_remove(member);
+                }
+                if !popped.is_empty() {
+                    notify_keyspace_event(notify::SET, "spop", &self.key, id);
+                }
+                Ok::<_, CommandError>(popped)
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        if self.count.is_none() {
+            return Ok(popped
+                .into_iter()
+                .next()
+                .map_or(Frame::Null, |member| Frame::BulkString(Some(member))));
+        }
+        Ok(Frame::Set(
+            popped
+                .into_iter()
+                .map(|member| Frame::BulkString(Some(member)))
+                .collect(),
+        ))
+    }
+}
+
+async fn spop(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: SPop = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("SPOP", spop);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, parser::Parser, set::spop::SPop},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_spop() {
+        assert!(SPop::try_from(Parser::from_args(&["SPOP", "s", "-1"])).is_err());
+        assert!(SPop::try_from(Parser::from_args(&["SPOP", "s", "1", "2"])).is_err());
+
+        let ctx = Context::test_client(1);
+        let mut set = RedisObject::new_set();
+        for i in 0..5 {
+            set.ptr.set_add(i.to_string().as_bytes());
+        }
+        ctx.db().insert("s".to_string(), set, None);
+        let spop = |args: &[&str]| {
+            SPop::try_from(Parser::from_args(args))
+                .unwrap()
+                .execute(ctx.clone())
+        };
+        let len = || ctx.db().get_with("s", |value| value.ptr.set_len());
+
+        assert!(matches!(
+            spop(&["SPOP", "s"]).await.unwrap(),
+            Frame::BulkString(Some(_))
+        ));
+        assert_eq!(len(), Some(4));
+        let Frame::Set(popped) = spop(&["SPOP", "s", "3"]).await.unwrap() else {
+            panic!("SPOP with a count replies a set");
+        };
+        assert_eq!(popped.len(), 3);
+        assert_eq!(len(), Some(1));
+        assert_eq!(spop(&["SPOP", "s", "0"]).await.unwrap(), Frame::Set(vec![]));
+
+        // the last member deletes the key
+        let Frame::Set(popped) = spop(&["SPOP", "s", "10"]).await.unwrap() else {
+            panic!("SPOP with a count replies a set");
+        };
+        assert_eq!(popped.len(), 1);
+        assert!(!ctx.db().contains_key("s"));
+        assert_eq!(spop(&["SPOP", "s"]).await.unwrap(), Frame::Null);
+        assert_eq!(spop(&["SPOP", "s", "2"]).await.unwrap(), Frame::Set(vec![]));
+    }
+}
@@ -0,0 +1,140 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor, error::CommandError, parser::Parser, random_picks,
+        registry::CommandResult, set::as_set,
+    },
+    context::Context,
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `SRANDMEMBER key [count]`.
+///
+/// A positive count replies distinct members, a negative one may repeat them.
+#[derive(PartialEq, Eq, Debug)]
+struct SRandMember {
+    key: String,
+    count: Option<i64>,
+}
+
+impl TryFrom<Parser> for SRandMember {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let key = parser.next()?;
+        let count = if parser.has_next() {
+            Some(parser.next()?)
+        } else {
+            None
+        };
+        if parser.has_next() {
+            return Err(CommandError::SyntaxError);
+        }
+        // a negative count is negated
+        if count == Some(i64::MIN) {
+            return Err(CommandError::OutOfRange);
+        }
+        Ok(SRandMember { key, count })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for SRandMember {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let picked = ctx
+            .db()
+            .get_with(&self.key, |value| {
+                let set = as_set(value)?;
+                // without a count a single member is picked
+                let count = self.count.unwrap_or(1);
+                let picked = random_picks(set.set_iter(), set.set_len(), count)
+                    .into_iter()
+                    .map(|member| Frame::BulkString(Some(member.into_owned())))
+                    .collect::<Vec<_>>();
+                Ok::<_, CommandError>(picked)
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        if self.count.is_none() {
+            return Ok(picked.into_iter().next().unwrap_or(Frame::Null));
+        }
+        Ok(Frame::Array(Some(picked)))
+    }
+}
+
+async fn srandmember(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: SRandMember = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("SRANDMEMBER", srandmember);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{
+            CommandExecutor, error::CommandError, parser::Parser, set::srandmember::SRandMember,
+        },
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[test]
+    fn test_srandmember_count_range() {
+        let parse = |args: &[&str]| SRandMember::try_from(Parser::from_args(args));
+        assert!(matches!(
+            parse(&["SRANDMEMBER", "s", "-9223372036854775808"]),
+            Err(CommandError::OutOfRange)
+        ));
+        assert!(parse(&["SRANDMEMBER", "s", "-9223372036854775807"]).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_srandmember() {
+        let ctx = Context::test_client(1);
+        let mut set = RedisObject::new_set();
+        for member in [b"a", b"b", b"c"] {
+            set.ptr.set_add(member);
+        }
+        ctx.db().insert("s".to_string(), set, None);
+        let srandmember = |key: &str, count| {
+            SRandMember {
+                key: key.to_string(),
+                count,
+            }
+            .execute(ctx.clone())
+        };
+        let bulk = |s: &str| Frame::BulkString(Some(s.as_bytes().to_vec()));
+        let members = |frame: Frame| match frame {
+            Frame::Array(Some(frames)) => frames,
+            frame => panic!("unexpected reply {:?}", frame),
+        };
+
+        assert!(matches!(
+            srandmember("s", None).await.unwrap(),
+            Frame::BulkString(Some(_))
+        ));
+        assert_eq!(srandmember("missing", None).await.unwrap(), Frame::Null);
+        assert_eq!(
+            srandmember("missing", Some(3)).await.unwrap(),
+            Frame::Array(Some(vec![]))
+        );
+
+        let mut distinct = members(srandmember("s", Some(5)).await.unwrap());
+        distinct.sort_by_key(|frame| format!("{:?}", frame));
+        assert_eq!(distinct, [bulk("a"), bulk("b"), bulk("c")]);
+        assert_eq!(members(srandmember("s", Some(2)).await.unwrap()).len(), 2);
+        assert_eq!(
+            members(srandmember("s", Some(-10)).await.unwrap()).len(),
+            10
+        );
+        // it doesn't remove anything
+        assert_eq!(ctx.db().get_with("s", |value| value.ptr.set_len()), Some(3));
+    }
+}
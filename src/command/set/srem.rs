@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor, error::CommandError, parser::Parser, registry::CommandResult,
+        set::as_set_mut,
+    },
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `SREM key member [member ...]`, replies the count of removed members. The key is deleted
+/// with its last member.
+#[derive(PartialEq, Eq, Debug)]
+struct SRem {
+    key: String,
+    members: Vec<Vec<u8>>,
+}
+
+impl TryFrom<Parser> for SRem {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        Ok(SRem {
+            key: parser.next()?,
+            members: parser.remaining()?,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for SRem {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let id = db.id();
+        let removed = db
+            .modify(&self.key, |value| {
+                let set = as_set_mut(value)?;
+                let removed = self
+                    .members
+                    .iter()
+                    .filter(|member| set.set_remove(member))
+                    .count();
+                if removed > 0 {
+                    notify_keyspace_event(notify::SET, "srem", &self.key, id);
+                }
+                Ok::<_, CommandError>(removed)
+            })
+            .transpose()?
+            .unwrap_or(0);
+        Ok(Frame::Integer(removed as i64))
+    }
+}
+
+async fn srem(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: SRem = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("SREM", srem);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, set::srem::SRem},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_srem() {
+        let ctx = Context::test_client(1);
+        let mut set = RedisObject::new_set();
+        set.ptr.set_add(b"a");
+        set.ptr.set_add(b"b");
+        ctx.db().insert("s".to_string(), set, None);
+        let srem = |members: &[&str]| {
+            SRem {
+                key: "s".to_string(),
+                members: members
+                    .iter()
+                    .map(|member| member.as_bytes().to_vec())
+                    .collect(),
+            }
+            .execute(ctx.clone())
+        };
+
+        assert_eq!(srem(&["a", "c"]).await.unwrap(), Frame::Integer(1));
+        assert_eq!(srem(&["a"]).await.unwrap(), Frame::Integer(0));
+        // the last member deletes the key
+        assert_eq!(srem(&["b"]).await.unwrap(), Frame::Integer(1));
+        assert!(!ctx.db().contains_key("s"));
+        assert_eq!(srem(&["b"]).await.unwrap(), Frame::Integer(0));
+    }
+}
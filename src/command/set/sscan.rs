@@ -0,0 +1,104 @@
+use std::{borrow::Cow, sync::Arc};
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        option::{ScanArgs, scan_reply},
+        parser::Parser,
+        registry::CommandResult,
+        set::as_set,
+    },
+    context::Context,
+    register_redis_command,
+};
+
+/// `SSCAN key cursor [MATCH pattern] [COUNT count]`
+#[derive(PartialEq, Eq, Debug)]
+struct SScan {
+    key: String,
+    args: ScanArgs,
+}
+
+impl TryFrom<Parser> for SScan {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let key = parser.next()?;
+        Ok(SScan {
+            key,
+            args: ScanArgs::parse(&mut parser, None)?,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for SScan {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let args = self.args;
+        let page = ctx.db().get_with(&self.key, |value| {
+            let (cursor, page) = as_set(value)?.set_scan(args.cursor, args.count);
+            let members = page
+                .into_iter()
+                .filter(|member| args.matches(member))
+                .map(Cow::into_owned)
+                .collect();
+            Ok::<_, CommandError>((cursor, members))
+        });
+        let (cursor, members) = page.transpose()?.unwrap_or_default();
+        Ok(scan_reply(cursor, members))
+    }
+}
+
+async fn sscan(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: SScan = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("SSCAN", sscan);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, parser::Parser, set::sscan::SScan},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_sscan() {
+        let ctx = Context::test_client(1);
+        let mut set = RedisObject::new_set();
+        for i in 0..30 {
+            set.ptr.set_add(i.to_string().as_bytes());
+        }
+        ctx.db().insert("s".to_string(), set, None);
+
+        let mut members = Vec::new();
+        let mut cursor = "0".to_string();
+        loop {
+            let cmd = SScan::try_from(Parser::from_args(&["SSCAN", "s", &cursor, "MATCH", "2*"]))
+                .unwrap();
+            let Frame::Array(Some(reply)) = cmd.execute(ctx.clone()).await.unwrap() else {
+                panic!("SSCAN replies an array");
+            };
+            let Frame::Array(Some(page)) = &reply[1] else {
+                panic!("SSCAN replies the members in an array");
+            };
+            members.extend(page.iter().cloned());
+            let Frame::BulkString(Some(next)) = &reply[0] else {
+                panic!("SSCAN replies a cursor");
+            };
+            cursor = String::from_utf8(next.clone()).unwrap();
+            if cursor == "0" {
+                break;
+            }
+        }
+        // 2 and 20..=29
+        assert_eq!(members.len(), 11);
+        assert!(SScan::try_from(Parser::from_args(&["SSCAN", "s", "0", "NOVALUES"])).is_err());
+    }
+}
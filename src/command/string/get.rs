@@ -4,7 +4,7 @@ use async_trait::async_trait;
 use rudis_macros::Command;
 
 use crate::{
-    command::{CommandExecutor, registry::CommandResult},
+    command::{CommandExecutor, registry::CommandResult, string::string_reply},
     context::Context,
     protocol::Frame,
 };
@@ -18,11 +18,11 @@ struct Get {
 #[async_trait]
 impl CommandExecutor for Get {
     async fn execute(self, ctx: Arc<Context>) -> CommandResult {
-        let db = ctx.db.clone();
+        let db = ctx.db();
         log::debug!("[string] ctx {} get {}", ctx.id, &self.key);
         if let Some(o) = db.get(&self.key) {
             log::debug!("value get: {}", &self.key);
-            Ok(o.into())
+            string_reply(o)
         } else {
             Ok(Frame::Null)
         }
@@ -33,7 +33,12 @@ impl CommandExecutor for Get {
 mod test {
     use crate::command::parser::Parser;
     #[cfg(test)]
-    use crate::{command::string::get::Get, protocol::Frame};
+    use crate::{
+        command::{CommandExecutor, error::CommandError, string::get::Get},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
 
     #[test]
     fn test_try_from_frame_to_set_ok() {
@@ -47,4 +52,29 @@ mod test {
             }
         )
     }
+
+    #[tokio::test]
+    async fn test_get_wrong_type() {
+        let ctx = Context::test_client(1);
+        let mut set = RedisObject::new_set();
+        set.ptr.set_add(b"a");
+        ctx.db().insert("s".to_string(), set, None);
+        ctx.db().set(
+            "n".to_string(),
+            RedisObject::new_string(b"12".to_vec()),
+            None,
+        );
+        let get = |key: &str| {
+            Get {
+                key: key.to_string(),
+            }
+            .execute(ctx.clone())
+        };
+        assert_eq!(
+            get("n").await.unwrap(),
+            Frame::BulkString(Some(b"12".to_vec()))
+        );
+        assert_eq!(get("missing").await.unwrap(), Frame::Null);
+        assert!(matches!(get("s").await, Err(CommandError::WrongType)));
+    }
 }
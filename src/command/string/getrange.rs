@@ -37,10 +37,10 @@ fn get_range(len: usize, start: i64, end: i64) -> Option<Range<usize>> {
 #[async_trait]
 impl CommandExecutor for GetRangeCommand {
     async fn execute(self, ctx: Arc<Context>) -> CommandResult {
-        let db = ctx.db.clone();
+        let db = ctx.db();
         log::debug!("Getting range for {}", &self.key);
         if let Some(o) = db.get(&self.key) {
-            if &o.header.obj_type() != &ObjectType::String {
+            if o.header.obj_type() != ObjectType::String {
                 log::error!("`GETRANGE` operation on {} value", &o.header.obj_type());
                 return Err(CommandError::WrongType);
             }
@@ -4,8 +4,9 @@ use async_trait::async_trait;
 use rudis_macros::Command;
 
 use crate::{
-    command::{CommandExecutor, registry::CommandResult},
+    command::{CommandExecutor, registry::CommandResult, string::string_reply},
     context::Context,
+    notify::{self, notify_keyspace_event},
     object::redis_object::RedisObject,
     protocol::Frame,
 };
@@ -20,13 +21,51 @@ struct GetSetCommand {
 #[async_trait]
 impl CommandExecutor for GetSetCommand {
     async fn execute(self, ctx: Arc<Context>) -> CommandResult {
-        let db = ctx.db.clone();
-        if let Some(origin_val) = db.get(&self.key) {
-            db.set(self.key, RedisObject::new_string(self.value), None);
-            Ok(origin_val.into())
-        } else {
-            db.set(self.key, RedisObject::new_string(self.value), None);
-            Ok(Frame::Null)
-        }
+        let db = ctx.db();
+        // a value of another type is left alone
+        let origin_val = db.get(&self.key).map(string_reply).transpose()?;
+        let key = self.key.clone();
+        db.set(self.key, RedisObject::new_string(self.value), None);
+        notify_keyspace_event(notify::STRING, "set", &key, db.id());
+        Ok(origin_val.unwrap_or(Frame::Null))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, error::CommandError, string::getset::GetSetCommand},
+        context::Context,
+        object::redis_object::{ObjectType, RedisObject},
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_getset() {
+        let ctx = Context::test_client(1);
+        let mut set = RedisObject::new_set();
+        set.ptr.set_add(b"a");
+        ctx.db().insert("s".to_string(), set, None);
+        let getset = |key: &str, value: &str| {
+            GetSetCommand {
+                key: key.to_string(),
+                value: value.as_bytes().to_vec(),
+            }
+            .execute(ctx.clone())
+        };
+
+        assert_eq!(getset("k", "a").await.unwrap(), Frame::Null);
+        assert_eq!(
+            getset("k", "b").await.unwrap(),
+            Frame::BulkString(Some(b"a".to_vec()))
+        );
+        assert!(matches!(
+            getset("s", "x").await,
+            Err(CommandError::WrongType)
+        ));
+        assert_eq!(
+            ctx.db().get_with("s", |value| value.header.obj_type()),
+            Some(ObjectType::Set)
+        );
     }
 }
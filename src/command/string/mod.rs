@@ -1,4 +1,24 @@
+use crate::{
+    command::error::CommandError,
+    object::redis_object::{ObjectType, RedisObject, RedisValue},
+    protocol::Frame,
+};
+
 mod get;
 mod set;
 mod getrange;
 mod getset;
+
+/// The reply of a string value, `WRONGTYPE` for the other types
+fn string_reply(value: RedisObject) -> Result<Frame, CommandError> {
+    if value.header.obj_type() != ObjectType::String {
+        return Err(CommandError::WrongType);
+    }
+    let bytes = match value.ptr {
+        RedisValue::Int(i) => i.to_string().into_bytes(),
+        RedisValue::EmbStr(emb_str) => emb_str.into(),
+        RedisValue::Raw(raw) => raw.into(),
+        _ => return Err(CommandError::WrongType),
+    };
+    Ok(Frame::BulkString(Some(bytes)))
+}
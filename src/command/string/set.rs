@@ -8,6 +8,7 @@ use crate::object::redis_object::RedisObject;
 use crate::{
     command::{CommandExecutor, registry::CommandResult},
     context::Context,
+    notify::{self, notify_keyspace_event},
     protocol::Frame,
 };
 
@@ -38,16 +39,18 @@ struct SetCommand {
 impl CommandExecutor for SetCommand {
     async fn execute(self, ctx: Arc<Context>) -> CommandResult {
         log::debug!("{:?}", &self);
-        let db = ctx.db.clone();
+        let db = ctx.db();
         log::debug!(
             "[string] ctx {} set {{{}: {:?}}}",
             ctx.id,
             &self.key,
             &self.value[..self.value.len().min(16)]
         );
+        let key = self.key.clone();
         db.set(self.key, RedisObject::new_string(self.value), None);
+        notify_keyspace_event(notify::STRING, "set", &key, db.id());
         log::debug!("value set");
-        Ok(Frame::Boolean(true))
+        Ok(Frame::SimpleString("OK".to_string()))
     }
 }
 
@@ -55,7 +58,11 @@ impl CommandExecutor for SetCommand {
 mod test {
     use crate::command::parser::Parser;
     #[cfg(test)]
-    use crate::{command::string::set::SetCommand, protocol::Frame};
+    use crate::{
+        command::{CommandExecutor, string::set::SetCommand},
+        context::Context,
+        protocol::Frame,
+    };
 
     #[test]
     fn test_try_from_frame_to_set_ok() {
@@ -78,4 +85,20 @@ mod test {
             }
         )
     }
+
+    #[tokio::test]
+    async fn test_set_replies_ok() {
+        let ctx = Context::test_client(1);
+        let parser = Parser::new(Frame::Array(Some(vec![
+            Frame::BulkString(Some(b"key".to_vec())),
+            Frame::BulkString(Some(b"value".to_vec())),
+        ])))
+        .unwrap();
+        let command: SetCommand = parser.try_into().unwrap();
+        assert_eq!(
+            command.execute(ctx.clone()).await.unwrap(),
+            Frame::SimpleString("OK".to_string())
+        );
+        assert!(ctx.db().contains_key("key"));
+    }
 }
@@ -0,0 +1,127 @@
+use crate::{
+    command::error::CommandError,
+    object::redis_object::{ObjectType, RedisObject, RedisValue},
+};
+
+mod zadd;
+mod zcard;
+mod zcount;
+mod zrange;
+mod zrank;
+mod zrem;
+mod zscan;
+mod zscore;
+
+fn as_zset(value: &RedisObject) -> Result<&RedisValue, CommandError> {
+    match value.header.obj_type() {
+        ObjectType::Zset => Ok(&value.ptr),
+        _ => Err(CommandError::WrongType),
+    }
+}
+
+fn as_zset_mut(value: &mut RedisObject) -> Result<&mut RedisValue, CommandError> {
+    match value.header.obj_type() {
+        ObjectType::Zset => Ok(&mut value.ptr),
+        _ => Err(CommandError::WrongType),
+    }
+}
+
+/// A float argument, `inf` and `-inf` included but not NaN
+fn parse_double(arg: &[u8]) -> Option<f64> {
+    std::str::from_utf8(arg)
+        .ok()
+        .and_then(|arg| arg.parse::<f64>().ok())
+        .filter(|score| !score.is_nan())
+}
+
+/// A score to store, infinite only when written as `inf`, `+inf` or `-inf` and not when a
+/// number overflows
+fn parse_score(arg: &[u8]) -> Result<f64, CommandError> {
+    let literal = |arg: &[u8]| {
+        [&b"inf"[..], b"+inf", b"-inf"]
+            .iter()
+            .any(|inf| arg.eq_ignore_ascii_case(inf))
+    };
+    parse_double(arg)
+        .filter(|score| score.is_finite() || literal(arg))
+        .ok_or(CommandError::NotFloat)
+}
+
+/// An end of a `BYLEX` range: `-`, `+`, or a member after `[` or `(` when it's excluded
+#[derive(PartialEq, Eq, Debug)]
+enum LexBound {
+    Min,
+    Max,
+    Inclusive(Vec<u8>),
+    Exclusive(Vec<u8>),
+}
+
+impl LexBound {
+    fn parse(arg: &[u8]) -> Result<Self, CommandError> {
+        match arg {
+            b"-" => Ok(LexBound::Min),
+            b"+" => Ok(LexBound::Max),
+            [b'[', member @ ..] => Ok(LexBound::Inclusive(member.to_vec())),
+            [b'(', member @ ..] => Ok(LexBound::Exclusive(member.to_vec())),
+            _ => Err(CommandError::InvalidLexRange),
+        }
+    }
+}
+
+/// Members between `min` and `max` by score, or by member when they all have the same score
+#[derive(PartialEq, Debug)]
+enum Range {
+    /// Each score is excluded when its flag is set, it was written after `(`
+    Score((f64, bool), (f64, bool)),
+    Lex(LexBound, LexBound),
+}
+
+impl Range {
+    fn parse_score(min: &[u8], max: &[u8]) -> Result<Self, CommandError> {
+        let bound = |arg: &[u8]| {
+            let (arg, exclusive) = match arg {
+                [b'(', arg @ ..] => (arg, true),
+                arg => (arg, false),
+            };
+            parse_double(arg)
+                .map(|score| (score, exclusive))
+                .ok_or(CommandError::MinMaxNotFloat)
+        };
+        Ok(Range::Score(bound(min)?, bound(max)?))
+    }
+
+    fn parse_lex(min: &[u8], max: &[u8]) -> Result<Self, CommandError> {
+        Ok(Range::Lex(LexBound::parse(min)?, LexBound::parse(max)?))
+    }
+
+    /// Whether a member comes before `min`
+    fn before_min(&self, member: &[u8], score: f64) -> bool {
+        match self {
+            Range::Score((min, true), _) => score <= *min,
+            Range::Score((min, false), _) => score < *min,
+            Range::Lex(LexBound::Min, _) => false,
+            Range::Lex(LexBound::Max, _) => true,
+            Range::Lex(LexBound::Inclusive(min), _) => member < &min[..],
+            Range::Lex(LexBound::Exclusive(min), _) => member <= &min[..],
+        }
+    }
+
+    /// Whether a member doesn't come after `max`
+    fn within_max(&self, member: &[u8], score: f64) -> bool {
+        match self {
+            Range::Score(_, (max, true)) => score < *max,
+            Range::Score(_, (max, false)) => score <= *max,
+            Range::Lex(_, LexBound::Min) => false,
+            Range::Lex(_, LexBound::Max) => true,
+            Range::Lex(_, LexBound::Inclusive(max)) => member <= &max[..],
+            Range::Lex(_, LexBound::Exclusive(max)) => member < &max[..],
+        }
+    }
+
+    /// Ranks `start..end` of the members in the range, found in O(log n) on a skiplist
+    fn ranks(&self, zset: &RedisValue) -> (usize, usize) {
+        let start = zset.zset_count_while(|member, score| self.before_min(member, score));
+        let end = zset.zset_count_while(|member, score| self.within_max(member, score));
+        (start, end.max(start))
+    }
+}
@@ -0,0 +1,278 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        parser::Parser,
+        registry::CommandResult,
+        zset::{as_zset_mut, parse_score},
+    },
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    object::redis_object::RedisObject,
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `ZADD key [NX | XX] [GT | LT] [CH] [INCR] score member [score member ...]`, replies the
+/// count of new members, or of changed ones as well with `CH`.
+///
+/// With `INCR` the score is added to the current one and the new score is replied, nil when
+/// a condition refused the update. `ZINCRBY key increment member` is the same.
+#[derive(PartialEq, Debug, Default)]
+struct ZAdd {
+    key: String,
+    /// Only add new members
+    nx: bool,
+    /// Only update existing members
+    xx: bool,
+    /// Only update to a greater score
+    gt: bool,
+    /// Only update to a lower score
+    lt: bool,
+    ch: bool,
+    incr: bool,
+    pairs: Vec<(f64, Vec<u8>)>,
+}
+
+impl TryFrom<Parser> for ZAdd {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let mut cmd = ZAdd {
+            key: parser.next()?,
+            ..Default::default()
+        };
+        let args: Vec<Vec<u8>> = parser.remaining()?;
+        let mut args = args.into_iter().peekable();
+        while let Some(arg) = args.peek() {
+            let flag = match arg.to_ascii_uppercase().as_slice() {
+                b"NX" => &mut cmd.nx,
+                b"XX" => &mut cmd.xx,
+                b"GT" => &mut cmd.gt,
+                b"LT" => &mut cmd.lt,
+                b"CH" => &mut cmd.ch,
+                b"INCR" => &mut cmd.incr,
+                _ => break,
+            };
+            *flag = true;
+            args.next();
+        }
+        let args: Vec<Vec<u8>> = args.collect();
+        if args.is_empty() || !args.len().is_multiple_of(2) {
+            return Err(CommandError::SyntaxError);
+        }
+        if cmd.nx && cmd.xx {
+            return Err(CommandError::IncompatibleOptions("XX and NX".to_string()));
+        }
+        if (cmd.gt && cmd.lt) || (cmd.nx && (cmd.gt || cmd.lt)) {
+            return Err(CommandError::IncompatibleOptions(
+                "GT, LT, and/or NX".to_string(),
+            ));
+        }
+        if cmd.incr && args.len() > 2 {
+            return Err(CommandError::IncrSinglePair);
+        }
+        for pair in args.chunks(2) {
+            cmd.pairs.push((parse_score(&pair[0])?, pair[1].clone()));
+        }
+        Ok(cmd)
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for ZAdd {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let id = db.id();
+        let add = |value: &mut RedisObject| {
+            let zset = as_zset_mut(value)?;
+            let (mut added, mut updated) = (0, 0);
+            // the score of the last member added or updated
+            let mut score = None;
+            for (increment, member) in self.pairs.iter() {
+                let current = zset.zset_score(member);
+                if (self.nx && current.is_some()) || (self.xx && current.is_none()) {
+                    continue;
+                }
+                let new = match current {
+                    Some(current) if self.incr => current + increment,
+                    _ => *increment,
+                };
+                if new.is_nan() {
+                    return Err(CommandError::ScoreNaN);
+                }
+                if let Some(current) = current {
+                    if (self.gt && new <= current) || (self.lt && new >= current) {
+                        continue;
+                    }
+                    if new != current {
+                        zset.zset_set(member, new);
+                        updated += 1;
+                    }
+                } else {
+                    zset.zset_set(member, new);
+                    added += 1;
+                }
+                score = Some(new);
+            }
+            if added + updated > 0 {
+                let event = if self.incr { "zincr" } else { "zadd" };
+                notify_keyspace_event(notify::ZSET, event, &self.key, id);
+            }
+            Ok::<_, CommandError>((added, updated, score))
+        };
+        // a member is never added with XX, so the key isn't created
+        let (added, updated, score) = if self.xx {
+            db.modify(&self.key, add).transpose()?.unwrap_or_default()
+        } else {
+            db.modify_or_insert(&self.key, RedisObject::new_zset, add)?
+        };
+
+        if self.incr {
+            return Ok(score.map_or(Frame::Null, Frame::Double));
+        }
+        let changed = if self.ch { added + updated } else { added };
+        Ok(Frame::Integer(changed))
+    }
+}
+
+async fn zadd(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: ZAdd = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+async fn zincrby(ctx: Arc<Context>, mut parser: Parser) -> CommandResult {
+    let key = parser.next()?;
+    let increment: Vec<u8> = parser.next()?;
+    let member = parser.next()?;
+    if parser.has_next() {
+        return Err(CommandError::SyntaxError);
+    }
+    let cmd = ZAdd {
+        key,
+        incr: true,
+        pairs: vec![(parse_score(&increment)?, member)],
+        ..Default::default()
+    };
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("ZADD", zadd);
+register_redis_command!("ZINCRBY", zincrby);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, parser::Parser, zset::zadd::ZAdd},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[test]
+    fn test_parse_zadd() {
+        let cmd = ZAdd::try_from(Parser::from_args(&[
+            "ZADD", "z", "xx", "CH", "1.5", "a", "-inf", "b", "+INF", "c",
+        ]));
+        let cmd = cmd.unwrap();
+        assert!(cmd.xx && cmd.ch && !cmd.nx);
+        assert_eq!(
+            cmd.pairs,
+            [
+                (1.5, b"a".to_vec()),
+                (f64::NEG_INFINITY, b"b".to_vec()),
+                (f64::INFINITY, b"c".to_vec())
+            ]
+        );
+        for args in [
+            &["ZADD", "z", "1"][..],
+            &["ZADD", "z", "NX"],
+            &["ZADD", "z", "nan", "a"],
+            &["ZADD", "z", "1e400", "a"],
+            &["ZADD", "z", "-1e400", "a"],
+            &["ZADD", "z", "infinity", "a"],
+            &["ZADD", "z", "one", "a"],
+            &["ZADD", "z", "NX", "XX", "1", "a"],
+            &["ZADD", "z", "GT", "LT", "1", "a"],
+            &["ZADD", "z", "NX", "GT", "1", "a"],
+            &["ZADD", "z", "INCR", "1", "a", "2", "b"],
+        ] {
+            assert!(
+                ZAdd::try_from(Parser::from_args(args)).is_err(),
+                "{:?}",
+                args
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_zadd() {
+        let ctx = Context::test_client(1);
+        let zadd = |args: &[&str]| {
+            ZAdd::try_from(Parser::from_args(args))
+                .unwrap()
+                .execute(ctx.clone())
+        };
+        let score = |member: &str| {
+            ctx.db()
+                .get_with("z", |value| value.ptr.zset_score(member.as_bytes()))
+                .flatten()
+        };
+
+        assert_eq!(
+            zadd(&["ZADD", "z", "XX", "1", "a"]).await.unwrap(),
+            Frame::Integer(0)
+        );
+        assert!(!ctx.db().contains_key("z"));
+        assert_eq!(
+            zadd(&["ZADD", "z", "1", "a", "2", "b"]).await.unwrap(),
+            Frame::Integer(2)
+        );
+        assert_eq!(
+            zadd(&["ZADD", "z", "CH", "3", "a", "2", "b", "1", "c"])
+                .await
+                .unwrap(),
+            Frame::Integer(2)
+        );
+        assert_eq!(
+            zadd(&["ZADD", "z", "NX", "10", "a", "4", "d"])
+                .await
+                .unwrap(),
+            Frame::Integer(1)
+        );
+        assert_eq!(score("a"), Some(3.0));
+        assert_eq!(
+            zadd(&["ZADD", "z", "GT", "CH", "1", "a", "5", "b"])
+                .await
+                .unwrap(),
+            Frame::Integer(1)
+        );
+        assert_eq!((score("a"), score("b")), (Some(3.0), Some(5.0)));
+
+        assert_eq!(
+            zadd(&["ZADD", "z", "INCR", "2.5", "a"]).await.unwrap(),
+            Frame::Double(5.5)
+        );
+        assert_eq!(
+            zadd(&["ZADD", "z", "LT", "INCR", "1", "a"]).await.unwrap(),
+            Frame::Null
+        );
+        assert_eq!(
+            zadd(&["ZADD", "z", "INCR", "+inf", "a"]).await.unwrap(),
+            Frame::Double(f64::INFINITY)
+        );
+        assert!(zadd(&["ZADD", "z", "INCR", "-inf", "a"]).await.is_err());
+        assert_eq!(score("a"), Some(f64::INFINITY));
+
+        ctx.db().set(
+            "str".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+        assert!(zadd(&["ZADD", "str", "1", "a"]).await.is_err());
+    }
+}
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rudis_macros::Command;
+
+use crate::{
+    command::{CommandExecutor, registry::CommandResult, zset::as_zset},
+    context::Context,
+    protocol::Frame,
+};
+
+/// `ZCARD key`, 0 when the key doesn't exist
+#[derive(PartialEq, Eq, Command, Debug)]
+#[command("ZCARD")]
+struct ZCard {
+    key: String,
+}
+
+#[async_trait]
+impl CommandExecutor for ZCard {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let len = ctx
+            .db()
+            .get_with(&self.key, |value| {
+                as_zset(value).map(|zset| zset.zset_len())
+            })
+            .transpose()?
+            .unwrap_or(0);
+        Ok(Frame::Integer(len as i64))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, zset::zcard::ZCard},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_zcard() {
+        let ctx = Context::test_client(1);
+        let mut zset = RedisObject::new_zset();
+        zset.ptr.zset_set(b"a", 1.0);
+        zset.ptr.zset_set(b"b", 1.0);
+        ctx.db().insert("z".to_string(), zset, None);
+        ctx.db().set(
+            "str".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+        let zcard = |key: &str| {
+            ZCard {
+                key: key.to_string(),
+            }
+            .execute(ctx.clone())
+        };
+
+        assert_eq!(zcard("z").await.unwrap(), Frame::Integer(2));
+        assert_eq!(zcard("missing").await.unwrap(), Frame::Integer(0));
+        assert!(zcard("str").await.is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        parser::Parser,
+        registry::CommandResult,
+        zset::{Range, as_zset},
+    },
+    context::Context,
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `ZCOUNT key min max` counts the members with a score in the range, `ZLEXCOUNT key min max`
+/// the members in the range when they all have the same score
+#[derive(PartialEq, Debug)]
+struct ZCount {
+    key: String,
+    range: Range,
+}
+
+impl ZCount {
+    fn parse(lex: bool, mut parser: Parser) -> Result<Self, CommandError> {
+        let key = parser.next()?;
+        let min: Vec<u8> = parser.next()?;
+        let max: Vec<u8> = parser.next()?;
+        if parser.has_next() {
+            return Err(CommandError::SyntaxError);
+        }
+        let range = if lex {
+            Range::parse_lex(&min, &max)?
+        } else {
+            Range::parse_score(&min, &max)?
+        };
+        Ok(ZCount { key, range })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for ZCount {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let count = ctx
+            .db()
+            .get_with(&self.key, |value| {
+                let (start, end) = self.range.ranks(as_zset(value)?);
+                Ok::<_, CommandError>(end - start)
+            })
+            .transpose()?
+            .unwrap_or(0);
+        Ok(Frame::Integer(count as i64))
+    }
+}
+
+async fn zcount(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    ZCount::parse(false, parser)?.execute(ctx).await
+}
+
+async fn zlexcount(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    ZCount::parse(true, parser)?.execute(ctx).await
+}
+
+register_redis_command!("ZCOUNT", zcount);
+register_redis_command!("ZLEXCOUNT", zlexcount);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, parser::Parser, zset::zcount::ZCount},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_zcount() {
+        assert!(ZCount::parse(false, Parser::from_args(&["ZCOUNT", "z", "a", "1"])).is_err());
+        assert!(ZCount::parse(false, Parser::from_args(&["ZCOUNT", "z", "(nan", "1"])).is_err());
+        assert!(ZCount::parse(true, Parser::from_args(&["ZLEXCOUNT", "z", "a", "+"])).is_err());
+
+        let ctx = Context::test_client(1);
+        // scores on the listpack, members on the skiplist
+        let mut scores = RedisObject::new_zset();
+        for i in 0..10 {
+            scores.ptr.zset_set(format!("m{}", i).as_bytes(), i as f64);
+        }
+        ctx.db().insert("scores".to_string(), scores, None);
+        let mut members = RedisObject::new_zset();
+        for c in b'a'..=b'z' {
+            members.ptr.zset_set(&[c], 0.0);
+        }
+        members.ptr.zset_set(&[b'~'; 100], 0.0);
+        assert_eq!(members.ptr.encoding(), "skiplist");
+        ctx.db().insert("members".to_string(), members, None);
+        let count = |lex, args: &[&str]| {
+            let cmd = ZCount::parse(lex, Parser::from_args(args)).unwrap();
+            let ctx = ctx.clone();
+            async move {
+                match cmd.execute(ctx).await.unwrap() {
+                    Frame::Integer(count) => count,
+                    frame => panic!("unexpected reply {:?}", frame),
+                }
+            }
+        };
+
+        assert_eq!(count(false, &["ZCOUNT", "scores", "2", "5"]).await, 4);
+        assert_eq!(count(false, &["ZCOUNT", "scores", "(2", "(5"]).await, 2);
+        assert_eq!(
+            count(false, &["ZCOUNT", "scores", "-inf", "+inf"]).await,
+            10
+        );
+        assert_eq!(count(false, &["ZCOUNT", "scores", "5", "2"]).await, 0);
+        assert_eq!(count(false, &["ZCOUNT", "missing", "0", "1"]).await, 0);
+        assert_eq!(count(true, &["ZLEXCOUNT", "members", "[b", "[d"]).await, 3);
+        assert_eq!(count(true, &["ZLEXCOUNT", "members", "(b", "(d"]).await, 1);
+        assert_eq!(count(true, &["ZLEXCOUNT", "members", "-", "+"]).await, 27);
+        assert_eq!(count(true, &["ZLEXCOUNT", "members", "[x", "-"]).await, 0);
+    }
+}
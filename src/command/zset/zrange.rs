@@ -0,0 +1,285 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        parser::Parser,
+        registry::CommandResult,
+        zset::{Range, as_zset},
+    },
+    context::Context,
+    object::redis_object::RedisValue,
+    protocol::{Frame, ProtocolVersion},
+    register_redis_command,
+};
+
+/// What the `start` and `stop` of `ZRANGE` are
+#[derive(PartialEq, Debug)]
+enum Bounds {
+    /// Ranks, negative ones count from the end
+    Index(i64, i64),
+    /// `BYSCORE` or `BYLEX`, with `LIMIT offset count`
+    Range(Range, Option<(i64, i64)>),
+}
+
+/// `ZRANGE key start stop [BYSCORE | BYLEX] [REV] [LIMIT offset count] [WITHSCORES]`.
+///
+/// `REV` replies the members by descending score, `start` and `stop` of a `BYSCORE` or
+/// `BYLEX` range are then its max and min.
+#[derive(PartialEq, Debug)]
+struct ZRange {
+    key: String,
+    bounds: Bounds,
+    rev: bool,
+    with_scores: bool,
+}
+
+impl TryFrom<Parser> for ZRange {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let key = parser.next()?;
+        let start: Vec<u8> = parser.next()?;
+        let stop: Vec<u8> = parser.next()?;
+        let (mut by_score, mut by_lex, mut rev, mut with_scores) = (false, false, false, false);
+        let mut limit = None;
+        while parser.has_next() {
+            let option: String = parser.next()?;
+            match option.to_ascii_uppercase().as_str() {
+                "BYSCORE" => by_score = true,
+                "BYLEX" => by_lex = true,
+                "REV" => rev = true,
+                "WITHSCORES" => with_scores = true,
+                "LIMIT" => limit = Some((parser.next()?, parser.next()?)),
+                _ => return Err(CommandError::SyntaxError),
+            }
+        }
+        if by_score && by_lex {
+            return Err(CommandError::SyntaxError);
+        }
+        if limit.is_some() && !by_score && !by_lex {
+            return Err(CommandError::LimitWithoutBy);
+        }
+        if with_scores && by_lex {
+            return Err(CommandError::WithScoresByLex);
+        }
+        let (min, max) = if rev {
+            (&stop, &start)
+        } else {
+            (&start, &stop)
+        };
+        let bounds = if by_score {
+            Bounds::Range(Range::parse_score(min, max)?, limit)
+        } else if by_lex {
+            Bounds::Range(Range::parse_lex(min, max)?, limit)
+        } else {
+            let index = |arg: Vec<u8>| -> Result<i64, CommandError> {
+                Ok(String::from_utf8(arg)?.parse()?)
+            };
+            Bounds::Index(index(start)?, index(stop)?)
+        };
+        Ok(ZRange {
+            key,
+            bounds,
+            rev,
+            with_scores,
+        })
+    }
+}
+
+impl ZRange {
+    /// Ranks `start..end` of the replied members, in ascending order
+    fn ranks(&self, zset: &RedisValue) -> (usize, usize) {
+        let len = zset.zset_len() as i64;
+        match &self.bounds {
+            Bounds::Index(start, stop) => {
+                let start = if *start < 0 { len + start } else { *start }.max(0);
+                let stop = if *stop < 0 { len + stop } else { *stop }.min(len - 1);
+                if start > stop {
+                    (0, 0)
+                } else if self.rev {
+                    ((len - 1 - stop) as usize, (len - start) as usize)
+                } else {
+                    (start as usize, stop as usize + 1)
+                }
+            }
+            Bounds::Range(range, limit) => {
+                let (start, end) = range.ranks(zset);
+                let Some((offset, count)) = *limit else {
+                    return (start, end);
+                };
+                if offset < 0 {
+                    return (0, 0);
+                }
+                let offset = offset as usize;
+                // a negative count replies every member after the offset
+                let count = usize::try_from(count).unwrap_or(usize::MAX);
+                if self.rev {
+                    let end = end.saturating_sub(offset).max(start);
+                    (end.saturating_sub(count).max(start), end)
+                } else {
+                    let start = start.saturating_add(offset).min(end);
+                    (start, start.saturating_add(count).min(end))
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for ZRange {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let members = ctx
+            .db()
+            .get_with(&self.key, |value| {
+                let zset = as_zset(value)?;
+                let (start, end) = self.ranks(zset);
+                let range = zset.zset_range(start, end);
+                let members: Vec<(Vec<u8>, f64)> = if self.rev {
+                    range
+                        .rev()
+                        .map(|(member, score)| (member.to_vec(), score))
+                        .collect()
+                } else {
+                    range
+                        .map(|(member, score)| (member.to_vec(), score))
+                        .collect()
+                };
+                Ok::<_, CommandError>(members)
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let members = members
+            .into_iter()
+            .map(|(member, score)| (Frame::BulkString(Some(member)), Frame::Double(score)));
+        let frames = if !self.with_scores {
+            members.map(|(member, _)| member).collect()
+        } else if ctx.protocol_version() == ProtocolVersion::Resp3 {
+            members
+                .map(|(member, score)| Frame::Array(Some(vec![member, score])))
+                .collect()
+        } else {
+            members
+                .flat_map(|(member, score)| [member, score])
+                .collect()
+        };
+        Ok(Frame::Array(Some(frames)))
+    }
+}
+
+async fn zrange(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: ZRange = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("ZRANGE", zrange);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, parser::Parser, zset::zrange::ZRange},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_zrange() {
+        for args in [
+            &["ZRANGE", "z", "0", "1", "LIMIT", "0", "1"][..],
+            &["ZRANGE", "z", "[a", "[b", "BYLEX", "WITHSCORES"],
+            &["ZRANGE", "z", "0", "1", "BYSCORE", "BYLEX"],
+            &["ZRANGE", "z", "a", "1", "BYSCORE"],
+            &["ZRANGE", "z", "a", "b", "BYLEX"],
+            &["ZRANGE", "z", "0", "1.5"],
+            &["ZRANGE", "z", "0", "1", "LIMIT", "0"],
+        ] {
+            assert!(
+                ZRange::try_from(Parser::from_args(args)).is_err(),
+                "{:?}",
+                args
+            );
+        }
+
+        let ctx = Context::test_client(1);
+        // the same members on the listpack and on the skiplist
+        let mut small = RedisObject::new_zset();
+        let mut large = RedisObject::new_zset();
+        for (i, member) in ["a", "b", "c", "d", "e"].iter().enumerate() {
+            small.ptr.zset_set(member.as_bytes(), i as f64);
+            large.ptr.zset_set(member.as_bytes(), i as f64);
+        }
+        large.ptr.zset_set(&[b'~'; 100], 10.0);
+        large.ptr.zset_remove(&[b'~'; 100]);
+        assert_eq!(large.ptr.encoding(), "skiplist");
+        ctx.db().insert("small".to_string(), small, None);
+        ctx.db().insert("large".to_string(), large, None);
+        let bulk = |s: &str| Frame::BulkString(Some(s.as_bytes().to_vec()));
+
+        for key in ["small", "large"] {
+            let zrange = |args: &[&str]| {
+                let mut args = args.to_vec();
+                args.insert(1, key);
+                let cmd = ZRange::try_from(Parser::from_args(&args)).unwrap();
+                let ctx = ctx.clone();
+                async move {
+                    match cmd.execute(ctx).await.unwrap() {
+                        Frame::Array(Some(frames)) => frames,
+                        frame => panic!("unexpected reply {:?}", frame),
+                    }
+                }
+            };
+            let members = |members: &[&str]| members.iter().map(|m| bulk(m)).collect::<Vec<_>>();
+
+            assert_eq!(zrange(&["ZRANGE", "1", "2"]).await, members(&["b", "c"]));
+            assert_eq!(zrange(&["ZRANGE", "-2", "100"]).await, members(&["d", "e"]));
+            assert_eq!(
+                zrange(&["ZRANGE", "0", "1", "REV"]).await,
+                members(&["e", "d"])
+            );
+            assert_eq!(zrange(&["ZRANGE", "3", "1"]).await, members(&[]));
+            assert_eq!(
+                zrange(&["ZRANGE", "(1", "3", "BYSCORE"]).await,
+                members(&["c", "d"])
+            );
+            assert_eq!(
+                zrange(&[
+                    "ZRANGE", "+inf", "-inf", "BYSCORE", "REV", "LIMIT", "1", "2"
+                ])
+                .await,
+                members(&["d", "c"])
+            );
+            assert_eq!(
+                zrange(&["ZRANGE", "1", "10", "BYSCORE", "LIMIT", "3", "-1"]).await,
+                members(&["e"])
+            );
+            assert_eq!(
+                zrange(&["ZRANGE", "0", "10", "BYSCORE", "LIMIT", "-1", "2"]).await,
+                members(&[])
+            );
+            assert_eq!(
+                zrange(&["ZRANGE", "[b", "(d", "BYLEX"]).await,
+                members(&["b", "c"])
+            );
+            assert_eq!(
+                zrange(&["ZRANGE", "+", "-", "BYLEX", "REV", "LIMIT", "0", "2"]).await,
+                members(&["e", "d"])
+            );
+            // RESP2 replies the scores flattened after their members
+            assert_eq!(
+                zrange(&["ZRANGE", "0", "0", "WITHSCORES"]).await,
+                [bulk("a"), Frame::Double(0.0)]
+            );
+        }
+        let missing =
+            ZRange::try_from(Parser::from_args(&["ZRANGE", "missing", "0", "-1"])).unwrap();
+        assert_eq!(
+            missing.execute(ctx.clone()).await.unwrap(),
+            Frame::Array(Some(vec![]))
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor, error::CommandError, parser::Parser, registry::CommandResult,
+        zset::as_zset,
+    },
+    context::Context,
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `ZRANK key member [WITHSCORE]` replies the rank of `member` by ascending score, starting
+/// at 0. `ZREVRANK` ranks by descending score.
+#[derive(PartialEq, Eq, Debug)]
+struct ZRank {
+    key: String,
+    member: Vec<u8>,
+    rev: bool,
+    with_score: bool,
+}
+
+impl ZRank {
+    fn parse(rev: bool, mut parser: Parser) -> Result<Self, CommandError> {
+        let key = parser.next()?;
+        let member = parser.next()?;
+        let with_score = if parser.has_next() {
+            let option: String = parser.next()?;
+            if !option.eq_ignore_ascii_case("WITHSCORE") {
+                return Err(CommandError::SyntaxError);
+            }
+            true
+        } else {
+            false
+        };
+        if parser.has_next() {
+            return Err(CommandError::SyntaxError);
+        }
+        Ok(ZRank {
+            key,
+            member,
+            rev,
+            with_score,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for ZRank {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let ranked = ctx
+            .db()
+            .get_with(&self.key, |value| {
+                let zset = as_zset(value)?;
+                let ranked = zset.zset_rank(&self.member).map(|rank| {
+                    let rank = if self.rev {
+                        zset.zset_len() - 1 - rank
+                    } else {
+                        rank
+                    };
+                    (rank, zset.zset_score(&self.member).unwrap_or_default())
+                });
+                Ok::<_, CommandError>(ranked)
+            })
+            .transpose()?
+            .flatten();
+        Ok(match ranked {
+            Some((rank, score)) if self.with_score => Frame::Array(Some(vec![
+                Frame::Integer(rank as i64),
+                Frame::Double(score),
+            ])),
+            Some((rank, _)) => Frame::Integer(rank as i64),
+            None if self.with_score => Frame::Array(None),
+            None => Frame::Null,
+        })
+    }
+}
+
+async fn zrank(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    ZRank::parse(false, parser)?.execute(ctx).await
+}
+
+async fn zrevrank(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    ZRank::parse(true, parser)?.execute(ctx).await
+}
+
+register_redis_command!("ZRANK", zrank);
+register_redis_command!("ZREVRANK", zrevrank);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, parser::Parser, zset::zrank::ZRank},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_zrank() {
+        assert!(
+            ZRank::parse(false, Parser::from_args(&["ZRANK", "z", "a", "WITHSCORES"])).is_err()
+        );
+
+        let ctx = Context::test_client(1);
+        let mut zset = RedisObject::new_zset();
+        for i in 0..200 {
+            zset.ptr
+                .zset_set(format!("player{}", i).as_bytes(), (i * 10) as f64);
+        }
+        assert_eq!(zset.ptr.encoding(), "skiplist");
+        ctx.db().insert("z".to_string(), zset, None);
+        let zrank = |rev, args: &[&str]| {
+            ZRank::parse(rev, Parser::from_args(args))
+                .unwrap()
+                .execute(ctx.clone())
+        };
+
+        assert_eq!(
+            zrank(false, &["ZRANK", "z", "player42"]).await.unwrap(),
+            Frame::Integer(42)
+        );
+        assert_eq!(
+            zrank(true, &["ZREVRANK", "z", "player42", "WITHSCORE"])
+                .await
+                .unwrap(),
+            Frame::Array(Some(vec![Frame::Integer(157), Frame::Double(420.0)]))
+        );
+        assert_eq!(
+            zrank(false, &["ZRANK", "z", "nobody"]).await.unwrap(),
+            Frame::Null
+        );
+        assert_eq!(
+            zrank(false, &["ZRANK", "missing", "a", "withscore"])
+                .await
+                .unwrap(),
+            Frame::Array(None)
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor, error::CommandError, parser::Parser, registry::CommandResult,
+        zset::as_zset_mut,
+    },
+    context::Context,
+    notify::{self, notify_keyspace_event},
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `ZREM key member [member ...]`, replies the count of removed members. The key is deleted
+/// with its last member.
+#[derive(PartialEq, Eq, Debug)]
+struct ZRem {
+    key: String,
+    members: Vec<Vec<u8>>,
+}
+
+impl TryFrom<Parser> for ZRem {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        Ok(ZRem {
+            key: parser.next()?,
+            members: parser.remaining()?,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for ZRem {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let db = ctx.db();
+        let id = db.id();
+        let removed = db
+            .modify(&self.key, |value| {
+                let zset = as_zset_mut(value)?;
+                let removed = self
+                    .members
+                    .iter()
+                    .filter(|member| zset.zset_remove(member))
+                    .count();
+                if removed > 0 {
+                    notify_keyspace_event(notify::ZSET, "zrem", &self.key, id);
+                }
+                Ok::<_, CommandError>(removed)
+            })
+            .transpose()?
+            .unwrap_or(0);
+        Ok(Frame::Integer(removed as i64))
+    }
+}
+
+async fn zrem(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: ZRem = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("ZREM", zrem);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, zset::zrem::ZRem},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_zrem() {
+        let ctx = Context::test_client(1);
+        let mut zset = RedisObject::new_zset();
+        zset.ptr.zset_set(b"a", 1.0);
+        zset.ptr.zset_set(b"b", 2.0);
+        ctx.db().insert("z".to_string(), zset, None);
+        let zrem = |members: &[&str]| {
+            ZRem {
+                key: "z".to_string(),
+                members: members
+                    .iter()
+                    .map(|member| member.as_bytes().to_vec())
+                    .collect(),
+            }
+            .execute(ctx.clone())
+        };
+
+        assert_eq!(zrem(&["a", "c"]).await.unwrap(), Frame::Integer(1));
+        assert_eq!(zrem(&["a"]).await.unwrap(), Frame::Integer(0));
+        // the last member deletes the key
+        assert_eq!(zrem(&["b"]).await.unwrap(), Frame::Integer(1));
+        assert!(!ctx.db().contains_key("z"));
+        assert_eq!(zrem(&["b"]).await.unwrap(), Frame::Integer(0));
+    }
+}
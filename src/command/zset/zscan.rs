@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor,
+        error::CommandError,
+        option::{ScanArgs, scan_reply},
+        parser::Parser,
+        registry::CommandResult,
+        zset::as_zset,
+    },
+    context::Context,
+    protocol::format_double,
+    register_redis_command,
+};
+
+/// `ZSCAN key cursor [MATCH pattern] [COUNT count] [NOSCORES]`
+#[derive(PartialEq, Eq, Debug)]
+struct ZScan {
+    key: String,
+    args: ScanArgs,
+}
+
+impl TryFrom<Parser> for ZScan {
+    type Error = CommandError;
+
+    fn try_from(mut parser: Parser) -> Result<Self, Self::Error> {
+        let key = parser.next()?;
+        Ok(ZScan {
+            key,
+            args: ScanArgs::parse(&mut parser, Some("NOSCORES"))?,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for ZScan {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let args = self.args;
+        let page = ctx.db().get_with(&self.key, |value| {
+            let (cursor, page) = as_zset(value)?.zset_scan(args.cursor, args.count);
+            let mut elements = Vec::new();
+            for (member, score) in page.into_iter().filter(|(member, _)| args.matches(member)) {
+                elements.push(member.to_vec());
+                if !args.no_values {
+                    elements.push(format_double(score).into_bytes());
+                }
+            }
+            Ok::<_, CommandError>((cursor, elements))
+        });
+        let (cursor, elements) = page.transpose()?.unwrap_or_default();
+        Ok(scan_reply(cursor, elements))
+    }
+}
+
+async fn zscan(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    let cmd: ZScan = parser.try_into()?;
+    cmd.execute(ctx).await
+}
+
+register_redis_command!("ZSCAN", zscan);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, parser::Parser, zset::zscan::ZScan},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[test]
+    fn test_try_from_parser_to_zscan() {
+        let cmd = ZScan::try_from(Parser::from_args(&[
+            "ZSCAN", "z", "42", "NOSCORES", "COUNT", "5",
+        ]))
+        .unwrap();
+        assert_eq!(cmd.key, "z");
+        assert_eq!((cmd.args.cursor, cmd.args.count), (42, 5));
+        assert!(cmd.args.no_values);
+        assert!(ZScan::try_from(Parser::from_args(&["ZSCAN", "z", "0", "TYPE", "zset"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_zscan() {
+        let ctx = Context::test_client(1);
+        let mut zset = RedisObject::new_zset();
+        for i in 0..200 {
+            zset.ptr
+                .zset_set(format!("m{}", i).as_bytes(), i as f64 / 2.0);
+        }
+        ctx.db().insert("z".to_string(), zset, None);
+
+        let mut elements = Vec::new();
+        let mut cursor = "0".to_string();
+        loop {
+            let cmd = ZScan::try_from(Parser::from_args(&["ZSCAN", "z", &cursor, "MATCH", "m1?"]))
+                .unwrap();
+            let Frame::Array(Some(reply)) = cmd.execute(ctx.clone()).await.unwrap() else {
+                panic!("ZSCAN replies an array");
+            };
+            let Frame::Array(Some(page)) = &reply[1] else {
+                panic!("ZSCAN replies the members in an array");
+            };
+            elements.extend(page.iter().cloned());
+            let Frame::BulkString(Some(next)) = &reply[0] else {
+                panic!("ZSCAN replies a cursor");
+            };
+            cursor = String::from_utf8(next.clone()).unwrap();
+            if cursor == "0" {
+                break;
+            }
+        }
+        // m10..=m19 and their scores
+        assert_eq!(elements.len(), 20);
+        let position = elements
+            .iter()
+            .position(|element| *element == Frame::BulkString(Some(b"m15".to_vec())))
+            .unwrap();
+        assert_eq!(
+            elements[position + 1],
+            Frame::BulkString(Some(b"7.5".to_vec()))
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    command::{
+        CommandExecutor, error::CommandError, parser::Parser, registry::CommandResult,
+        zset::as_zset,
+    },
+    context::Context,
+    protocol::Frame,
+    register_redis_command,
+};
+
+/// `ZSCORE key member` and `ZMSCORE key member [member ...]`, the latter replies an array
+/// with the score of every member, nil for a missing one
+#[derive(PartialEq, Eq, Debug)]
+struct ZScore {
+    key: String,
+    members: Vec<Vec<u8>>,
+    multi: bool,
+}
+
+impl ZScore {
+    fn parse(multi: bool, mut parser: Parser) -> Result<Self, CommandError> {
+        let key = parser.next()?;
+        let members = if multi {
+            parser.remaining()?
+        } else {
+            vec![parser.next()?]
+        };
+        if parser.has_next() {
+            return Err(CommandError::SyntaxError);
+        }
+        Ok(ZScore {
+            key,
+            members,
+            multi,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for ZScore {
+    async fn execute(self, ctx: Arc<Context>) -> CommandResult {
+        let scores = ctx
+            .db()
+            .get_with(&self.key, |value| {
+                let zset = as_zset(value)?;
+                Ok::<_, CommandError>(
+                    self.members
+                        .iter()
+                        .map(|member| zset.zset_score(member))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .transpose()?
+            .unwrap_or_else(|| vec![None; self.members.len()]);
+        let mut frames = scores
+            .into_iter()
+            .map(|score| score.map_or(Frame::Null, Frame::Double));
+        if self.multi {
+            Ok(Frame::Array(Some(frames.collect())))
+        } else {
+            Ok(frames.next().unwrap_or(Frame::Null))
+        }
+    }
+}
+
+async fn zscore(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    ZScore::parse(false, parser)?.execute(ctx).await
+}
+
+async fn zmscore(ctx: Arc<Context>, parser: Parser) -> CommandResult {
+    ZScore::parse(true, parser)?.execute(ctx).await
+}
+
+register_redis_command!("ZSCORE", zscore);
+register_redis_command!("ZMSCORE", zmscore);
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandExecutor, parser::Parser, zset::zscore::ZScore},
+        context::Context,
+        object::redis_object::RedisObject,
+        protocol::Frame,
+    };
+
+    #[tokio::test]
+    async fn test_zscore() {
+        assert!(ZScore::parse(false, Parser::from_args(&["ZSCORE", "z", "a", "b"])).is_err());
+
+        let ctx = Context::test_client(1);
+        let mut zset = RedisObject::new_zset();
+        zset.ptr.zset_set(b"a", 1.5);
+        ctx.db().insert("z".to_string(), zset, None);
+        let zscore = |multi, args: &[&str]| {
+            ZScore::parse(multi, Parser::from_args(args))
+                .unwrap()
+                .execute(ctx.clone())
+        };
+
+        assert_eq!(
+            zscore(false, &["ZSCORE", "z", "a"]).await.unwrap(),
+            Frame::Double(1.5)
+        );
+        assert_eq!(
+            zscore(false, &["ZSCORE", "missing", "a"]).await.unwrap(),
+            Frame::Null
+        );
+        assert_eq!(
+            zscore(true, &["ZMSCORE", "z", "b", "a"]).await.unwrap(),
+            Frame::Array(Some(vec![Frame::Null, Frame::Double(1.5)]))
+        );
+        assert_eq!(
+            zscore(true, &["ZMSCORE", "missing", "a"]).await.unwrap(),
+            Frame::Array(Some(vec![Frame::Null]))
+        );
+    }
+}
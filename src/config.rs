@@ -1,37 +1,364 @@
 ﻿use log::LevelFilter;
-use std::{env, sync::OnceLock};
+use std::{
+    env,
+    fmt::{Display, Formatter},
+    path::PathBuf,
+    str::FromStr,
+    sync::OnceLock,
+};
 
 #[derive(Debug)]
 pub struct Config {
-    pub listen_ip: String,
+    /// Addresses to listen on, `bind`. An address prefixed with `-` is skipped when it is not
+    /// available, `*` and `::*` are the IPv4 and IPv6 wildcards. default: 127.0.0.1 -::1
+    pub bind: Vec<String>,
+    /// TCP port, 0 disables the TCP listeners. default: 6379
     pub port: u16,
 
+    /// Path of the unix socket to listen on, `unixsocket`, default: none
+    pub unixsocket: Option<PathBuf>,
+    /// Permission bits of the unix socket, `unixsocketperm`, e.g. `700`, default: umask
+    pub unixsocketperm: Option<u32>,
+
+    /// TLS port on the `bind` addresses, 0 disables TLS. default: 0
+    pub tls_port: u16,
+    /// PEM certificate chain of the server, `tls-cert-file`
+    pub tls_cert_file: Option<PathBuf>,
+    /// PEM private key of the server, `tls-key-file`
+    pub tls_key_file: Option<PathBuf>,
+    /// PEM CA certificates used to verify clients, `tls-ca-cert-file`
+    pub tls_ca_cert_file: Option<PathBuf>,
+    /// Whether clients must present a certificate, `tls-auth-clients`, default: yes
+    pub tls_auth_clients: TlsAuthClients,
+
+    /// Count of logical databases, `databases`, default: 16
+    pub databases: usize,
+
+    /// Seconds to wait for in-flight commands on shutdown, `shutdown-timeout`, default: 10
+    pub shutdown_timeout: u64,
+
+    /// Frequency of the background tasks like the active expire cycle, 1 to 500. default: 10
+    pub hz: u32,
+
+    /// Max count of connected clients, `maxclients`, default: 10000
+    pub maxclients: usize,
+    /// Close clients idle for more than this many seconds, 0 disables it. default: 0
+    pub timeout: u64,
+    /// Seconds between TCP keepalive probes, `tcp-keepalive`, 0 disables it. default: 300
+    pub tcp_keepalive: u64,
+
+    /// Memory limit in bytes, `maxmemory`, 0 means no limit. default: 0
+    pub maxmemory: usize,
+    /// How keys are picked for eviction once `maxmemory` is reached, default: noeviction
+    pub maxmemory_policy: MaxmemoryPolicy,
+    /// Keys sampled per database to pick the one to evict, `maxmemory-samples`, default: 5
+    pub maxmemory_samples: usize,
+    /// Accesses needed to grow the LFU counter of a key, `lfu-log-factor`. default: 10
+    pub lfu_log_factor: u32,
+    /// Minutes after which an idle LFU counter is decremented, 0 never decays. default: 1
+    pub lfu_decay_time: u32,
+
+    /// Entries of a list node when positive, or its size from -1 (4kb) to -5 (64kb),
+    /// `list-max-listpack-size`. default: -2
+    pub list_max_listpack_size: i64,
+    /// List nodes left uncompressed at each end, 0 disables compression,
+    /// `list-compress-depth`. default: 0
+    pub list_compress_depth: usize,
+    /// Fields of a hash kept in a listpack, `hash-max-listpack-entries`. default: 128
+    pub hash_max_listpack_entries: usize,
+    /// Longest field or value of a hash kept in a listpack, `hash-max-listpack-value`.
+    /// default: 64
+    pub hash_max_listpack_value: usize,
+    /// Members of a set of integers kept in an intset, `set-max-intset-entries`. default: 512
+    pub set_max_intset_entries: usize,
+    /// Members of a set kept in a listpack, `set-max-listpack-entries`. default: 128
+    pub set_max_listpack_entries: usize,
+    /// Longest member of a set kept in a listpack, `set-max-listpack-value`. default: 64
+    pub set_max_listpack_value: usize,
+    /// Members of a sorted set kept in a listpack, `zset-max-listpack-entries`. default: 128
+    pub zset_max_listpack_entries: usize,
+    /// Longest member of a sorted set kept in a listpack, `zset-max-listpack-value`.
+    /// default: 64
+    pub zset_max_listpack_value: usize,
+
+    /// Evicted values are dropped on the lazy free thread, `lazyfree-lazy-eviction`. default: no
+    pub lazyfree_lazy_eviction: bool,
+    /// Expired values are dropped on the lazy free thread, `lazyfree-lazy-expire`. default: no
+    pub lazyfree_lazy_expire: bool,
+    /// Values overwritten by e.g. `SET` or `RENAME` are dropped on the lazy free thread,
+    /// `lazyfree-lazy-server-del`. default: no
+    pub lazyfree_lazy_server_del: bool,
+    /// `DEL` behaves like `UNLINK`, `lazyfree-lazy-user-del`. default: no
+    pub lazyfree_lazy_user_del: bool,
+
+    /// Classes of keyspace events published, `notify-keyspace-events`. default: ""
+    pub notify_keyspace_events: u32,
+
     /// default: info
     pub log_level: log::LevelFilter,
 
-    /// default: 512MB
-    pub string_max_length: usize,
+    /// Max length of a bulk string in a request, `proto-max-bulk-len`, default: 512MB
+    pub proto_max_bulk_len: usize,
+
+    /// Max count of elements of an aggregate in a request, default: 2147483647
+    pub proto_max_multibulk_len: usize,
+
+    /// Max depth of nested aggregates in a request, default: 16
+    pub proto_max_nesting: usize,
+
+    /// Max length of an inline request or a type header line, default: 64KB
+    pub proto_inline_max_size: usize,
+
+    /// Max bytes buffered for the pending request of a client, `client-query-buffer-limit`,
+    /// default: 1GB
+    pub client_query_buffer_limit: usize,
+
+    /// Max bytes of pushes, e.g. pub/sub messages, waiting to be written to a client before it
+    /// is closed, the hard limit of `client-output-buffer-limit pubsub`, 0 for no limit,
+    /// default: 32MB
+    pub client_output_buffer_limit_pubsub: usize,
+}
+
+/// `tls-auth-clients`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TlsAuthClients {
+    /// A valid client certificate is required
+    Yes,
+    /// Client certificates are neither requested nor verified
+    No,
+    /// A client certificate is verified if the client sends one
+    Optional,
+}
+
+impl FromStr for TlsAuthClients {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "yes" => Ok(TlsAuthClients::Yes),
+            "no" => Ok(TlsAuthClients::No),
+            "optional" => Ok(TlsAuthClients::Optional),
+            _ => Err(format!("invalid tls-auth-clients: {}", s)),
+        }
+    }
+}
+
+/// `maxmemory-policy`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaxmemoryPolicy {
+    /// Write commands fail with `-OOM` instead of evicting keys
+    NoEviction,
+    AllKeysLru,
+    VolatileLru,
+    AllKeysLfu,
+    VolatileLfu,
+    AllKeysRandom,
+    VolatileRandom,
+    /// Evict the keys which expire soonest
+    VolatileTtl,
+}
+
+impl MaxmemoryPolicy {
+    /// Only keys with an expire time are evicted
+    pub fn is_volatile(&self) -> bool {
+        matches!(
+            self,
+            MaxmemoryPolicy::VolatileLru
+                | MaxmemoryPolicy::VolatileLfu
+                | MaxmemoryPolicy::VolatileRandom
+                | MaxmemoryPolicy::VolatileTtl
+        )
+    }
+
+    /// The `lru` bits of objects hold LFU counters instead of access times
+    pub fn is_lfu(&self) -> bool {
+        matches!(
+            self,
+            MaxmemoryPolicy::AllKeysLfu | MaxmemoryPolicy::VolatileLfu
+        )
+    }
+
+    pub fn is_random(&self) -> bool {
+        matches!(
+            self,
+            MaxmemoryPolicy::AllKeysRandom | MaxmemoryPolicy::VolatileRandom
+        )
+    }
+}
+
+impl FromStr for MaxmemoryPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "noeviction" => Ok(MaxmemoryPolicy::NoEviction),
+            "allkeys-lru" => Ok(MaxmemoryPolicy::AllKeysLru),
+            "volatile-lru" => Ok(MaxmemoryPolicy::VolatileLru),
+            "allkeys-lfu" => Ok(MaxmemoryPolicy::AllKeysLfu),
+            "volatile-lfu" => Ok(MaxmemoryPolicy::VolatileLfu),
+            "allkeys-random" => Ok(MaxmemoryPolicy::AllKeysRandom),
+            "volatile-random" => Ok(MaxmemoryPolicy::VolatileRandom),
+            "volatile-ttl" => Ok(MaxmemoryPolicy::VolatileTtl),
+            _ => Err(format!("invalid maxmemory-policy: {}", s)),
+        }
+    }
+}
+
+impl Display for MaxmemoryPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            MaxmemoryPolicy::NoEviction => "noeviction",
+            MaxmemoryPolicy::AllKeysLru => "allkeys-lru",
+            MaxmemoryPolicy::VolatileLru => "volatile-lru",
+            MaxmemoryPolicy::AllKeysLfu => "allkeys-lfu",
+            MaxmemoryPolicy::VolatileLfu => "volatile-lfu",
+            MaxmemoryPolicy::AllKeysRandom => "allkeys-random",
+            MaxmemoryPolicy::VolatileRandom => "volatile-random",
+            MaxmemoryPolicy::VolatileTtl => "volatile-ttl",
+        };
+        write!(f, "{}", name)
+    }
 }
 
 static CONFIG: OnceLock<Config> = OnceLock::new();
 
 pub fn init_config() -> &'static Config {
     CONFIG.get_or_init(|| Config {
-        listen_ip: env::var("RUDIS_LISTEN_IP").unwrap_or("127.0.0.1".into()),
+        bind: env::var("RUDIS_BIND")
+            .or_else(|_| env::var("RUDIS_LISTEN_IP"))
+            .unwrap_or("127.0.0.1 -::1".into())
+            .split_whitespace()
+            .map(String::from)
+            .collect(),
         port: env::var("RUDIS_PORT")
             .map(|p| p.parse().expect("invalid RUDIS_PORT"))
             .unwrap_or(6379),
+        unixsocket: env::var("RUDIS_UNIXSOCKET").ok().map(PathBuf::from),
+        unixsocketperm: env::var("RUDIS_UNIXSOCKETPERM").ok().map(|perm| {
+            u32::from_str_radix(&perm, 8).expect("invalid RUDIS_UNIXSOCKETPERM")
+        }),
+
+        tls_port: env_parse("RUDIS_TLS_PORT", 0),
+        tls_cert_file: env::var("RUDIS_TLS_CERT_FILE").ok().map(PathBuf::from),
+        tls_key_file: env::var("RUDIS_TLS_KEY_FILE").ok().map(PathBuf::from),
+        tls_ca_cert_file: env::var("RUDIS_TLS_CA_CERT_FILE").ok().map(PathBuf::from),
+        tls_auth_clients: env_parse("RUDIS_TLS_AUTH_CLIENTS", TlsAuthClients::Yes),
+
+        databases: env_parse("RUDIS_DATABASES", 16).max(1),
+
+        shutdown_timeout: env_parse("RUDIS_SHUTDOWN_TIMEOUT", 10),
+
+        hz: env_parse("RUDIS_HZ", 10).clamp(1, 500),
+
+        maxclients: env_parse("RUDIS_MAXCLIENTS", 10000),
+        timeout: env_parse("RUDIS_TIMEOUT", 0),
+        tcp_keepalive: env_parse("RUDIS_TCP_KEEPALIVE", 300),
+
+        maxmemory: env_memory("RUDIS_MAXMEMORY", 0),
+        maxmemory_policy: env_parse("RUDIS_MAXMEMORY_POLICY", MaxmemoryPolicy::NoEviction),
+        maxmemory_samples: env_parse("RUDIS_MAXMEMORY_SAMPLES", 5).max(1),
+        lfu_log_factor: env_parse("RUDIS_LFU_LOG_FACTOR", 10),
+        lfu_decay_time: env_parse("RUDIS_LFU_DECAY_TIME", 1),
+
+        list_max_listpack_size: env_parse("RUDIS_LIST_MAX_LISTPACK_SIZE", -2),
+        list_compress_depth: env_parse("RUDIS_LIST_COMPRESS_DEPTH", 0),
+        hash_max_listpack_entries: env_parse("RUDIS_HASH_MAX_LISTPACK_ENTRIES", 128),
+        hash_max_listpack_value: env_parse("RUDIS_HASH_MAX_LISTPACK_VALUE", 64),
+        set_max_intset_entries: env_parse("RUDIS_SET_MAX_INTSET_ENTRIES", 512),
+        set_max_listpack_entries: env_parse("RUDIS_SET_MAX_LISTPACK_ENTRIES", 128),
+        set_max_listpack_value: env_parse("RUDIS_SET_MAX_LISTPACK_VALUE", 64),
+        zset_max_listpack_entries: env_parse("RUDIS_ZSET_MAX_LISTPACK_ENTRIES", 128),
+        zset_max_listpack_value: env_parse("RUDIS_ZSET_MAX_LISTPACK_VALUE", 64),
+
+        lazyfree_lazy_eviction: env_bool("RUDIS_LAZYFREE_LAZY_EVICTION", false),
+        lazyfree_lazy_expire: env_bool("RUDIS_LAZYFREE_LAZY_EXPIRE", false),
+        lazyfree_lazy_server_del: env_bool("RUDIS_LAZYFREE_LAZY_SERVER_DEL", false),
+        lazyfree_lazy_user_del: env_bool("RUDIS_LAZYFREE_LAZY_USER_DEL", false),
+
+        notify_keyspace_events: env::var("RUDIS_NOTIFY_KEYSPACE_EVENTS")
+            .map(|flags| {
+                crate::notify::parse_flags(&flags).expect("invalid RUDIS_NOTIFY_KEYSPACE_EVENTS")
+            })
+            .unwrap_or(0),
 
         log_level: env::var("RUDIS_LOG_LEVEL")
             .map(|level| level.parse().expect("invalid RUDIS_LOG_LEVEL"))
             .unwrap_or(LevelFilter::Info),
 
-        string_max_length: env::var("RUDIS_STRING_MAX_LENGTH")
-            .map(|len| len.parse().expect(""))
-            .unwrap_or(2 << 25),
+        proto_max_bulk_len: env_memory("RUDIS_PROTO_MAX_BULK_LEN", 512 << 20),
+        proto_max_multibulk_len: env_parse("RUDIS_PROTO_MAX_MULTIBULK_LEN", i32::MAX as usize),
+        proto_max_nesting: env_parse("RUDIS_PROTO_MAX_NESTING", 16),
+        proto_inline_max_size: env_memory("RUDIS_PROTO_INLINE_MAX_SIZE", 64 << 10),
+        client_query_buffer_limit: env_memory("RUDIS_CLIENT_QUERY_BUFFER_LIMIT", 1 << 30),
+        client_output_buffer_limit_pubsub: env_memory(
+            "RUDIS_CLIENT_OUTPUT_BUFFER_LIMIT_PUBSUB",
+            32 << 20,
+        ),
     })
 }
 
+fn env_parse<T: FromStr>(key: &str, default: T) -> T {
+    env::var(key)
+        .map(|value| value.parse().unwrap_or_else(|_| panic!("invalid {}", key)))
+        .unwrap_or(default)
+}
+
+/// A redis style boolean, `yes` or `no`
+fn env_bool(key: &str, default: bool) -> bool {
+    env::var(key)
+        .map(|value| match value.to_ascii_lowercase().as_str() {
+            "yes" => true,
+            "no" => false,
+            _ => panic!("invalid {}", key),
+        })
+        .unwrap_or(default)
+}
+
+fn env_memory(key: &str, default: usize) -> usize {
+    env::var(key)
+        .map(|value| parse_memory(&value).unwrap_or_else(|| panic!("invalid {}", key)))
+        .unwrap_or(default)
+}
+
+/// Parse a redis style memory size, e.g. `1gb`, `64kb`, `1m` or `1024`.
+///
+/// Like redis, `k`/`m`/`g` are powers of 1000 while `kb`/`mb`/`gb` are powers of 1024.
+pub fn parse_memory(value: &str) -> Option<usize> {
+    let value = value.trim().to_ascii_lowercase();
+    const UNITS: [(&str, usize); 7] = [
+        ("kb", 1 << 10),
+        ("mb", 1 << 20),
+        ("gb", 1 << 30),
+        ("k", 1_000),
+        ("m", 1_000_000),
+        ("g", 1_000_000_000),
+        ("b", 1),
+    ];
+    for (suffix, unit) in UNITS {
+        if let Some(number) = value.strip_suffix(suffix) {
+            return number.parse::<usize>().ok()?.checked_mul(unit);
+        }
+    }
+    value.parse().ok()
+}
+
+/// Returns the server config, loading it from the environment on first use.
 pub fn get_server_config() -> &'static Config {
-    &CONFIG.get().expect("config hasn't been initialized")
+    init_config()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::config::parse_memory;
+
+    #[test]
+    fn test_parse_memory() {
+        assert_eq!(parse_memory("1024"), Some(1024));
+        assert_eq!(parse_memory("64kb"), Some(64 * 1024));
+        assert_eq!(parse_memory("512MB"), Some(512 * 1024 * 1024));
+        assert_eq!(parse_memory("1g"), Some(1_000_000_000));
+        assert_eq!(parse_memory("10b"), Some(10));
+        assert_eq!(parse_memory("mb"), None);
+        assert_eq!(parse_memory("-1"), None);
+    }
 }
@@ -0,0 +1,220 @@
+use bytes::BytesMut;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::{
+    config::get_server_config,
+    protocol::{Frame, FrameError, ProtocolVersion, decoder::Decoder, encoder::Encoder},
+};
+
+/// Initial capacity of the query buffer
+const READ_BUFFER_SIZE: usize = 16 * 1024;
+
+/// A client connection.
+///
+/// Pipelined commands are decoded from the same read, their replies are buffered by the
+/// encoder and sent with a single write once no more buffered commands are left.
+pub struct Connection<S> {
+    stream: S,
+    buffer: BytesMut,
+    decoder: Decoder,
+    encoder: Encoder,
+    /// `client-query-buffer-limit`
+    max_query_buffer: usize,
+}
+
+impl<S> Connection<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            buffer: BytesMut::with_capacity(READ_BUFFER_SIZE),
+            decoder: Decoder::new(),
+            encoder: Encoder::new(),
+            max_query_buffer: get_server_config().client_query_buffer_limit,
+        }
+    }
+
+    /// Decode the next request from the bytes which have already been read, without waiting
+    pub fn next_buffered_frame(&mut self) -> Result<Option<Frame>, FrameError> {
+        while let Some(frame) = self.decoder.decode(&mut self.buffer)? {
+            if is_request(&frame)? {
+                return Ok(Some(frame));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Read from the socket until a complete frame arrives.
+    ///
+    /// Returns `Ok(None)` when the peer closed the connection between two frames.
+    pub async fn read_frame(&mut self) -> Result<Option<Frame>, FrameError> {
+        loop {
+            if let Some(frame) = self.next_buffered_frame()? {
+                return Ok(Some(frame));
+            }
+            if self.query_buffer_len() > self.max_query_buffer {
+                return Err(FrameError::Protocol(
+                    "client query buffer limit reached".to_string(),
+                ));
+            }
+            if self.stream.read_buf(&mut self.buffer).await? == 0 {
+                if self.buffer.is_empty() && self.decoder.is_idle() {
+                    return Ok(None);
+                }
+                return Err(std::io::Error::from(std::io::ErrorKind::ConnectionReset).into());
+            }
+        }
+    }
+
+    /// Resolves once the peer closed the connection, e.g. while its command is blocked.
+    ///
+    /// The bytes received meanwhile are kept for the next frames, up to the query buffer limit.
+    pub async fn wait_closed(&mut self) {
+        while self.query_buffer_len() <= self.max_query_buffer {
+            match self.stream.read_buf(&mut self.buffer).await {
+                Ok(0) | Err(_) => return,
+                Ok(_) => {}
+            }
+        }
+        std::future::pending().await
+    }
+
+    /// Bytes received for requests which haven't been decoded completely
+    pub fn query_buffer_len(&self) -> usize {
+        self.buffer.len() + self.decoder.pending_bytes()
+    }
+
+    /// Queue a reply, it is sent by the next `flush`
+    pub fn write_frame(&mut self, frame: &Frame, version: ProtocolVersion) {
+        self.encoder.encode(frame, version);
+    }
+
+    /// Send all queued replies with one write
+    pub async fn flush(&mut self) -> std::io::Result<()> {
+        if !self.encoder.is_empty() {
+            self.stream.write_all(self.encoder.buffer()).await?;
+            self.encoder.clear();
+        }
+        self.stream.flush().await
+    }
+}
+
+/// Whether `frame` is a request to run, which is an array of bulk strings as redis-server
+/// reads it. An empty array is skipped, any other frame is a protocol error.
+fn is_request(frame: &Frame) -> Result<bool, FrameError> {
+    let expected = |expected: char, frame: &Frame| {
+        FrameError::Protocol(format!(
+            "expected '{}', got '{}'",
+            expected,
+            frame.type_byte() as char
+        ))
+    };
+    let items = match frame {
+        Frame::Array(Some(items)) => items,
+        Frame::Array(None) => return Ok(false),
+        other => return Err(expected('*', other)),
+    };
+    for item in items {
+        match item {
+            Frame::BulkString(Some(_)) => {}
+            Frame::BulkString(None) => {
+                return Err(FrameError::Protocol("invalid bulk length".to_string()));
+            }
+            other => return Err(expected('$', other)),
+        }
+    }
+    Ok(!items.is_empty())
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt, duplex};
+
+    use crate::{
+        connection::Connection,
+        protocol::{Frame, FrameError, ProtocolVersion},
+    };
+
+    #[tokio::test]
+    async fn test_pipelined_frames_replied_in_one_batch() {
+        let (client, server) = duplex(1024);
+        let mut conn = Connection::new(server);
+        let (mut client_reader, mut client_writer) = tokio::io::split(client);
+
+        client_writer
+            .write_all(b"*1\r\n$4\r\nPING\r\nPING\r\n")
+            .await
+            .unwrap();
+        let first = conn.read_frame().await.unwrap().unwrap();
+        let second = conn.next_buffered_frame().unwrap().unwrap();
+        assert_eq!(first, second);
+        assert!(conn.next_buffered_frame().unwrap().is_none());
+
+        let pong = Frame::SimpleString("PONG".to_string());
+        conn.write_frame(&pong, ProtocolVersion::Resp2);
+        conn.write_frame(&pong, ProtocolVersion::Resp2);
+        conn.flush().await.unwrap();
+
+        let mut buf = [0u8; 14];
+        client_reader.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"+PONG\r\n+PONG\r\n");
+
+        drop(client_writer);
+        drop(client_reader);
+        assert!(conn.read_frame().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_query_buffer_limit() {
+        let (mut client, server) = duplex(1024);
+        let mut conn = Connection::new(server);
+        conn.max_query_buffer = 16;
+
+        client.write_all(b"*1\r\n$4\r\nPING\r\n").await.unwrap();
+        assert!(conn.read_frame().await.unwrap().is_some());
+
+        // a bulk payload which is still arriving counts towards the limit
+        client.write_all(b"*1\r\n$64\r\n").await.unwrap();
+        client.write_all(&[b'x'; 32]).await.unwrap();
+        match conn.read_frame().await {
+            Err(FrameError::Protocol(message)) => {
+                assert_eq!(message, "client query buffer limit reached")
+            }
+            other => panic!("expect a protocol error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_arrays_of_bulk_strings() {
+        let (mut client, server) = duplex(1024);
+        let mut conn = Connection::new(server);
+
+        // an empty array is skipped
+        client
+            .write_all(b"*0\r\n*1\r\n$4\r\nPING\r\n")
+            .await
+            .unwrap();
+        assert_eq!(
+            conn.read_frame().await.unwrap(),
+            Some(Frame::Array(Some(vec![Frame::BulkString(Some(
+                b"PING".to_vec()
+            ))])))
+        );
+
+        for (request, message) in [
+            (&b"*2\r\n:1\r\n:2\r\n"[..], "expected '$', got ':'"),
+            (b"*1\r\n%1\r\n+a\r\n+b\r\n", "expected '$', got '%'"),
+            (b"$4\r\nPING\r\n", "expected '*', got '$'"),
+        ] {
+            let (mut client, server) = duplex(1024);
+            let mut conn = Connection::new(server);
+            client.write_all(request).await.unwrap();
+            match conn.read_frame().await {
+                Err(FrameError::Protocol(error)) => assert_eq!(error, message),
+                other => panic!("expect a protocol error, got {:?}", other),
+            }
+        }
+    }
+}
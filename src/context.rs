@@ -1,15 +1,380 @@
-use std::sync::Arc;
+use std::{
+    sync::{
+        Arc, Mutex, MutexGuard,
+        atomic::{AtomicBool, AtomicU8, AtomicU64, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
 
-use crate::storage::database::Database;
+use tokio::sync::{
+    Notify,
+    mpsc::{self, UnboundedReceiver, UnboundedSender, error::TryRecvError},
+};
 
-/// Request context
+use crate::{
+    blocked::UnblockReason,
+    config::get_server_config,
+    protocol::{Frame, ProtocolVersion},
+    pubsub::Subscriptions,
+    storage::database::{Database, Databases},
+};
+
+/// Ids are never reused during the life of the server, the first client gets 1
+static NEXT_CLIENT_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// Request context, one per client connection
 pub struct Context {
     pub id: usize,
-    pub db: Arc<Database>,
+    pub dbs: Arc<Databases>,
+    /// Index of the selected database
+    db_index: AtomicUsize,
+    /// Peer address, `ip:port`
+    pub addr: String,
+    /// Local address the client connected to
+    pub laddr: String,
+    created: Instant,
+    /// Milliseconds between `created` and the last command
+    last_interaction: AtomicU64,
+    protocol: AtomicU8,
+    name: Mutex<Option<String>>,
+    lib_name: Mutex<Option<String>>,
+    lib_ver: Mutex<Option<String>>,
+    last_command: Mutex<String>,
+    kill: Arc<KillSwitch>,
+    /// Close without sending the reply of the current command
+    discard_reply: AtomicBool,
+    /// The current command sent its replies through `push` already
+    skip_reply: AtomicBool,
+    /// Frames sent to the client besides the replies, e.g. pub/sub messages
+    pusher: Pusher,
+    /// Taken by the connection, which writes the pushed frames
+    pushes: Mutex<Option<PushReceiver>>,
+    subscriptions: Mutex<Subscriptions>,
+    /// Waiting in a blocking command, e.g. `BLPOP`
+    blocked: AtomicBool,
+    /// Wakes the blocking command once a key it waits for may be served
+    block_notify: Notify,
+    /// Set by `CLIENT UNBLOCK`
+    unblock: Mutex<Option<UnblockReason>>,
+}
+
+/// Set once the connection has to close, shared with the `Pusher` of the client
+#[derive(Default)]
+struct KillSwitch {
+    killed: AtomicBool,
+    notify: Notify,
+}
+
+impl KillSwitch {
+    fn kill(&self) {
+        self.killed.store(true, Ordering::Relaxed);
+        self.notify.notify_one();
+    }
+}
+
+/// Sends frames to a client out of the request/reply flow, see `Context::push`
+#[derive(Clone)]
+pub struct Pusher {
+    sender: UnboundedSender<Frame>,
+    /// Bytes sent but not taken by the connection yet
+    pending: Arc<AtomicUsize>,
+    kill: Arc<KillSwitch>,
+}
+
+impl Pusher {
+    /// Queue `frame` for the client, returns false if it is dropped.
+    ///
+    /// A client more than `client-output-buffer-limit pubsub` bytes behind is killed instead,
+    /// a subscriber that doesn't read can't make the server buffer without limit.
+    pub fn send(&self, frame: Frame) -> bool {
+        let limit = get_server_config().client_output_buffer_limit_pubsub;
+        let size = frame.payload_len();
+        let pending = self.pending.fetch_add(size, Ordering::Relaxed) + size;
+        if limit > 0 && pending > limit {
+            self.pending.fetch_sub(size, Ordering::Relaxed);
+            self.kill.kill();
+            return false;
+        }
+        // the receiver is only dropped once the connection is closed
+        if self.sender.send(frame).is_err() {
+            self.pending.fetch_sub(size, Ordering::Relaxed);
+            return false;
+        }
+        true
+    }
+}
+
+/// The frames sent by a `Pusher`, written by the connection of the client
+pub struct PushReceiver {
+    receiver: UnboundedReceiver<Frame>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl PushReceiver {
+    pub fn try_recv(&mut self) -> Result<Frame, TryRecvError> {
+        let frame = self.receiver.try_recv()?;
+        self.pending
+            .fetch_sub(frame.payload_len(), Ordering::Relaxed);
+        Ok(frame)
+    }
+
+    pub async fn recv(&mut self) -> Option<Frame> {
+        let frame = self.receiver.recv().await?;
+        self.pending
+            .fetch_sub(frame.payload_len(), Ordering::Relaxed);
+        Some(frame)
+    }
 }
 
 impl Context {
-    pub fn new(id: usize, db: Arc<Database>) -> Self {
-        Self { db, id }
+    pub fn new(id: usize, dbs: Arc<Databases>, addr: String, laddr: String) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let pending = Arc::new(AtomicUsize::new(0));
+        let kill = Arc::new(KillSwitch::default());
+        let pusher = Pusher {
+            sender,
+            pending: pending.clone(),
+            kill: kill.clone(),
+        };
+        let pushes = PushReceiver { receiver, pending };
+        Self {
+            dbs,
+            db_index: AtomicUsize::new(0),
+            id,
+            addr,
+            laddr,
+            created: Instant::now(),
+            last_interaction: AtomicU64::new(0),
+            protocol: AtomicU8::new(ProtocolVersion::default() as u8),
+            name: Mutex::new(None),
+            lib_name: Mutex::new(None),
+            lib_ver: Mutex::new(None),
+            last_command: Mutex::new("NULL".to_string()),
+            kill,
+            discard_reply: AtomicBool::new(false),
+            skip_reply: AtomicBool::new(false),
+            pusher,
+            pushes: Mutex::new(Some(pushes)),
+            subscriptions: Mutex::new(Subscriptions::default()),
+            blocked: AtomicBool::new(false),
+            block_notify: Notify::new(),
+            unblock: Mutex::new(None),
+        }
+    }
+
+    /// Allocate a unique, monotonically increasing client id
+    pub fn next_id() -> usize {
+        NEXT_CLIENT_ID.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// The selected database
+    pub fn db(&self) -> Arc<Database> {
+        self.dbs
+            .get(self.db_index())
+            .expect("the selected database is always in range")
+    }
+
+    pub fn db_index(&self) -> usize {
+        self.db_index.load(Ordering::Relaxed)
+    }
+
+    /// Select the database at `index`, returns false when it is out of range
+    pub fn select(&self, index: usize) -> bool {
+        if index >= self.dbs.len() {
+            return false;
+        }
+        self.db_index.store(index, Ordering::Relaxed);
+        true
+    }
+
+    /// The RESP version this connection speaks, RESP2 until the client sends `HELLO 3`
+    pub fn protocol_version(&self) -> ProtocolVersion {
+        match self.protocol.load(Ordering::Relaxed) {
+            3 => ProtocolVersion::Resp3,
+            _ => ProtocolVersion::Resp2,
+        }
+    }
+
+    pub fn set_protocol_version(&self, version: ProtocolVersion) {
+        self.protocol.store(version as u8, Ordering::Relaxed);
+    }
+
+    pub fn name(&self) -> Option<String> {
+        self.name.lock().unwrap().clone()
+    }
+
+    pub fn set_name(&self, name: Option<String>) {
+        *self.name.lock().unwrap() = name;
+    }
+
+    pub fn set_lib_name(&self, lib_name: Option<String>) {
+        *self.lib_name.lock().unwrap() = lib_name;
+    }
+
+    pub fn set_lib_ver(&self, lib_ver: Option<String>) {
+        *self.lib_ver.lock().unwrap() = lib_ver;
+    }
+
+    /// Record a command of this client, it resets the idle time
+    pub fn touch(&self, command: &str) {
+        self.last_interaction
+            .store(self.created.elapsed().as_millis() as u64, Ordering::Relaxed);
+        let mut last_command = self.last_command.lock().unwrap();
+        last_command.clear();
+        last_command.push_str(command);
+    }
+
+    pub fn age(&self) -> Duration {
+        self.created.elapsed()
+    }
+
+    pub fn idle(&self) -> Duration {
+        self.created.elapsed().saturating_sub(Duration::from_millis(
+            self.last_interaction.load(Ordering::Relaxed),
+        ))
+    }
+
+    /// Ask the connection to close, it is done once the reply being built has been sent
+    pub fn kill(&self) {
+        self.kill.kill();
+    }
+
+    pub fn is_killed(&self) -> bool {
+        self.kill.killed.load(Ordering::Relaxed)
+    }
+
+    /// Like `kill`, but the reply of the current command is dropped, e.g. after `SHUTDOWN`
+    pub fn kill_without_reply(&self) {
+        self.discard_reply.store(true, Ordering::Relaxed);
+        self.kill();
+    }
+
+    pub fn discards_reply(&self) -> bool {
+        self.discard_reply.load(Ordering::Relaxed)
+    }
+
+    /// Send a frame to the client out of the request/reply flow, e.g. a pub/sub message.
+    ///
+    /// Frames pushed while a command runs are sent before its reply.
+    pub fn push(&self, frame: Frame) {
+        self.pusher.send(frame);
+    }
+
+    pub fn pusher(&self) -> Pusher {
+        self.pusher.clone()
+    }
+
+    /// The frames sent by `push`, the connection takes them once
+    pub fn take_push_receiver(&self) -> Option<PushReceiver> {
+        self.pushes.lock().unwrap().take()
+    }
+
+    /// Don't send the reply of the current command, e.g. `SUBSCRIBE` pushes one per channel
+    pub fn skip_reply(&self) {
+        self.skip_reply.store(true, Ordering::Relaxed);
+    }
+
+    pub fn take_skip_reply(&self) -> bool {
+        self.skip_reply.swap(false, Ordering::Relaxed)
+    }
+
+    pub fn subscriptions(&self) -> MutexGuard<'_, Subscriptions> {
+        self.subscriptions.lock().unwrap()
+    }
+
+    /// Whether the client subscribed to a channel or pattern, it's in the pub/sub mode then
+    pub fn is_subscribed(&self) -> bool {
+        !self.subscriptions().is_empty()
+    }
+
+    pub fn set_blocked(&self, blocked: bool) {
+        self.blocked.store(blocked, Ordering::Relaxed);
+    }
+
+    pub fn is_blocked(&self) -> bool {
+        self.blocked.load(Ordering::Relaxed)
+    }
+
+    /// Have the blocking command retry, a wake before it waits isn't lost
+    pub fn wake(&self) {
+        self.block_notify.notify_one();
+    }
+
+    pub async fn woken(&self) {
+        self.block_notify.notified().await;
+    }
+
+    /// End the wait of the blocking command, returns false when the client isn't blocked
+    pub fn unblock(&self, reason: UnblockReason) -> bool {
+        if !self.is_blocked() {
+            return false;
+        }
+        *self.unblock.lock().unwrap() = Some(reason);
+        self.wake();
+        true
+    }
+
+    pub fn take_unblock(&self) -> Option<UnblockReason> {
+        self.unblock.lock().unwrap().take()
+    }
+
+    /// Resolves once `kill` is called
+    pub async fn killed(&self) {
+        while !self.is_killed() {
+            self.kill.notify.notified().await;
+        }
+    }
+
+    /// One line of `CLIENT LIST`, also the reply of `CLIENT INFO`
+    pub fn client_info(&self) -> String {
+        let protocol = self.protocol_version() as u8;
+        let (sub, psub) = {
+            let subscriptions = self.subscriptions();
+            (subscriptions.channels.len(), subscriptions.patterns.len())
+        };
+        let flags = if sub + psub > 0 {
+            "P"
+        } else if self.is_blocked() {
+            "b"
+        } else {
+            "N"
+        };
+        format!(
+            "id={} addr={} laddr={} name={} age={} idle={} flags={} db={} sub={} psub={} \
+             multi=-1 cmd={} user=default resp={} lib-name={} lib-ver={}",
+            self.id,
+            self.addr,
+            self.laddr,
+            self.name().unwrap_or_default(),
+            self.age().as_secs(),
+            self.idle().as_secs(),
+            flags,
+            self.db_index(),
+            sub,
+            psub,
+            self.last_command.lock().unwrap(),
+            protocol,
+            self.lib_name.lock().unwrap().as_deref().unwrap_or_default(),
+            self.lib_ver.lock().unwrap().as_deref().unwrap_or_default(),
+        )
+    }
+}
+
+#[cfg(test)]
+impl Context {
+    /// A client of `count` empty databases, for tests
+    pub fn test_client(count: usize) -> Arc<Self> {
+        Self::test_client_of(Arc::new(Databases::new(count)))
+    }
+
+    /// A client of `dbs` for tests, its port is made from its id so the address is unique
+    pub fn test_client_of(dbs: Arc<Databases>) -> Arc<Self> {
+        let id = Self::next_id();
+        Arc::new(Self::new(
+            id,
+            dbs,
+            format!("127.0.0.1:{}", 10000 + id),
+            "127.0.0.1:6379".to_string(),
+        ))
     }
 }
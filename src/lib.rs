@@ -1,7 +1,18 @@
+pub mod blocked;
+pub mod client;
 pub mod context;
+pub mod connection;
 pub mod errors;
 pub mod protocol;
 pub mod command;
 pub mod storage;
 pub mod object;
 pub mod config;
+pub mod memory;
+pub mod notify;
+pub mod pubsub;
+pub mod server;
+pub mod shutdown;
+pub mod stats;
+pub mod tls;
+pub mod util;
@@ -0,0 +1,71 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Bytes currently allocated through `CountingAllocator`
+static USED_MEMORY: AtomicUsize = AtomicUsize::new(0);
+
+/// Highest `USED_MEMORY` seen by `update_peak_memory`
+static PEAK_MEMORY: AtomicUsize = AtomicUsize::new(0);
+
+/// `USED_MEMORY` once the server was initialized, before it took any data
+static STARTUP_MEMORY: AtomicUsize = AtomicUsize::new(0);
+
+/// The system allocator counting the allocated bytes, like `zmalloc` of redis.
+///
+/// The server binary installs it as the global allocator, `used_memory` stays 0 without it.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            USED_MEMORY.fetch_add(layout.size(), Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            USED_MEMORY.fetch_add(layout.size(), Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        USED_MEMORY.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            USED_MEMORY.fetch_add(new_size, Ordering::Relaxed);
+            USED_MEMORY.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+        new_ptr
+    }
+}
+
+/// Bytes allocated by the server, compared against `maxmemory`
+pub fn used_memory() -> usize {
+    USED_MEMORY.load(Ordering::Relaxed)
+}
+
+/// Record the used memory as the peak if it's higher, called by the server cron.
+/// Returns the peak.
+pub fn update_peak_memory() -> usize {
+    let used = used_memory();
+    PEAK_MEMORY.fetch_max(used, Ordering::Relaxed).max(used)
+}
+
+/// Record the memory the server uses without any data
+pub fn record_startup_memory() {
+    STARTUP_MEMORY.store(used_memory(), Ordering::Relaxed);
+}
+
+pub fn startup_memory() -> usize {
+    STARTUP_MEMORY.load(Ordering::Relaxed)
+}
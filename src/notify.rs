@@ -0,0 +1,198 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use once_cell::sync::Lazy;
+
+use crate::{config::get_server_config, pubsub::PUBSUB};
+
+/// `K`, publish to `__keyspace@<db>__:<key>`
+pub const KEYSPACE: u32 = 1 << 0;
+/// `E`, publish to `__keyevent@<db>__:<event>`
+pub const KEYEVENT: u32 = 1 << 1;
+/// `g`, generic commands like `DEL`, `EXPIRE` and `RENAME`
+pub const GENERIC: u32 = 1 << 2;
+/// `$`
+pub const STRING: u32 = 1 << 3;
+/// `l`
+pub const LIST: u32 = 1 << 4;
+/// `s`
+pub const SET: u32 = 1 << 5;
+/// `h`
+pub const HASH: u32 = 1 << 6;
+/// `z`
+pub const ZSET: u32 = 1 << 7;
+/// `x`, a key deleted because its TTL elapsed
+pub const EXPIRED: u32 = 1 << 8;
+/// `e`, a key deleted by `maxmemory`
+pub const EVICTED: u32 = 1 << 9;
+/// `t`
+pub const STREAM: u32 = 1 << 10;
+/// `m`, a read of a missing key
+pub const KEY_MISS: u32 = 1 << 11;
+/// `n`, a key added to a database
+pub const NEW: u32 = 1 << 12;
+/// `A`, every class but `m` and `n`
+pub const ALL: u32 = GENERIC | STRING | LIST | SET | HASH | ZSET | EXPIRED | EVICTED | STREAM;
+
+/// Classes of the events published, `notify-keyspace-events`
+static FLAGS: Lazy<AtomicU32> =
+    Lazy::new(|| AtomicU32::new(get_server_config().notify_keyspace_events));
+
+/// Parse the `notify-keyspace-events` syntax, e.g. `KEA` or `Egx`, `None` on unknown flags
+pub fn parse_flags(classes: &str) -> Option<u32> {
+    classes.chars().try_fold(0, |flags, class| {
+        let flag = match class {
+            'A' => ALL,
+            'g' => GENERIC,
+            '$' => STRING,
+            'l' => LIST,
+            's' => SET,
+            'h' => HASH,
+            'z' => ZSET,
+            'x' => EXPIRED,
+            'e' => EVICTED,
+            't' => STREAM,
+            'm' => KEY_MISS,
+            'n' => NEW,
+            'K' => KEYSPACE,
+            'E' => KEYEVENT,
+            _ => return None,
+        };
+        Some(flags | flag)
+    })
+}
+
+/// The `notify-keyspace-events` syntax of `flags`, `A` stands for all of its classes
+pub fn flags_to_string(flags: u32) -> String {
+    let mut classes = String::new();
+    if flags & ALL == ALL {
+        classes.push('A');
+    } else {
+        for (flag, class) in [
+            (GENERIC, 'g'),
+            (STRING, '$'),
+            (LIST, 'l'),
+            (SET, 's'),
+            (HASH, 'h'),
+            (ZSET, 'z'),
+            (EXPIRED, 'x'),
+            (EVICTED, 'e'),
+            (STREAM, 't'),
+        ] {
+            if flags & flag != 0 {
+                classes.push(class);
+            }
+        }
+    }
+    for (flag, class) in [
+        (KEYSPACE, 'K'),
+        (KEYEVENT, 'E'),
+        (KEY_MISS, 'm'),
+        (NEW, 'n'),
+    ] {
+        if flags & flag != 0 {
+            classes.push(class);
+        }
+    }
+    classes
+}
+
+pub fn flags() -> u32 {
+    FLAGS.load(Ordering::Relaxed)
+}
+
+pub fn set_flags(flags: u32) {
+    FLAGS.store(flags, Ordering::Relaxed);
+}
+
+/// Publish `event` of the `class` about `key` of the database `db`, when the class is enabled
+pub fn notify_keyspace_event(class: u32, event: &str, key: &str, db: usize) {
+    let flags = flags();
+    if flags & class == 0 {
+        return;
+    }
+    if flags & KEYSPACE != 0 {
+        let channel = format!("__keyspace@{}__:{}", db, key);
+        PUBSUB.publish(&channel, event.as_bytes());
+    }
+    if flags & KEYEVENT != 0 {
+        let channel = format!("__keyevent@{}__:{}", db, event);
+        PUBSUB.publish(&channel, key.as_bytes());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, SystemTime};
+
+    use crate::{
+        context::Context,
+        notify::{
+            ALL, EVICTED, EXPIRED, GENERIC, KEY_MISS, KEYEVENT, KEYSPACE, NEW, STRING,
+            flags_to_string, parse_flags, set_flags,
+        },
+        object::redis_object::RedisObject,
+        protocol::Frame,
+        pubsub::PUBSUB,
+        storage::database::Database,
+    };
+
+    #[test]
+    fn test_parse_flags() {
+        assert_eq!(parse_flags(""), Some(0));
+        assert_eq!(parse_flags("KEA"), Some(KEYSPACE | KEYEVENT | ALL));
+        assert_eq!(parse_flags("Egx"), Some(KEYEVENT | GENERIC | EXPIRED));
+        assert_eq!(
+            parse_flags("K$mn"),
+            Some(KEYSPACE | STRING | KEY_MISS | NEW)
+        );
+        assert_eq!(parse_flags("Kq"), None);
+        assert_eq!(ALL & (KEY_MISS | NEW), 0);
+
+        assert_eq!(flags_to_string(parse_flags("AKE").unwrap()), "AKE");
+        assert_eq!(flags_to_string(parse_flags("xEge").unwrap()), "gxeE");
+        assert_eq!(
+            flags_to_string(parse_flags("g$lshzxetKEmn").unwrap()),
+            "AKEmn"
+        );
+        assert_eq!(flags_to_string(EVICTED), "e");
+    }
+
+    #[test]
+    fn test_notify_keyspace_event() {
+        let ctx = Context::test_client(1);
+        let mut pushes = ctx.take_push_receiver().unwrap();
+        PUBSUB.psubscribe(&ctx, "__key*@13__:*");
+        set_flags(parse_flags("KEA").unwrap());
+
+        // a database no other test uses, the flags and channels are global
+        let db = Database::new(13);
+        db.insert(
+            "k".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            Some(SystemTime::now() - Duration::from_secs(1)),
+        );
+        assert!(db.get("k").is_none());
+        set_flags(0);
+        assert!(db.get("k").is_none());
+
+        let message = |channel: &str, payload: &str| {
+            Frame::Push(
+                ["pmessage", "__key*@13__:*", channel, payload]
+                    .iter()
+                    .map(|part| Frame::BulkString(Some(part.as_bytes().to_vec())))
+                    .collect(),
+            )
+        };
+        // `new` and `keymiss` aren't part of `A`
+        assert_eq!(
+            pushes.try_recv().unwrap(),
+            message("__keyspace@13__:k", "expired")
+        );
+        assert_eq!(
+            pushes.try_recv().unwrap(),
+            message("__keyevent@13__:expired", "k")
+        );
+        assert!(pushes.try_recv().is_err());
+        PUBSUB.remove_client(&ctx);
+    }
+}
@@ -0,0 +1,197 @@
+//! The hash table of the members of a hash, set or sorted set.
+//!
+//! Besides the table, the members are kept ordered by `scan_hash`, so a page of the `SCAN`
+//! family starts at its cursor instead of walking the whole table.
+
+use std::collections::{BTreeSet, HashMap, hash_map};
+
+use crate::{object::redis_object::table_size, storage::scan::scan_hash};
+
+/// Bytes a value of a `Dict` allocates besides itself
+pub trait HeapSize {
+    fn heap_size(&self) -> usize;
+}
+
+impl HeapSize for Vec<u8> {
+    fn heap_size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl HeapSize for () {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl HeapSize for u64 {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Dict<V> {
+    map: HashMap<Vec<u8>, V>,
+    /// The members of `map` with their hashes, ordered by hash
+    by_hash: BTreeSet<(u64, Vec<u8>)>,
+    /// Bytes allocated by the entries, kept up to date as they change
+    allocated: usize,
+}
+
+impl<V: HeapSize> Default for Dict<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: HeapSize> Dict<V> {
+    pub fn new() -> Self {
+        Self {
+            map: HashMap::new(),
+            by_hash: BTreeSet::new(),
+            allocated: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn get(&self, member: &[u8]) -> Option<&V> {
+        self.map.get(member)
+    }
+
+    pub fn contains_key(&self, member: &[u8]) -> bool {
+        self.map.contains_key(member)
+    }
+
+    /// Set the value of `member`, returns the previous one
+    pub fn insert(&mut self, member: Vec<u8>, value: V) -> Option<V> {
+        match self.map.entry(member) {
+            hash_map::Entry::Occupied(mut entry) => {
+                self.allocated += value.heap_size();
+                let old = entry.insert(value);
+                self.allocated -= old.heap_size();
+                Some(old)
+            }
+            hash_map::Entry::Vacant(entry) => {
+                self.allocated += entry_size(entry.key(), &value);
+                self.by_hash
+                    .insert((scan_hash(entry.key()), entry.key().clone()));
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, member: &[u8]) -> Option<V> {
+        let (member, value) = self.map.remove_entry(member)?;
+        self.allocated -= entry_size(&member, &value);
+        self.by_hash.remove(&(scan_hash(&member), member));
+        Some(value)
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, Vec<u8>, V> {
+        self.map.iter()
+    }
+
+    pub fn keys(&self) -> hash_map::Keys<'_, Vec<u8>, V> {
+        self.map.keys()
+    }
+
+    /// Visit at least `count` members from `cursor` on, returns them with their values and
+    /// the next cursor
+    pub fn scan(&self, cursor: u64, count: usize) -> (u64, Vec<(&[u8], &V)>) {
+        let mut page = Vec::new();
+        let mut last = None;
+        for (hash, member) in self.by_hash.range((cursor, Vec::new())..) {
+            // members with the same hash are returned together, the cursor can't split them
+            if page.len() >= count && last != Some(*hash) {
+                return (*hash, page);
+            }
+            last = Some(*hash);
+            page.push((member.as_slice(), &self.map[member]));
+        }
+        (0, page)
+    }
+
+    /// Bytes allocated by the table and the index besides the `Dict` itself, see
+    /// `RedisValue::mem_usage`
+    pub fn mem_usage(&self) -> usize {
+        table_size::<(Vec<u8>, V)>(self.map.capacity()) + self.allocated
+    }
+}
+
+/// Bytes allocated for an entry, a member is stored twice, in the table and in the index
+fn entry_size<V: HeapSize>(member: &[u8], value: &V) -> usize {
+    2 * member.len() + size_of::<(u64, Vec<u8>)>() + value.heap_size()
+}
+
+impl<V: HeapSize> FromIterator<(Vec<u8>, V)> for Dict<V> {
+    fn from_iter<I: IntoIterator<Item = (Vec<u8>, V)>>(iter: I) -> Self {
+        let mut dict = Self::new();
+        for (member, value) in iter {
+            dict.insert(member, value);
+        }
+        dict
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::object::encoding::dict::Dict;
+
+    #[test]
+    fn test_dict_scan() {
+        let mut dict: Dict<u64> = (0..1000)
+            .map(|i| (format!("m{}", i).into_bytes(), i))
+            .collect();
+        assert_eq!(dict.insert(b"m0".to_vec(), 7), Some(0));
+        assert_eq!(dict.remove(b"m1"), Some(1));
+        assert_eq!(dict.remove(b"m1"), None);
+        assert_eq!(dict.len(), 999);
+
+        let mut seen = HashSet::new();
+        let mut cursor = 0;
+        loop {
+            let (next, page) = dict.scan(cursor, 10);
+            assert!(page.len() >= 10 || next == 0);
+            for (member, value) in page {
+                assert_eq!(dict.get(member), Some(value));
+                assert!(seen.insert(member.to_vec()));
+            }
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+        }
+        assert_eq!(seen.len(), 999);
+        assert!(!seen.contains(&b"m1"[..]));
+    }
+
+    #[test]
+    fn test_dict_mem_usage() {
+        let mut dict: Dict<Vec<u8>> = Dict::new();
+        assert_eq!(dict.mem_usage(), 0);
+        for i in 0..100 {
+            dict.insert(format!("f{}", i).into_bytes(), b"value".to_vec());
+        }
+        dict.insert(b"f0".to_vec(), b"a longer value".to_vec());
+        for i in 50..100 {
+            dict.remove(format!("f{}", i).as_bytes());
+        }
+        let rebuilt: Dict<Vec<u8>> = dict.iter().map(|(f, v)| (f.clone(), v.clone())).collect();
+        assert_eq!(dict.allocated, rebuilt.allocated);
+        for i in 0..50 {
+            dict.remove(format!("f{}", i).as_bytes());
+        }
+        assert_eq!(dict.allocated, 0);
+    }
+}
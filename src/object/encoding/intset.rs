@@ -0,0 +1,175 @@
+//! A sorted array of integers, the small encoding of a set of integers.
+//!
+//! The integers are stored in the narrowest of 16, 32 or 64 bits which fits all of them. The
+//! array is upgraded to a wider type when an integer doesn't fit, and never downgraded.
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Intset {
+    Int16(Vec<i16>),
+    Int32(Vec<i32>),
+    Int64(Vec<i64>),
+}
+
+impl Default for Intset {
+    fn default() -> Self {
+        Intset::Int16(Vec::new())
+    }
+}
+
+/// Bytes taken by an integer of the narrowest type fitting `value`
+fn width_of(value: i64) -> usize {
+    if i16::try_from(value).is_ok() {
+        2
+    } else if i32::try_from(value).is_ok() {
+        4
+    } else {
+        8
+    }
+}
+
+impl Intset {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Intset::Int16(values) => values.len(),
+            Intset::Int32(values) => values.len(),
+            Intset::Int64(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Bytes taken by an integer
+    pub fn width(&self) -> usize {
+        match self {
+            Intset::Int16(_) => 2,
+            Intset::Int32(_) => 4,
+            Intset::Int64(_) => 8,
+        }
+    }
+
+    /// Bytes allocated for the integers
+    pub fn capacity(&self) -> usize {
+        let capacity = match self {
+            Intset::Int16(values) => values.capacity(),
+            Intset::Int32(values) => values.capacity(),
+            Intset::Int64(values) => values.capacity(),
+        };
+        capacity * self.width()
+    }
+
+    /// Position of `value`, or where it would be inserted
+    fn search(&self, value: i64) -> Result<usize, usize> {
+        if width_of(value) > self.width() {
+            // wider than every integer, so either the smallest or the largest
+            return Err(if value < 0 { 0 } else { self.len() });
+        }
+        match self {
+            Intset::Int16(values) => values.binary_search(&(value as i16)),
+            Intset::Int32(values) => values.binary_search(&(value as i32)),
+            Intset::Int64(values) => values.binary_search(&value),
+        }
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.search(value).is_ok()
+    }
+
+    pub fn get(&self, index: usize) -> Option<i64> {
+        match self {
+            Intset::Int16(values) => values.get(index).map(|&value| value as i64),
+            Intset::Int32(values) => values.get(index).map(|&value| value as i64),
+            Intset::Int64(values) => values.get(index).copied(),
+        }
+    }
+
+    /// Insert `value`, returns false when it's already there
+    pub fn insert(&mut self, value: i64) -> bool {
+        let Err(index) = self.search(value) else {
+            return false;
+        };
+        if width_of(value) > self.width() {
+            self.upgrade(width_of(value));
+        }
+        match self {
+            Intset::Int16(values) => values.insert(index, value as i16),
+            Intset::Int32(values) => values.insert(index, value as i32),
+            Intset::Int64(values) => values.insert(index, value),
+        }
+        true
+    }
+
+    /// Remove `value`, returns false when it isn't there
+    pub fn remove(&mut self, value: i64) -> bool {
+        let Ok(index) = self.search(value) else {
+            return false;
+        };
+        match self {
+            Intset::Int16(values) => {
+                values.remove(index);
+            }
+            Intset::Int32(values) => {
+                values.remove(index);
+            }
+            Intset::Int64(values) => {
+                values.remove(index);
+            }
+        }
+        true
+    }
+
+    /// Store the integers in `width` bytes each
+    fn upgrade(&mut self, width: usize) {
+        let values: Vec<i64> = self.iter().collect();
+        *self = match width {
+            4 => Intset::Int32(values.into_iter().map(|value| value as i32).collect()),
+            _ => Intset::Int64(values),
+        };
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = i64> + ExactSizeIterator + '_ {
+        (0..self.len()).map(|index| self.get(index).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::object::encoding::intset::Intset;
+
+    #[test]
+    fn test_intset() {
+        let mut set = Intset::new();
+        for value in [5, -3, 100, 5] {
+            set.insert(value);
+        }
+        assert_eq!(set.iter().collect::<Vec<_>>(), [-3, 5, 100]);
+        assert_eq!(set.width(), 2);
+
+        // a wider integer upgrades every one of them
+        assert!(set.insert(70_000));
+        assert_eq!(set.width(), 4);
+        assert!(set.insert(i64::MIN));
+        assert_eq!(set.width(), 8);
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            [i64::MIN, -3, 5, 100, 70_000]
+        );
+        assert!(set.contains(100));
+        assert!(!set.contains(i64::MAX));
+
+        assert!(set.remove(5));
+        assert!(!set.remove(5));
+        assert_eq!(set.get(1), Some(-3));
+        assert_eq!(set.len(), 4);
+
+        let mut small = Intset::new();
+        small.insert(1);
+        assert!(!small.contains(1 << 40));
+        assert!(!small.remove(-(1 << 40)));
+    }
+}
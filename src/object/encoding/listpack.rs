@@ -0,0 +1,304 @@
+//! A compact list of byte strings packed in one buffer, the node of a quicklist.
+//!
+//! Every entry is `<len><data><backlen>`. `len` is a LEB128 varint and `backlen` the size of
+//! `<len><data>`, written so it can be decoded from its last byte. The entries can be walked
+//! from both ends without any pointer.
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Listpack {
+    buf: Vec<u8>,
+    len: usize,
+}
+
+/// Bytes of `value` in 7 bit groups
+fn varint_size(mut value: usize) -> usize {
+    let mut size = 1;
+    while value >= 0x80 {
+        value >>= 7;
+        size += 1;
+    }
+    size
+}
+
+/// Bytes taken by an entry of `len` bytes of data
+pub fn entry_size(len: usize) -> usize {
+    let size = varint_size(len) + len;
+    size + varint_size(size)
+}
+
+fn encode_entry(data: &[u8]) -> Vec<u8> {
+    let mut entry = Vec::with_capacity(entry_size(data.len()));
+    let mut len = data.len();
+    while len >= 0x80 {
+        entry.push(len as u8 & 0x7f | 0x80);
+        len >>= 7;
+    }
+    entry.push(len as u8);
+    entry.extend_from_slice(data);
+    // the lowest group comes last, every group but the highest has the continuation bit
+    let backlen = entry.len();
+    let size = varint_size(backlen);
+    for i in 0..size {
+        let group = (backlen >> (7 * (size - 1 - i))) as u8 & 0x7f;
+        entry.push(if i > 0 { group | 0x80 } else { group });
+    }
+    entry
+}
+
+impl Listpack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Listpack of a buffer built by another listpack, e.g. a decompressed quicklist node
+    pub fn from_raw(buf: Vec<u8>, len: usize) -> Self {
+        Self { buf, len }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Bytes taken by the entries
+    pub fn bytes(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Bytes allocated for the entries
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    pub fn as_raw(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// The data of the entry at `offset` and the offset of the next entry
+    fn entry_at(&self, offset: usize) -> (&[u8], usize) {
+        let mut len = 0;
+        let mut pos = offset;
+        let mut shift = 0;
+        loop {
+            let byte = self.buf[pos];
+            pos += 1;
+            len |= ((byte & 0x7f) as usize) << shift;
+            if byte & 0x80 == 0 {
+                break;
+            }
+            shift += 7;
+        }
+        let data = &self.buf[pos..pos + len];
+        (data, pos + len + varint_size(pos + len - offset))
+    }
+
+    /// Offset of the entry ending at `end`
+    fn prev_offset(&self, end: usize) -> usize {
+        let mut backlen = 0;
+        let mut size = 0;
+        loop {
+            let byte = self.buf[end - 1 - size];
+            backlen |= ((byte & 0x7f) as usize) << (7 * size);
+            size += 1;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        end - size - backlen
+    }
+
+    /// Offset of the entry at `index`, walked from the nearest end. Index `len` is the end.
+    fn offset_of(&self, index: usize) -> usize {
+        if index <= self.len / 2 {
+            (0..index).fold(0, |offset, _| self.entry_at(offset).1)
+        } else {
+            (index..self.len).fold(self.buf.len(), |end, _| self.prev_offset(end))
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&[u8]> {
+        if index >= self.len {
+            return None;
+        }
+        Some(self.entry_at(self.offset_of(index)).0)
+    }
+
+    /// Insert `data` before the entry at `index`, or at the end when `index` is `len`
+    pub fn insert(&mut self, index: usize, data: &[u8]) {
+        assert!(index <= self.len, "listpack index out of bounds");
+        let offset = self.offset_of(index);
+        self.buf.splice(offset..offset, encode_entry(data));
+        self.len += 1;
+    }
+
+    pub fn push_front(&mut self, data: &[u8]) {
+        self.insert(0, data);
+    }
+
+    pub fn push_back(&mut self, data: &[u8]) {
+        self.buf.extend_from_slice(&encode_entry(data));
+        self.len += 1;
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Vec<u8>> {
+        if index >= self.len {
+            return None;
+        }
+        let offset = self.offset_of(index);
+        let (data, next) = self.entry_at(offset);
+        let data = data.to_vec();
+        self.buf.drain(offset..next);
+        self.len -= 1;
+        Some(data)
+    }
+
+    pub fn pop_front(&mut self) -> Option<Vec<u8>> {
+        self.remove(0)
+    }
+
+    pub fn pop_back(&mut self) -> Option<Vec<u8>> {
+        self.remove(self.len.checked_sub(1)?)
+    }
+
+    /// Replace the entry at `index`, returns false when it's out of range
+    pub fn replace(&mut self, index: usize, data: &[u8]) -> bool {
+        if index >= self.len {
+            return false;
+        }
+        let offset = self.offset_of(index);
+        let next = self.entry_at(offset).1;
+        self.buf.splice(offset..next, encode_entry(data));
+        true
+    }
+
+    /// Split the entries from `at` into a new listpack
+    pub fn split_off(&mut self, at: usize) -> Listpack {
+        let offset = self.offset_of(at.min(self.len));
+        let tail = Listpack::from_raw(self.buf.split_off(offset), self.len - at.min(self.len));
+        self.len = at.min(self.len);
+        tail
+    }
+
+    /// Move the entries of `other` to the end
+    pub fn append(&mut self, other: Listpack) {
+        self.buf.extend_from_slice(&other.buf);
+        self.len += other.len;
+    }
+
+    /// Keep the entries matched by `f`, returns the count of removed ones
+    pub fn retain(&mut self, mut f: impl FnMut(&[u8]) -> bool) -> usize {
+        let mut kept = Vec::with_capacity(self.buf.len());
+        let mut removed = 0;
+        let mut offset = 0;
+        while offset < self.buf.len() {
+            let (data, next) = self.entry_at(offset);
+            if f(data) {
+                kept.extend_from_slice(&self.buf[offset..next]);
+            } else {
+                removed += 1;
+            }
+            offset = next;
+        }
+        self.buf = kept;
+        self.len -= removed;
+        removed
+    }
+
+    /// Release the capacity left by removed entries
+    pub fn shrink_to_fit(&mut self) {
+        self.buf.shrink_to_fit();
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            listpack: self,
+            front: 0,
+            back: self.buf.len(),
+            remaining: self.len,
+        }
+    }
+}
+
+/// Entries of a listpack from either end
+pub struct Iter<'a> {
+    listpack: &'a Listpack,
+    front: usize,
+    back: usize,
+    remaining: usize,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let (data, next) = self.listpack.entry_at(self.front);
+        self.front = next;
+        self.remaining -= 1;
+        Some(data)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.back = self.listpack.prev_offset(self.back);
+        self.remaining -= 1;
+        Some(self.listpack.entry_at(self.back).0)
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+#[cfg(test)]
+mod test {
+    use crate::object::encoding::listpack::{Listpack, entry_size};
+
+    #[test]
+    fn test_listpack() {
+        let mut lp = Listpack::new();
+        lp.push_back(b"b");
+        lp.push_front(b"a");
+        let long = vec![b'x'; 300];
+        lp.push_back(&long);
+        lp.insert(2, b"c");
+        assert_eq!(lp.len(), 4);
+        assert_eq!(lp.bytes(), 3 * entry_size(1) + entry_size(300));
+        assert_eq!(entry_size(300), 2 + 300 + 2);
+
+        let entries: Vec<&[u8]> = lp.iter().collect();
+        assert_eq!(entries, [&b"a"[..], b"b", b"c", &long]);
+        let reversed: Vec<&[u8]> = lp.iter().rev().collect();
+        assert_eq!(reversed, [&long[..], b"c", b"b", b"a"]);
+        assert_eq!(lp.get(3), Some(&long[..]));
+        assert_eq!(lp.get(4), None);
+
+        assert!(lp.replace(1, b"bb"));
+        assert_eq!(lp.remove(3), Some(long));
+        assert_eq!(lp.pop_front(), Some(b"a".to_vec()));
+        assert_eq!(lp.iter().collect::<Vec<_>>(), [&b"bb"[..], b"c"]);
+
+        let mut numbers = Listpack::new();
+        for i in 0..10 {
+            numbers.push_back(i.to_string().as_bytes());
+        }
+        let tail = numbers.split_off(6);
+        assert_eq!(tail.iter().next(), Some(&b"6"[..]));
+        assert_eq!((numbers.len(), tail.len()), (6, 4));
+        numbers.append(tail);
+        assert_eq!(numbers.retain(|entry| entry[0] % 2 == 0), 5);
+        assert_eq!(numbers.iter().next_back(), Some(&b"8"[..]));
+        assert_eq!(numbers.pop_back(), Some(b"8".to_vec()));
+    }
+}
@@ -0,0 +1,125 @@
+//! LZF compression of quicklist nodes, the format used by redis.
+//!
+//! A control byte below 32 starts a run of `ctrl + 1` literal bytes. Otherwise its top 3
+//! bits are the length of a back reference minus 2 (7 means an extra length byte follows)
+//! and its low 5 bits with the next byte the distance minus 1.
+
+const HASH_LOG: usize = 14;
+const MAX_LITERAL: usize = 32;
+const MAX_OFFSET: usize = 1 << 13;
+const MAX_REF: usize = (1 << 8) + (1 << 3);
+
+fn hash(input: &[u8]) -> usize {
+    let v = (input[0] as usize) << 16 | (input[1] as usize) << 8 | input[2] as usize;
+    (v.wrapping_mul(2654435761) >> 10) & ((1 << HASH_LOG) - 1)
+}
+
+fn flush_literals(out: &mut Vec<u8>, literals: &mut Vec<u8>) {
+    if !literals.is_empty() {
+        out.push((literals.len() - 1) as u8);
+        out.append(literals);
+    }
+}
+
+/// Compress `input`, `None` when the output wouldn't be smaller
+pub fn compress(input: &[u8]) -> Option<Vec<u8>> {
+    let mut table = vec![0usize; 1 << HASH_LOG];
+    let mut out = Vec::with_capacity(input.len());
+    let mut literals = Vec::with_capacity(MAX_LITERAL);
+    let mut pos = 0;
+    while pos + 2 < input.len() {
+        let slot = hash(&input[pos..]);
+        // positions are stored plus one, 0 is an empty slot
+        let candidate = table[slot];
+        table[slot] = pos + 1;
+        if candidate > 0 {
+            let start = candidate - 1;
+            let offset = pos - start - 1;
+            if offset < MAX_OFFSET && input[start..start + 3] == input[pos..pos + 3] {
+                let max = (input.len() - pos).min(MAX_REF);
+                let mut len = 3;
+                while len < max && input[start + len] == input[pos + len] {
+                    len += 1;
+                }
+                flush_literals(&mut out, &mut literals);
+                let code = len - 2;
+                if code < 7 {
+                    out.push((code << 5 | offset >> 8) as u8);
+                } else {
+                    out.push((7 << 5 | offset >> 8) as u8);
+                    out.push((code - 7) as u8);
+                }
+                out.push(offset as u8);
+                pos += len;
+                if out.len() >= input.len() {
+                    return None;
+                }
+                continue;
+            }
+        }
+        literals.push(input[pos]);
+        if literals.len() == MAX_LITERAL {
+            flush_literals(&mut out, &mut literals);
+        }
+        pos += 1;
+    }
+    for &byte in &input[pos..] {
+        literals.push(byte);
+        if literals.len() == MAX_LITERAL {
+            flush_literals(&mut out, &mut literals);
+        }
+    }
+    flush_literals(&mut out, &mut literals);
+    (out.len() < input.len()).then_some(out)
+}
+
+/// Decompress the output of `compress`, `len` is the size of the original input
+pub fn decompress(input: &[u8], len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(len);
+    let mut pos = 0;
+    while pos < input.len() {
+        let ctrl = input[pos] as usize;
+        pos += 1;
+        if ctrl < MAX_LITERAL {
+            out.extend_from_slice(&input[pos..pos + ctrl + 1]);
+            pos += ctrl + 1;
+            continue;
+        }
+        let mut count = ctrl >> 5;
+        if count == 7 {
+            count += input[pos] as usize;
+            pos += 1;
+        }
+        let offset = (ctrl & 0x1f) << 8 | input[pos] as usize;
+        pos += 1;
+        // the reference may overlap the bytes it produces
+        let start = out.len() - offset - 1;
+        for i in 0..count + 2 {
+            out.push(out[start + i]);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use crate::object::encoding::lzf::{compress, decompress};
+
+    #[test]
+    fn test_compress() {
+        let input: Vec<u8> = (0..2000)
+            .flat_map(|i| format!("job:{}:pending;", i % 50).into_bytes())
+            .collect();
+        let compressed = compress(&input).unwrap();
+        assert!(compressed.len() < input.len() / 4);
+        assert_eq!(decompress(&compressed, input.len()), input);
+
+        let runs = vec![b'a'; 1000];
+        assert_eq!(decompress(&compress(&runs).unwrap(), runs.len()), runs);
+
+        // bytes without repetition don't shrink
+        let noise: Vec<u8> = (0..=255).collect();
+        assert!(compress(&noise).is_none());
+        assert!(compress(b"ab").is_none());
+    }
+}
@@ -1 +1,7 @@
+pub mod dict;
+pub mod intset;
+pub mod listpack;
+pub(crate) mod lzf;
+pub mod quicklist;
 pub(crate) mod sds;
+pub mod skiplist;
@@ -0,0 +1,540 @@
+//! The encoding of lists, a deque of listpack nodes.
+//!
+//! Nodes are filled up to `list-max-listpack-size`. With `list-compress-depth`, the nodes
+//! farther than the depth from both ends are kept LZF compressed, as lists are mostly
+//! pushed and popped at the ends.
+
+use std::{borrow::Cow, collections::VecDeque};
+
+use crate::object::encoding::{
+    listpack::{self, Listpack},
+    lzf,
+};
+
+/// Node sizes of the negative `list-max-listpack-size` values, -1 to -5
+const SIZE_LIMITS: [usize; 5] = [4096, 8192, 16384, 32768, 65536];
+
+/// Nodes smaller than this aren't worth compressing
+const MIN_COMPRESS_BYTES: usize = 48;
+
+/// A compressed node has to save at least this much to be kept
+const MIN_COMPRESS_IMPROVE: usize = 8;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Node {
+    Plain(Listpack),
+    /// The raw buffer of a listpack of `len` entries and `bytes` bytes, LZF compressed
+    Compressed {
+        data: Vec<u8>,
+        bytes: usize,
+        len: usize,
+    },
+}
+
+impl Node {
+    fn len(&self) -> usize {
+        match self {
+            Node::Plain(listpack) => listpack.len(),
+            Node::Compressed { len, .. } => *len,
+        }
+    }
+
+    /// Bytes of the entries once decompressed
+    fn bytes(&self) -> usize {
+        match self {
+            Node::Plain(listpack) => listpack.bytes(),
+            Node::Compressed { bytes, .. } => *bytes,
+        }
+    }
+
+    fn allocated(&self) -> usize {
+        match self {
+            Node::Plain(listpack) => listpack.capacity(),
+            Node::Compressed { data, .. } => data.capacity(),
+        }
+    }
+
+    fn listpack(&self) -> Cow<'_, Listpack> {
+        match self {
+            Node::Plain(listpack) => Cow::Borrowed(listpack),
+            Node::Compressed { data, bytes, len } => {
+                Cow::Owned(Listpack::from_raw(lzf::decompress(data, *bytes), *len))
+            }
+        }
+    }
+
+    fn compress(&mut self) {
+        let Node::Plain(listpack) = self else {
+            return;
+        };
+        let (bytes, len) = (listpack.bytes(), listpack.len());
+        if bytes < MIN_COMPRESS_BYTES {
+            return;
+        }
+        if let Some(mut data) = lzf::compress(listpack.as_raw())
+            && data.len() + MIN_COMPRESS_IMPROVE <= bytes
+        {
+            data.shrink_to_fit();
+            *self = Node::Compressed { data, bytes, len };
+        }
+    }
+
+    fn decompress(&mut self) {
+        if let Node::Compressed { data, bytes, len } = self {
+            *self = Node::Plain(Listpack::from_raw(lzf::decompress(data, *bytes), *len));
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Quicklist {
+    nodes: VecDeque<Node>,
+    len: usize,
+    /// Bytes allocated by the nodes, kept up to date as they change
+    allocated: usize,
+    /// `list-max-listpack-size`, the entries of a node when positive, a size class otherwise
+    fill: i64,
+    /// `list-compress-depth`, the nodes kept plain at each end, 0 disables compression
+    compress_depth: usize,
+}
+
+impl Quicklist {
+    pub fn new(fill: i64, compress_depth: usize) -> Self {
+        Self {
+            nodes: VecDeque::new(),
+            len: 0,
+            allocated: 0,
+            fill,
+            compress_depth,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Bytes allocated by the list besides the object holding it
+    pub fn mem_usage(&self) -> usize {
+        self.nodes.capacity() * size_of::<Node>() + self.allocated
+    }
+
+    fn size_limit(&self) -> usize {
+        SIZE_LIMITS[self.fill.unsigned_abs().clamp(1, 5) as usize - 1]
+    }
+
+    /// Whether a node of `len` entries and `bytes` bytes may take an entry of `size` bytes
+    fn allows_insert(&self, len: usize, bytes: usize, size: usize) -> bool {
+        if len == 0 {
+            return true;
+        }
+        match usize::try_from(self.fill) {
+            Ok(count) => len < count.max(1),
+            Err(_) => bytes + size <= self.size_limit(),
+        }
+    }
+
+    fn is_over_limit(&self, index: usize) -> bool {
+        let node = &self.nodes[index];
+        match usize::try_from(self.fill) {
+            Ok(count) => node.len() > count.max(1),
+            Err(_) => node.bytes() > self.size_limit(),
+        }
+    }
+
+    /// Apply `f` to the entries of the node at `index`, which is decompressed first
+    fn update<R>(&mut self, index: usize, f: impl FnOnce(&mut Listpack) -> R) -> R {
+        let node = &mut self.nodes[index];
+        let before = node.allocated();
+        node.decompress();
+        let result = match node {
+            Node::Plain(listpack) => f(listpack),
+            Node::Compressed { .. } => unreachable!("the node was decompressed"),
+        };
+        self.allocated = self.allocated + node.allocated() - before;
+        result
+    }
+
+    fn insert_node(&mut self, index: usize, listpack: Listpack) {
+        self.allocated += listpack.capacity();
+        self.nodes.insert(index, Node::Plain(listpack));
+    }
+
+    fn remove_node(&mut self, index: usize) {
+        if let Some(node) = self.nodes.remove(index) {
+            self.allocated -= node.allocated();
+        }
+    }
+
+    fn set_compressed(&mut self, index: usize, compressed: bool) {
+        let node = &mut self.nodes[index];
+        let before = node.allocated();
+        if compressed {
+            node.compress();
+        } else {
+            node.decompress();
+        }
+        self.allocated = self.allocated + node.allocated() - before;
+    }
+
+    /// Keep the nodes within `compress_depth` of both ends plain and the others compressed.
+    ///
+    /// After one node was added or removed, only the nodes at the depth boundaries and the
+    /// `touched` ones can be in the wrong state.
+    fn balance(&mut self, touched: &[usize]) {
+        if self.compress_depth == 0 {
+            return;
+        }
+        let (count, depth) = (self.nodes.len(), self.compress_depth);
+        let boundaries = [
+            depth.wrapping_sub(1),
+            depth,
+            count.wrapping_sub(depth + 1),
+            count.wrapping_sub(depth),
+        ];
+        for &index in boundaries.iter().chain(touched) {
+            if index < count {
+                self.set_compressed(index, index >= depth && index + depth < count);
+            }
+        }
+    }
+
+    fn balance_all(&mut self) {
+        if self.compress_depth == 0 {
+            return;
+        }
+        let (count, depth) = (self.nodes.len(), self.compress_depth);
+        for index in 0..count {
+            self.set_compressed(index, index >= depth && index + depth < count);
+        }
+    }
+
+    /// The node holding the entry at `index` and the index of the entry in the node
+    fn locate(&self, index: usize) -> Option<(usize, usize)> {
+        if index >= self.len {
+            return None;
+        }
+        if index < self.len / 2 {
+            let mut local = index;
+            for (i, node) in self.nodes.iter().enumerate() {
+                if local < node.len() {
+                    return Some((i, local));
+                }
+                local -= node.len();
+            }
+        } else {
+            let mut from_end = self.len - index;
+            for (i, node) in self.nodes.iter().enumerate().rev() {
+                if from_end <= node.len() {
+                    return Some((i, node.len() - from_end));
+                }
+                from_end -= node.len();
+            }
+        }
+        None
+    }
+
+    /// Split the node at `index` in halves once it grew over the fill limit
+    fn split_if_over_limit(&mut self, index: usize) {
+        if self.nodes[index].len() > 1 && self.is_over_limit(index) {
+            let tail = self.update(index, |listpack| {
+                let tail = listpack.split_off(listpack.len() / 2);
+                listpack.shrink_to_fit();
+                tail
+            });
+            self.insert_node(index + 1, tail);
+        }
+    }
+
+    pub fn push_front(&mut self, data: &[u8]) {
+        let size = listpack::entry_size(data.len());
+        let fits = self
+            .nodes
+            .front()
+            .is_some_and(|node| self.allows_insert(node.len(), node.bytes(), size));
+        if fits {
+            self.update(0, |listpack| listpack.push_front(data));
+        } else {
+            let mut listpack = Listpack::new();
+            listpack.push_back(data);
+            self.insert_node(0, listpack);
+        }
+        self.len += 1;
+        self.balance(&[0]);
+    }
+
+    pub fn push_back(&mut self, data: &[u8]) {
+        let size = listpack::entry_size(data.len());
+        let fits = self
+            .nodes
+            .back()
+            .is_some_and(|node| self.allows_insert(node.len(), node.bytes(), size));
+        if fits {
+            self.update(self.nodes.len() - 1, |listpack| listpack.push_back(data));
+        } else {
+            let mut listpack = Listpack::new();
+            listpack.push_back(data);
+            self.insert_node(self.nodes.len(), listpack);
+        }
+        self.len += 1;
+        self.balance(&[self.nodes.len() - 1]);
+    }
+
+    pub fn pop_front(&mut self) -> Option<Vec<u8>> {
+        self.remove(0)
+    }
+
+    pub fn pop_back(&mut self) -> Option<Vec<u8>> {
+        self.remove(self.len.checked_sub(1)?)
+    }
+
+    pub fn get(&self, index: usize) -> Option<Vec<u8>> {
+        let (node, local) = self.locate(index)?;
+        self.nodes[node].listpack().get(local).map(<[u8]>::to_vec)
+    }
+
+    /// Replace the entry at `index`, returns false when it's out of range
+    pub fn set(&mut self, index: usize, data: &[u8]) -> bool {
+        let Some((node, local)) = self.locate(index) else {
+            return false;
+        };
+        self.update(node, |listpack| listpack.replace(local, data));
+        self.split_if_over_limit(node);
+        self.balance(&[node, node + 1]);
+        true
+    }
+
+    /// Insert `data` before the entry at `index`, or at the end when `index` is `len`
+    pub fn insert(&mut self, index: usize, data: &[u8]) {
+        if index == 0 {
+            return self.push_front(data);
+        }
+        let Some((node, local)) = self.locate(index) else {
+            return self.push_back(data);
+        };
+        self.update(node, |listpack| listpack.insert(local, data));
+        self.len += 1;
+        self.split_if_over_limit(node);
+        self.balance(&[node, node + 1]);
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Vec<u8>> {
+        let (node, local) = self.locate(index)?;
+        let data = self.update(node, |listpack| listpack.remove(local));
+        self.len -= 1;
+        if self.nodes[node].len() == 0 {
+            self.remove_node(node);
+        }
+        self.balance(&[node]);
+        data
+    }
+
+    /// Entries from `start` to `stop` inclusive
+    pub fn range(&self, start: usize, stop: usize) -> Vec<Vec<u8>> {
+        if start > stop || start >= self.len {
+            return Vec::new();
+        }
+        let mut take = stop.min(self.len - 1) - start + 1;
+        let mut skip = start;
+        let mut entries = Vec::with_capacity(take);
+        for node in &self.nodes {
+            if take == 0 {
+                break;
+            }
+            if skip >= node.len() {
+                skip -= node.len();
+                continue;
+            }
+            let count = (node.len() - skip).min(take);
+            let listpack = node.listpack();
+            entries.extend(listpack.iter().skip(skip).take(count).map(<[u8]>::to_vec));
+            take -= count;
+            skip = 0;
+        }
+        entries
+    }
+
+    /// Every entry from the head, nodes are decompressed one at a time
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = Vec<u8>> + '_ {
+        self.nodes.iter().flat_map(|node| {
+            node.listpack()
+                .iter()
+                .map(<[u8]>::to_vec)
+                .collect::<Vec<_>>()
+        })
+    }
+
+    /// Remove up to `count` entries equal to `data`, all of them when 0, starting from the
+    /// tail when `from_tail`. Returns the count of removed entries.
+    pub fn remove_matching(&mut self, data: &[u8], count: usize, from_tail: bool) -> usize {
+        let mut removed = 0;
+        let order: Vec<usize> = if from_tail {
+            (0..self.nodes.len()).rev().collect()
+        } else {
+            (0..self.nodes.len()).collect()
+        };
+        for index in order {
+            if count > 0 && removed == count {
+                break;
+            }
+            if !self.nodes[index].listpack().iter().any(|entry| entry == data) {
+                continue;
+            }
+            let limit = if count == 0 { usize::MAX } else { count - removed };
+            removed += self.update(index, |listpack| {
+                let mut matches: Vec<usize> = listpack
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, entry)| *entry == data)
+                    .map(|(i, _)| i)
+                    .collect();
+                if from_tail {
+                    matches.reverse();
+                }
+                matches.truncate(limit);
+                matches.sort_unstable_by(|a, b| b.cmp(a));
+                for &i in &matches {
+                    listpack.remove(i);
+                }
+                listpack.shrink_to_fit();
+                matches.len()
+            });
+        }
+        self.len -= removed;
+        for index in (0..self.nodes.len()).rev() {
+            if self.nodes[index].len() == 0 {
+                self.remove_node(index);
+            }
+        }
+        self.balance_all();
+        removed
+    }
+
+    /// Keep the entries from `start` to `stop` inclusive only
+    pub fn trim(&mut self, start: usize, stop: usize) {
+        if start > stop || start >= self.len {
+            self.nodes.clear();
+            self.allocated = 0;
+            self.len = 0;
+            return;
+        }
+        let stop = stop.min(self.len - 1);
+        let mut head = start;
+        while head > 0 {
+            let len = self.nodes[0].len();
+            if len <= head {
+                self.remove_node(0);
+                head -= len;
+            } else {
+                self.update(0, |listpack| *listpack = listpack.split_off(head));
+                head = 0;
+            }
+        }
+        let mut tail = self.len - 1 - stop;
+        while tail > 0 {
+            let last = self.nodes.len() - 1;
+            let len = self.nodes[last].len();
+            if len <= tail {
+                self.remove_node(last);
+                tail -= len;
+            } else {
+                self.update(last, |listpack| {
+                    listpack.split_off(len - tail);
+                    listpack.shrink_to_fit();
+                });
+                tail = 0;
+            }
+        }
+        self.len = stop - start + 1;
+        self.balance_all();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::object::encoding::quicklist::{Node, Quicklist};
+
+    fn entries(list: &Quicklist) -> Vec<String> {
+        list.iter()
+            .map(|entry| String::from_utf8(entry).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_quicklist() {
+        let mut list = Quicklist::new(4, 0);
+        for i in 0..10 {
+            list.push_back(i.to_string().as_bytes());
+        }
+        list.push_front(b"head");
+        assert_eq!(list.len(), 11);
+        assert_eq!(list.node_count(), 4);
+        assert_eq!(list.get(0), Some(b"head".to_vec()));
+        assert_eq!(list.get(10), Some(b"9".to_vec()));
+        assert_eq!(list.get(11), None);
+
+        list.insert(5, b"mid");
+        assert!(list.set(6, b"five"));
+        assert!(!list.set(12, b"none"));
+        assert_eq!(list.remove(1), Some(b"0".to_vec()));
+        assert_eq!(
+            entries(&list),
+            ["head", "1", "2", "3", "mid", "five", "5", "6", "7", "8", "9"]
+        );
+        assert_eq!(list.range(3, 6), [b"3".to_vec(), b"mid".to_vec(), b"five".to_vec(), b"5".to_vec()]);
+        assert_eq!(list.iter().next_back(), Some(b"9".to_vec()));
+
+        list.push_back(b"5");
+        assert_eq!(list.remove_matching(b"5", 1, true), 1);
+        assert_eq!(list.remove_matching(b"mid", 0, false), 1);
+        list.trim(1, 5);
+        assert_eq!(entries(&list), ["1", "2", "3", "five", "5"]);
+        assert_eq!(list.pop_front(), Some(b"1".to_vec()));
+        assert_eq!(list.pop_back(), Some(b"5".to_vec()));
+        list.trim(5, 1);
+        assert!(list.is_empty());
+        assert_eq!((list.node_count(), list.mem_usage() > 0), (0, true));
+    }
+
+    #[test]
+    fn test_quicklist_compression() {
+        let mut list = Quicklist::new(-1, 1);
+        let entry = b"a job payload which repeats itself in every entry".to_vec();
+        for _ in 0..1000 {
+            list.push_back(&entry);
+            list.push_front(&entry);
+        }
+        let count = list.node_count();
+        assert!(count > 3);
+        let compressed = |list: &Quicklist| {
+            list.nodes
+                .iter()
+                .map(|node| matches!(node, Node::Compressed { .. }))
+                .collect::<Vec<_>>()
+        };
+        let states = compressed(&list);
+        assert!(!states[0] && !states[count - 1]);
+        assert!(states[1..count - 1].iter().all(|&state| state));
+        let allocated: usize = list.nodes.iter().map(|node| node.allocated()).sum();
+        assert_eq!(list.allocated, allocated);
+        assert!(allocated < 2000 * entry.len() / 4);
+
+        assert_eq!(list.get(1000), Some(entry.clone()));
+        assert!(list.set(1000, b"changed"));
+        assert_eq!(list.get(1000), Some(b"changed".to_vec()));
+        assert!(compressed(&list)[1..list.node_count() - 1].iter().all(|&state| state));
+
+        list.trim(10, 1500);
+        assert_eq!(list.len(), 1491);
+        assert_eq!(list.range(990, 990), [b"changed".to_vec()]);
+        while list.pop_front().is_some() {}
+        assert_eq!((list.node_count(), list.allocated), (0, 0));
+    }
+}
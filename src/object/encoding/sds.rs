@@ -11,6 +11,31 @@ pub struct Raw {
     buf: Vec<u8>,
 }
 
+impl EmbStr {
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl Raw {
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Bytes allocated for the string
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+}
+
 /// Create an embed string from bytes
 impl From<Vec<u8>> for EmbStr {
     fn from(value: Vec<u8>) -> Self {
@@ -22,10 +47,10 @@ impl From<Vec<u8>> for EmbStr {
 }
 
 /// Clone an embed string to bytes
-impl Into<Vec<u8>> for EmbStr {
-    fn into(self) -> Vec<u8> {
+impl From<EmbStr> for Vec<u8> {
+    fn from(value: EmbStr) -> Self {
         let mut vec = Vec::new();
-        vec.extend_from_slice(&self.buf[..self.len as usize]);
+        vec.extend_from_slice(&value.buf[..value.len as usize]);
         vec
     }
 }
@@ -33,14 +58,14 @@ impl Into<Vec<u8>> for EmbStr {
 /// Create a raw string from bytes
 impl From<Vec<u8>> for Raw {
     fn from(buf: Vec<u8>) -> Self {
-        return Self { buf };
+        Self { buf }
     }
 }
 
 /// Clone a raw string to bytes
-impl Into<Vec<u8>> for Raw {
-    fn into(self) -> Vec<u8> {
-        self.buf.clone()
+impl From<Raw> for Vec<u8> {
+    fn from(value: Raw) -> Self {
+        value.buf
     }
 }
 
@@ -67,21 +92,19 @@ mod test {
         let embstr: EmbStr = vec.into();
 
         let mut stub = [0; EMB_LEN];
-        for i in 0..30 {
-            stub[i] = 1;
-        }
+        stub[..30].fill(1);
         assert_eq!(embstr.buf, stub);
         assert_eq!(embstr.len, 30);
     }
     
     #[test]
     fn test_vec_to_embstr_on_vec_len_equal_to_buf() {
-        let vec:Vec<u8> = (0u8..EMB_LEN as u8).into_iter().collect();
+        let vec: Vec<u8> = (0u8..EMB_LEN as u8).collect();
         let embstr: EmbStr = vec.into();
         
         let mut stub = [0u8; EMB_LEN];
-        for i  in 0..EMB_LEN {
-            stub[i] = i as u8;
+        for (i, b) in stub.iter_mut().enumerate() {
+            *b = i as u8;
         }
         assert_eq!(embstr.buf, stub);
         assert_eq!(embstr.len, EMB_LEN as u8);
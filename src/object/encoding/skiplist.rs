@@ -0,0 +1,397 @@
+//! A skiplist of members ordered by score then member, the large encoding of a sorted set.
+//!
+//! Every link of a node counts the nodes it skips, so the rank of a member and the member at
+//! a rank are both found in O(log n). The nodes live in a vector and link to each other by
+//! index, the first one is the header and holds no member.
+
+const MAX_LEVEL: usize = 32;
+/// Chance of a node to have one more level
+const P: f64 = 0.25;
+/// Index of a missing node
+const NIL: usize = usize::MAX;
+
+#[derive(Debug, Clone, Copy)]
+struct Level {
+    forward: usize,
+    /// Count of nodes from this one to `forward`, or to the end of the list
+    span: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    member: Vec<u8>,
+    score: f64,
+    backward: usize,
+    levels: Vec<Level>,
+}
+
+impl Node {
+    /// Bytes allocated by the member and the levels
+    fn size(&self) -> usize {
+        self.member.len() + self.levels.len() * size_of::<Level>()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SkipList {
+    nodes: Vec<Node>,
+    /// Slots of removed nodes, reused by the next inserts
+    free: Vec<usize>,
+    tail: usize,
+    len: usize,
+    /// Levels in use by the header
+    level: usize,
+    /// Bytes allocated by the nodes, kept up to date as they change
+    allocated: usize,
+}
+
+impl PartialEq for SkipList {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+// the scores of a sorted set are never NaN
+impl Eq for SkipList {}
+
+impl Default for SkipList {
+    fn default() -> Self {
+        let header = Node {
+            member: Vec::new(),
+            score: 0.0,
+            backward: NIL,
+            levels: vec![
+                Level {
+                    forward: NIL,
+                    span: 0,
+                };
+                MAX_LEVEL
+            ],
+        };
+        Self {
+            allocated: header.size(),
+            nodes: vec![header],
+            free: Vec::new(),
+            tail: NIL,
+            len: 0,
+            level: 1,
+        }
+    }
+}
+
+fn random_level() -> usize {
+    let mut level = 1;
+    while level < MAX_LEVEL && rand::random::<f64>() < P {
+        level += 1;
+    }
+    level
+}
+
+/// Whether `(score, member)` comes before `(other_score, other_member)`
+fn precedes(score: f64, member: &[u8], other_score: f64, other_member: &[u8]) -> bool {
+    score < other_score || (score == other_score && member < other_member)
+}
+
+impl SkipList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The last node of every level before `(score, member)`
+    fn predecessors(&self, score: f64, member: &[u8]) -> ([usize; MAX_LEVEL], [usize; MAX_LEVEL]) {
+        let mut update = [0; MAX_LEVEL];
+        let mut rank = [0; MAX_LEVEL];
+        let mut x = 0;
+        for i in (0..self.level).rev() {
+            rank[i] = if i == self.level - 1 { 0 } else { rank[i + 1] };
+            loop {
+                let Level { forward, span } = self.nodes[x].levels[i];
+                if forward == NIL {
+                    break;
+                }
+                let next = &self.nodes[forward];
+                if !precedes(next.score, &next.member, score, member) {
+                    break;
+                }
+                rank[i] += span;
+                x = forward;
+            }
+            update[i] = x;
+        }
+        (update, rank)
+    }
+
+    /// Insert a member which isn't in the list yet
+    pub fn insert(&mut self, score: f64, member: Vec<u8>) {
+        let (mut update, mut rank) = self.predecessors(score, &member);
+        let level = random_level();
+        if level > self.level {
+            for i in self.level..level {
+                rank[i] = 0;
+                update[i] = 0;
+                self.nodes[0].levels[i].span = self.len;
+            }
+            self.level = level;
+        }
+        let node = Node {
+            member,
+            score,
+            backward: if update[0] == 0 { NIL } else { update[0] },
+            levels: vec![
+                Level {
+                    forward: NIL,
+                    span: 0,
+                };
+                level
+            ],
+        };
+        self.allocated += node.size();
+        let id = match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = node;
+                id
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+        for i in 0..level {
+            let prev = self.nodes[update[i]].levels[i];
+            // the new node splits the link of its predecessor
+            self.nodes[id].levels[i] = Level {
+                forward: prev.forward,
+                span: prev.span - (rank[0] - rank[i]),
+            };
+            self.nodes[update[i]].levels[i] = Level {
+                forward: id,
+                span: rank[0] - rank[i] + 1,
+            };
+        }
+        for (i, &prev) in update.iter().enumerate().take(self.level).skip(level) {
+            self.nodes[prev].levels[i].span += 1;
+        }
+        match self.nodes[id].levels[0].forward {
+            NIL => self.tail = id,
+            next => self.nodes[next].backward = id,
+        }
+        self.len += 1;
+    }
+
+    /// Remove a member, returns false when it isn't in the list with `score`
+    pub fn remove(&mut self, score: f64, member: &[u8]) -> bool {
+        let (update, _) = self.predecessors(score, member);
+        let x = self.nodes[update[0]].levels[0].forward;
+        if x == NIL || self.nodes[x].score != score || self.nodes[x].member != member {
+            return false;
+        }
+        for (i, &prev) in update.iter().enumerate().take(self.level) {
+            let removed = self.nodes[x].levels.get(i).copied();
+            let prev = &mut self.nodes[prev].levels[i];
+            match removed {
+                Some(level) if prev.forward == x => {
+                    prev.span = prev.span + level.span - 1;
+                    prev.forward = level.forward;
+                }
+                _ => prev.span -= 1,
+            }
+        }
+        let backward = self.nodes[x].backward;
+        match self.nodes[x].levels[0].forward {
+            NIL => self.tail = backward,
+            next => self.nodes[next].backward = backward,
+        }
+        while self.level > 1 && self.nodes[0].levels[self.level - 1].forward == NIL {
+            self.level -= 1;
+        }
+        self.allocated -= self.nodes[x].size();
+        self.nodes[x].member = Vec::new();
+        self.nodes[x].levels = Vec::new();
+        self.free.push(x);
+        self.len -= 1;
+        true
+    }
+
+    /// Count of the first members matched by `f`, which must match a prefix of the list
+    pub fn count_while(&self, f: impl Fn(&[u8], f64) -> bool) -> usize {
+        let mut rank = 0;
+        let mut x = 0;
+        for i in (0..self.level).rev() {
+            loop {
+                let Level { forward, span } = self.nodes[x].levels[i];
+                if forward == NIL || !f(&self.nodes[forward].member, self.nodes[forward].score) {
+                    break;
+                }
+                rank += span;
+                x = forward;
+            }
+        }
+        rank
+    }
+
+    /// Rank of `member` starting at 0, its score must be `score`
+    pub fn rank(&self, score: f64, member: &[u8]) -> usize {
+        self.count_while(|other, other_score| precedes(other_score, other, score, member))
+    }
+
+    /// Node at the rank starting at 0, `NIL` when it's out of range
+    fn node_at(&self, rank: usize) -> usize {
+        let target = rank + 1;
+        let mut traversed = 0;
+        let mut x = 0;
+        for i in (0..self.level).rev() {
+            loop {
+                let Level { forward, span } = self.nodes[x].levels[i];
+                if forward == NIL || traversed + span > target {
+                    break;
+                }
+                traversed += span;
+                x = forward;
+            }
+            if traversed == target {
+                return x;
+            }
+        }
+        NIL
+    }
+
+    /// Members and scores of the ranks `start..end`
+    pub fn range(&self, start: usize, end: usize) -> Iter<'_> {
+        let end = end.min(self.len);
+        if start >= end {
+            return Iter {
+                list: self,
+                front: NIL,
+                back: NIL,
+                remaining: 0,
+            };
+        }
+        Iter {
+            list: self,
+            front: self.node_at(start),
+            back: self.node_at(end - 1),
+            remaining: end - start,
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            list: self,
+            front: self.nodes[0].levels[0].forward,
+            back: self.tail,
+            remaining: self.len,
+        }
+    }
+
+    /// Bytes allocated by the nodes
+    pub fn mem_usage(&self) -> usize {
+        self.nodes.capacity() * size_of::<Node>()
+            + self.free.capacity() * size_of::<usize>()
+            + self.allocated
+    }
+}
+
+/// Members and scores of a skiplist from either end
+pub struct Iter<'a> {
+    list: &'a SkipList,
+    front: usize,
+    back: usize,
+    remaining: usize,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a [u8], f64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = &self.list.nodes[self.front];
+        self.front = node.levels[0].forward;
+        self.remaining -= 1;
+        Some((&node.member, node.score))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = &self.list.nodes[self.back];
+        self.back = node.backward;
+        self.remaining -= 1;
+        Some((&node.member, node.score))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+#[cfg(test)]
+mod test {
+    use crate::object::encoding::skiplist::SkipList;
+
+    #[test]
+    fn test_skiplist() {
+        let mut list = SkipList::new();
+        for i in 0..1000 {
+            // inserted out of order, with ties broken by the member
+            let score = ((i * 7) % 1000 / 2) as f64;
+            list.insert(score, format!("m{:04}", (i * 7) % 1000).into_bytes());
+        }
+        assert_eq!(list.len(), 1000);
+        let members: Vec<String> = list
+            .iter()
+            .map(|(member, _)| String::from_utf8(member.to_vec()).unwrap())
+            .collect();
+        let expected: Vec<String> = (0..1000).map(|i| format!("m{:04}", i)).collect();
+        assert_eq!(members, expected);
+
+        assert_eq!(list.rank(250.0, b"m0500"), 500);
+        assert_eq!(list.rank(250.0, b"m0501"), 501);
+        assert_eq!(list.count_while(|_, score| score < 100.0), 200);
+        let range: Vec<_> = list.range(998, 2000).collect();
+        assert_eq!(range, [(&b"m0998"[..], 499.0), (&b"m0999"[..], 499.0)]);
+        assert_eq!(list.range(10, 13).next_back(), Some((&b"m0012"[..], 6.0)));
+        assert_eq!(list.range(5, 5).count(), 0);
+
+        for i in (0..1000).step_by(2) {
+            assert!(list.remove((i / 2) as f64, format!("m{:04}", i).as_bytes()));
+        }
+        assert!(!list.remove(0.0, b"m0000"));
+        // the score must match as well
+        assert!(!list.remove(1.0, b"m0001"));
+        assert_eq!(list.len(), 500);
+        assert_eq!(list.rank(250.0, b"m0501"), 250);
+        assert_eq!(list.range(250, 251).next(), Some((&b"m0501"[..], 250.0)));
+        assert_eq!(list.iter().next_back(), Some((&b"m0999"[..], 499.0)));
+
+        // the slots of removed nodes are reused
+        list.insert(-1.0, b"first".to_vec());
+        assert_eq!(list.iter().next(), Some((&b"first"[..], -1.0)));
+        assert_eq!(list.rank(-1.0, b"first"), 0);
+        assert_eq!(list.len(), 501);
+
+        // removed nodes give back their bytes
+        let left: Vec<_> = list
+            .iter()
+            .map(|(member, score)| (member.to_vec(), score))
+            .collect();
+        for (member, score) in left {
+            assert!(list.remove(score, &member));
+        }
+        assert_eq!(list.allocated, SkipList::new().allocated);
+    }
+}
@@ -0,0 +1,362 @@
+//! The hash type on either of its encodings.
+//!
+//! A hash starts as a listpack of alternating fields and values, and is converted to a hash
+//! table once it holds more than `hash-max-listpack-entries` fields or a field or value
+//! longer than `hash-max-listpack-value`. It is never converted back.
+//!
+//! Once a field gets an expire time the hash is wrapped in a `HashEx`, which keeps the expire
+//! times aside of the fields. It's unwrapped again when no field has one left.
+
+use std::{
+    collections::{BTreeSet, HashMap, hash_map},
+    mem,
+};
+
+use crate::{
+    config::get_server_config,
+    object::{
+        encoding::{dict::Dict, listpack},
+        redis_object::{RedisValue, table_size},
+    },
+};
+
+/// A hash with an expire time on some of its fields
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct HashEx {
+    /// A `ListPack` or `HashTable` hash
+    fields: RedisValue,
+    /// Expire time of the fields which have one, unix milliseconds
+    expires: HashMap<Vec<u8>, i64>,
+    /// The same expire times ordered, the earliest comes first
+    by_time: BTreeSet<(i64, Vec<u8>)>,
+    /// Bytes allocated by the expire times, kept up to date as they change
+    allocated: usize,
+}
+
+impl HashEx {
+    /// Name of the encoding, as reported by `OBJECT ENCODING`
+    pub fn encoding(&self) -> &'static str {
+        match self.fields {
+            RedisValue::ListPack(_) => "listpackex",
+            _ => "hashtable",
+        }
+    }
+
+    /// Bytes allocated by the fields and their expire times, see `RedisValue::mem_usage`
+    pub fn mem_usage(&self) -> usize {
+        size_of::<Self>()
+            + self.fields.mem_usage()
+            + table_size::<(Vec<u8>, i64)>(self.expires.capacity())
+            + self.allocated
+    }
+
+    pub fn free_effort(&self) -> usize {
+        self.fields.free_effort() + self.expires.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty_collection()
+    }
+
+    fn set_expire(&mut self, field: &[u8], at: i64) {
+        self.remove_expire(field);
+        self.expires.insert(field.to_vec(), at);
+        self.by_time.insert((at, field.to_vec()));
+        self.allocated += expire_size(field);
+    }
+
+    fn remove_expire(&mut self, field: &[u8]) -> Option<i64> {
+        let at = self.expires.remove(field)?;
+        self.by_time.remove(&(at, field.to_vec()));
+        self.allocated -= expire_size(field);
+        Some(at)
+    }
+}
+
+/// Bytes allocated for the expire time of `field`, it's stored in both indexes with a copy
+/// of the field each
+fn expire_size(field: &[u8]) -> usize {
+    2 * field.len() + size_of::<(i64, Vec<u8>)>()
+}
+
+/// A field of a hash and its value
+pub type Field<'a> = (&'a [u8], &'a [u8]);
+
+/// Fields and values of a hash
+pub enum HashIter<'a> {
+    ListPack(listpack::Iter<'a>),
+    HashTable(hash_map::Iter<'a, Vec<u8>, Vec<u8>>),
+}
+
+impl<'a> Iterator for HashIter<'a> {
+    type Item = Field<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            HashIter::ListPack(entries) => Some((entries.next()?, entries.next()?)),
+            HashIter::HashTable(entries) => entries
+                .next()
+                .map(|(field, value)| (field.as_slice(), value.as_slice())),
+        }
+    }
+}
+
+impl RedisValue {
+    /// Count of fields, 0 for a value which isn't a hash
+    pub fn hash_len(&self) -> usize {
+        match self {
+            RedisValue::ListPack(entries) => entries.len() / 2,
+            RedisValue::HashTable(map) => map.len(),
+            RedisValue::HashEx(hash) => hash.fields.hash_len(),
+            _ => 0,
+        }
+    }
+
+    pub fn hash_get(&self, field: &[u8]) -> Option<&[u8]> {
+        match self {
+            RedisValue::HashEx(hash) => hash.fields.hash_get(field),
+            RedisValue::ListPack(_) => self
+                .hash_iter()
+                .find(|(name, _)| *name == field)
+                .map(|(_, value)| value),
+            RedisValue::HashTable(map) => map.get(field).map(Vec::as_slice),
+            _ => None,
+        }
+    }
+
+    /// Set `field` to `value`, returns true when the field is new. The field loses its expire
+    /// time.
+    pub fn hash_set(&mut self, field: &[u8], value: &[u8]) -> bool {
+        self.hash_persist(field);
+        if let RedisValue::HashEx(hash) = self {
+            return hash.fields.hash_set(field, value);
+        }
+        if let RedisValue::ListPack(entries) = self {
+            let position = entries.iter().step_by(2).position(|name| name == field);
+            let config = get_server_config();
+            let fits = field.len() <= config.hash_max_listpack_value
+                && value.len() <= config.hash_max_listpack_value
+                && (position.is_some() || entries.len() / 2 < config.hash_max_listpack_entries);
+            if fits {
+                match position {
+                    Some(index) => {
+                        entries.replace(index * 2 + 1, value);
+                    }
+                    None => {
+                        entries.push_back(field);
+                        entries.push_back(value);
+                    }
+                }
+                return position.is_none();
+            }
+            self.hash_convert();
+        }
+        match self {
+            RedisValue::HashTable(map) => map.insert(field.to_vec(), value.to_vec()).is_none(),
+            _ => panic!("hash_set on a value which isn't a hash"),
+        }
+    }
+
+    /// Remove `field`, returns false when it doesn't exist
+    pub fn hash_remove(&mut self, field: &[u8]) -> bool {
+        match self {
+            RedisValue::ListPack(entries) => {
+                let Some(index) = entries.iter().step_by(2).position(|name| name == field) else {
+                    return false;
+                };
+                entries.remove(index * 2);
+                entries.remove(index * 2);
+                true
+            }
+            RedisValue::HashTable(map) => map.remove(field).is_some(),
+            RedisValue::HashEx(_) => {
+                self.hash_persist(field);
+                match self {
+                    RedisValue::HashEx(hash) => hash.fields.hash_remove(field),
+                    _ => self.hash_remove(field),
+                }
+            }
+            _ => false,
+        }
+    }
+
+    pub fn hash_iter(&self) -> HashIter<'_> {
+        match self {
+            RedisValue::ListPack(entries) => HashIter::ListPack(entries.iter()),
+            RedisValue::HashTable(map) => HashIter::HashTable(map.iter()),
+            RedisValue::HashEx(hash) => hash.fields.hash_iter(),
+            _ => panic!("hash_iter on a value which isn't a hash"),
+        }
+    }
+
+    /// Expire time of `field` in unix milliseconds, `None` when it has none
+    pub fn hash_expire_time(&self, field: &[u8]) -> Option<i64> {
+        match self {
+            RedisValue::HashEx(hash) => hash.expires.get(field).copied(),
+            _ => None,
+        }
+    }
+
+    /// Set the expire time of `field`, which must exist
+    pub fn hash_set_expire(&mut self, field: &[u8], at: i64) {
+        if !matches!(self, RedisValue::HashEx(_)) {
+            let fields = mem::replace(self, RedisValue::Int(0));
+            *self = RedisValue::HashEx(Box::new(HashEx {
+                fields,
+                expires: HashMap::new(),
+                by_time: BTreeSet::new(),
+                allocated: 0,
+            }));
+        }
+        let RedisValue::HashEx(hash) = self else {
+            unreachable!()
+        };
+        hash.set_expire(field, at);
+    }
+
+    /// Remove the expire time of `field`, returns false when it has none
+    pub fn hash_persist(&mut self, field: &[u8]) -> bool {
+        let RedisValue::HashEx(hash) = self else {
+            return false;
+        };
+        if hash.remove_expire(field).is_none() {
+            return false;
+        }
+        if hash.expires.is_empty() {
+            let RedisValue::HashEx(hash) = mem::replace(self, RedisValue::Int(0)) else {
+                unreachable!()
+            };
+            *self = hash.fields;
+        }
+        true
+    }
+
+    /// The earliest expire time of the fields, `None` when no field has one
+    pub fn hash_min_expire(&self) -> Option<i64> {
+        match self {
+            RedisValue::HashEx(hash) => hash.by_time.first().map(|(at, _)| *at),
+            _ => None,
+        }
+    }
+
+    /// Remove the fields which expired at `now`, unix milliseconds. Returns their count.
+    pub fn hash_remove_expired(&mut self, now: i64) -> usize {
+        let mut expired = Vec::new();
+        if let RedisValue::HashEx(hash) = self {
+            while let Some((at, _)) = hash.by_time.first()
+                && *at <= now
+            {
+                expired.push(hash.by_time.pop_first().unwrap().1);
+            }
+        }
+        for field in expired.iter() {
+            self.hash_remove(field);
+        }
+        expired.len()
+    }
+
+    /// One page of `HSCAN` from `cursor`, returns the next cursor. A listpack isn't indexed
+    /// by hash, it's returned whole.
+    pub fn hash_scan(&self, cursor: u64, count: usize) -> (u64, Vec<Field<'_>>) {
+        match self {
+            RedisValue::HashTable(map) => {
+                let (cursor, page) = map.scan(cursor, count);
+                let page = page
+                    .into_iter()
+                    .map(|(field, value)| (field, value.as_slice()))
+                    .collect();
+                (cursor, page)
+            }
+            RedisValue::HashEx(hash) => hash.fields.hash_scan(cursor, count),
+            _ => (0, self.hash_iter().collect()),
+        }
+    }
+
+    /// Convert a listpack hash to a hash table
+    fn hash_convert(&mut self) {
+        let map: Dict<Vec<u8>> = self
+            .hash_iter()
+            .map(|(field, value)| (field.to_vec(), value.to_vec()))
+            .collect();
+        *self = RedisValue::HashTable(Box::new(map));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::object::{
+        hash::expire_size,
+        redis_object::{RedisObject, RedisValue},
+    };
+
+    #[test]
+    fn test_hash_encoding() {
+        let mut hash = RedisObject::new_hash();
+        assert_eq!(hash.ptr.encoding(), "listpack");
+        assert!(hash.ptr.hash_set(b"name", b"ada"));
+        assert!(hash.ptr.hash_set(b"\x00bin", b"\xff"));
+        assert!(!hash.ptr.hash_set(b"name", b"grace"));
+        assert_eq!(hash.ptr.hash_get(b"name"), Some(&b"grace"[..]));
+        assert_eq!(hash.ptr.hash_get(b"\x00bin"), Some(&b"\xff"[..]));
+        assert_eq!(hash.ptr.hash_len(), 2);
+        assert!(hash.ptr.hash_remove(b"\x00bin"));
+        assert!(!hash.ptr.hash_remove(b"\x00bin"));
+        assert_eq!(hash.ptr.hash_len(), 1);
+
+        // a long value converts it, the fields are kept
+        assert!(hash.ptr.hash_set(b"bio", &[b'x'; 100]));
+        assert_eq!(hash.ptr.encoding(), "hashtable");
+        assert_eq!(hash.ptr.hash_get(b"name"), Some(&b"grace"[..]));
+        assert_eq!(hash.ptr.hash_iter().count(), 2);
+
+        let mut many = RedisObject::new_hash();
+        for i in 0..128 {
+            many.ptr.hash_set(format!("f{}", i).as_bytes(), b"v");
+        }
+        assert_eq!(many.ptr.encoding(), "listpack");
+        many.ptr.hash_set(b"f128", b"v");
+        assert_eq!(many.ptr.encoding(), "hashtable");
+        assert_eq!(many.ptr.hash_len(), 129);
+    }
+
+    #[test]
+    fn test_field_expires() {
+        let mut hash = RedisObject::new_hash();
+        for field in [b"a", b"b", b"c"] {
+            hash.ptr.hash_set(field, b"v");
+        }
+        hash.ptr.hash_set_expire(b"a", 300);
+        hash.ptr.hash_set_expire(b"b", 100);
+        hash.ptr.hash_set_expire(b"b", 200);
+        assert_eq!(hash.ptr.encoding(), "listpackex");
+        assert_eq!(hash.ptr.hash_expire_time(b"b"), Some(200));
+        assert_eq!(hash.ptr.hash_expire_time(b"c"), None);
+        assert_eq!(hash.ptr.hash_min_expire(), Some(200));
+        let RedisValue::HashEx(ex) = &hash.ptr else {
+            unreachable!()
+        };
+        assert_eq!(ex.allocated, 2 * expire_size(b"a"));
+
+        // a new value drops the expire time
+        hash.ptr.hash_set(b"b", b"w");
+        assert_eq!(hash.ptr.hash_min_expire(), Some(300));
+        hash.ptr.hash_set_expire(b"b", 200);
+        assert_eq!(hash.ptr.hash_remove_expired(250), 1);
+        assert_eq!(hash.ptr.hash_get(b"b"), None);
+        assert_eq!(hash.ptr.hash_len(), 2);
+
+        // without expire times left it's a plain hash again
+        assert!(hash.ptr.hash_persist(b"a"));
+        assert!(!hash.ptr.hash_persist(b"a"));
+        assert_eq!(hash.ptr.encoding(), "listpack");
+        assert_eq!(hash.ptr.hash_min_expire(), None);
+
+        hash.ptr.hash_set_expire(b"c", 100);
+        assert!(hash.ptr.hash_set(b"big", &[b'x'; 100]));
+        assert_eq!(hash.ptr.encoding(), "hashtable");
+        assert_eq!(hash.ptr.hash_remove_expired(100), 1);
+        assert_eq!(hash.ptr.hash_iter().count(), 2);
+        assert!(hash.ptr.hash_remove(b"a"));
+        assert!(!hash.ptr.is_empty_collection());
+    }
+}
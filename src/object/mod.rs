@@ -1,2 +1,5 @@
 pub mod redis_object;
 pub mod encoding;
+pub mod hash;
+pub mod set;
+pub mod zset;
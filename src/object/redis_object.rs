@@ -1,12 +1,20 @@
-use std::{
-    collections::{HashMap, HashSet},
-    fmt::{Display, Formatter},
-};
+// the expansion of `#[bitfield]` wraps the field types in parentheses
+#![allow(unused_parens)]
+
+use std::fmt::{Display, Formatter};
 
 use modular_bitfield::{bitfield, prelude::B24, Specifier};
 
-use crate::protocol::Frame;
+use crate::config::get_server_config;
+use crate::object::encoding::dict::Dict;
+use crate::object::encoding::intset::Intset;
+use crate::object::encoding::listpack::Listpack;
+use crate::object::encoding::quicklist::Quicklist;
 use crate::object::encoding::sds::{self, EmbStr, Raw};
+use crate::object::hash::HashEx;
+use crate::object::zset::Zset;
+use crate::protocol::Frame;
+use crate::storage::evict::initial_lru;
 
 #[derive(Specifier, Debug, PartialEq, Eq, Clone)]
 #[bits = 8]
@@ -30,6 +38,20 @@ impl Display for ObjectType {
     }
 }
 
+impl ObjectType {
+    /// The type named as by `TYPE`, e.g. for `SCAN ... TYPE name`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "string" => Some(ObjectType::String),
+            "list" => Some(ObjectType::List),
+            "hash" => Some(ObjectType::Hash),
+            "set" => Some(ObjectType::Set),
+            "zset" => Some(ObjectType::Zset),
+            _ => None,
+        }
+    }
+}
+
 #[bitfield(bytes = 8)]
 #[derive(Specifier, Debug, PartialEq, Eq, Clone)]
 pub struct ObjectHeader {
@@ -48,11 +70,13 @@ pub enum RedisValue {
     Int(i64),
     EmbStr(EmbStr),
     Raw(Raw),
-    HashTable(HashMap<String, String>),
-    LinkedList(Vec<Box<RedisObject>>),
-    ZipList,
-    IntSet(HashSet<i64>),
-    SkipList,
+    HashTable(Box<Dict<Vec<u8>>>),
+    HashEx(Box<HashEx>),
+    QuickList(Box<Quicklist>),
+    ListPack(Listpack),
+    IntSet(Intset),
+    HashSet(Box<Dict<()>>),
+    SkipList(Box<Zset>),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -61,26 +85,135 @@ pub struct RedisObject {
     pub ptr: RedisValue,
 }
 
+impl RedisValue {
+    /// Name of the encoding, as reported by `OBJECT ENCODING`
+    pub fn encoding(&self) -> &'static str {
+        match self {
+            RedisValue::Int(_) => "int",
+            RedisValue::EmbStr(_) => "embstr",
+            RedisValue::Raw(_) => "raw",
+            RedisValue::HashTable(_) => "hashtable",
+            RedisValue::HashEx(hash) => hash.encoding(),
+            // a list of a single node is as compact as a plain listpack
+            RedisValue::QuickList(list) if list.node_count() <= 1 => "listpack",
+            RedisValue::QuickList(_) => "quicklist",
+            RedisValue::ListPack(_) => "listpack",
+            RedisValue::IntSet(_) => "intset",
+            RedisValue::HashSet(_) => "hashtable",
+            RedisValue::SkipList(_) => "skiplist",
+        }
+    }
+
+    /// Bytes allocated by the value besides the object holding it. Collections keep their
+    /// size up to date as they change, so it takes O(1).
+    pub fn mem_usage(&self) -> usize {
+        match self {
+            RedisValue::Int(_) | RedisValue::EmbStr(_) => 0,
+            RedisValue::Raw(raw) => raw.capacity(),
+            RedisValue::HashTable(map) => size_of::<Dict<Vec<u8>>>() + map.mem_usage(),
+            RedisValue::HashEx(hash) => hash.mem_usage(),
+            RedisValue::QuickList(list) => size_of::<Quicklist>() + list.mem_usage(),
+            RedisValue::ListPack(entries) => entries.capacity(),
+            RedisValue::IntSet(set) => set.capacity(),
+            RedisValue::HashSet(set) => size_of::<Dict<()>>() + set.mem_usage(),
+            RedisValue::SkipList(zset) => zset.mem_usage(),
+        }
+    }
+
+    /// Allocations released by dropping the value, large values are dropped by the lazy
+    /// free thread
+    pub fn free_effort(&self) -> usize {
+        match self {
+            RedisValue::HashTable(map) => map.len(),
+            RedisValue::HashEx(hash) => hash.free_effort(),
+            RedisValue::HashSet(set) => set.len(),
+            RedisValue::SkipList(zset) => zset.len(),
+            RedisValue::QuickList(list) => list.node_count(),
+            _ => 1,
+        }
+    }
+
+    /// Whether the value is a collection without elements, such a key is deleted
+    pub fn is_empty_collection(&self) -> bool {
+        match self {
+            RedisValue::HashTable(map) => map.is_empty(),
+            RedisValue::HashEx(hash) => hash.is_empty(),
+            RedisValue::QuickList(list) => list.is_empty(),
+            RedisValue::ListPack(entries) => entries.is_empty(),
+            RedisValue::IntSet(set) => set.is_empty(),
+            RedisValue::HashSet(set) => set.is_empty(),
+            RedisValue::SkipList(zset) => zset.is_empty(),
+            _ => false,
+        }
+    }
+}
+
+/// Bytes of a hash table able to hold `capacity` entries of `T`, with a control byte per
+/// bucket and buckets kept at most 7/8 full
+pub fn table_size<T>(capacity: usize) -> usize {
+    if capacity == 0 {
+        return 0;
+    }
+    (capacity * 8 / 7).next_power_of_two() * (size_of::<T>() + 1)
+}
+
 impl RedisObject {
+    /// A new object referenced once, its access is stamped as of now
+    pub fn new(obj_type: ObjectType, ptr: RedisValue) -> Self {
+        Self {
+            header: ObjectHeader::new()
+                .with_obj_type(obj_type)
+                .with_lru(initial_lru())
+                .with_ref_count(1),
+            ptr,
+        }
+    }
+
+    /// Bytes of the object and its value, see `RedisValue::mem_usage`
+    pub fn mem_usage(&self) -> usize {
+        size_of::<Self>() + self.ptr.mem_usage()
+    }
+
+    /// An empty list, sized by `list-max-listpack-size` and `list-compress-depth`
+    pub fn new_list() -> Self {
+        let config = get_server_config();
+        Self::new(
+            ObjectType::List,
+            RedisValue::QuickList(Box::new(Quicklist::new(
+                config.list_max_listpack_size,
+                config.list_compress_depth,
+            ))),
+        )
+    }
+
+    /// An empty hash, it starts as a listpack
+    pub fn new_hash() -> Self {
+        Self::new(ObjectType::Hash, RedisValue::ListPack(Listpack::new()))
+    }
+
+    /// An empty set, it starts as an intset
+    pub fn new_set() -> Self {
+        Self::new(ObjectType::Set, RedisValue::IntSet(Intset::new()))
+    }
+
+    /// An empty sorted set, it starts as a listpack
+    pub fn new_zset() -> Self {
+        Self::new(ObjectType::Zset, RedisValue::ListPack(Listpack::new()))
+    }
+
     pub fn new_string(buf: Vec<u8>) -> Self {
         if buf.len() <= sds::EMB_LEN {
-            Self {
-                header: ObjectHeader::new().with_obj_type(ObjectType::String),
-                ptr: RedisValue::EmbStr(buf.into()),
-            }
+            Self::new(ObjectType::String, RedisValue::EmbStr(buf.into()))
         } else {
-            Self {
-                header: ObjectHeader::new().with_obj_type(ObjectType::String),
-                ptr: RedisValue::Raw(buf.into()),
-            }
+            Self::new(ObjectType::String, RedisValue::Raw(buf.into()))
         }
     }
 }
 
-impl Into<Frame> for RedisObject {
-    fn into(self) -> Frame {
-        match self.header.obj_type() {
-            ObjectType::String => match self.ptr {
+impl From<RedisObject> for Frame {
+    fn from(value: RedisObject) -> Self {
+        match value.header.obj_type() {
+            ObjectType::String => match value.ptr {
                 RedisValue::Int(i) => {
                     Frame::BulkString(Some(i.to_string().as_bytes().to_vec()))
                 }
@@ -88,10 +221,42 @@ impl Into<Frame> for RedisObject {
                 RedisValue::Raw(raw) => Frame::BulkString(Some(raw.into())),
                 _ => Frame::Null,
             },
-            ObjectType::List => Frame::Error("Not Implemented".to_string()),
-            ObjectType::Hash => Frame::Error("Not Implemented".to_string()),
-            ObjectType::Set => Frame::Error("Not Implemented".to_string()),
-            ObjectType::Zset => Frame::Error("Not Implemented".to_string()),
+            ObjectType::List => match value.ptr {
+                RedisValue::QuickList(list) => Frame::Array(Some(
+                    list.iter()
+                        .map(|entry| Frame::BulkString(Some(entry)))
+                        .collect(),
+                )),
+                _ => Frame::Null,
+            },
+            ObjectType::Hash => Frame::Map(
+                value
+                    .ptr
+                    .hash_iter()
+                    .map(|(field, value)| {
+                        (
+                            Frame::BulkString(Some(field.to_vec())),
+                            Frame::BulkString(Some(value.to_vec())),
+                        )
+                    })
+                    .collect(),
+            ),
+            ObjectType::Set => Frame::Set(
+                value
+                    .ptr
+                    .set_iter()
+                    .map(|member| Frame::BulkString(Some(member.into_owned())))
+                    .collect(),
+            ),
+            ObjectType::Zset => Frame::Map(
+                value
+                    .ptr
+                    .zset_iter()
+                    .map(|(member, score)| {
+                        (Frame::BulkString(Some(member.to_vec())), Frame::Double(score))
+                    })
+                    .collect(),
+            ),
         }
     }
 }
@@ -100,7 +265,10 @@ impl Into<Frame> for RedisObject {
 mod test {
     #[cfg(test)]
     use crate::object::{
-        encoding::sds::{EmbStr, Raw},
+        encoding::{
+            dict::Dict,
+            sds::{EmbStr, Raw},
+        },
         redis_object::{ObjectHeader, ObjectType, RedisObject, RedisValue},
     };
     #[cfg(test)]
@@ -120,12 +288,28 @@ mod test {
         assert_eq!(mem::size_of::<RedisObject>(), 64);
     }
 
+    #[test]
+    fn test_mem_usage() {
+        let small = RedisObject::new_string(b"v".to_vec());
+        assert_eq!(small.mem_usage(), mem::size_of::<RedisObject>());
+        let raw = RedisObject::new_string(vec![b'x'; 1000]);
+        assert_eq!(raw.mem_usage(), mem::size_of::<RedisObject>() + 1000);
+
+        let map: Dict<_> = (0..100)
+            .map(|i| (format!("field{:03}", i).into_bytes(), b"value".to_vec()))
+            .collect();
+        let hash = RedisValue::HashTable(Box::new(map));
+        assert!(hash.mem_usage() > 100 * (8 + 5));
+    }
+
     #[test]
     fn test_new_string_object_on_buf_len_less_than_emblen() {
         let obj = RedisObject::new_string("string".as_bytes().to_vec());
 
         assert_eq!(obj.header.obj_type(), ObjectType::String);
+        assert_eq!(obj.header.ref_count(), 1);
         assert!(matches!(obj.ptr, RedisValue::EmbStr(_)));
+        assert_eq!(obj.ptr.encoding(), "embstr");
     }
 
     #[test]
@@ -134,5 +318,6 @@ mod test {
 
         assert_eq!(obj.header.obj_type(), ObjectType::String);
         assert!(matches!(obj.ptr, RedisValue::Raw(_)));
+        assert_eq!(obj.ptr.encoding(), "raw");
     }
 }
@@ -0,0 +1,221 @@
+//! The set type on any of its encodings.
+//!
+//! A set of integers starts as an intset, a set with another member as a listpack. Either is
+//! converted once it grows past `set-max-intset-entries`, or `set-max-listpack-entries` and
+//! `set-max-listpack-value`: an intset to a listpack when the members fit, anything else to a
+//! hash set. It is never converted back.
+
+use std::{borrow::Cow, collections::hash_map, ops::Range};
+
+use crate::{
+    config::get_server_config,
+    object::{
+        encoding::{dict::Dict, intset::Intset, listpack},
+        redis_object::RedisValue,
+    },
+};
+
+/// The integer `member` is the text of, `None` unless it's written as `i64::to_string` would
+fn as_integer(member: &[u8]) -> Option<i64> {
+    let value: i64 = std::str::from_utf8(member).ok()?.parse().ok()?;
+    (value.to_string().as_bytes() == member).then_some(value)
+}
+
+/// Members of a set
+pub enum SetIter<'a> {
+    IntSet(&'a Intset, Range<usize>),
+    ListPack(listpack::Iter<'a>),
+    HashSet(hash_map::Keys<'a, Vec<u8>, ()>),
+}
+
+impl<'a> Iterator for SetIter<'a> {
+    type Item = Cow<'a, [u8]>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            SetIter::IntSet(set, indexes) => {
+                let value = set.get(indexes.next()?)?;
+                Some(Cow::Owned(value.to_string().into_bytes()))
+            }
+            SetIter::ListPack(members) => members.next().map(Cow::Borrowed),
+            SetIter::HashSet(members) => members.next().map(|member| Cow::Borrowed(&member[..])),
+        }
+    }
+}
+
+impl RedisValue {
+    /// Count of members, 0 for a value which isn't a set
+    pub fn set_len(&self) -> usize {
+        match self {
+            RedisValue::IntSet(set) => set.len(),
+            RedisValue::ListPack(members) => members.len(),
+            RedisValue::HashSet(set) => set.len(),
+            _ => 0,
+        }
+    }
+
+    pub fn set_contains(&self, member: &[u8]) -> bool {
+        match self {
+            RedisValue::IntSet(set) => as_integer(member).is_some_and(|value| set.contains(value)),
+            RedisValue::ListPack(members) => members.iter().any(|entry| entry == member),
+            RedisValue::HashSet(set) => set.contains_key(member),
+            _ => false,
+        }
+    }
+
+    /// Add `member`, returns false when it's already there
+    pub fn set_add(&mut self, member: &[u8]) -> bool {
+        let config = get_server_config();
+        if let RedisValue::IntSet(set) = self {
+            match as_integer(member) {
+                Some(value) if set.contains(value) => return false,
+                Some(value) if set.len() < config.set_max_intset_entries => {
+                    return set.insert(value);
+                }
+                _ => self.set_convert(member.len()),
+            }
+        }
+        if let RedisValue::ListPack(members) = self {
+            if members.iter().any(|entry| entry == member) {
+                return false;
+            }
+            if members.len() < config.set_max_listpack_entries
+                && member.len() <= config.set_max_listpack_value
+            {
+                members.push_back(member);
+                return true;
+            }
+            self.set_convert(usize::MAX);
+        }
+        match self {
+            RedisValue::HashSet(set) => set.insert(member.to_vec(), ()).is_none(),
+            _ => panic!("set_add on a value which isn't a set"),
+        }
+    }
+
+    /// Remove `member`, returns false when it isn't there
+    pub fn set_remove(&mut self, member: &[u8]) -> bool {
+        match self {
+            RedisValue::IntSet(set) => as_integer(member).is_some_and(|value| set.remove(value)),
+            RedisValue::ListPack(members) => {
+                match members.iter().position(|entry| entry == member) {
+                    Some(index) => members.remove(index).is_some(),
+                    None => false,
+                }
+            }
+            RedisValue::HashSet(set) => set.remove(member).is_some(),
+            _ => false,
+        }
+    }
+
+    pub fn set_iter(&self) -> SetIter<'_> {
+        match self {
+            RedisValue::IntSet(set) => SetIter::IntSet(set, 0..set.len()),
+            RedisValue::ListPack(members) => SetIter::ListPack(members.iter()),
+            RedisValue::HashSet(set) => SetIter::HashSet(set.keys()),
+            _ => panic!("set_iter on a value which isn't a set"),
+        }
+    }
+
+    /// One page of `SSCAN` from `cursor`, returns the next cursor. An intset or a listpack
+    /// isn't indexed by hash, it's returned whole.
+    pub fn set_scan(&self, cursor: u64, count: usize) -> (u64, Vec<Cow<'_, [u8]>>) {
+        match self {
+            RedisValue::HashSet(set) => {
+                let (cursor, page) = set.scan(cursor, count);
+                let page = page
+                    .into_iter()
+                    .map(|(member, _)| Cow::Borrowed(member))
+                    .collect();
+                (cursor, page)
+            }
+            _ => (0, self.set_iter().collect()),
+        }
+    }
+
+    /// Convert a small set before a member of `len` bytes is added, to a listpack when the
+    /// members of an intset fit in one, to a hash set otherwise
+    fn set_convert(&mut self, len: usize) {
+        let config = get_server_config();
+        let members: Vec<Vec<u8>> = self.set_iter().map(Cow::into_owned).collect();
+        // the longest integer is 20 bytes, shorter than any sensible limit
+        let fits = matches!(self, RedisValue::IntSet(_))
+            && members.len() < config.set_max_listpack_entries
+            && len <= config.set_max_listpack_value
+            && members
+                .iter()
+                .all(|member| member.len() <= config.set_max_listpack_value);
+        *self = if fits {
+            let mut listpack = listpack::Listpack::new();
+            for member in members.iter() {
+                listpack.push_back(member);
+            }
+            RedisValue::ListPack(listpack)
+        } else {
+            let set: Dict<()> = members.into_iter().map(|member| (member, ())).collect();
+            RedisValue::HashSet(Box::new(set))
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::object::redis_object::RedisObject;
+
+    #[test]
+    fn test_set_encoding() {
+        let mut set = RedisObject::new_set();
+        assert_eq!(set.ptr.encoding(), "intset");
+        assert!(set.ptr.set_add(b"3"));
+        assert!(set.ptr.set_add(b"-1"));
+        assert!(!set.ptr.set_add(b"3"));
+        assert!(set.ptr.set_contains(b"-1"));
+        // not the canonical text of an integer
+        assert!(!set.ptr.set_contains(b"03"));
+        let members: Vec<_> = set.ptr.set_iter().collect();
+        assert_eq!(members, [&b"-1"[..], b"3"]);
+
+        // a string member converts it, the integers are kept
+        assert!(set.ptr.set_add(b"03"));
+        assert_eq!(set.ptr.encoding(), "listpack");
+        assert!(set.ptr.set_contains(b"3") && set.ptr.set_contains(b"03"));
+        assert!(set.ptr.set_remove(b"-1"));
+        assert!(!set.ptr.set_remove(b"-1"));
+        assert_eq!(set.ptr.set_len(), 2);
+
+        assert!(set.ptr.set_add(&[b'x'; 100]));
+        assert_eq!(set.ptr.encoding(), "hashtable");
+        assert_eq!(set.ptr.set_iter().count(), 3);
+        assert!(set.ptr.set_remove(b"3"));
+
+        let mut integers = RedisObject::new_set();
+        for i in 0..512 {
+            integers.ptr.set_add(i.to_string().as_bytes());
+        }
+        assert_eq!(integers.ptr.encoding(), "intset");
+        // too many members for a listpack
+        integers.ptr.set_add(b"512");
+        assert_eq!(integers.ptr.encoding(), "hashtable");
+        assert_eq!(integers.ptr.set_len(), 513);
+
+        let mut small = RedisObject::new_set();
+        small.ptr.set_add(b"1");
+        small.ptr.set_add(&[b'x'; 100]);
+        assert_eq!(small.ptr.encoding(), "hashtable");
+    }
+
+    #[test]
+    fn test_set_mem_usage() {
+        let mut set = RedisObject::new_set();
+        for i in 0..1000 {
+            set.ptr.set_add(format!("m{}", i).as_bytes());
+        }
+        assert_eq!(set.ptr.encoding(), "hashtable");
+        let usage = set.mem_usage();
+        assert!(set.ptr.set_remove(b"m1"));
+        assert!(set.mem_usage() < usage);
+        // the table keeps its capacity, the member is counted again as it was
+        assert!(set.ptr.set_add(b"m1"));
+        assert_eq!(set.mem_usage(), usage);
+    }
+}
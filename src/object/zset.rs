@@ -0,0 +1,292 @@
+//! The sorted set type on either of its encodings.
+//!
+//! A sorted set starts as a listpack of alternating members and scores, ordered by score then
+//! member. It's converted to a skiplist with a dictionary of the scores once it holds more
+//! than `zset-max-listpack-entries` members or a member longer than `zset-max-listpack-value`.
+//! It is never converted back.
+
+use crate::{
+    config::get_server_config,
+    object::{
+        encoding::{dict::Dict, listpack, skiplist, skiplist::SkipList},
+        redis_object::RedisValue,
+    },
+};
+
+/// A sorted set of the skiplist encoding, the dictionary finds the score of a member
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Zset {
+    dict: Dict<ScoreBits>,
+    list: SkipList,
+}
+
+/// A score kept by its bits, so the dictionary can be `Eq`. Scores are never NaN.
+type ScoreBits = u64;
+
+impl Zset {
+    /// Bytes allocated by the members and scores, see `RedisValue::mem_usage`
+    pub fn mem_usage(&self) -> usize {
+        size_of::<Self>() + self.dict.mem_usage() + self.list.mem_usage()
+    }
+
+    pub fn len(&self) -> usize {
+        self.dict.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dict.is_empty()
+    }
+}
+
+/// Scores are kept in a listpack as their 8 bytes
+fn score_of(entry: &[u8]) -> f64 {
+    f64::from_be_bytes(entry.try_into().expect("a listpack score is 8 bytes"))
+}
+
+/// Members and scores of a sorted set in order, from either end
+pub enum ZsetIter<'a> {
+    ListPack(listpack::Iter<'a>),
+    SkipList(skiplist::Iter<'a>),
+}
+
+impl<'a> Iterator for ZsetIter<'a> {
+    type Item = (&'a [u8], f64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            ZsetIter::ListPack(entries) => Some((entries.next()?, score_of(entries.next()?))),
+            ZsetIter::SkipList(members) => members.next(),
+        }
+    }
+}
+
+impl DoubleEndedIterator for ZsetIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self {
+            ZsetIter::ListPack(entries) => {
+                let score = score_of(entries.next_back()?);
+                Some((entries.next_back()?, score))
+            }
+            ZsetIter::SkipList(members) => members.next_back(),
+        }
+    }
+}
+
+impl RedisValue {
+    /// Count of members, 0 for a value which isn't a sorted set
+    pub fn zset_len(&self) -> usize {
+        match self {
+            RedisValue::ListPack(entries) => entries.len() / 2,
+            RedisValue::SkipList(zset) => zset.len(),
+            _ => 0,
+        }
+    }
+
+    pub fn zset_score(&self, member: &[u8]) -> Option<f64> {
+        match self {
+            RedisValue::ListPack(_) => self
+                .zset_iter()
+                .find(|(other, _)| *other == member)
+                .map(|(_, score)| score),
+            RedisValue::SkipList(zset) => zset.dict.get(member).map(|bits| f64::from_bits(*bits)),
+            _ => None,
+        }
+    }
+
+    /// Set the score of `member`, returns true when the member is new
+    pub fn zset_set(&mut self, member: &[u8], score: f64) -> bool {
+        if let RedisValue::ListPack(entries) = self {
+            let position = entries.iter().step_by(2).position(|other| other == member);
+            let config = get_server_config();
+            let fits = member.len() <= config.zset_max_listpack_value
+                && (position.is_some() || entries.len() / 2 < config.zset_max_listpack_entries);
+            if fits {
+                if let Some(index) = position {
+                    entries.remove(index * 2);
+                    entries.remove(index * 2);
+                }
+                let index = self
+                    .zset_iter()
+                    .position(|(other, other_score)| {
+                        other_score > score || (other_score == score && other > member)
+                    })
+                    .unwrap_or(self.zset_len());
+                let RedisValue::ListPack(entries) = self else {
+                    unreachable!()
+                };
+                entries.insert(index * 2, member);
+                entries.insert(index * 2 + 1, &score.to_be_bytes());
+                return position.is_none();
+            }
+            self.zset_convert();
+        }
+        let RedisValue::SkipList(zset) = self else {
+            panic!("zset_set on a value which isn't a sorted set");
+        };
+        match zset.dict.insert(member.to_vec(), score.to_bits()) {
+            Some(old) => {
+                let old = f64::from_bits(old);
+                if old != score {
+                    zset.list.remove(old, member);
+                    zset.list.insert(score, member.to_vec());
+                }
+                false
+            }
+            None => {
+                zset.list.insert(score, member.to_vec());
+                true
+            }
+        }
+    }
+
+    /// Remove `member`, returns false when it isn't there
+    pub fn zset_remove(&mut self, member: &[u8]) -> bool {
+        match self {
+            RedisValue::ListPack(entries) => {
+                let Some(index) = entries.iter().step_by(2).position(|other| other == member)
+                else {
+                    return false;
+                };
+                entries.remove(index * 2);
+                entries.remove(index * 2);
+                true
+            }
+            RedisValue::SkipList(zset) => match zset.dict.remove(member) {
+                Some(score) => zset.list.remove(f64::from_bits(score), member),
+                None => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Rank of `member` by ascending score, starting at 0
+    pub fn zset_rank(&self, member: &[u8]) -> Option<usize> {
+        match self {
+            RedisValue::ListPack(_) => self.zset_iter().position(|(other, _)| other == member),
+            RedisValue::SkipList(zset) => {
+                let score = f64::from_bits(*zset.dict.get(member)?);
+                Some(zset.list.rank(score, member))
+            }
+            _ => None,
+        }
+    }
+
+    /// Count of the first members matched by `f`, which must match a prefix of the set
+    pub fn zset_count_while(&self, f: impl Fn(&[u8], f64) -> bool) -> usize {
+        match self {
+            RedisValue::SkipList(zset) => zset.list.count_while(f),
+            _ => self
+                .zset_iter()
+                .take_while(|(member, score)| f(member, *score))
+                .count(),
+        }
+    }
+
+    /// Members and scores of the ranks `start..end`
+    pub fn zset_range(&self, start: usize, end: usize) -> ZsetIter<'_> {
+        match self {
+            RedisValue::ListPack(entries) => {
+                let end = end.min(entries.len() / 2);
+                let mut iter = entries.iter();
+                if start >= end {
+                    iter.nth(entries.len().saturating_sub(1));
+                } else {
+                    if start > 0 {
+                        iter.nth(start * 2 - 1);
+                    }
+                    if end < entries.len() / 2 {
+                        iter.nth_back(entries.len() - end * 2 - 1);
+                    }
+                }
+                ZsetIter::ListPack(iter)
+            }
+            RedisValue::SkipList(zset) => ZsetIter::SkipList(zset.list.range(start, end)),
+            _ => panic!("zset_range on a value which isn't a sorted set"),
+        }
+    }
+
+    pub fn zset_iter(&self) -> ZsetIter<'_> {
+        match self {
+            RedisValue::ListPack(entries) => ZsetIter::ListPack(entries.iter()),
+            RedisValue::SkipList(zset) => ZsetIter::SkipList(zset.list.iter()),
+            _ => panic!("zset_iter on a value which isn't a sorted set"),
+        }
+    }
+
+    /// One page of `ZSCAN` from `cursor`, returns the next cursor. A listpack isn't indexed by
+    /// hash, it's returned whole.
+    pub fn zset_scan(&self, cursor: u64, count: usize) -> (u64, Vec<(&[u8], f64)>) {
+        match self {
+            RedisValue::SkipList(zset) => {
+                let (cursor, page) = zset.dict.scan(cursor, count);
+                let page = page
+                    .into_iter()
+                    .map(|(member, bits)| (member, f64::from_bits(*bits)))
+                    .collect();
+                (cursor, page)
+            }
+            _ => (0, self.zset_iter().collect()),
+        }
+    }
+
+    /// Convert a listpack sorted set to a skiplist
+    fn zset_convert(&mut self) {
+        let mut zset = Zset::default();
+        for (member, score) in self.zset_iter() {
+            zset.dict.insert(member.to_vec(), score.to_bits());
+            zset.list.insert(score, member.to_vec());
+        }
+        *self = RedisValue::SkipList(Box::new(zset));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::object::redis_object::RedisObject;
+
+    #[test]
+    fn test_zset_encoding() {
+        let mut zset = RedisObject::new_zset();
+        assert_eq!(zset.ptr.encoding(), "listpack");
+        assert!(zset.ptr.zset_set(b"b", 2.0));
+        assert!(zset.ptr.zset_set(b"a", 2.0));
+        assert!(zset.ptr.zset_set(b"c", -1.5));
+        assert!(!zset.ptr.zset_set(b"c", 3.0));
+        let members: Vec<_> = zset.ptr.zset_iter().collect();
+        assert_eq!(members, [(&b"a"[..], 2.0), (b"b", 2.0), (b"c", 3.0)]);
+        assert_eq!(zset.ptr.zset_score(b"c"), Some(3.0));
+        assert_eq!(zset.ptr.zset_rank(b"b"), Some(1));
+        assert_eq!(zset.ptr.zset_count_while(|_, score| score < 3.0), 2);
+        assert_eq!(zset.ptr.zset_range(1, 5).collect::<Vec<_>>(), members[1..]);
+        assert_eq!(
+            zset.ptr.zset_range(0, 2).next_back(),
+            Some((&b"b"[..], 2.0))
+        );
+        assert_eq!(zset.ptr.zset_range(3, 3).count(), 0);
+        assert!(zset.ptr.zset_remove(b"a"));
+        assert!(!zset.ptr.zset_remove(b"a"));
+
+        // a long member converts it, the members are kept
+        assert!(zset.ptr.zset_set(&[b'x'; 100], 0.0));
+        assert_eq!(zset.ptr.encoding(), "skiplist");
+        assert_eq!(zset.ptr.zset_len(), 3);
+        assert_eq!(zset.ptr.zset_rank(b"c"), Some(2));
+        assert!(!zset.ptr.zset_set(b"c", -5.0));
+        assert_eq!(zset.ptr.zset_rank(b"c"), Some(0));
+        assert_eq!(
+            zset.ptr.zset_range(1, 2).next(),
+            Some((&[b'x'; 100][..], 0.0))
+        );
+        assert!(zset.ptr.zset_remove(b"c"));
+        assert_eq!(zset.ptr.zset_score(b"c"), None);
+
+        let mut many = RedisObject::new_zset();
+        for i in 0..128 {
+            many.ptr.zset_set(format!("m{}", i).as_bytes(), i as f64);
+        }
+        assert_eq!(many.ptr.encoding(), "listpack");
+        many.ptr.zset_set(b"m128", 128.0);
+        assert_eq!(many.ptr.encoding(), "skiplist");
+        assert_eq!(many.ptr.zset_rank(b"m100"), Some(100));
+    }
+}
@@ -0,0 +1,621 @@
+use bytes::{Buf, BytesMut};
+
+use crate::{
+    config::{Config, get_server_config},
+    protocol::{Frame, FrameError},
+};
+
+/// Incremental RESP decoder.
+///
+/// Bytes are consumed from the front of the read buffer as soon as an element is decoded, the
+/// decoder remembers the aggregates under construction and the length of a bulk payload which
+/// hasn't fully arrived, so a partial read is resumed where it stopped instead of being parsed
+/// again. Nested aggregates are tracked with an explicit stack rather than recursion.
+///
+/// A top-level line which doesn't start with a RESP type byte is treated as an inline command,
+/// e.g. `SET a b\r\n` typed in `nc` or telnet, and decoded into an array of bulk strings.
+#[derive(Debug)]
+pub struct Decoder {
+    limits: DecoderLimits,
+    stack: Vec<Partial>,
+    /// Type byte and length of a blob whose header has been consumed
+    pending_blob: Option<(u8, usize)>,
+    /// Bytes consumed for the frame being decoded
+    pending_bytes: usize,
+}
+
+/// Bounds applied to untrusted input, so a malformed client can't make the server allocate
+/// arbitrary amounts of memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecoderLimits {
+    pub max_bulk_len: usize,
+    pub max_multibulk_len: usize,
+    pub max_nesting: usize,
+    pub max_inline_len: usize,
+}
+
+impl From<&Config> for DecoderLimits {
+    fn from(config: &Config) -> Self {
+        Self {
+            max_bulk_len: config.proto_max_bulk_len,
+            max_multibulk_len: config.proto_max_multibulk_len,
+            max_nesting: config.proto_max_nesting,
+            max_inline_len: config.proto_inline_max_size,
+        }
+    }
+}
+
+/// An aggregate frame whose children are still being decoded
+#[derive(Debug)]
+struct Partial {
+    kind: u8,
+    remaining: usize,
+    items: Vec<Frame>,
+}
+
+enum Element {
+    Frame(Frame),
+    Aggregate(Partial),
+    /// An empty inline line, ignored the way redis-server does
+    Skip,
+    Incomplete,
+}
+
+/// Upper bound of the capacity reserved up front for an aggregate, its real length is only
+/// trusted once the elements have arrived.
+const MAX_PREALLOCATED_ITEMS: usize = 1024;
+
+/// Upper bound of the capacity reserved at once for a bulk payload, the buffer keeps growing
+/// as the payload arrives instead of trusting the announced length.
+const MAX_BLOB_RESERVE: usize = 64 * 1024;
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Self::with_limits(get_server_config().into())
+    }
+}
+
+impl Decoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_limits(limits: DecoderLimits) -> Self {
+        Self {
+            limits,
+            stack: Vec::new(),
+            pending_blob: None,
+            pending_bytes: 0,
+        }
+    }
+
+    /// Decode the next complete frame from `buf`.
+    ///
+    /// Returns `Ok(None)` when more bytes are needed, in which case everything decoded so far
+    /// has been consumed from `buf` and kept in the decoder.
+    pub fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<Frame>, FrameError> {
+        loop {
+            let mut frame = match self.decode_element(buf)? {
+                Element::Frame(frame) => frame,
+                Element::Aggregate(partial) if partial.remaining == 0 => partial.finish(),
+                Element::Aggregate(partial) => {
+                    self.stack.push(partial);
+                    continue;
+                }
+                Element::Skip => continue,
+                Element::Incomplete => return Ok(None),
+            };
+
+            // attach the frame to its parent, completing as many aggregates as possible
+            loop {
+                let Some(top) = self.stack.last_mut() else {
+                    self.pending_bytes = 0;
+                    return Ok(Some(frame));
+                };
+                top.items.push(frame);
+                top.remaining -= 1;
+                if top.remaining > 0 {
+                    break;
+                }
+                frame = self.stack.pop().expect("stack is not empty").finish();
+            }
+        }
+    }
+
+    /// Whether a frame has been partially decoded
+    pub fn is_idle(&self) -> bool {
+        self.stack.is_empty() && self.pending_blob.is_none()
+    }
+
+    /// Bytes already consumed from the read buffer for the frame being decoded, they count
+    /// towards the query buffer of the client.
+    pub fn pending_bytes(&self) -> usize {
+        self.pending_bytes
+    }
+
+    fn decode_element(&mut self, buf: &mut BytesMut) -> Result<Element, FrameError> {
+        if let Some((prefix, len)) = self.pending_blob {
+            if buf.len() < len + 2 {
+                buf.reserve((len + 2 - buf.len()).min(MAX_BLOB_RESERVE));
+                return Ok(Element::Incomplete);
+            }
+            let data = buf.split_to(len).to_vec();
+            expect_crlf(buf)?;
+            self.pending_blob = None;
+            self.pending_bytes += len + 2;
+            return blob_frame(prefix, data).map(Element::Frame);
+        }
+
+        let Some(&prefix) = buf.first() else {
+            return Ok(Element::Incomplete);
+        };
+        if self.stack.is_empty() && !is_type_byte(prefix) {
+            return decode_inline(buf, self.limits.max_inline_len);
+        }
+
+        let line_len = find_crlf(buf);
+        if line_len.unwrap_or(buf.len()) > self.limits.max_inline_len {
+            return Err(FrameError::Protocol(
+                match prefix {
+                    b'*' => "too big mbulk count string",
+                    b'$' => "too big bulk count string",
+                    _ => "too big line",
+                }
+                .to_string(),
+            ));
+        }
+        let Some(line_len) = line_len else {
+            return Ok(Element::Incomplete);
+        };
+        let line = buf.split_to(line_len + 2);
+        let content = &line[1..line_len];
+        self.pending_bytes += line.len();
+
+        let frame = match prefix {
+            b'+' => Frame::SimpleString(String::from_utf8_lossy(content).into_owned()),
+            b'-' => Frame::Error(String::from_utf8_lossy(content).into_owned()),
+            b':' => Frame::Integer(parse_int(content)?),
+            b'_' => Frame::Null,
+            b'#' => match content {
+                b"t" => Frame::Boolean(true),
+                b"f" => Frame::Boolean(false),
+                other => {
+                    return Err(FrameError::InvalidBoolean(
+                        String::from_utf8_lossy(other).into_owned(),
+                    ));
+                }
+            },
+            b',' => Frame::Double(parse_double(content)?),
+            b'(' => Frame::BigNumber(std::str::from_utf8(content)?.to_string()),
+            b'$' | b'!' | b'=' => {
+                let len = parse_int(content)
+                    .map_err(|_| FrameError::Protocol("invalid bulk length".to_string()))?;
+                if len == -1 && prefix == b'$' {
+                    return Ok(Element::Frame(Frame::BulkString(None)));
+                }
+                let len = usize::try_from(len)
+                    .ok()
+                    .filter(|&len| len <= self.limits.max_bulk_len)
+                    .ok_or_else(|| FrameError::Protocol("invalid bulk length".to_string()))?;
+                self.pending_blob = Some((prefix, len));
+                return self.decode_element(buf);
+            }
+            b'*' | b'%' | b'~' | b'>' | b'|' => {
+                let count = parse_int(content)
+                    .map_err(|_| FrameError::Protocol("invalid multibulk length".to_string()))?;
+                if count == -1 && prefix == b'*' {
+                    return Ok(Element::Frame(Frame::Array(None)));
+                }
+                let count = usize::try_from(count)
+                    .ok()
+                    .filter(|&count| count <= self.limits.max_multibulk_len)
+                    .ok_or_else(|| FrameError::Protocol("invalid multibulk length".to_string()))?;
+                if self.stack.len() >= self.limits.max_nesting {
+                    return Err(FrameError::Protocol(
+                        "too deeply nested request".to_string(),
+                    ));
+                }
+                let remaining = match prefix {
+                    b'%' => count * 2,
+                    // the attributes are followed by the frame they describe
+                    b'|' => count * 2 + 1,
+                    _ => count,
+                };
+                return Ok(Element::Aggregate(Partial {
+                    kind: prefix,
+                    remaining,
+                    items: Vec::with_capacity(remaining.min(MAX_PREALLOCATED_ITEMS)),
+                }));
+            }
+            _ => return Err(FrameError::UnsupportedType(prefix as char)),
+        };
+        Ok(Element::Frame(frame))
+    }
+}
+
+impl Partial {
+    fn finish(self) -> Frame {
+        match self.kind {
+            b'%' => Frame::Map(into_pairs(self.items)),
+            b'~' => Frame::Set(self.items),
+            b'>' => Frame::Push(self.items),
+            b'|' => {
+                let mut items = self.items;
+                let data = items.pop().expect("attribute is followed by a frame");
+                Frame::Attribute(into_pairs(items), Box::new(data))
+            }
+            _ => Frame::Array(Some(self.items)),
+        }
+    }
+}
+
+fn into_pairs(items: Vec<Frame>) -> Vec<(Frame, Frame)> {
+    let mut pairs = Vec::with_capacity(items.len() / 2);
+    let mut items = items.into_iter();
+    while let (Some(key), Some(value)) = (items.next(), items.next()) {
+        pairs.push((key, value));
+    }
+    pairs
+}
+
+fn is_type_byte(b: u8) -> bool {
+    matches!(
+        b,
+        b'+' | b'-'
+            | b':'
+            | b'$'
+            | b'*'
+            | b'_'
+            | b'#'
+            | b','
+            | b'('
+            | b'!'
+            | b'='
+            | b'%'
+            | b'~'
+            | b'|'
+            | b'>'
+    )
+}
+
+fn blob_frame(prefix: u8, mut data: Vec<u8>) -> Result<Frame, FrameError> {
+    match prefix {
+        b'!' => Ok(Frame::BulkError(data)),
+        b'=' => {
+            if data.len() < 4 || data[3] != b':' {
+                return Err(invalid_format("verbatim string without format"));
+            }
+            let format = String::from_utf8(data[..3].to_vec())?;
+            data.drain(..4);
+            Ok(Frame::Verbatim(format, data))
+        }
+        _ => Ok(Frame::BulkString(Some(data))),
+    }
+}
+
+/// Decode an inline command, arguments are split the way `sdssplitargs` does.
+fn decode_inline(buf: &mut BytesMut, max_len: usize) -> Result<Element, FrameError> {
+    let newline = buf.iter().position(|&b| b == b'\n');
+    if newline.unwrap_or(buf.len()) > max_len {
+        return Err(FrameError::Protocol("too big inline request".to_string()));
+    }
+    let Some(newline) = newline else {
+        return Ok(Element::Incomplete);
+    };
+    let line = buf.split_to(newline + 1);
+    let line = line[..newline]
+        .strip_suffix(b"\r")
+        .unwrap_or(&line[..newline]);
+
+    let args = split_args(line)
+        .ok_or_else(|| FrameError::Protocol("unbalanced quotes in request".to_string()))?;
+    if args.is_empty() {
+        return Ok(Element::Skip);
+    }
+    Ok(Element::Frame(Frame::Array(Some(
+        args.into_iter()
+            .map(|arg| Frame::BulkString(Some(arg)))
+            .collect(),
+    ))))
+}
+
+/// Split a line into arguments, supporting `"..."` with escapes and `'...'` quoting.
+///
+/// Returns `None` on unbalanced quotes.
+fn split_args(line: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let mut args = Vec::new();
+    let mut i = 0;
+    loop {
+        while i < line.len() && line[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if i == line.len() {
+            return Some(args);
+        }
+
+        let mut arg = Vec::new();
+        let mut in_double = false;
+        let mut in_single = false;
+        loop {
+            let Some(&c) = line.get(i) else {
+                if in_double || in_single {
+                    return None;
+                }
+                break;
+            };
+            if in_double {
+                match c {
+                    b'\\'
+                        if i + 3 < line.len()
+                            && line[i + 1] == b'x'
+                            && line[i + 2].is_ascii_hexdigit()
+                            && line[i + 3].is_ascii_hexdigit() =>
+                    {
+                        let hex = std::str::from_utf8(&line[i + 2..i + 4]).ok()?;
+                        arg.push(u8::from_str_radix(hex, 16).ok()?);
+                        i += 3;
+                    }
+                    b'\\' if i + 1 < line.len() => {
+                        i += 1;
+                        arg.push(match line[i] {
+                            b'n' => b'\n',
+                            b'r' => b'\r',
+                            b't' => b'\t',
+                            b'b' => 0x08,
+                            b'a' => 0x07,
+                            other => other,
+                        });
+                    }
+                    b'"' => {
+                        // the closing quote must be followed by a space or nothing at all
+                        if line.get(i + 1).is_some_and(|b| !b.is_ascii_whitespace()) {
+                            return None;
+                        }
+                        in_double = false;
+                    }
+                    other => arg.push(other),
+                }
+            } else if in_single {
+                match c {
+                    b'\\' if line.get(i + 1) == Some(&b'\'') => {
+                        i += 1;
+                        arg.push(b'\'');
+                    }
+                    b'\'' => {
+                        if line.get(i + 1).is_some_and(|b| !b.is_ascii_whitespace()) {
+                            return None;
+                        }
+                        in_single = false;
+                    }
+                    other => arg.push(other),
+                }
+            } else {
+                match c {
+                    c if c.is_ascii_whitespace() => break,
+                    b'"' => in_double = true,
+                    b'\'' => in_single = true,
+                    other => arg.push(other),
+                }
+            }
+            i += 1;
+        }
+        args.push(arg);
+    }
+}
+
+/// Position of the `\r\n` which terminates the line at the front of `buf`
+fn find_crlf(buf: &[u8]) -> Option<usize> {
+    buf.windows(2).position(|window| window == b"\r\n")
+}
+
+fn expect_crlf(buf: &mut BytesMut) -> Result<(), FrameError> {
+    if !buf.starts_with(b"\r\n") {
+        return Err(FrameError::Protocol(
+            "expected CRLF after bulk data".to_string(),
+        ));
+    }
+    buf.advance(2);
+    Ok(())
+}
+
+fn parse_int(content: &[u8]) -> Result<i64, FrameError> {
+    Ok(std::str::from_utf8(content)?.parse()?)
+}
+
+fn parse_double(content: &[u8]) -> Result<f64, FrameError> {
+    match content {
+        b"inf" | b"+inf" => Ok(f64::INFINITY),
+        b"-inf" => Ok(f64::NEG_INFINITY),
+        b"nan" => Ok(f64::NAN),
+        other => Ok(std::str::from_utf8(other)?.parse()?),
+    }
+}
+
+fn invalid_format(message: &str) -> FrameError {
+    FrameError::InvalidFormat {
+        message: message.to_string(),
+        source: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bytes::BytesMut;
+
+    use crate::protocol::{
+        Frame, FrameError,
+        decoder::{Decoder, DecoderLimits},
+    };
+
+    fn limits() -> DecoderLimits {
+        DecoderLimits {
+            max_bulk_len: 8,
+            max_multibulk_len: 4,
+            max_nesting: 2,
+            max_inline_len: 16,
+        }
+    }
+
+    fn protocol_error(input: &[u8]) -> String {
+        let mut decoder = Decoder::with_limits(limits());
+        let mut buf = BytesMut::from(input);
+        match decoder.decode(&mut buf) {
+            Err(FrameError::Protocol(message)) => message,
+            other => panic!("expect a protocol error, got {:?}", other),
+        }
+    }
+
+    fn bulk(s: &str) -> Frame {
+        Frame::BulkString(Some(s.as_bytes().to_vec()))
+    }
+
+    fn decode_all(input: &[u8]) -> Vec<Frame> {
+        let mut decoder = Decoder::new();
+        let mut buf = BytesMut::from(input);
+        let mut frames = Vec::new();
+        while let Some(frame) = decoder.decode(&mut buf).unwrap() {
+            frames.push(frame);
+        }
+        assert!(buf.is_empty());
+        frames
+    }
+
+    #[test]
+    fn test_decode_array_ok() {
+        assert_eq!(
+            decode_all(b"*3\r\n$3\r\nset\r\n$3\r\nkey\r\n$5\r\nvalue\r\n"),
+            vec![Frame::Array(Some(vec![
+                bulk("set"),
+                bulk("key"),
+                bulk("value")
+            ]))]
+        );
+    }
+
+    #[test]
+    fn test_decode_pipelined_commands_from_one_read() {
+        assert_eq!(
+            decode_all(b"*1\r\n$4\r\nPING\r\n*2\r\n$3\r\nGET\r\n$1\r\na\r\nPING\r\n"),
+            vec![
+                Frame::Array(Some(vec![bulk("PING")])),
+                Frame::Array(Some(vec![bulk("GET"), bulk("a")])),
+                Frame::Array(Some(vec![bulk("PING")])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_resumes_on_partial_input() {
+        let input = b"*2\r\n$3\r\nfoo\r\n%1\r\n+k\r\n*2\r\n:1\r\n$5\r\nhello\r\n";
+        let mut decoder = Decoder::new();
+        let mut buf = BytesMut::new();
+        let mut frames = Vec::new();
+        for b in input.iter() {
+            buf.extend_from_slice(&[*b]);
+            if let Some(frame) = decoder.decode(&mut buf).unwrap() {
+                frames.push(frame);
+            }
+        }
+        assert!(decoder.is_idle());
+        assert_eq!(
+            frames,
+            vec![Frame::Array(Some(vec![
+                bulk("foo"),
+                Frame::Map(vec![(
+                    Frame::SimpleString("k".to_string()),
+                    Frame::Array(Some(vec![Frame::Integer(1), bulk("hello")]))
+                )])
+            ]))]
+        );
+    }
+
+    #[test]
+    fn test_decode_binary_and_nulls() {
+        assert_eq!(
+            decode_all(b"$3\r\n\xff\r\n\r\n$-1\r\n*-1\r\n*0\r\n"),
+            vec![
+                Frame::BulkString(Some(vec![0xff, b'\r', b'\n'])),
+                Frame::BulkString(None),
+                Frame::Array(None),
+                Frame::Array(Some(vec![])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_inline_commands() {
+        assert_eq!(
+            decode_all(b"PING\r\n\r\nSET a  \"b c\\x41\\n\"\n  get 'it\\'s'\r\n"),
+            vec![
+                Frame::Array(Some(vec![bulk("PING")])),
+                Frame::Array(Some(vec![bulk("SET"), bulk("a"), bulk("b cA\n")])),
+                Frame::Array(Some(vec![bulk("get"), bulk("it's")])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_inline_unbalanced_quotes() {
+        let mut decoder = Decoder::new();
+        let mut buf = BytesMut::from(&b"SET a \"b\r\n"[..]);
+        assert!(decoder.decode(&mut buf).is_err());
+
+        let mut buf = BytesMut::from(&b"SET a \"b\"c\r\n"[..]);
+        assert!(decoder.decode(&mut buf).is_err());
+    }
+
+    #[test]
+    fn test_decode_rejects_missing_crlf_after_bulk() {
+        let mut decoder = Decoder::new();
+        let mut buf = BytesMut::from(&b"$3\r\nfooXX"[..]);
+        assert!(decoder.decode(&mut buf).is_err());
+    }
+
+    #[test]
+    fn test_decode_limits() {
+        assert_eq!(protocol_error(b"$9\r\n"), "invalid bulk length");
+        assert_eq!(protocol_error(b"$-2\r\n"), "invalid bulk length");
+        assert_eq!(protocol_error(b"$abc\r\n"), "invalid bulk length");
+        assert_eq!(protocol_error(b"*5\r\n"), "invalid multibulk length");
+        assert_eq!(
+            protocol_error(b"*1\r\n*1\r\n*1\r\n"),
+            "too deeply nested request"
+        );
+        assert_eq!(
+            protocol_error(b"PING PING PING PING\r\n"),
+            "too big inline request"
+        );
+        assert_eq!(
+            protocol_error(b"PING PING PING PING"),
+            "too big inline request"
+        );
+        assert_eq!(
+            protocol_error(b"*11111111111111111111"),
+            "too big mbulk count string"
+        );
+    }
+
+    #[test]
+    fn test_decode_counts_pending_bytes() {
+        let mut decoder = Decoder::with_limits(limits());
+        let mut buf = BytesMut::from(&b"*2\r\n$3\r\nfoo\r\n$8\r\nba"[..]);
+        assert!(decoder.decode(&mut buf).unwrap().is_none());
+        assert_eq!(decoder.pending_bytes(), 4 + 4 + 5 + 4);
+        assert_eq!(&buf[..], b"ba");
+
+        buf.extend_from_slice(b"rbazqu\r\n");
+        assert!(decoder.decode(&mut buf).unwrap().is_some());
+        assert_eq!(decoder.pending_bytes(), 0);
+    }
+
+    #[test]
+    fn test_decode_does_not_preallocate_big_bulk() {
+        let mut decoder = Decoder::with_limits(DecoderLimits {
+            max_bulk_len: 512 * 1024 * 1024,
+            ..limits()
+        });
+        let mut buf = BytesMut::from(&b"*1\r\n$536870912\r\nab"[..]);
+        assert!(decoder.decode(&mut buf).unwrap().is_none());
+        assert!(buf.capacity() <= 128 * 1024);
+    }
+}
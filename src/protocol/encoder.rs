@@ -0,0 +1,304 @@
+use crate::protocol::{Frame, ProtocolVersion, write_double};
+
+/// Serializes `Frame`s into a reusable byte buffer.
+///
+/// Nested frames are walked with an explicit stack instead of recursion, and integer headers
+/// are formatted on the stack, so encoding a reply only allocates when the buffer has to grow.
+/// Replies of a pipelined batch are appended one after another and flushed with a single write.
+#[derive(Debug, Default)]
+pub struct Encoder {
+    buf: Vec<u8>,
+}
+
+/// Decimal digits of `i64::MIN` plus the sign
+const MAX_INT_LEN: usize = 20;
+
+/// Longest output of `write_double`, e.g. `-2.2250738585072014e-308`
+const MAX_DOUBLE_LEN: usize = 32;
+
+impl Encoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `frame` to the buffer, RESP3-only types are downgraded when `version` is RESP2,
+    /// e.g. `Null` becomes `$-1`, maps become flat arrays and booleans become integers.
+    pub fn encode(&mut self, frame: &Frame, version: ProtocolVersion) {
+        let resp3 = version == ProtocolVersion::Resp3;
+        let mut stack = vec![frame];
+
+        while let Some(frame) = stack.pop() {
+            match frame {
+                Frame::SimpleString(s) => self.write_line(b'+', s.as_bytes()),
+                Frame::Error(e) => self.write_line(b'-', e.as_bytes()),
+                Frame::Integer(i) => self.write_int(b':', *i),
+                Frame::BulkString(Some(data)) => self.write_blob(b'$', &[data]),
+                Frame::BulkString(None) | Frame::Null | Frame::Array(None) if resp3 => {
+                    self.buf.extend_from_slice(b"_\r\n")
+                }
+                Frame::BulkString(None) | Frame::Null => self.buf.extend_from_slice(b"$-1\r\n"),
+                Frame::Array(None) => self.buf.extend_from_slice(b"*-1\r\n"),
+                Frame::Array(Some(items)) => {
+                    self.write_int(b'*', items.len() as i64);
+                    stack.extend(items.iter().rev());
+                }
+                Frame::Boolean(b) if resp3 => {
+                    self.buf
+                        .extend_from_slice(if *b { b"#t\r\n" } else { b"#f\r\n" })
+                }
+                Frame::Boolean(b) => self.write_int(b':', *b as i64),
+                Frame::Double(d) => self.write_double(*d, resp3),
+                Frame::BigNumber(n) if resp3 => self.write_line(b'(', n.as_bytes()),
+                Frame::BigNumber(n) => self.write_blob(b'$', &[n.as_bytes()]),
+                Frame::BulkError(e) if resp3 => self.write_blob(b'!', &[e]),
+                Frame::BulkError(e) => {
+                    self.buf.push(b'-');
+                    self.buf.extend(
+                        e.iter()
+                            .map(|&b| if b == b'\r' || b == b'\n' { b' ' } else { b }),
+                    );
+                    self.buf.extend_from_slice(b"\r\n");
+                }
+                Frame::Verbatim(format, data) if resp3 => {
+                    self.write_blob(b'=', &[format.as_bytes(), b":", data])
+                }
+                Frame::Verbatim(_, data) => self.write_blob(b'$', &[data]),
+                Frame::Map(pairs) => {
+                    if resp3 {
+                        self.write_int(b'%', pairs.len() as i64);
+                    } else {
+                        self.write_int(b'*', pairs.len() as i64 * 2);
+                    }
+                    stack.extend(pairs.iter().rev().flat_map(|(k, v)| [v, k]));
+                }
+                Frame::Set(items) | Frame::Push(items) => {
+                    let prefix = match frame {
+                        _ if !resp3 => b'*',
+                        Frame::Set(_) => b'~',
+                        _ => b'>',
+                    };
+                    self.write_int(prefix, items.len() as i64);
+                    stack.extend(items.iter().rev());
+                }
+                Frame::Attribute(attrs, data) => {
+                    stack.push(data);
+                    if resp3 {
+                        self.write_int(b'|', attrs.len() as i64);
+                        stack.extend(attrs.iter().rev().flat_map(|(k, v)| [v, k]));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Encoded bytes which haven't been sent yet
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Drop the sent bytes but keep the allocation for the next batch
+    pub fn clear(&mut self) {
+        self.buf.clear();
+    }
+
+    fn write_line(&mut self, prefix: u8, line: &[u8]) {
+        self.buf.push(prefix);
+        self.buf.extend_from_slice(line);
+        self.buf.extend_from_slice(b"\r\n");
+    }
+
+    fn write_int(&mut self, prefix: u8, value: i64) {
+        let mut digits = [0u8; MAX_INT_LEN];
+        let len = format_int(value, &mut digits);
+        self.write_line(prefix, &digits[MAX_INT_LEN - len..]);
+    }
+
+    /// Write a length-prefixed payload made of several `parts`
+    fn write_blob(&mut self, prefix: u8, parts: &[&[u8]]) {
+        self.write_int(
+            prefix,
+            parts.iter().map(|part| part.len()).sum::<usize>() as i64,
+        );
+        for part in parts {
+            self.buf.extend_from_slice(part);
+        }
+        self.buf.extend_from_slice(b"\r\n");
+    }
+
+    fn write_double(&mut self, d: f64, resp3: bool) {
+        let mut scratch = [0u8; MAX_DOUBLE_LEN];
+        let mut cursor = &mut scratch[..];
+        write_double(&mut cursor, d).expect("formatted double exceeds the scratch buffer");
+        let len = MAX_DOUBLE_LEN - cursor.len();
+        if resp3 {
+            self.write_line(b',', &scratch[..len]);
+        } else {
+            self.write_blob(b'$', &[&scratch[..len]]);
+        }
+    }
+}
+
+/// Format `value` into the tail of `buf`, returns the count of bytes written.
+fn format_int(value: i64, buf: &mut [u8; MAX_INT_LEN]) -> usize {
+    let mut n = value.unsigned_abs();
+    let mut pos = MAX_INT_LEN;
+    loop {
+        pos -= 1;
+        buf[pos] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    if value < 0 {
+        pos -= 1;
+        buf[pos] = b'-';
+    }
+    MAX_INT_LEN - pos
+}
+
+#[cfg(test)]
+mod test {
+    use bytes::BytesMut;
+
+    use crate::protocol::{
+        Frame, ProtocolVersion,
+        decoder::Decoder,
+        encoder::{Encoder, MAX_INT_LEN, format_int},
+    };
+
+    fn encode(frame: &Frame, version: ProtocolVersion) -> Vec<u8> {
+        let mut encoder = Encoder::new();
+        encoder.encode(frame, version);
+        encoder.buffer().to_vec()
+    }
+
+    fn round_trip(frame: &Frame, version: ProtocolVersion) -> Frame {
+        let mut buf = BytesMut::from(encode(frame, version).as_slice());
+        let decoded = Decoder::new().decode(&mut buf).unwrap().unwrap();
+        assert!(buf.is_empty());
+        decoded
+    }
+
+    fn bulk(s: &str) -> Frame {
+        Frame::BulkString(Some(s.as_bytes().to_vec()))
+    }
+
+    #[test]
+    fn test_format_int() {
+        for value in [0, 7, -7, 10, 1234567890, i64::MAX, i64::MIN] {
+            let mut buf = [0u8; MAX_INT_LEN];
+            let len = format_int(value, &mut buf);
+            assert_eq!(&buf[MAX_INT_LEN - len..], value.to_string().as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_encode_resp2_nulls_and_headers() {
+        assert_eq!(
+            encode(&Frame::BulkString(None), ProtocolVersion::Resp2),
+            b"$-1\r\n"
+        );
+        assert_eq!(
+            encode(&Frame::Array(None), ProtocolVersion::Resp2),
+            b"*-1\r\n"
+        );
+        assert_eq!(
+            encode(
+                &Frame::Array(Some(vec![bulk("a"), Frame::Integer(-1)])),
+                ProtocolVersion::Resp2
+            ),
+            b"*2\r\n$1\r\na\r\n:-1\r\n"
+        );
+    }
+
+    #[test]
+    fn test_encode_batch_appends() {
+        let mut encoder = Encoder::new();
+        encoder.encode(
+            &Frame::SimpleString("OK".to_string()),
+            ProtocolVersion::Resp2,
+        );
+        encoder.encode(&bulk("v"), ProtocolVersion::Resp2);
+        assert_eq!(encoder.buffer(), b"+OK\r\n$1\r\nv\r\n");
+        encoder.clear();
+        assert!(encoder.is_empty());
+    }
+
+    #[test]
+    fn test_round_trip_resp3() {
+        let frames = [
+            Frame::SimpleString("OK".to_string()),
+            Frame::Error("ERR wrong".to_string()),
+            Frame::Integer(i64::MIN),
+            bulk(""),
+            Frame::Null,
+            Frame::Boolean(false),
+            Frame::Double(-0.125),
+            Frame::Double(f64::INFINITY),
+            Frame::BigNumber("-3492890328409238509324850943850943825024385".to_string()),
+            Frame::BulkError(b"SYNTAX invalid".to_vec()),
+            Frame::Verbatim("txt".to_string(), b"Some string".to_vec()),
+            Frame::Array(Some(vec![
+                bulk("a"),
+                Frame::Array(Some(vec![Frame::Integer(1), Frame::Array(Some(vec![]))])),
+            ])),
+            Frame::Map(vec![
+                (bulk("k1"), Frame::Set(vec![Frame::Integer(1), bulk("x")])),
+                (bulk("k2"), Frame::Map(vec![(bulk("nested"), Frame::Null)])),
+            ]),
+            Frame::Push(vec![bulk("message"), bulk("channel"), bulk("payload")]),
+            Frame::Attribute(
+                vec![(bulk("key-popularity"), Frame::Double(0.1923))],
+                Box::new(Frame::Array(Some(vec![Frame::Integer(2039123)]))),
+            ),
+        ];
+        for frame in frames.iter() {
+            assert_eq!(&round_trip(frame, ProtocolVersion::Resp3), frame);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_resp2_downgrade() {
+        let cases = [
+            (Frame::Null, Frame::BulkString(None)),
+            (Frame::Boolean(true), Frame::Integer(1)),
+            (Frame::Double(3.0), bulk("3")),
+            (Frame::Double(1e20), bulk("1e20")),
+            (Frame::BigNumber("123".to_string()), bulk("123")),
+            (
+                Frame::BulkError(b"ERR multi\r\nline".to_vec()),
+                Frame::Error("ERR multi  line".to_string()),
+            ),
+            (
+                Frame::Verbatim("mkd".to_string(), b"# t".to_vec()),
+                bulk("# t"),
+            ),
+            (
+                Frame::Map(vec![(
+                    bulk("k"),
+                    Frame::Map(vec![(bulk("n"), Frame::Null)]),
+                )]),
+                Frame::Array(Some(vec![
+                    bulk("k"),
+                    Frame::Array(Some(vec![bulk("n"), Frame::BulkString(None)])),
+                ])),
+            ),
+            (
+                Frame::Set(vec![Frame::Boolean(false)]),
+                Frame::Array(Some(vec![Frame::Integer(0)])),
+            ),
+            (
+                Frame::Attribute(vec![(bulk("a"), bulk("b"))], Box::new(bulk("data"))),
+                bulk("data"),
+            ),
+        ];
+        for (frame, expected) in cases.iter() {
+            assert_eq!(&round_trip(frame, ProtocolVersion::Resp2), expected);
+        }
+    }
+}
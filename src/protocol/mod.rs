@@ -0,0 +1,268 @@
+use std::fmt::Display;
+
+use thiserror::Error;
+
+pub mod decoder;
+pub mod encoder;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Frame {
+    SimpleString(String),
+    Error(String),
+    Integer(i64),
+    BulkString(Option<Vec<u8>>),
+    Array(Option<Vec<Frame>>),
+    Null,
+    Boolean(bool),
+    Double(f64),
+    BigNumber(String),
+    BulkError(Vec<u8>),
+    /// Verbatim string, the first element is the three bytes format, e.g. `txt` or `mkd`
+    Verbatim(String, Vec<u8>),
+    Map(Vec<(Frame, Frame)>),
+    Set(Vec<Frame>),
+    /// Out-of-band attributes, followed by the frame they describe
+    Attribute(Vec<(Frame, Frame)>, Box<Frame>),
+    Push(Vec<Frame>),
+}
+
+/// The RESP version negotiated by a client through `HELLO`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProtocolVersion {
+    #[default]
+    Resp2 = 2,
+    Resp3 = 3,
+}
+
+impl TryFrom<i64> for ProtocolVersion {
+    type Error = i64;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        match value {
+            2 => Ok(ProtocolVersion::Resp2),
+            3 => Ok(ProtocolVersion::Resp3),
+            other => Err(other),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum FrameError {
+    #[error("Invalid Frame format: {message}")]
+    InvalidFormat {
+        message: String,
+        #[source]
+        source: Option<Box<dyn std::error::Error + Send + Sync>>,
+    },
+
+    /// Malformed or oversized input from a client, which gets the error and is disconnected
+    #[error("Protocol error: {0}")]
+    Protocol(String),
+
+    #[error("Unsupported Frame type: {0}")]
+    UnsupportedType(char),
+
+    #[error("Incomplete data: expected {expected} bytes, got {actual} bytes")]
+    IncompleteData { expected: usize, actual: usize },
+
+    #[error("Unexpected Frame type: expected {expected}, got {actual}")]
+    UnexpectedType {
+        expected: String,
+        actual: &'static str,
+    },
+
+    #[error("Null value not allowed")]
+    NullValue,
+
+    #[error("Empty Frame not allowed")]
+    EmptyArray,
+
+    #[error("Array length mismatch: expected {expected}, got {actual}")]
+    ArrayLengthMismatch { expected: usize, actual: usize },
+
+    #[error("UTF-8 conversion error")]
+    Utf8Error(#[from] std::str::Utf8Error),
+
+    #[error("String conversion error")]
+    FromUtf8Error(#[from] std::string::FromUtf8Error),
+
+    #[error("Value is not an integer or out of range")]
+    ParseIntError(#[from] std::num::ParseIntError),
+
+    #[error("Value is not a valid float")]
+    ParseFloatError(#[from] std::num::ParseFloatError),
+
+    #[error("Value too long: {length} bytes (max: {max})")]
+    ValueTooLong { length: usize, max: usize },
+
+    #[error("Invalid boolean value: {0}")]
+    InvalidBoolean(String),
+
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+
+    #[error("Command {0} execute error")]
+    CommandError(String, #[source] crate::command::error::CommandError),
+}
+
+/// Format a double the way RESP3 `,` frames and RESP2 bulk replies expect.
+pub fn format_double(d: f64) -> String {
+    let mut buf = Vec::new();
+    write_double(&mut buf, d).expect("writing to a Vec never fails");
+    String::from_utf8(buf).expect("formatted double is ASCII")
+}
+
+/// Write the shortest representation of `d` which parses back to the same value, very large
+/// and very small magnitudes use exponent notation to keep the output short.
+pub(crate) fn write_double<W: std::io::Write>(writer: &mut W, d: f64) -> std::io::Result<()> {
+    if d.is_nan() {
+        writer.write_all(b"nan")
+    } else if d.is_infinite() {
+        writer.write_all(if d > 0.0 { b"inf" } else { b"-inf" })
+    } else if d == 0.0 || (1e-5..1e17).contains(&d.abs()) {
+        write!(writer, "{}", d)
+    } else {
+        write!(writer, "{:e}", d)
+    }
+}
+
+impl Frame {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Frame::SimpleString(_) => "SimpleString",
+            Frame::Error(_) => "Error",
+            Frame::Integer(_) => "Integer",
+            Frame::BulkString(_) => "BulkString",
+            Frame::Array(_) => "Array",
+            Frame::Null => "Null",
+            Frame::Boolean(_) => "Boolean",
+            Frame::Double(_) => "Double",
+            Frame::BigNumber(_) => "BigNumber",
+            Frame::BulkError(_) => "BulkError",
+            Frame::Verbatim(..) => "Verbatim",
+            Frame::Map(_) => "Map",
+            Frame::Set(_) => "Set",
+            Frame::Attribute(..) => "Attribute",
+            Frame::Push(_) => "Push",
+        }
+    }
+
+    /// The byte which starts the frame in RESP3
+    pub fn type_byte(&self) -> u8 {
+        match self {
+            Frame::SimpleString(_) => b'+',
+            Frame::Error(_) => b'-',
+            Frame::Integer(_) => b':',
+            Frame::BulkString(_) => b'$',
+            Frame::Array(_) => b'*',
+            Frame::Null => b'_',
+            Frame::Boolean(_) => b'#',
+            Frame::Double(_) => b',',
+            Frame::BigNumber(_) => b'(',
+            Frame::BulkError(_) => b'!',
+            Frame::Verbatim(..) => b'=',
+            Frame::Map(_) => b'%',
+            Frame::Set(_) => b'~',
+            Frame::Attribute(..) => b'|',
+            Frame::Push(_) => b'>',
+        }
+    }
+
+    /// Bytes of the strings and numbers held by the frame, about its encoded size
+    pub fn payload_len(&self) -> usize {
+        match self {
+            Frame::SimpleString(s) | Frame::Error(s) | Frame::BigNumber(s) => s.len(),
+            Frame::BulkString(data) => data.as_ref().map_or(0, Vec::len),
+            Frame::BulkError(data) | Frame::Verbatim(_, data) => data.len(),
+            Frame::Integer(_) | Frame::Double(_) => size_of::<i64>(),
+            Frame::Null | Frame::Boolean(_) => 1,
+            Frame::Array(frames) => frames.iter().flatten().map(Frame::payload_len).sum(),
+            Frame::Set(frames) | Frame::Push(frames) => frames.iter().map(Frame::payload_len).sum(),
+            Frame::Map(pairs) => pairs
+                .iter()
+                .map(|(key, value)| key.payload_len() + value.payload_len())
+                .sum(),
+            Frame::Attribute(pairs, data) => {
+                let attributes: usize = pairs
+                    .iter()
+                    .map(|(key, value)| key.payload_len() + value.payload_len())
+                    .sum();
+                attributes + data.payload_len()
+            }
+        }
+    }
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Frame::SimpleString(s) => write!(f, "{}", s),
+            Frame::Error(e) => write!(f, "{}", e),
+            Frame::Integer(i) => write!(f, "{}", i),
+            Frame::BulkString(items) => {
+                if let Some(items) = items {
+                    write!(f, "\"{}\"", String::from_utf8_lossy(items))
+                } else {
+                    write!(f, "\"\"")
+                }
+            }
+            Frame::Array(vals) => {
+                if let Some(vals) = vals {
+                    write!(f, "[")?;
+                    for val in vals.iter() {
+                        val.fmt(f)?;
+                    }
+                    write!(f, "]")
+                } else {
+                    write!(f, "[]")
+                }
+            }
+            Frame::Null => write!(f, "null"),
+            Frame::Boolean(b) => write!(f, "{}", b),
+            Frame::Double(d) => write!(f, "{}", format_double(*d)),
+            Frame::BigNumber(n) => write!(f, "{}", n),
+            Frame::BulkError(e) => write!(f, "{}", String::from_utf8_lossy(e)),
+            Frame::Verbatim(_, data) => write!(f, "\"{}\"", String::from_utf8_lossy(data)),
+            Frame::Map(pairs) | Frame::Attribute(pairs, _) => {
+                write!(f, "{{")?;
+                for (key, value) in pairs.iter() {
+                    write!(f, "{}: {},", key, value)?;
+                }
+                write!(f, "}}")?;
+                if let Frame::Attribute(_, data) = self {
+                    data.fmt(f)?;
+                }
+                Ok(())
+            }
+            Frame::Set(vals) | Frame::Push(vals) => {
+                write!(f, "[")?;
+                for val in vals.iter() {
+                    val.fmt(f)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::protocol::format_double;
+
+    #[test]
+    fn test() {
+        let s = String::from("12345\r\n");
+        s.chars().next();
+        dbg!("{}", &s[1..].trim_end());
+    }
+
+    #[test]
+    fn test_format_double() {
+        assert_eq!(format_double(3.0), "3");
+        assert_eq!(format_double(-0.5), "-0.5");
+        assert_eq!(format_double(1e20), "1e20");
+        assert_eq!(format_double(1.5e-7), "1.5e-7");
+        assert_eq!(format_double(f64::INFINITY), "inf");
+        assert_eq!(format_double(f64::NAN), "nan");
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::RwLock,
+};
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+
+use crate::{
+    context::{Context, Pusher},
+    protocol::Frame,
+    util::string_match,
+};
+
+/// Channel and pattern subscriptions of every client
+pub static PUBSUB: Lazy<PubSub> = Lazy::new(PubSub::new);
+
+/// Where the messages of a subscribed client are pushed, keyed by client id
+type Subscribers = HashMap<usize, Pusher>;
+
+/// The channels and patterns a client subscribed to
+#[derive(Debug, Default)]
+pub struct Subscriptions {
+    pub channels: HashSet<String>,
+    pub patterns: HashSet<String>,
+}
+
+impl Subscriptions {
+    /// Count of channels and patterns, reported by the (un)subscribe confirmations
+    pub fn len(&self) -> usize {
+        self.channels.len() + self.patterns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty() && self.patterns.is_empty()
+    }
+}
+
+pub struct PubSub {
+    channels: DashMap<String, Subscribers>,
+    /// Every message is matched against every pattern, so they are kept apart
+    patterns: RwLock<HashMap<String, Subscribers>>,
+}
+
+impl Default for PubSub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn bulk(data: &[u8]) -> Frame {
+    Frame::BulkString(Some(data.to_vec()))
+}
+
+impl PubSub {
+    pub fn new() -> Self {
+        Self {
+            channels: DashMap::new(),
+            patterns: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Subscribe the client to `channel`, returns false when it was already
+    pub fn subscribe(&self, ctx: &Context, channel: &str) -> bool {
+        if !ctx.subscriptions().channels.insert(channel.to_string()) {
+            return false;
+        }
+        self.channels
+            .entry(channel.to_string())
+            .or_default()
+            .insert(ctx.id, ctx.pusher());
+        true
+    }
+
+    /// Unsubscribe the client from `channel`, returns false when it wasn't subscribed
+    pub fn unsubscribe(&self, ctx: &Context, channel: &str) -> bool {
+        if !ctx.subscriptions().channels.remove(channel) {
+            return false;
+        }
+        self.channels.remove_if_mut(channel, |_, subscribers| {
+            subscribers.remove(&ctx.id);
+            subscribers.is_empty()
+        });
+        true
+    }
+
+    pub fn psubscribe(&self, ctx: &Context, pattern: &str) -> bool {
+        if !ctx.subscriptions().patterns.insert(pattern.to_string()) {
+            return false;
+        }
+        self.patterns
+            .write()
+            .unwrap()
+            .entry(pattern.to_string())
+            .or_default()
+            .insert(ctx.id, ctx.pusher());
+        true
+    }
+
+    pub fn punsubscribe(&self, ctx: &Context, pattern: &str) -> bool {
+        if !ctx.subscriptions().patterns.remove(pattern) {
+            return false;
+        }
+        let mut patterns = self.patterns.write().unwrap();
+        if let Some(subscribers) = patterns.get_mut(pattern) {
+            subscribers.remove(&ctx.id);
+            if subscribers.is_empty() {
+                patterns.remove(pattern);
+            }
+        }
+        true
+    }
+
+    /// Drop every subscription of a client, e.g. once it disconnected
+    pub fn remove_client(&self, ctx: &Context) {
+        let Subscriptions { channels, patterns } = std::mem::take(&mut *ctx.subscriptions());
+        for channel in channels {
+            self.channels.remove_if_mut(&channel, |_, subscribers| {
+                subscribers.remove(&ctx.id);
+                subscribers.is_empty()
+            });
+        }
+        let mut all_patterns = self.patterns.write().unwrap();
+        for pattern in patterns {
+            if let Some(subscribers) = all_patterns.get_mut(&pattern) {
+                subscribers.remove(&ctx.id);
+                if subscribers.is_empty() {
+                    all_patterns.remove(&pattern);
+                }
+            }
+        }
+    }
+
+    /// Push `message` to the subscribers of `channel` and of the patterns matching it.
+    ///
+    /// Returns the count of receivers, a client subscribed by several patterns counts for each.
+    pub fn publish(&self, channel: &str, message: &[u8]) -> usize {
+        let mut receivers = 0;
+        if let Some(subscribers) = self.channels.get(channel) {
+            let frame = Frame::Push(vec![
+                bulk(b"message"),
+                bulk(channel.as_bytes()),
+                bulk(message),
+            ]);
+            for pusher in subscribers.values() {
+                // dropped if the connection is closing or too far behind
+                if pusher.send(frame.clone()) {
+                    receivers += 1;
+                }
+            }
+        }
+        for (pattern, subscribers) in self.patterns.read().unwrap().iter() {
+            if !string_match(pattern.as_bytes(), channel.as_bytes(), false) {
+                continue;
+            }
+            let frame = Frame::Push(vec![
+                bulk(b"pmessage"),
+                bulk(pattern.as_bytes()),
+                bulk(channel.as_bytes()),
+                bulk(message),
+            ]);
+            for pusher in subscribers.values() {
+                if pusher.send(frame.clone()) {
+                    receivers += 1;
+                }
+            }
+        }
+        receivers
+    }
+
+    /// Count of channels with at least one subscriber
+    pub fn channels_len(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Count of patterns with at least one subscriber
+    pub fn patterns_len(&self) -> usize {
+        self.patterns.read().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        context::Context,
+        protocol::Frame,
+        pubsub::{PUBSUB, PubSub},
+    };
+
+    fn bulk(data: &str) -> Frame {
+        Frame::BulkString(Some(data.as_bytes().to_vec()))
+    }
+
+    #[test]
+    fn test_publish() {
+        let pubsub = PubSub::new();
+        let ctx = Context::test_client(1);
+        let mut pushes = ctx.take_push_receiver().unwrap();
+        assert!(pubsub.subscribe(&ctx, "news"));
+        assert!(!pubsub.subscribe(&ctx, "news"));
+        assert!(pubsub.psubscribe(&ctx, "n*"));
+        assert_eq!(ctx.subscriptions().len(), 2);
+
+        assert_eq!(pubsub.publish("news", b"hi"), 2);
+        assert_eq!(pubsub.publish("other", b"hi"), 0);
+        assert_eq!(
+            pushes.try_recv().unwrap(),
+            Frame::Push(vec![bulk("message"), bulk("news"), bulk("hi")])
+        );
+        assert_eq!(
+            pushes.try_recv().unwrap(),
+            Frame::Push(vec![bulk("pmessage"), bulk("n*"), bulk("news"), bulk("hi")])
+        );
+        assert!(pushes.try_recv().is_err());
+
+        assert!(pubsub.unsubscribe(&ctx, "news"));
+        assert!(!pubsub.unsubscribe(&ctx, "news"));
+        assert_eq!(pubsub.publish("news", b"hi"), 1);
+        pubsub.remove_client(&ctx);
+        assert_eq!(pubsub.publish("news", b"hi"), 0);
+        assert_eq!((pubsub.channels_len(), pubsub.patterns_len()), (0, 0));
+        assert!(ctx.subscriptions().is_empty());
+        // the global registry is separate
+        assert_eq!(PUBSUB.publish("news", b"hi"), 0);
+    }
+
+    #[test]
+    fn test_publish_output_buffer_limit() {
+        let pubsub = PubSub::new();
+        let (slow, fast) = (Context::test_client(1), Context::test_client(1));
+        let mut pushes = fast.take_push_receiver().unwrap();
+        pubsub.subscribe(&slow, "news");
+        pubsub.subscribe(&fast, "news");
+
+        // `slow` never reads, the 32nd message takes it past the 32MB limit
+        let message = vec![b'x'; 1 << 20];
+        for _ in 0..31 {
+            assert_eq!(pubsub.publish("news", &message), 2);
+            pushes.try_recv().unwrap();
+        }
+        assert!(!slow.is_killed());
+        assert_eq!(pubsub.publish("news", &message), 1);
+        assert!(slow.is_killed());
+        assert!(!fast.is_killed());
+    }
+}
@@ -0,0 +1,551 @@
+use std::{
+    fs, io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use anyhow::{Context as _, Result, bail};
+use socket2::{Domain, Protocol, SockRef, Socket, TcpKeepalive, Type};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpListener, TcpStream, UnixListener},
+    task::JoinSet,
+};
+use tokio_rustls::TlsAcceptor;
+
+use crate::{
+    client::{CLIENT_REGISTRY, clients, register_client},
+    command::{Command, CommandExecutor},
+    config::get_server_config,
+    connection::Connection,
+    context::Context,
+    memory::update_peak_memory,
+    protocol::{Frame, FrameError},
+    shutdown::{SHUTDOWN, ShutdownFlags},
+    storage::{database::Databases, expires::ActiveExpire},
+    tls::build_acceptor,
+};
+
+/// Pending connections of a listening socket, `tcp-backlog`
+const TCP_BACKLOG: i32 = 511;
+
+/// Clients admitted under `maxclients`
+static ADMISSIONS: Admissions = Admissions::new();
+
+/// Counts the admitted clients, a client takes its slot before it is registered so
+/// concurrent connections can't all pass the `maxclients` check
+struct Admissions {
+    count: AtomicUsize,
+}
+
+impl Admissions {
+    const fn new() -> Self {
+        Self {
+            count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Take a slot if fewer than `max` clients are admitted, it is released with the guard
+    fn admit(&self, max: usize) -> Option<Admission<'_>> {
+        if self.count.fetch_add(1, Ordering::AcqRel) >= max {
+            self.count.fetch_sub(1, Ordering::AcqRel);
+            return None;
+        }
+        Some(Admission { admissions: self })
+    }
+}
+
+struct Admission<'a> {
+    admissions: &'a Admissions,
+}
+
+impl Drop for Admission<'_> {
+    fn drop(&mut self) {
+        self.admissions.count.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// A listening socket, every kind of listener feeds accepted clients to `handle_socket`
+pub enum Listener {
+    Tcp(TcpListener),
+    Tls(TcpListener, TlsAcceptor),
+    Unix(UnixListener, PathBuf),
+}
+
+impl Listener {
+    pub fn local_addr(&self) -> String {
+        match self {
+            Listener::Tcp(listener) | Listener::Tls(listener, _) => listener
+                .local_addr()
+                .map(|addr| addr.to_string())
+                .unwrap_or_default(),
+            Listener::Unix(_, path) => path.display().to_string(),
+        }
+    }
+}
+
+/// Bind all listeners of the config and serve clients until the server is shut down
+pub async fn run(dbs: Arc<Databases>) -> Result<()> {
+    let config = get_server_config();
+    let mut listeners = Vec::new();
+    if config.port != 0 {
+        let tcp = bind_tcp_listeners(&config.bind, config.port)?;
+        listeners.extend(tcp.into_iter().map(Listener::Tcp));
+    }
+    if config.tls_port != 0 {
+        let acceptor = build_acceptor(
+            config
+                .tls_cert_file
+                .as_deref()
+                .context("tls-cert-file is required by tls-port")?,
+            config
+                .tls_key_file
+                .as_deref()
+                .context("tls-key-file is required by tls-port")?,
+            config.tls_ca_cert_file.as_deref(),
+            config.tls_auth_clients,
+        )?;
+        let tls = bind_tcp_listeners(&config.bind, config.tls_port)?;
+        listeners.extend(
+            tls.into_iter()
+                .map(|listener| Listener::Tls(listener, acceptor.clone())),
+        );
+    }
+    if let Some(path) = &config.unixsocket {
+        listeners.push(bind_unix_listener(path, config.unixsocketperm)?);
+    }
+    if listeners.is_empty() {
+        bail!("No listener is configured, set a bind address and port or a unix socket");
+    }
+
+    let mut tasks = JoinSet::new();
+    for listener in listeners {
+        let tls = if matches!(listener, Listener::Tls(..)) {
+            " (tls)"
+        } else {
+            ""
+        };
+        log::info!("redis is listening on {}{}", listener.local_addr(), tls);
+        tasks.spawn(serve(listener, dbs.clone()));
+    }
+    let cron = tokio::spawn(server_cron(dbs.clone()));
+    let flags = tokio::select! {
+        flags = SHUTDOWN.wait() => flags,
+        _ = async { while tasks.join_next().await.is_some() {} } => {
+            bail!("All listeners stopped");
+        }
+    };
+    log::warn!("user requested shutdown...");
+    // stop accepting new clients
+    tasks.abort_all();
+    cron.abort();
+    if let Some(path) = &config.unixsocket {
+        log::info!("removing the unix socket file");
+        if let Err(e) = fs::remove_file(path) {
+            log::warn!(
+                "error removing the unix socket file {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+    if flags.save == Some(true) {
+        log::warn!("there is no persistence yet, nothing to save");
+    }
+    close_clients(flags, Duration::from_secs(config.shutdown_timeout)).await;
+    log::warn!("redis is now ready to exit, bye bye...");
+    Ok(())
+}
+
+/// Background jobs run `hz` times a second, like the `serverCron` of redis
+async fn server_cron(dbs: Arc<Databases>) {
+    let config = get_server_config();
+    let mut interval = tokio::time::interval(Duration::from_secs(1) / config.hz);
+    let mut active_expire = ActiveExpire::new();
+    let mut ticks: u64 = 0;
+    loop {
+        interval.tick().await;
+        active_expire.cycle(&dbs, config.hz);
+        update_peak_memory();
+        // idle clients are checked once a second
+        if config.timeout > 0 && ticks.is_multiple_of(config.hz as u64) {
+            close_idle_clients(&clients(), Duration::from_secs(config.timeout));
+        }
+        ticks += 1;
+    }
+}
+
+/// Kill the clients which haven't sent a command within `timeout`, returns how many
+fn close_idle_clients(clients: &[Arc<Context>], timeout: Duration) -> usize {
+    let mut closed = 0;
+    for ctx in clients {
+        // subscribers wait for messages and blocked clients for their keys, they aren't idle
+        if ctx.idle() > timeout && !ctx.is_killed() && !ctx.is_subscribed() && !ctx.is_blocked()
+        {
+            log::info!("closing idle client {} ({})", ctx.id, ctx.addr);
+            ctx.kill();
+            closed += 1;
+        }
+    }
+    closed
+}
+
+/// Close every client, the commands being executed are allowed to finish within `timeout`
+async fn close_clients(flags: ShutdownFlags, timeout: Duration) {
+    for ctx in clients() {
+        ctx.kill();
+    }
+    if flags.now {
+        return;
+    }
+    let drained = tokio::time::timeout(timeout, async {
+        while !CLIENT_REGISTRY.is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    });
+    if drained.await.is_err() {
+        log::warn!(
+            "{} clients still busy after {:?}, closing them",
+            CLIENT_REGISTRY.len(),
+            timeout
+        );
+    }
+}
+
+/// Bind a TCP listener for each address of `bind`, addresses prefixed with `-` are optional.
+pub fn bind_tcp_listeners(bind: &[String], port: u16) -> Result<Vec<TcpListener>> {
+    let mut listeners = Vec::with_capacity(bind.len());
+    for address in bind {
+        let (optional, address) = match address.strip_prefix('-') {
+            Some(address) => (true, address),
+            None => (false, address.as_str()),
+        };
+        let ip: IpAddr = match address {
+            "*" => Ipv4Addr::UNSPECIFIED.into(),
+            "::*" => Ipv6Addr::UNSPECIFIED.into(),
+            _ => address
+                .parse()
+                .with_context(|| format!("Invalid bind address {}", address))?,
+        };
+        match bind_tcp(SocketAddr::new(ip, port)) {
+            Ok(listener) => listeners.push(listener),
+            Err(e) if optional => log::warn!("skip unavailable bind address {}: {}", address, e),
+            Err(e) => return Err(e).context(format!("Bind {}:{} failed", address, port)),
+        }
+    }
+    Ok(listeners)
+}
+
+fn bind_tcp(addr: SocketAddr) -> io::Result<TcpListener> {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+    // `*` and `::*` may both be configured, keep the IPv6 socket from taking IPv4 as well
+    if addr.is_ipv6() {
+        socket.set_only_v6(true)?;
+    }
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(TCP_BACKLOG)?;
+    TcpListener::from_std(socket.into())
+}
+
+/// Bind a unix socket at `path`, a stale socket file left by a previous run is replaced.
+pub fn bind_unix_listener(path: &Path, perm: Option<u32>) -> Result<Listener> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => {
+            return Err(e).context(format!("Remove {} failed", path.display()));
+        }
+        _ => {}
+    }
+    let listener = UnixListener::bind(path).context(format!("Bind {} failed", path.display()))?;
+    if let Some(perm) = perm {
+        fs::set_permissions(path, fs::Permissions::from_mode(perm))
+            .context(format!("Chmod {} failed", path.display()))?;
+    }
+    Ok(Listener::Unix(listener, path.to_path_buf()))
+}
+
+/// Accept clients of `listener`, each one is handled by its own task
+pub async fn serve(listener: Listener, dbs: Arc<Databases>) {
+    loop {
+        let accepted = match &listener {
+            Listener::Tcp(listener) => listener.accept().await.map(|(socket, addr)| {
+                let laddr = prepare_tcp_stream(&socket);
+                tokio::spawn(handle_client(socket, addr.to_string(), laddr, dbs.clone()));
+            }),
+            Listener::Tls(listener, acceptor) => listener.accept().await.map(|(socket, addr)| {
+                let laddr = prepare_tcp_stream(&socket);
+                let acceptor = acceptor.clone();
+                let dbs = dbs.clone();
+                // the handshake runs in the client task, a slow client can't stall the accept loop
+                tokio::spawn(async move {
+                    match acceptor.accept(socket).await {
+                        Ok(stream) => handle_client(stream, addr.to_string(), laddr, dbs).await,
+                        Err(e) => log::warn!("tls handshake with {} failed: {}", addr, e),
+                    }
+                });
+            }),
+            Listener::Unix(listener, path) => listener.accept().await.map(|(socket, _)| {
+                // unix clients have no port, they are shown as `path:0` like redis does
+                let addr = format!("{}:0", path.display());
+                tokio::spawn(handle_client(socket, addr.clone(), addr, dbs.clone()));
+            }),
+        };
+        if let Err(e) = accepted {
+            // e.g. out of file descriptors, back off instead of spinning
+            log::error!("accept on {} failed: {}", listener.local_addr(), e);
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+}
+
+/// Apply the socket options of accepted TCP clients, returns the local address
+fn prepare_tcp_stream(socket: &TcpStream) -> String {
+    if let Err(e) = socket.set_nodelay(true) {
+        log::warn!("set TCP_NODELAY failed: {}", e);
+    }
+    let keepalive = get_server_config().tcp_keepalive;
+    if keepalive > 0 {
+        // like redis, probes are sent every third of the interval once it's idle that long
+        let time = Duration::from_secs(keepalive);
+        let params = TcpKeepalive::new()
+            .with_time(time)
+            .with_interval((time / 3).max(Duration::from_secs(1)))
+            .with_retries(3);
+        if let Err(e) = SockRef::from(socket).set_tcp_keepalive(&params) {
+            log::warn!("set TCP keepalive failed: {}", e);
+        }
+    }
+    socket
+        .local_addr()
+        .map(|addr| addr.to_string())
+        .unwrap_or_default()
+}
+
+/// Create the context of an accepted client and serve it
+async fn handle_client<S>(socket: S, addr: String, laddr: String, dbs: Arc<Databases>)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let client_id = Context::next_id();
+    let ctx = Arc::new(Context::new(client_id, dbs, addr, laddr));
+    log::debug!("received connection from: {}, id: {}", ctx.addr, client_id);
+    if let Err(e) = handle_socket(socket, ctx).await {
+        log::debug!("ctx {} connection closed: {}", client_id, e);
+    }
+}
+
+/// Serve the requests of one client until it disconnects or is killed
+pub async fn handle_socket<S>(socket: S, context: Arc<Context>) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut conn = Connection::new(socket);
+    let Some(_admission) = ADMISSIONS.admit(get_server_config().maxclients) else {
+        log::warn!("reject {}: max number of clients reached", context.addr);
+        conn.write_frame(
+            &Frame::Error("ERR max number of clients reached".to_string()),
+            context.protocol_version(),
+        );
+        conn.flush().await?;
+        return Ok(());
+    };
+    let _guard = register_client(context.clone());
+    let mut pushes = context
+        .take_push_receiver()
+        .expect("a client is served by one connection");
+
+    loop {
+        // pushed frames, e.g. pub/sub messages, go out with the next batch of replies
+        while let Ok(push) = pushes.try_recv() {
+            conn.write_frame(&push, context.protocol_version());
+        }
+        if context.is_killed() {
+            return Ok(());
+        }
+        let frame = match conn.next_buffered_frame() {
+            Ok(None) => {
+                // the batch is drained, send its replies before waiting for more, a client that
+                // doesn't read them is left once killed, e.g. past its output buffer limit
+                tokio::select! {
+                    biased;
+                    _ = context.killed() => return Ok(()),
+                    result = conn.flush() => result?,
+                }
+                tokio::select! {
+                    biased;
+                    _ = context.killed() => return Ok(()),
+                    Some(push) = pushes.recv() => {
+                        conn.write_frame(&push, context.protocol_version());
+                        continue;
+                    }
+                    frame = conn.read_frame() => frame,
+                }
+            }
+            frame => frame,
+        };
+        let frame = match frame {
+            Ok(Some(frame)) => frame,
+            Ok(None) => return Ok(()),
+            Err(FrameError::Io(e)) => return Err(e.into()),
+            Err(e) => {
+                // malformed input can't be resynchronized, reply the error and disconnect
+                log::warn!("ctx {} protocol error: {}", context.id, e);
+                let message = match e {
+                    FrameError::Protocol(_) => format!("ERR {}", e),
+                    other => format!("ERR Protocol error: {}", other),
+                };
+                conn.write_frame(&Frame::Error(message), context.protocol_version());
+                conn.flush().await?;
+                return Ok(());
+            }
+        };
+        let ctx = context.clone();
+        let cid = ctx.id;
+        let result = match Command::parse(frame).await {
+            Ok(cmd) if cmd.may_block() => {
+                let name = cmd.name().to_string();
+                context.touch(&name);
+                // a client gone while blocked mustn't take the element it waited for
+                let result = tokio::select! {
+                    result = cmd.execute(ctx) => result?,
+                    _ = conn.wait_closed() => return Ok(()),
+                };
+                // the time it was blocked isn't idle time
+                context.touch(&name);
+                result
+            }
+            Ok(cmd) => {
+                context.touch(cmd.name());
+                cmd.execute(ctx).await?
+            }
+            Err(e) => {
+                log::error!("ctx {} parse command error: {:?}", cid, e);
+                Frame::Error(e.to_string())
+            }
+        };
+        log::debug!("ctx {} execute result: {:?}", cid, &result);
+        if context.discards_reply() {
+            conn.flush().await?;
+            return Ok(());
+        }
+        // frames pushed by the command itself precede its reply
+        while let Ok(push) = pushes.try_recv() {
+            conn.write_frame(&push, context.protocol_version());
+        }
+        if !context.take_skip_reply() {
+            conn.write_frame(&result, context.protocol_version());
+        }
+        if context.is_killed() {
+            // e.g. `QUIT` or `CLIENT KILL` against the client itself, the reply is sent first
+            conn.flush().await?;
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, os::unix::fs::PermissionsExt, sync::Arc, time::Duration};
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpStream, UnixStream},
+    };
+
+    use crate::{
+        command::registry::do_register,
+        context::Context,
+        server::{
+            Admissions, Listener, bind_tcp_listeners, bind_unix_listener, close_idle_clients,
+            handle_socket, serve,
+        },
+        storage::database::Databases,
+    };
+
+    async fn ping<S: AsyncReadExt + AsyncWriteExt + Unpin>(mut stream: S) {
+        stream.write_all(b"PING\r\n").await.unwrap();
+        let mut buf = [0u8; 7];
+        stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"+PONG\r\n");
+    }
+
+    #[tokio::test]
+    async fn test_tcp_listeners() {
+        do_register().await;
+        let bind = vec!["127.0.0.1".to_string(), "-192.0.2.1".to_string()];
+        let mut listeners = bind_tcp_listeners(&bind, 0).unwrap();
+        // the optional address isn't assigned to this host and is skipped
+        assert_eq!(listeners.len(), 1);
+        assert!(bind_tcp_listeners(&["192.0.2.1".to_string()], 0).is_err());
+        assert!(bind_tcp_listeners(&["localhost".to_string()], 0).is_err());
+
+        let listener = Listener::Tcp(listeners.pop().unwrap());
+        let addr = listener.local_addr();
+        tokio::spawn(serve(listener, Arc::new(Databases::new(1))));
+        ping(TcpStream::connect(addr).await.unwrap()).await;
+    }
+
+    #[tokio::test]
+    async fn test_unix_listener() {
+        do_register().await;
+        let path = std::env::temp_dir().join(format!("rudis-test-{}.sock", std::process::id()));
+        fs::write(&path, b"stale").unwrap();
+
+        let listener = bind_unix_listener(&path, Some(0o700)).unwrap();
+        assert!(matches!(listener, Listener::Unix(..)));
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+
+        tokio::spawn(serve(listener, Arc::new(Databases::new(1))));
+        ping(UnixStream::connect(&path).await.unwrap()).await;
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_close_idle_clients() {
+        let idle = Context::test_client(1);
+        let active = Context::test_client(1);
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        active.touch("ping");
+
+        let clients = [idle.clone(), active.clone()];
+        assert_eq!(close_idle_clients(&clients, Duration::from_millis(20)), 1);
+        assert!(idle.is_killed());
+        assert!(!active.is_killed());
+        assert_eq!(close_idle_clients(&clients, Duration::from_millis(20)), 0);
+    }
+
+    #[test]
+    fn test_admissions() {
+        let admissions = Admissions::new();
+        let first = admissions.admit(2).unwrap();
+        let _second = admissions.admit(2).unwrap();
+        assert!(admissions.admit(2).is_none());
+        // a rejected client doesn't keep a slot
+        assert!(admissions.admit(2).is_none());
+        drop(first);
+        assert!(admissions.admit(2).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_idle_client_disconnected() {
+        do_register().await;
+        let ctx = Context::test_client(1);
+        let (mut client, server) = tokio::io::duplex(1024);
+        let served = tokio::spawn(handle_socket(server, ctx.clone()));
+        ping(&mut client).await;
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(close_idle_clients(&[ctx], Duration::from_millis(20)), 1);
+        served.await.unwrap().unwrap();
+        let mut buf = [0u8; 1];
+        assert_eq!(client.read(&mut buf).await.unwrap(), 0);
+    }
+}
@@ -0,0 +1,135 @@
+use std::sync::{
+    Mutex,
+    atomic::{AtomicU8, Ordering},
+};
+
+use once_cell::sync::Lazy;
+use tokio::{
+    signal::unix::{SignalKind, signal},
+    sync::Notify,
+};
+
+/// The shutdown state of the server
+pub static SHUTDOWN: Lazy<Shutdown> = Lazy::new(Shutdown::new);
+
+const RUNNING: u8 = 0;
+const REQUESTED: u8 = 1;
+const CLOSING: u8 = 2;
+
+/// Options of `SHUTDOWN [NOSAVE|SAVE] [NOW] [FORCE]`, signals use the defaults
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ShutdownFlags {
+    /// `Some(true)` for `SAVE`, `Some(false)` for `NOSAVE`
+    pub save: Option<bool>,
+    /// Close clients without waiting for their in-flight commands
+    pub now: bool,
+    /// Ignore errors which would otherwise abort the shutdown
+    pub force: bool,
+}
+
+/// Coordinates a shutdown requested by a signal or the `SHUTDOWN` command.
+///
+/// A request stays pending until the server picks it up, it can be cancelled by
+/// `SHUTDOWN ABORT` until then.
+pub struct Shutdown {
+    state: AtomicU8,
+    flags: Mutex<ShutdownFlags>,
+    notify: Notify,
+}
+
+impl Shutdown {
+    fn new() -> Self {
+        Self {
+            state: AtomicU8::new(RUNNING),
+            flags: Mutex::new(ShutdownFlags::default()),
+            notify: Notify::new(),
+        }
+    }
+
+    pub fn request(&self, flags: ShutdownFlags) {
+        *self.flags.lock().unwrap() = flags;
+        if self
+            .state
+            .compare_exchange(RUNNING, REQUESTED, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+        {
+            self.notify.notify_one();
+        }
+    }
+
+    /// Cancel a pending request, returns false when there is none
+    pub fn abort(&self) -> bool {
+        self.state
+            .compare_exchange(REQUESTED, RUNNING, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+    }
+
+    pub fn in_progress(&self) -> bool {
+        self.state.load(Ordering::Acquire) != RUNNING
+    }
+
+    /// Wait until a shutdown is requested by a command, SIGINT or SIGTERM, and start closing.
+    pub async fn wait(&self) -> ShutdownFlags {
+        let mut sigterm = signal(SignalKind::terminate()).ok();
+        let mut sigint = signal(SignalKind::interrupt()).ok();
+        loop {
+            tokio::select! {
+                _ = self.notify.notified() => {}
+                Some(_) = recv(&mut sigterm) => {
+                    log::warn!("received SIGTERM, scheduling shutdown");
+                    self.request(ShutdownFlags::default());
+                }
+                Some(_) = recv(&mut sigint) => {
+                    log::warn!("received SIGINT, scheduling shutdown");
+                    self.request(ShutdownFlags::default());
+                }
+            }
+            if self
+                .state
+                .compare_exchange(REQUESTED, CLOSING, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                return *self.flags.lock().unwrap();
+            }
+        }
+    }
+}
+
+async fn recv(signal: &mut Option<tokio::signal::unix::Signal>) -> Option<()> {
+    match signal {
+        Some(signal) => signal.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::shutdown::{Shutdown, ShutdownFlags};
+
+    #[tokio::test]
+    async fn test_shutdown_request_and_abort() {
+        let shutdown = Shutdown::new();
+        assert!(!shutdown.abort());
+
+        shutdown.request(ShutdownFlags::default());
+        assert!(shutdown.in_progress());
+        assert!(shutdown.abort());
+        assert!(!shutdown.in_progress());
+        // the aborted request doesn't wake the server up
+        let waited = tokio::time::timeout(Duration::from_millis(20), shutdown.wait()).await;
+        assert!(waited.is_err());
+
+        let flags = ShutdownFlags {
+            save: Some(false),
+            now: true,
+            force: false,
+        };
+        shutdown.request(flags);
+        assert_eq!(shutdown.wait().await, flags);
+        // too late to abort once the server is closing
+        assert!(!shutdown.abort());
+        assert!(shutdown.in_progress());
+    }
+}
@@ -0,0 +1,45 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use once_cell::sync::Lazy;
+
+/// Server wide counters, reported by `INFO`
+pub static STATS: Lazy<Stats> = Lazy::new(Stats::new);
+
+pub struct Stats {
+    pub started: Instant,
+    /// Keys deleted because their TTL elapsed, either lazily or by the active expire cycle
+    pub expired_keys: AtomicU64,
+    /// Hash fields deleted because their TTL elapsed
+    pub expired_subkeys: AtomicU64,
+    /// Time spent in the active expire cycle
+    pub expire_cycle_cpu_microseconds: AtomicU64,
+    /// Active expire cycles which stopped because they used up their time budget
+    pub expired_time_cap_reached_count: AtomicU64,
+    /// Keys deleted to keep the used memory below `maxmemory`
+    pub evicted_keys: AtomicU64,
+}
+
+impl Stats {
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            expired_keys: AtomicU64::new(0),
+            expired_subkeys: AtomicU64::new(0),
+            expire_cycle_cpu_microseconds: AtomicU64::new(0),
+            expired_time_cap_reached_count: AtomicU64::new(0),
+            evicted_keys: AtomicU64::new(0),
+        }
+    }
+
+    pub fn record_expire_cycle(&self, elapsed: Duration, time_cap_reached: bool) {
+        self.expire_cycle_cpu_microseconds
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+        if time_cap_reached {
+            self.expired_time_cap_reached_count
+                .fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
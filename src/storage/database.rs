@@ -1,30 +1,371 @@
-use std::time::{Duration, SystemTime};
+use std::{
+    mem,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
-use dashmap::DashMap;
+use dashmap::{DashMap, Entry, mapref::one::RefMut};
 
-use crate::object::redis_object::RedisObject;
+use crate::{
+    blocked::BLOCKED,
+    config::get_server_config,
+    notify::{self, notify_keyspace_event},
+    object::redis_object::{ObjectType, RedisObject, RedisValue},
+    stats::STATS,
+    storage::{
+        evict::access_lru,
+        expires::{self, Expires},
+        lazyfree::{free_object, free_objects_async},
+        scan::KeyIndex,
+    },
+    util::{string_match, unix_millis},
+};
+
+/// Attempts of `random_key` to find a key which isn't expired
+const RANDOM_KEY_TRIES: usize = 100;
 
 pub struct Database {
+    /// Index of the database, it changes when `SWAPDB` moves the database
+    id: AtomicUsize,
     data: DashMap<String, RedisObject>,
-    expires: DashMap<String, SystemTime>,
+    expires: Expires,
+    /// Hashes with expiring fields by the earliest expire time of their fields
+    field_expires: Expires,
+    /// The keys of `data` in the order `SCAN` visits them
+    keys: KeyIndex,
+    /// Bytes taken by the keys and values of `data`, see `key_overhead`
+    used_memory: AtomicUsize,
+}
+
+/// Bytes taken by a key in `data` and `keys` besides its value
+pub fn key_overhead(key: &str) -> usize {
+    size_of::<(String, RedisObject)>() + size_of::<(u64, String)>() + 2 * key.len()
+}
+
+/// The time of a field expire time in unix milliseconds
+fn field_expire_time(at: i64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(at.max(0) as u64)
 }
 
 impl Database {
-    pub fn new(_: usize) -> Self {
+    pub fn new(id: usize) -> Self {
         Database {
+            id: AtomicUsize::new(id),
             data: DashMap::new(),
-            expires: DashMap::new(),
+            expires: Expires::new(),
+            field_expires: Expires::new(),
+            keys: KeyIndex::new(),
+            used_memory: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn id(&self) -> usize {
+        self.id.load(Ordering::Relaxed)
+    }
+
+    /// Count of keys, including the expired ones which haven't been removed yet
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Count of keys with an expire time
+    pub fn expires_len(&self) -> usize {
+        self.expires.len()
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        !self.expire_if_needed(key) && self.data.contains_key(key)
+    }
+
+    /// Remove the key, returns its value and the absolute expire time
+    pub fn remove(&self, key: &str) -> Option<(RedisObject, Option<SystemTime>)> {
+        if self.expire_if_needed(key) {
+            return None;
         }
+        self.remove_value_if(key, |_| true)
+    }
+
+    /// Delete the key, its value is dropped on the lazy free thread when `lazy` and it's large
+    pub fn delete(&self, key: &str, lazy: bool) -> bool {
+        match self.remove(key) {
+            Some((value, _)) => {
+                free_object(value, lazy);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Insert a key with an absolute expire time, e.g. the one returned by `remove`
+    pub fn insert(&self, key: String, value: RedisObject, expire: Option<SystemTime>) {
+        let _ = self.insert_value(key, value, expire, true);
+    }
+
+    /// Like `insert`, unless the key exists, which is checked under the lock of its shard.
+    /// The value is given back when the key exists.
+    pub fn insert_if_absent(
+        &self,
+        key: String,
+        value: RedisObject,
+        expire: Option<SystemTime>,
+    ) -> Result<(), RedisObject> {
+        self.expire_if_needed(&key);
+        self.insert_value(key, value, expire, false)
+    }
+
+    /// Bytes taken by the keys, values and expire times, kept up to date as keys change
+    pub fn used_memory(&self) -> usize {
+        self.used_memory.load(Ordering::Relaxed)
+            + self.expires.used_memory()
+            + self.field_expires.used_memory()
+    }
+
+    /// Bytes taken by the tables of the keys and of the expire times
+    pub fn overhead(&self) -> (usize, usize) {
+        let main = self.len() * (size_of::<(String, RedisObject)>() + size_of::<(u64, String)>());
+        (
+            main,
+            self.expires.used_memory() + self.field_expires.used_memory(),
+        )
+    }
+
+    /// Bytes taken by the key with its value and expire times, see `RedisValue::mem_usage`
+    pub fn memory_usage(&self, key: &str) -> Option<usize> {
+        let usage = self.peek_with(key, |value| key_overhead(key) + value.mem_usage())?;
+        let indexes = [&self.expires, &self.field_expires];
+        Some(
+            usage
+                + indexes
+                    .iter()
+                    .filter(|index| index.get(key).is_some())
+                    .map(|_| expires::entry_size(key))
+                    .sum::<usize>(),
+        )
+    }
+
+    /// Remove every key
+    pub fn clear(&self) {
+        self.clear_with(drop);
+    }
+
+    /// Remove every key, the values are dropped on the lazy free thread
+    pub fn clear_async(&self) {
+        let mut values = Vec::with_capacity(self.len());
+        self.clear_with(|value| values.push(value));
+        free_objects_async(values);
+    }
+
+    fn clear_with(&self, mut free: impl FnMut(RedisObject)) {
+        self.data.retain(|key, value| {
+            self.keys.remove(key);
+            self.used_memory
+                .fetch_sub(key_overhead(key) + value.mem_usage(), Ordering::Relaxed);
+            free(mem::replace(
+                value,
+                RedisObject::new(ObjectType::String, RedisValue::Int(0)),
+            ));
+            false
+        });
+        self.expires.clear();
+        self.field_expires.clear();
+    }
+
+    /// Update `data`, `keys`, `used_memory` and the expire time together, under the lock of
+    /// the shard of the key. An existing key is left alone and the value given back unless
+    /// `replace`.
+    fn insert_value(
+        &self,
+        key: String,
+        value: RedisObject,
+        expire: Option<SystemTime>,
+        replace: bool,
+    ) -> Result<(), RedisObject> {
+        let size = value.mem_usage();
+        let notify_key = (notify::flags() & notify::NEW != 0).then(|| key.clone());
+        // e.g. a list renamed or copied to a key clients wait for
+        let ready_key = (value.header.obj_type() == ObjectType::List && !BLOCKED.is_empty())
+            .then(|| key.clone());
+        let field_expire = value.ptr.hash_min_expire();
+        let field_expire_key = field_expire.map(|_| key.clone());
+        // a key expired meanwhile can't take a new value along
+        let set_expire = |key: &String| match expire {
+            Some(at) => self.expires.insert(key.clone(), at),
+            None => self.expires.remove(key),
+        };
+        let old = match self.data.entry(key) {
+            Entry::Occupied(_) if !replace => return Err(value),
+            Entry::Occupied(mut entry) => {
+                set_expire(entry.key());
+                self.used_memory.fetch_add(size, Ordering::Relaxed);
+                let old = entry.insert(value);
+                self.used_memory
+                    .fetch_sub(old.mem_usage(), Ordering::Relaxed);
+                if field_expire.is_none() && old.ptr.hash_min_expire().is_some() {
+                    self.field_expires.remove(entry.key());
+                }
+                Some(old)
+            }
+            Entry::Vacant(entry) => {
+                set_expire(entry.key());
+                self.keys.insert(entry.key());
+                self.used_memory
+                    .fetch_add(key_overhead(entry.key()) + size, Ordering::Relaxed);
+                entry.insert(value);
+                None
+            }
+        };
+        if let (Some(key), Some(at)) = (field_expire_key, field_expire) {
+            self.field_expires.insert(key, field_expire_time(at));
+        }
+        if let Some(key) = ready_key {
+            BLOCKED.signal(self.id(), &key);
+        }
+        // freed and published once the shard is unlocked
+        match old {
+            Some(old) => free_object(old, get_server_config().lazyfree_lazy_server_del),
+            None => {
+                if let Some(key) = notify_key {
+                    notify_keyspace_event(notify::NEW, "new", &key, self.id());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Modify the value of `key` in place by `f`, `None` when the key doesn't exist.
+    ///
+    /// `used_memory` follows the size of the value, and the key is deleted once `f` leaves
+    /// its collection empty. `f` runs under the lock of the shard, so it must not access the
+    /// database.
+    pub fn modify<F, R>(&self, key: &str, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut RedisObject) -> R,
+    {
+        if self.expire_if_needed(key) {
+            return None;
+        }
+        let value = self.data.get_mut(key)?;
+        Some(self.update(key, value, true, f))
+    }
+
+    /// Like `modify`, a missing key is added with the value made by `create` first
+    pub fn modify_or_insert<C, F, R>(&self, key: &str, create: C, f: F) -> R
+    where
+        C: FnOnce() -> RedisObject,
+        F: FnOnce(&mut RedisObject) -> R,
+    {
+        self.expire_if_needed(key);
+        let value = match self.data.entry(key.to_string()) {
+            Entry::Occupied(entry) => entry.into_ref(),
+            Entry::Vacant(entry) => {
+                let value = create();
+                self.keys.insert(entry.key());
+                self.used_memory
+                    .fetch_add(key_overhead(key) + value.mem_usage(), Ordering::Relaxed);
+                notify_keyspace_event(notify::NEW, "new", key, self.id());
+                entry.insert(value)
+            }
+        };
+        self.update(key, value, true, f)
+    }
+
+    /// Run `f` on the locked value, `touch` counts it as an access
+    fn update<F, R>(
+        &self,
+        key: &str,
+        mut value: RefMut<'_, String, RedisObject>,
+        touch: bool,
+        f: F,
+    ) -> R
+    where
+        F: FnOnce(&mut RedisObject) -> R,
+    {
+        let before = value.mem_usage();
+        let field_expire = value.ptr.hash_min_expire();
+        let result = f(&mut value);
+        self.used_memory
+            .fetch_add(value.mem_usage(), Ordering::Relaxed);
+        self.used_memory.fetch_sub(before, Ordering::Relaxed);
+        if touch {
+            let lru = value.header.lru();
+            value.header.set_lru(access_lru(lru));
+        }
+        let new_field_expire = value.ptr.hash_min_expire();
+        if new_field_expire != field_expire {
+            match new_field_expire {
+                Some(at) => self
+                    .field_expires
+                    .insert(key.to_string(), field_expire_time(at)),
+                None => self.field_expires.remove(key),
+            };
+        }
+        let empty = value.ptr.is_empty_collection();
+        let list = value.header.obj_type() == ObjectType::List;
+        drop(value);
+        if list && !empty {
+            BLOCKED.signal(self.id(), key);
+        }
+        if empty {
+            // unless another client filled the collection in the meantime
+            if self
+                .remove_value_if(key, |value| value.ptr.is_empty_collection())
+                .is_some()
+            {
+                notify_keyspace_event(notify::GENERIC, "del", key, self.id());
+            }
+        }
+        result
+    }
+
+    /// Publish the `keymiss` event of a read of a missing key
+    fn notify_miss(&self, key: &str) {
+        notify_keyspace_event(notify::KEY_MISS, "keymiss", key, self.id());
+    }
+
+    /// Remove the key when its value matches `f`, checked under the lock of its shard.
+    /// Returns the value and the expire time, which is removed under the lock as well.
+    fn remove_value_if(
+        &self,
+        key: &str,
+        f: impl FnOnce(&RedisObject) -> bool,
+    ) -> Option<(RedisObject, Option<SystemTime>)> {
+        let mut expire = None;
+        let (_, value) = self.data.remove_if(key, |key, value| {
+            if !f(value) {
+                return false;
+            }
+            // still under the lock of the shard, a concurrent insert can't be unindexed
+            expire = self.expires.remove(key);
+            self.keys.remove(key);
+            if value.ptr.hash_min_expire().is_some() {
+                self.field_expires.remove(key);
+            }
+            self.used_memory
+                .fetch_sub(key_overhead(key) + value.mem_usage(), Ordering::Relaxed);
+            true
+        })?;
+        Some((value, expire))
     }
 
     /// Returns the value's clone by key.
     pub fn get(&self, key: &str) -> Option<RedisObject> {
-        if self.is_expired(key) {
-            self.data.remove(key);
+        let value = if self.expire_if_needed(key) {
             None
         } else {
+            self.touch_value(key);
             self.data.get(key).map(|val| val.clone())
+        };
+        if value.is_none() {
+            self.notify_miss(key);
         }
+        value
     }
 
     /// Access the value by the closure `f`
@@ -32,27 +373,303 @@ impl Database {
     where
         F: FnOnce(&RedisObject) -> R,
     {
-        if self.is_expired(key) {
-            self.data.remove(key);
+        let result = if self.expire_if_needed(key) {
+            None
+        } else {
+            self.touch_value(key);
+            self.data.get(key).map(|ref_val| f(&ref_val))
+        };
+        if result.is_none() {
+            self.notify_miss(key);
+        }
+        result
+    }
+
+    /// Access the value by the closure `f` without counting it as an access, e.g. for `OBJECT`
+    pub fn peek_with<F, R>(&self, key: &str, f: F) -> Option<R>
+    where
+        F: FnOnce(&RedisObject) -> R,
+    {
+        if self.expire_if_needed(key) {
             None
         } else {
-            self.data.get(key).map(|ref_val| f(&*ref_val))
+            self.data.get(key).map(|ref_val| f(&ref_val))
         }
     }
 
+    /// Stamp an access on the `lru` field of the key, which drives LRU and LFU eviction.
+    ///
+    /// The value is only locked for writing when the field changes, once per clock tick.
+    fn touch_value(&self, key: &str) {
+        let Some(lru) = self.lru(key) else {
+            return;
+        };
+        let new_lru = access_lru(lru);
+        if new_lru != lru
+            && let Some(mut value) = self.data.get_mut(key)
+        {
+            value.header.set_lru(new_lru);
+        }
+    }
+
+    /// Update the access time of the key, returns false when it doesn't exist
+    pub fn touch(&self, key: &str) -> bool {
+        if self.expire_if_needed(key) || !self.data.contains_key(key) {
+            return false;
+        }
+        self.touch_value(key);
+        true
+    }
+
+    /// The `lru` field of the key, reading it doesn't count as an access
+    pub fn lru(&self, key: &str) -> Option<u32> {
+        self.data.get(key).map(|value| value.header.lru())
+    }
+
     pub fn set(&self, key: String, value: RedisObject, ttl: Option<Duration>) {
-        self.data.insert(key.clone(), value);
-        if let Some(ttl) = ttl {
-            self.expires.insert(key, SystemTime::now() + ttl);
+        self.insert(key, value, ttl.map(|ttl| SystemTime::now() + ttl));
+    }
+
+    /// Absolute expire time of the key
+    pub fn expire_time(&self, key: &str) -> Option<SystemTime> {
+        if self.expire_if_needed(key) {
+            None
         } else {
-            self.expires.remove(&key);
+            self.expires.get(key)
+        }
+    }
+
+    /// Set the absolute expire time of an existing key, returns false when it doesn't exist
+    pub fn set_expire(&self, key: &str, at: SystemTime) -> bool {
+        if self.expire_if_needed(key) {
+            return false;
         }
+        // hold the entry, so the key can't be removed before it gets its expire time
+        let Some(_entry) = self.data.get(key) else {
+            return false;
+        };
+        self.expires.insert(key.to_string(), at);
+        true
     }
 
-    fn is_expired(&self, key: &str) -> bool {
-        self.expires
-            .remove_if(key, |_, v| SystemTime::now() > *v)
-            .is_some()
+    /// Remove the expire time of the key, returns false when it doesn't exist or has none
+    pub fn persist(&self, key: &str) -> bool {
+        !self.expire_if_needed(key) && self.expires.remove(key).is_some()
+    }
+
+    pub fn key_type(&self, key: &str) -> Option<ObjectType> {
+        self.peek_with(key, |value| value.header.obj_type())
+    }
+
+    /// Rename `key` to `new_key`, the expire time moves along with the value.
+    ///
+    /// Returns `None` when `key` doesn't exist and `Some(false)` when `nx` is set and
+    /// `new_key` exists already.
+    pub fn rename(&self, key: &str, new_key: String, nx: bool) -> Option<bool> {
+        if !self.contains_key(key) {
+            return None;
+        }
+        if key == new_key {
+            return Some(!nx);
+        }
+        if nx && self.contains_key(&new_key) {
+            return Some(false);
+        }
+        let (value, expire) = self.remove(key)?;
+        self.insert(new_key, value, expire);
+        Some(true)
+    }
+
+    /// Copy `key` to `new_key` of `target` along with its expire time.
+    ///
+    /// Returns false when `key` doesn't exist, or `new_key` does and `replace` isn't set.
+    pub fn copy_to(&self, key: &str, target: &Database, new_key: String, replace: bool) -> bool {
+        let Some(value) = self.get(key) else {
+            return false;
+        };
+        if !replace && target.contains_key(&new_key) {
+            return false;
+        }
+        target.insert(new_key, value, self.expires.get(key));
+        true
+    }
+
+    /// Keys matching the glob-style `pattern`, expired keys are skipped
+    pub fn keys(&self, pattern: &str) -> Vec<String> {
+        let now = SystemTime::now();
+        let all = pattern == "*";
+        self.data
+            .iter()
+            .filter(|entry| all || string_match(pattern.as_bytes(), entry.key().as_bytes(), false))
+            .filter(|entry| self.expires.get(entry.key()).is_none_or(|at| at >= now))
+            .map(|entry| entry.key().clone())
+            .collect()
+    }
+
+    /// One page of `SCAN`, returns the next cursor and the keys matching the filters.
+    ///
+    /// At least `count` keys are visited, expired ones are deleted instead of returned.
+    pub fn scan(
+        &self,
+        cursor: u64,
+        count: usize,
+        pattern: Option<&str>,
+        obj_type: Option<ObjectType>,
+    ) -> (u64, Vec<String>) {
+        let (cursor, mut keys) = self.keys.scan(cursor, count);
+        keys.retain(|key| {
+            pattern.is_none_or(|pattern| string_match(pattern.as_bytes(), key.as_bytes(), false))
+                && !self.expire_if_needed(key)
+                && obj_type
+                    .as_ref()
+                    .is_none_or(|obj_type| self.key_type(key).as_ref() == Some(obj_type))
+        });
+        (cursor, keys)
+    }
+
+    /// A random key, expired keys met on the way are deleted
+    pub fn random_key(&self) -> Option<String> {
+        for _ in 0..RANDOM_KEY_TRIES {
+            let key = self.keys.sample(1).pop()?;
+            if !self.expire_if_needed(&key) {
+                return Some(key);
+            }
+        }
+        None
+    }
+
+    /// Up to `count` random keys, only keys with an expire time when `volatile` is set.
+    ///
+    /// The keys are neighbours in the index, expired ones aren't skipped.
+    pub fn sample_keys(&self, count: usize, volatile: bool) -> Vec<String> {
+        if volatile {
+            self.expires.sample(count)
+        } else {
+            self.keys.sample(count)
+        }
+    }
+
+    /// Delete the key if it's expired, returns whether it was. The expired fields of a hash
+    /// are removed otherwise.
+    fn expire_if_needed(&self, key: &str) -> bool {
+        let now = SystemTime::now();
+        if self.expires.get(key).is_none_or(|at| at >= now) {
+            if self.field_expires.get(key).is_some_and(|at| at < now) {
+                self.expire_fields(key);
+            }
+            return false;
+        }
+        self.expire_key(key, now, get_server_config().lazyfree_lazy_expire)
+    }
+
+    /// Delete the key if it's expired at `now`, returns whether it was.
+    ///
+    /// The expire time is checked again under the lock of the shard, a key which got a new
+    /// value or expire time since it was found expired is kept.
+    fn expire_key(&self, key: &str, now: SystemTime, lazy: bool) -> bool {
+        let expired = |_: &RedisObject| self.expires.get(key).is_some_and(|at| at < now);
+        let Some((value, _)) = self.remove_value_if(key, expired) else {
+            // an expire time left without its key mustn't be found again
+            self.expires.remove_expired(key, now);
+            return false;
+        };
+        free_object(value, lazy);
+        STATS.expired_keys.fetch_add(1, Ordering::Relaxed);
+        notify_keyspace_event(notify::EXPIRED, "expired", key, self.id());
+        true
+    }
+
+    /// One step of the active expire cycle, up to `count` keys with an expire time are
+    /// checked. Returns the count of checked and deleted keys.
+    pub fn active_expire(&self, count: usize) -> (usize, usize) {
+        let now = SystemTime::now();
+        let (checked, expired) = self.expires.find_expired(count, now);
+        let lazy = get_server_config().lazyfree_lazy_expire;
+        let deleted = expired
+            .iter()
+            .filter(|key| self.expire_key(key, now, lazy))
+            .count();
+        (checked, deleted)
+    }
+
+    /// Remove the expired fields of the hash `key`, the key is deleted with its last field.
+    /// Returns the count of removed fields.
+    fn expire_fields(&self, key: &str) -> usize {
+        let Some(value) = self.data.get_mut(key) else {
+            return 0;
+        };
+        let now = unix_millis(SystemTime::now());
+        let id = self.id();
+        let expired = self.update(key, value, false, |value| {
+            let expired = value.ptr.hash_remove_expired(now);
+            if expired > 0 {
+                notify_keyspace_event(notify::HASH, "hexpired", key, id);
+            }
+            expired
+        });
+        STATS
+            .expired_subkeys
+            .fetch_add(expired as u64, Ordering::Relaxed);
+        expired
+    }
+
+    /// One step of the active expire cycle over the hashes with expiring fields, up to
+    /// `count` of them are checked. Returns the count of checked hashes and of the ones with
+    /// expired fields.
+    pub fn active_expire_fields(&self, count: usize) -> (usize, usize) {
+        let (checked, expired) = self.field_expires.take_expired(count, SystemTime::now());
+        for key in expired.iter() {
+            self.expire_fields(key);
+        }
+        (checked, expired.len())
+    }
+}
+
+/// The logical databases of the server, `databases`.
+///
+/// Clients refer to a database by index, so `SWAPDB` is visible to every client which
+/// selected one of the swapped databases.
+pub struct Databases {
+    dbs: RwLock<Vec<Arc<Database>>>,
+}
+
+impl Databases {
+    pub fn new(count: usize) -> Self {
+        Self {
+            dbs: RwLock::new((0..count).map(|id| Arc::new(Database::new(id))).collect()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.dbs.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dbs.read().unwrap().is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<Arc<Database>> {
+        self.dbs.read().unwrap().get(index).cloned()
+    }
+
+    /// Snapshot of all databases ordered by index
+    pub fn all(&self) -> Vec<Arc<Database>> {
+        self.dbs.read().unwrap().clone()
+    }
+
+    /// Swap the databases at `a` and `b`, returns false when an index is out of range
+    pub fn swap(&self, a: usize, b: usize) -> bool {
+        let mut dbs = self.dbs.write().unwrap();
+        if a >= dbs.len() || b >= dbs.len() {
+            return false;
+        }
+        dbs.swap(a, b);
+        dbs[a].id.store(a, Ordering::Relaxed);
+        dbs[b].id.store(b, Ordering::Relaxed);
+        // the clients blocked in either database may find their keys now
+        BLOCKED.signal_db(a);
+        BLOCKED.signal_db(b);
+        true
     }
 }
 
@@ -62,6 +679,12 @@ mod test {
     use std::collections::HashMap;
     #[cfg(test)]
     use std::mem;
+    use std::time::{Duration, SystemTime};
+
+    use crate::{
+        object::redis_object::{ObjectType, RedisObject},
+        storage::database::{Database, Databases},
+    };
 
     #[test]
     fn test() {
@@ -74,4 +697,176 @@ mod test {
         let instant = std::time::Instant::now();
         let _ = instant.elapsed();
     }
+
+    #[test]
+    fn test_remove_and_insert_keep_expire() {
+        let db = Database::new(0);
+        db.set(
+            "k".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            Some(Duration::from_secs(60)),
+        );
+        assert!(db.contains_key("k"));
+        let (value, expire) = db.remove("k").unwrap();
+        assert!(expire.unwrap() > SystemTime::now());
+        assert!(db.is_empty());
+
+        let other = Database::new(1);
+        other.insert("k".to_string(), value, expire);
+        assert!(other.contains_key("k"));
+        other.clear();
+        assert!(!other.contains_key("k"));
+    }
+
+    #[test]
+    fn test_insert_if_absent() {
+        let db = Database::new(0);
+        let value = |v: &[u8]| RedisObject::new_string(v.to_vec());
+        let expire = SystemTime::now() + Duration::from_secs(60);
+        assert!(
+            db.insert_if_absent("k".to_string(), value(b"a"), Some(expire))
+                .is_ok()
+        );
+        assert_eq!(db.expire_time("k"), Some(expire));
+        let rejected = db
+            .insert_if_absent("k".to_string(), value(b"b"), None)
+            .unwrap_err();
+        assert_eq!(rejected.ptr, value(b"b").ptr);
+        assert_eq!(db.get("k").unwrap().ptr, value(b"a").ptr);
+        assert_eq!(db.expire_time("k"), Some(expire));
+    }
+
+    #[test]
+    fn test_expire_key_keeps_a_key_set_again() {
+        let db = Database::new(0);
+        let value = || RedisObject::new_string(b"v".to_vec());
+        db.set("k".to_string(), value(), Some(Duration::ZERO));
+        let found_expired = SystemTime::now() + Duration::from_millis(1);
+        // set again between the expire time being found and the key being deleted
+        db.set("k".to_string(), value(), None);
+        assert!(!db.expire_key("k", found_expired, false));
+        db.set("k".to_string(), value(), Some(Duration::from_secs(60)));
+        assert!(!db.expire_key("k", found_expired, false));
+        assert!(db.contains_key("k"));
+        assert!(db.expire_key("k", SystemTime::now() + Duration::from_secs(61), false));
+        assert!(db.is_empty());
+        assert_eq!(db.expires_len(), 0);
+    }
+
+    #[test]
+    fn test_used_memory() {
+        let db = Database::new(0);
+        let value = |len| RedisObject::new_string(vec![b'v'; len]);
+        db.set("a".to_string(), value(100), None);
+        let a = db.used_memory();
+        assert_eq!(a, db.memory_usage("a").unwrap());
+        db.set("b".to_string(), value(200), Some(Duration::from_secs(60)));
+        assert_eq!(db.used_memory(), a + db.memory_usage("b").unwrap());
+        // overwriting a value accounts for the difference only
+        db.set("a".to_string(), value(1000), None);
+        assert_eq!(db.used_memory(), a + 900 + db.memory_usage("b").unwrap());
+        db.remove("b");
+        assert_eq!(db.used_memory(), a + 900);
+        db.clear();
+        assert_eq!(db.used_memory(), 0);
+    }
+
+    #[test]
+    fn test_rename_and_copy_keep_expire() {
+        let db = Database::new(0);
+        let value = || RedisObject::new_string(b"v".to_vec());
+        db.set("a".to_string(), value(), Some(Duration::from_secs(60)));
+        db.set("b".to_string(), value(), None);
+
+        assert_eq!(db.rename("missing", "c".to_string(), false), None);
+        assert_eq!(db.rename("a", "b".to_string(), true), Some(false));
+        assert_eq!(db.rename("a", "a".to_string(), false), Some(true));
+        assert_eq!(db.rename("a", "c".to_string(), false), Some(true));
+        assert!(!db.contains_key("a"));
+        assert!(db.expire_time("c").is_some());
+        // the target loses its own expire time
+        assert_eq!(db.rename("c", "b".to_string(), false), Some(true));
+        assert!(db.expire_time("b").is_some());
+        assert_eq!(db.len(), 1);
+
+        let other = Database::new(1);
+        other.set("b".to_string(), value(), None);
+        assert!(!db.copy_to("b", &other, "b".to_string(), false));
+        assert!(other.expire_time("b").is_none());
+        assert!(db.copy_to("b", &other, "b".to_string(), true));
+        assert!(other.expire_time("b").is_some());
+        assert!(db.copy_to("b", &db, "d".to_string(), false));
+        assert_eq!(db.len(), 2);
+        assert!(!db.copy_to("missing", &db, "e".to_string(), true));
+    }
+
+    #[test]
+    fn test_keys_and_random_key() {
+        let db = Database::new(0);
+        assert_eq!(db.random_key(), None);
+        for key in ["user:1", "user:2", "session:1"] {
+            db.set(
+                key.to_string(),
+                RedisObject::new_string(b"v".to_vec()),
+                None,
+            );
+        }
+        db.set(
+            "user:3".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            Some(Duration::ZERO),
+        );
+        std::thread::sleep(Duration::from_millis(1));
+
+        let mut keys = db.keys("user:*");
+        keys.sort();
+        assert_eq!(keys, vec!["user:1", "user:2"]);
+        assert_eq!(db.keys("*").len(), 3);
+        assert!(db.keys("nothing*").is_empty());
+        for _ in 0..10 {
+            assert_ne!(db.random_key().unwrap(), "user:3");
+        }
+    }
+
+    #[test]
+    fn test_scan() {
+        let db = Database::new(0);
+        for i in 0..20 {
+            let ttl = (i % 4 == 0).then_some(Duration::ZERO);
+            db.set(
+                format!("k{}", i),
+                RedisObject::new_string(b"v".to_vec()),
+                ttl,
+            );
+        }
+        std::thread::sleep(Duration::from_millis(1));
+
+        let (cursor, keys) = db.scan(0, 100, None, None);
+        assert_eq!((cursor, keys.len()), (0, 15));
+        // the expired keys were deleted on the way
+        assert_eq!(db.len(), 15);
+        let (_, keys) = db.scan(0, 100, Some("k1?"), Some(ObjectType::String));
+        assert_eq!(keys.len(), 8);
+        assert!(db.scan(0, 100, None, Some(ObjectType::Hash)).1.is_empty());
+
+        db.clear();
+        assert_eq!(db.scan(0, 100, None, None), (0, vec![]));
+    }
+
+    #[test]
+    fn test_swap_databases() {
+        let dbs = Databases::new(2);
+        dbs.get(0).unwrap().set(
+            "k".to_string(),
+            RedisObject::new_string(b"v".to_vec()),
+            None,
+        );
+        assert!(dbs.swap(0, 1));
+        assert!(!dbs.swap(0, 2));
+        let db1 = dbs.get(1).unwrap();
+        assert_eq!(db1.id(), 1);
+        assert!(db1.contains_key("k"));
+        assert!(dbs.get(0).unwrap().is_empty());
+        assert!(dbs.get(2).is_none());
+    }
 }
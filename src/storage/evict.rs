@@ -0,0 +1,404 @@
+use std::{
+    sync::{Arc, Mutex, atomic::Ordering},
+    time::SystemTime,
+};
+
+use once_cell::sync::Lazy;
+
+use crate::{
+    config::{MaxmemoryPolicy, get_server_config},
+    memory::used_memory,
+    notify::{self, notify_keyspace_event},
+    stats::STATS,
+    storage::{
+        database::{Database, Databases},
+        lazyfree::pending_objects,
+    },
+    util::unix_millis,
+};
+
+/// Largest value of the 24 bits `lru` field of `ObjectHeader`
+pub const LRU_CLOCK_MAX: u32 = (1 << 24) - 1;
+
+/// Milliseconds per tick of the LRU clock
+pub const LRU_CLOCK_RESOLUTION: u64 = 1000;
+
+/// Counter of a new key under the LFU policies, so it isn't evicted right away
+pub const LFU_INIT_VAL: u8 = 5;
+
+/// Candidates kept by the eviction pool across evictions, `EVPOOL_SIZE` of redis
+const EVPOOL_SIZE: usize = 16;
+
+/// The LRU clock, seconds wrapping around at `LRU_CLOCK_MAX`
+pub fn lru_clock() -> u32 {
+    ((unix_millis(SystemTime::now()) as u64 / LRU_CLOCK_RESOLUTION) & LRU_CLOCK_MAX as u64) as u32
+}
+
+/// Milliseconds since the access stamped as `lru`, once the clock wrapped around
+/// this underestimates keys idle for longer than 194 days
+pub fn estimate_idle_time(lru: u32) -> u64 {
+    let clock = lru_clock();
+    let ticks = if clock >= lru {
+        clock - lru
+    } else {
+        clock + (LRU_CLOCK_MAX - lru)
+    };
+    ticks as u64 * LRU_CLOCK_RESOLUTION
+}
+
+/// Minutes wrapping around at 16 bits, the high part of `lru` under the LFU policies
+pub fn lfu_time_in_minutes() -> u32 {
+    ((unix_millis(SystemTime::now()) as u64 / 60_000) & 65535) as u32
+}
+
+/// Minutes since `ldt`, the last decrement time of an LFU counter
+fn lfu_time_elapsed(ldt: u32) -> u32 {
+    let now = lfu_time_in_minutes();
+    if now >= ldt {
+        now - ldt
+    } else {
+        65535 - ldt + now
+    }
+}
+
+/// Logarithmic increment of an LFU counter, the higher it is and `lfu-log-factor` are,
+/// the less likely it grows
+pub fn lfu_log_incr(counter: u8) -> u8 {
+    if counter == u8::MAX {
+        return counter;
+    }
+    let base = counter.saturating_sub(LFU_INIT_VAL) as f64;
+    let p = 1.0 / (base * get_server_config().lfu_log_factor as f64 + 1.0);
+    if rand::random::<f64>() < p {
+        counter + 1
+    } else {
+        counter
+    }
+}
+
+/// The LFU counter of `lru` decremented once per `lfu-decay-time` minutes elapsed
+pub fn lfu_decr_and_return(lru: u32) -> u8 {
+    let counter = (lru & 255) as u8;
+    let decay_time = get_server_config().lfu_decay_time;
+    if decay_time == 0 {
+        return counter;
+    }
+    let periods = lfu_time_elapsed(lru >> 8) / decay_time;
+    counter.saturating_sub(periods.min(255) as u8)
+}
+
+/// `lru` field of a new object, the access time or the LFU counter with its decrement time
+pub fn initial_lru() -> u32 {
+    if get_server_config().maxmemory_policy.is_lfu() {
+        lfu_time_in_minutes() << 8 | LFU_INIT_VAL as u32
+    } else {
+        lru_clock()
+    }
+}
+
+/// `lru` field of an object accessed now
+pub fn access_lru(lru: u32) -> u32 {
+    if get_server_config().maxmemory_policy.is_lfu() {
+        let counter = lfu_log_incr(lfu_decr_and_return(lru));
+        lfu_time_in_minutes() << 8 | counter as u32
+    } else {
+        lru_clock()
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum EvictResult {
+    /// Used memory is below `maxmemory`
+    Ok,
+    /// Nothing could be evicted, commands which may grow memory are refused
+    Fail,
+    /// Still over `maxmemory` while the lazy free thread releases evicted values, commands
+    /// run as usual
+    Running,
+}
+
+/// Candidate of the eviction pool, the higher `idle` the better to evict
+#[derive(PartialEq, Eq, Debug)]
+struct PoolEntry {
+    idle: u64,
+    db: usize,
+    key: String,
+}
+
+/// Keys sampled for eviction ordered by ascending idle score, redis' `EvictionPoolLRU`.
+///
+/// Good candidates survive across evictions, so fewer samples approximate the true
+/// LRU, LFU or TTL order better.
+pub struct EvictionPool {
+    entries: Vec<PoolEntry>,
+    /// The database the next random eviction starts with
+    next_db: usize,
+}
+
+static POOL: Lazy<Mutex<EvictionPool>> = Lazy::new(|| Mutex::new(EvictionPool::new()));
+
+impl Default for EvictionPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EvictionPool {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::with_capacity(EVPOOL_SIZE),
+            next_db: 0,
+        }
+    }
+
+    /// Add a candidate, when the pool is full it replaces the one with the lowest score
+    fn insert(&mut self, idle: u64, db: usize, key: String) {
+        if self
+            .entries
+            .iter()
+            .any(|entry| entry.db == db && entry.key == key)
+        {
+            return;
+        }
+        let mut pos = self.entries.partition_point(|entry| entry.idle < idle);
+        if self.entries.len() >= EVPOOL_SIZE {
+            if pos == 0 {
+                return;
+            }
+            self.entries.remove(0);
+            pos -= 1;
+        }
+        self.entries.insert(pos, PoolEntry { idle, db, key });
+    }
+
+    /// Evict keys by `policy` while `over_limit`, fails when no key is left to evict
+    pub fn evict(
+        &mut self,
+        dbs: &Databases,
+        policy: MaxmemoryPolicy,
+        samples: usize,
+        over_limit: impl Fn() -> bool,
+    ) -> EvictResult {
+        if policy == MaxmemoryPolicy::NoEviction {
+            return EvictResult::Fail;
+        }
+        let lazy = get_server_config().lazyfree_lazy_eviction;
+        let dbs = dbs.all();
+        while over_limit() {
+            // the memory of lazily freed values is only released by the lazy free thread
+            if lazy && pending_objects() > 0 {
+                return EvictResult::Running;
+            }
+            let Some((db, key)) = self.pick(&dbs, policy, samples) else {
+                return EvictResult::Fail;
+            };
+            if dbs[db].delete(&key, lazy) {
+                log::debug!("evicted {} of db {} by {}", key, db, policy);
+                STATS.evicted_keys.fetch_add(1, Ordering::Relaxed);
+                notify_keyspace_event(notify::EVICTED, "evicted", &key, dbs[db].id());
+            }
+        }
+        EvictResult::Ok
+    }
+
+    /// The best key to evict as its database index and name
+    fn pick(
+        &mut self,
+        dbs: &[Arc<Database>],
+        policy: MaxmemoryPolicy,
+        samples: usize,
+    ) -> Option<(usize, String)> {
+        if dbs.is_empty() {
+            return None;
+        }
+        if policy.is_random() {
+            for _ in 0..dbs.len() {
+                let index = self.next_db % dbs.len();
+                self.next_db = (index + 1) % dbs.len();
+                let key = if policy.is_volatile() {
+                    dbs[index].sample_keys(1, true).pop()
+                } else {
+                    dbs[index].random_key()
+                };
+                if let Some(key) = key {
+                    return Some((index, key));
+                }
+            }
+            return None;
+        }
+        loop {
+            let mut sampled = false;
+            for (index, db) in dbs.iter().enumerate() {
+                for key in db.sample_keys(samples, policy.is_volatile()) {
+                    sampled = true;
+                    if let Some(idle) = idle_score(db, &key, policy) {
+                        self.insert(idle, index, key);
+                    }
+                }
+            }
+            // candidates may have been deleted or modified since they were sampled
+            while let Some(entry) = self.entries.pop() {
+                if dbs
+                    .get(entry.db)
+                    .is_some_and(|db| db.contains_key(&entry.key))
+                {
+                    return Some((entry.db, entry.key));
+                }
+            }
+            if !sampled {
+                return None;
+            }
+        }
+    }
+}
+
+/// How good `key` is to evict by `policy`, `None` when it's gone
+fn idle_score(db: &Database, key: &str, policy: MaxmemoryPolicy) -> Option<u64> {
+    match policy {
+        MaxmemoryPolicy::VolatileTtl => db
+            .expire_time(key)
+            .map(|at| u64::MAX - unix_millis(at).max(0) as u64),
+        _ if policy.is_lfu() => db.lru(key).map(|lru| 255 - lfu_decr_and_return(lru) as u64),
+        _ => db.lru(key).map(estimate_idle_time),
+    }
+}
+
+/// Evict keys until the used memory is below `maxmemory`, called before every command.
+///
+/// It fails under `noeviction` or when there is nothing left to evict.
+pub fn perform_evictions(dbs: &Databases) -> EvictResult {
+    let config = get_server_config();
+    if config.maxmemory == 0 || used_memory() <= config.maxmemory {
+        return EvictResult::Ok;
+    }
+    POOL.lock().unwrap().evict(
+        dbs,
+        config.maxmemory_policy,
+        config.maxmemory_samples,
+        || used_memory() > config.maxmemory,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::Cell, time::Duration};
+
+    use crate::{
+        config::MaxmemoryPolicy,
+        object::redis_object::RedisObject,
+        storage::{
+            database::Databases,
+            evict::{
+                EVPOOL_SIZE, EvictResult, EvictionPool, LFU_INIT_VAL, LRU_CLOCK_MAX,
+                LRU_CLOCK_RESOLUTION, estimate_idle_time, lfu_decr_and_return, lfu_log_incr,
+                lfu_time_in_minutes, lru_clock,
+            },
+        },
+    };
+
+    fn value() -> RedisObject {
+        RedisObject::new_string(b"v".to_vec())
+    }
+
+    #[test]
+    fn test_lru_and_lfu_counters() {
+        let clock = lru_clock();
+        assert_eq!(estimate_idle_time(clock), 0);
+        assert_eq!(
+            estimate_idle_time(clock.wrapping_sub(10) & 0xFF_FFFF),
+            10 * LRU_CLOCK_RESOLUTION
+        );
+
+        // new counters grow fast, high ones hardly
+        assert_eq!(lfu_log_incr(0), 1);
+        assert_eq!(lfu_log_incr(LFU_INIT_VAL), LFU_INIT_VAL + 1);
+        assert_eq!(lfu_log_incr(255), 255);
+        let mut counter = LFU_INIT_VAL;
+        for _ in 0..1000 {
+            counter = lfu_log_incr(counter);
+        }
+        assert!(counter > 10 && counter < 100);
+
+        let now = lfu_time_in_minutes();
+        assert_eq!(lfu_decr_and_return(now << 8 | 20), 20);
+        let earlier = (now + 65535 - 3) % 65535;
+        assert_eq!(lfu_decr_and_return(earlier << 8 | 20), 17);
+        assert_eq!(lfu_decr_and_return(earlier << 8 | 2), 0);
+    }
+
+    #[test]
+    fn test_pool_keeps_the_best_candidates() {
+        let mut pool = EvictionPool::new();
+        for idle in 0..EVPOOL_SIZE as u64 * 2 {
+            pool.insert(idle, 0, format!("k{}", idle));
+        }
+        pool.insert(100, 0, "k31".to_string());
+        pool.insert(0, 0, "low".to_string());
+        assert_eq!(pool.entries.len(), EVPOOL_SIZE);
+        let idles: Vec<_> = pool.entries.iter().map(|entry| entry.idle).collect();
+        assert_eq!(
+            idles,
+            (EVPOOL_SIZE as u64..EVPOOL_SIZE as u64 * 2).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_allkeys_lru_evicts_idle_keys() {
+        let dbs = Databases::new(2);
+        let db = dbs.get(1).unwrap();
+        for i in 0..10 {
+            let mut value = value();
+            // k0 is the least recently used
+            value
+                .header
+                .set_lru((lru_clock() + LRU_CLOCK_MAX - 100 + i * 10) % LRU_CLOCK_MAX);
+            db.set(format!("k{}", i), value, None);
+        }
+        let evictions = Cell::new(0);
+        let result = EvictionPool::new().evict(&dbs, MaxmemoryPolicy::AllKeysLru, 64, || {
+            evictions.set(evictions.get() + 1);
+            evictions.get() <= 3
+        });
+        assert_eq!(result, EvictResult::Ok);
+        assert_eq!(db.len(), 7);
+        assert!((0..3).all(|i| !db.contains_key(&format!("k{}", i))));
+    }
+
+    #[test]
+    fn test_volatile_policies_spare_persistent_keys() {
+        let dbs = Databases::new(1);
+        let db = dbs.get(0).unwrap();
+        db.set("persistent".to_string(), value(), None);
+        db.set("soon".to_string(), value(), Some(Duration::from_secs(10)));
+        db.set("later".to_string(), value(), Some(Duration::from_secs(100)));
+
+        let mut pool = EvictionPool::new();
+        let once = || {
+            let done = Cell::new(false);
+            move || !done.replace(true)
+        };
+        assert_eq!(
+            pool.evict(&dbs, MaxmemoryPolicy::VolatileTtl, 5, once()),
+            EvictResult::Ok
+        );
+        assert!(!db.contains_key("soon"));
+        assert!(db.contains_key("later"));
+
+        assert_eq!(
+            pool.evict(&dbs, MaxmemoryPolicy::VolatileRandom, 5, || true),
+            EvictResult::Fail
+        );
+        assert_eq!(db.len(), 1);
+        assert!(db.contains_key("persistent"));
+
+        assert_eq!(
+            pool.evict(&dbs, MaxmemoryPolicy::NoEviction, 5, || true),
+            EvictResult::Fail
+        );
+        assert_eq!(
+            pool.evict(&dbs, MaxmemoryPolicy::AllKeysRandom, 5, || true),
+            EvictResult::Fail
+        );
+        assert!(db.is_empty());
+    }
+}
@@ -0,0 +1,392 @@
+use std::{
+    collections::{HashMap, hash_map::RandomState},
+    hash::BuildHasher,
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{stats::STATS, storage::database::Databases};
+
+/// Count of shards of `Expires`
+const SHARDS: usize = 64;
+
+/// Keys checked by one step of the active expire cycle, `ACTIVE_EXPIRE_CYCLE_KEYS_PER_LOOP`
+const KEYS_PER_LOOP: usize = 20;
+
+/// A database is checked again while more than this percent of the checked keys expired
+const ACCEPTABLE_STALE: usize = 10;
+
+/// Percent of the cron period the active expire cycle may take
+const CYCLE_TIME_PERC: u32 = 25;
+
+/// Expire times of the keys of a database.
+///
+/// Keys are kept in the vector of their shard as well as in its map, so the active expire
+/// cycle walks them with a cursor instead of iterating the whole keyspace.
+pub struct Expires {
+    hasher: RandomState,
+    shards: Box<[Mutex<Shard>]>,
+    /// Shard and position where the next active expire step starts
+    cursor: Mutex<(usize, usize)>,
+}
+
+#[derive(Default)]
+struct Shard {
+    /// Expire time and position in `keys`
+    index: HashMap<String, (SystemTime, usize)>,
+    keys: Vec<String>,
+    /// Bytes taken by the entries, see `entry_size`
+    memory: usize,
+}
+
+/// Bytes taken by the expire time of `key`, its entry in the index and its copy in `keys`
+pub fn entry_size(key: &str) -> usize {
+    size_of::<(String, (SystemTime, usize))>() + 1 + size_of::<String>() + 2 * key.len()
+}
+
+impl Shard {
+    fn remove(&mut self, key: &str) -> Option<SystemTime> {
+        let (at, pos) = self.index.remove(key)?;
+        self.memory -= entry_size(key);
+        self.keys.swap_remove(pos);
+        if let Some(moved) = self.keys.get(pos) {
+            self.index.get_mut(moved).unwrap().1 = pos;
+        }
+        Some(at)
+    }
+}
+
+impl Default for Expires {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Expires {
+    pub fn new() -> Self {
+        Self {
+            hasher: RandomState::new(),
+            shards: (0..SHARDS).map(|_| Mutex::default()).collect(),
+            cursor: Mutex::new((0, 0)),
+        }
+    }
+
+    fn shard(&self, key: &str) -> &Mutex<Shard> {
+        &self.shards[self.hasher.hash_one(key) as usize % SHARDS]
+    }
+
+    pub fn get(&self, key: &str) -> Option<SystemTime> {
+        self.shard(key)
+            .lock()
+            .unwrap()
+            .index
+            .get(key)
+            .map(|&(at, _)| at)
+    }
+
+    /// Set the expire time of `key`, returns the previous one
+    pub fn insert(&self, key: String, at: SystemTime) -> Option<SystemTime> {
+        let mut shard = self.shard(&key).lock().unwrap();
+        if let Some(entry) = shard.index.get_mut(&key) {
+            return Some(std::mem::replace(&mut entry.0, at));
+        }
+        let pos = shard.keys.len();
+        shard.memory += entry_size(&key);
+        shard.keys.push(key.clone());
+        shard.index.insert(key, (at, pos));
+        None
+    }
+
+    pub fn remove(&self, key: &str) -> Option<SystemTime> {
+        self.shard(key).lock().unwrap().remove(key)
+    }
+
+    /// Remove the expire time of `key` if it is earlier than `now`
+    pub fn remove_expired(&self, key: &str, now: SystemTime) -> bool {
+        let mut shard = self.shard(key).lock().unwrap();
+        match shard.index.get(key) {
+            Some(&(at, _)) if at < now => shard.remove(key).is_some(),
+            _ => false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().unwrap().keys.len())
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shards
+            .iter()
+            .all(|shard| shard.lock().unwrap().keys.is_empty())
+    }
+
+    /// Bytes taken by the expire times
+    pub fn used_memory(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().unwrap().memory)
+            .sum()
+    }
+
+    pub fn clear(&self) {
+        for shard in self.shards.iter() {
+            *shard.lock().unwrap() = Shard::default();
+        }
+    }
+
+    /// Up to `count` keys with an expire time from a random position on
+    pub fn sample(&self, count: usize) -> Vec<String> {
+        let first = rand::random_range(0..SHARDS);
+        let mut keys = Vec::new();
+        for i in 0..SHARDS {
+            let shard = self.shards[(first + i) % SHARDS].lock().unwrap();
+            let start = if i == 0 && !shard.keys.is_empty() {
+                rand::random_range(0..shard.keys.len())
+            } else {
+                0
+            };
+            let wanted = count - keys.len();
+            keys.extend(shard.keys[start..].iter().take(wanted).cloned());
+            if keys.len() >= count {
+                break;
+            }
+        }
+        keys
+    }
+
+    /// Check up to `count` keys from the cursor on, the expired ones are removed and returned.
+    ///
+    /// Returns the count of checked keys as well.
+    pub fn take_expired(&self, count: usize, now: SystemTime) -> (usize, Vec<String>) {
+        self.walk_expired(count, now, true)
+    }
+
+    /// Like `take_expired`, the expire times are left for the owner of the keys to remove
+    /// along with the keys
+    pub fn find_expired(&self, count: usize, now: SystemTime) -> (usize, Vec<String>) {
+        self.walk_expired(count, now, false)
+    }
+
+    fn walk_expired(&self, count: usize, now: SystemTime, remove: bool) -> (usize, Vec<String>) {
+        let mut cursor = self.cursor.lock().unwrap();
+        let (mut shard_index, mut pos) = *cursor;
+        let mut checked = 0;
+        let mut expired = Vec::new();
+        // give up after a full round over the shards, e.g. when there are only a few keys
+        for _ in 0..=SHARDS {
+            let mut shard = self.shards[shard_index].lock().unwrap();
+            while checked < count && pos < shard.keys.len() {
+                checked += 1;
+                let key = &shard.keys[pos];
+                if shard.index[key].0 < now && remove {
+                    let key = key.clone();
+                    // the last key is swapped into `pos`, it's checked next
+                    shard.remove(&key);
+                    expired.push(key);
+                } else {
+                    if shard.index[key].0 < now {
+                        expired.push(key.clone());
+                    }
+                    pos += 1;
+                }
+            }
+            if checked >= count {
+                break;
+            }
+            shard_index = (shard_index + 1) % SHARDS;
+            pos = 0;
+        }
+        *cursor = (shard_index, pos);
+        (checked, expired)
+    }
+}
+
+/// Runs the active expire cycle at every tick of the server cron
+pub struct ActiveExpire {
+    /// The database the next cycle starts with
+    next_db: usize,
+}
+
+impl Default for ActiveExpire {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ActiveExpire {
+    pub fn new() -> Self {
+        Self { next_db: 0 }
+    }
+
+    /// Delete expired keys and hash fields of all databases, it's done in steps of
+    /// `KEYS_PER_LOOP` keys or hashes.
+    ///
+    /// A database is checked again as long as more than `ACCEPTABLE_STALE` percent of the
+    /// checked keys were expired, the cycle stops once it used its share of the cron period.
+    /// Returns the count of deleted keys.
+    pub fn cycle(&mut self, dbs: &Databases, hz: u32) -> usize {
+        let start = Instant::now();
+        let time_limit = Duration::from_secs(1) * CYCLE_TIME_PERC / 100 / hz.max(1);
+        let dbs = dbs.all();
+        let mut deleted = 0;
+
+        for _ in 0..dbs.len() {
+            let db = &dbs[self.next_db % dbs.len()];
+            self.next_db = (self.next_db + 1) % dbs.len();
+            // the keys first, then the fields of the hashes which are left
+            for fields in [false, true] {
+                loop {
+                    let (checked, expired) = if fields {
+                        db.active_expire_fields(KEYS_PER_LOOP)
+                    } else {
+                        let (checked, expired) = db.active_expire(KEYS_PER_LOOP);
+                        deleted += expired;
+                        (checked, expired)
+                    };
+                    if start.elapsed() > time_limit {
+                        STATS.record_expire_cycle(start.elapsed(), true);
+                        return deleted;
+                    }
+                    if checked == 0 || expired * 100 <= checked * ACCEPTABLE_STALE {
+                        break;
+                    }
+                }
+            }
+        }
+        STATS.record_expire_cycle(start.elapsed(), false);
+        deleted
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, SystemTime};
+
+    use crate::{
+        object::redis_object::RedisObject,
+        storage::{
+            database::Databases,
+            expires::{ActiveExpire, Expires, SHARDS, entry_size},
+        },
+        util::unix_millis,
+    };
+
+    #[test]
+    fn test_expires_index() {
+        let expires = Expires::new();
+        let now = SystemTime::now();
+        let later = now + Duration::from_secs(60);
+        for i in 0..100 {
+            expires.insert(format!("k{}", i), later);
+        }
+        assert_eq!(expires.insert("k0".to_string(), now), Some(later));
+        assert_eq!(expires.len(), 100);
+        assert_eq!(expires.remove("k1"), Some(later));
+        assert_eq!(expires.remove("k1"), None);
+        assert!(!expires.remove_expired("k2", now));
+        assert!(expires.remove_expired("k0", now + Duration::from_secs(1)));
+        assert_eq!(expires.len(), 98);
+        assert_eq!(
+            expires.used_memory(),
+            (3..100).map(|i| entry_size(&format!("k{}", i))).sum::<usize>() + entry_size("k2")
+        );
+        for i in 3..100 {
+            assert_eq!(expires.get(&format!("k{}", i)), Some(later));
+        }
+    }
+
+    #[test]
+    fn test_take_expired_walks_every_shard() {
+        let expires = Expires::new();
+        let now = SystemTime::now();
+        let past = now - Duration::from_secs(1);
+        let future = now + Duration::from_secs(60);
+        for i in 0..SHARDS * 10 {
+            let at = if i % 2 == 0 { past } else { future };
+            expires.insert(format!("k{}", i), at);
+        }
+
+        let mut expired = Vec::new();
+        let mut checked = 0;
+        while checked < SHARDS * 10 {
+            let (n, keys) = expires.take_expired(20, now);
+            checked += n;
+            expired.extend(keys);
+        }
+        assert_eq!(expired.len(), SHARDS * 5);
+        assert!(
+            expired
+                .iter()
+                .all(|key| key[1..].parse::<usize>().unwrap() % 2 == 0)
+        );
+        assert_eq!(expires.len(), SHARDS * 5);
+        assert_eq!(expires.take_expired(20, now).1.len(), 0);
+    }
+
+    #[test]
+    fn test_active_expire_cycle() {
+        let dbs = Databases::new(2);
+        let db = dbs.get(1).unwrap();
+        for i in 0..1000 {
+            let ttl = if i < 900 {
+                Duration::ZERO
+            } else {
+                Duration::from_secs(60)
+            };
+            db.set(
+                format!("k{}", i),
+                RedisObject::new_string(b"v".to_vec()),
+                Some(ttl),
+            );
+        }
+        std::thread::sleep(Duration::from_millis(2));
+
+        let mut active_expire = ActiveExpire::new();
+        let mut deleted = 0;
+        // a cycle may run out of time, the next ones carry on from the cursor
+        for _ in 0..100 {
+            deleted += active_expire.cycle(&dbs, 10);
+            if deleted == 900 {
+                break;
+            }
+        }
+        assert_eq!(deleted, 900);
+        assert_eq!(db.len(), 100);
+    }
+
+    #[test]
+    fn test_active_expire_fields() {
+        let dbs = Databases::new(1);
+        let db = dbs.get(0).unwrap();
+        let past = unix_millis(SystemTime::now()) - 1;
+        for i in 0..100 {
+            let mut hash = RedisObject::new_hash();
+            hash.ptr.hash_set(b"token", b"t");
+            hash.ptr.hash_set_expire(b"token", past);
+            // every other hash keeps a field without expire time
+            if i % 2 == 0 {
+                hash.ptr.hash_set(b"name", b"n");
+            }
+            db.insert(format!("h{}", i), hash, None);
+        }
+
+        let mut active_expire = ActiveExpire::new();
+        for _ in 0..100 {
+            active_expire.cycle(&dbs, 10);
+        }
+        // the emptied hashes are deleted, the others are plain hashes again
+        assert_eq!(db.len(), 50);
+        assert!(db.keys("*").iter().all(|key| {
+            db.peek_with(key, |value| value.ptr.encoding() == "listpack")
+                .unwrap()
+        }));
+        let usage: usize = (0..100)
+            .step_by(2)
+            .map(|i| db.memory_usage(&format!("h{}", i)).unwrap())
+            .sum();
+        assert_eq!(db.used_memory(), usage);
+    }
+}
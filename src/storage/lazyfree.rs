@@ -0,0 +1,113 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Sender},
+    },
+    thread,
+};
+
+use once_cell::sync::Lazy;
+
+use crate::object::redis_object::RedisObject;
+
+/// Values with a larger `free_effort` are dropped on the lazy free thread
+pub const LAZYFREE_THRESHOLD: usize = 64;
+
+/// Objects handed to the lazy free thread and not dropped yet
+static PENDING_OBJECTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Objects dropped by the lazy free thread since the start
+static FREED_OBJECTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Values to drop with the count of objects they hold
+struct Job {
+    objects: usize,
+    value: Box<dyn Send>,
+}
+
+static FREE_THREAD: Lazy<Sender<Job>> = Lazy::new(|| {
+    let (sender, receiver) = mpsc::channel::<Job>();
+    thread::Builder::new()
+        .name("lazyfree".to_string())
+        .spawn(move || {
+            for job in receiver {
+                drop(job.value);
+                PENDING_OBJECTS.fetch_sub(job.objects, Ordering::Relaxed);
+                FREED_OBJECTS.fetch_add(job.objects, Ordering::Relaxed);
+            }
+        })
+        .expect("failed to spawn the lazy free thread");
+    sender
+});
+
+fn submit(objects: usize, value: Box<dyn Send>) {
+    PENDING_OBJECTS.fetch_add(objects, Ordering::Relaxed);
+    if let Err(mpsc::SendError(job)) = FREE_THREAD.send(Job { objects, value }) {
+        // the thread never exits, this only drops the value in place
+        PENDING_OBJECTS.fetch_sub(job.objects, Ordering::Relaxed);
+    }
+}
+
+/// Drop `value` on the lazy free thread when `lazy` and it's large enough to stall the
+/// caller, in place otherwise
+pub fn free_object(value: RedisObject, lazy: bool) {
+    if lazy && value.ptr.free_effort() > LAZYFREE_THRESHOLD {
+        submit(1, Box::new(value));
+    }
+}
+
+/// Drop the values of a flushed database on the lazy free thread
+pub fn free_objects_async(values: Vec<RedisObject>) {
+    if !values.is_empty() {
+        submit(values.len(), Box::new(values));
+    }
+}
+
+/// `lazyfree_pending_objects` of `INFO`
+pub fn pending_objects() -> usize {
+    PENDING_OBJECTS.load(Ordering::Relaxed)
+}
+
+/// `lazyfreed_objects` of `INFO`
+pub fn freed_objects() -> usize {
+    FREED_OBJECTS.load(Ordering::Relaxed)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use crate::{
+        object::{
+            encoding::quicklist::Quicklist,
+            redis_object::{ObjectType, RedisObject, RedisValue},
+        },
+        storage::lazyfree::{LAZYFREE_THRESHOLD, free_object, free_objects_async, freed_objects},
+    };
+
+    #[test]
+    fn test_free_object() {
+        // one entry per node, the free effort of a list is its count of nodes
+        let list = |len: usize| {
+            let mut list = Quicklist::new(1, 0);
+            for i in 0..len {
+                list.push_back(i.to_string().as_bytes());
+            }
+            RedisObject::new(ObjectType::List, RedisValue::QuickList(Box::new(list)))
+        };
+        let freed = freed_objects();
+        // small values and non lazy frees never reach the thread
+        free_object(list(LAZYFREE_THRESHOLD), true);
+        free_object(list(LAZYFREE_THRESHOLD + 1), false);
+        free_object(list(LAZYFREE_THRESHOLD + 1), true);
+        free_objects_async(vec![list(1), list(2)]);
+        free_objects_async(Vec::new());
+
+        // other tests may free objects concurrently
+        let start = Instant::now();
+        while freed_objects() < freed + 3 {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
@@ -1 +1,5 @@
 pub mod database;
+pub mod evict;
+pub mod expires;
+pub mod lazyfree;
+pub mod scan;
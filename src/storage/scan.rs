@@ -0,0 +1,169 @@
+use std::{
+    collections::{BTreeSet, hash_map::RandomState},
+    hash::BuildHasher,
+    ops::Bound,
+    sync::Mutex,
+};
+
+use once_cell::sync::Lazy;
+
+/// Count of shards of `KeyIndex`, each one holds a contiguous range of the hash space
+const SHARDS: usize = 64;
+
+/// Seeds the hash of `scan_hash`, so cursors stay valid as long as the server runs
+static HASHER: Lazy<RandomState> = Lazy::new(RandomState::new);
+
+/// Position of a key or member in the hash space walked by the cursors of the `SCAN` family.
+///
+/// A cursor is the hash of the next element to visit, elements are visited in hash order
+/// and 0 ends the scan. So every element present for the whole scan is returned at least
+/// once, whatever happens to the table holding it meanwhile.
+pub fn scan_hash(member: &[u8]) -> u64 {
+    HASHER.hash_one(member)
+}
+
+/// Keys of a range of the hash space with their hashes, ordered by hash
+type Shard = BTreeSet<(u64, String)>;
+
+/// Keys of a database ordered by `scan_hash`, `SCAN` pages through it.
+///
+/// It holds a copy of every key besides the `DashMap` of the database, whose iteration
+/// order changes when it resizes.
+pub struct KeyIndex {
+    shards: Box<[Mutex<Shard>]>,
+}
+
+impl Default for KeyIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeyIndex {
+    pub fn new() -> Self {
+        Self {
+            shards: (0..SHARDS).map(|_| Mutex::default()).collect(),
+        }
+    }
+
+    fn shard(&self, hash: u64) -> &Mutex<Shard> {
+        &self.shards[(hash >> (u64::BITS - SHARDS.ilog2())) as usize]
+    }
+
+    pub fn insert(&self, key: &str) {
+        let hash = scan_hash(key.as_bytes());
+        self.shard(hash)
+            .lock()
+            .unwrap()
+            .insert((hash, key.to_string()));
+    }
+
+    pub fn remove(&self, key: &str) {
+        let hash = scan_hash(key.as_bytes());
+        self.shard(hash)
+            .lock()
+            .unwrap()
+            .remove(&(hash, key.to_string()));
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().unwrap().len())
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shards
+            .iter()
+            .all(|shard| shard.lock().unwrap().is_empty())
+    }
+
+    /// Visit at least `count` keys from `cursor` on, returns them with the next cursor
+    pub fn scan(&self, cursor: u64, count: usize) -> (u64, Vec<String>) {
+        let mut keys = Vec::new();
+        let mut last = None;
+        let first = (cursor >> (u64::BITS - SHARDS.ilog2())) as usize;
+        for shard in self.shards[first..].iter() {
+            let shard = shard.lock().unwrap();
+            for (hash, key) in shard.range((cursor, String::new())..) {
+                if keys.len() >= count && last != Some(*hash) {
+                    return (*hash, keys);
+                }
+                last = Some(*hash);
+                keys.push(key.clone());
+            }
+        }
+        (0, keys)
+    }
+
+    /// Up to `count` keys following a random point of the hash space, wrapping around
+    pub fn sample(&self, count: usize) -> Vec<String> {
+        let start = (rand::random::<u64>(), String::new());
+        let first = (start.0 >> (u64::BITS - SHARDS.ilog2())) as usize;
+        let mut keys = Vec::new();
+        for i in 0..=SHARDS {
+            let shard = self.shards[(first + i) % SHARDS].lock().unwrap();
+            // the first shard is visited twice, from the start on and then up to it
+            let range = match i {
+                0 => (Bound::Included(&start), Bound::Unbounded),
+                SHARDS => (Bound::Unbounded, Bound::Excluded(&start)),
+                _ => (Bound::Unbounded, Bound::Unbounded),
+            };
+            for (_, key) in shard.range(range) {
+                if keys.len() >= count {
+                    return keys;
+                }
+                keys.push(key.clone());
+            }
+        }
+        keys
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::storage::scan::KeyIndex;
+
+    #[test]
+    fn test_key_index_scan() {
+        let index = KeyIndex::new();
+        for i in 0..1000 {
+            index.insert(&format!("k{}", i));
+        }
+        index.insert("k0");
+        assert_eq!(index.len(), 1000);
+
+        let mut seen = HashSet::new();
+        let mut cursor = 0;
+        let mut pages = 0;
+        loop {
+            let (next, keys) = index.scan(cursor, 10);
+            assert!(next == 0 || next > cursor);
+            pages += 1;
+            // keys removed and added while scanning don't break the scan
+            if pages == 20 {
+                for i in 0..100 {
+                    index.remove(&format!("k{}", i));
+                    index.insert(&format!("new{}", i));
+                }
+            }
+            seen.extend(keys);
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+        }
+        assert!(pages >= 90);
+        assert!((100..1000).all(|i| seen.contains(&format!("k{}", i))));
+        assert_eq!(index.scan(0, 2000).1.len(), 1000);
+
+        index.remove("missing");
+        for i in 100..1000 {
+            index.remove(&format!("k{}", i));
+        }
+        assert_eq!(index.len(), 100);
+    }
+}
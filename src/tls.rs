@@ -0,0 +1,203 @@
+use std::{path::Path, sync::Arc};
+
+use anyhow::{Context, Result};
+use tokio_rustls::{
+    TlsAcceptor,
+    rustls::{
+        RootCertStore, ServerConfig,
+        crypto::ring,
+        pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+        server::WebPkiClientVerifier,
+    },
+};
+
+use crate::config::TlsAuthClients;
+
+/// Build the acceptor of the `tls-port` listeners from PEM files.
+///
+/// `ca_cert_file` is required unless `auth_clients` is `No`, client certificates are verified
+/// against it.
+pub fn build_acceptor(
+    cert_file: &Path,
+    key_file: &Path,
+    ca_cert_file: Option<&Path>,
+    auth_clients: TlsAuthClients,
+) -> Result<TlsAcceptor> {
+    let certs = load_certs(cert_file)?;
+    let key = PrivateKeyDer::from_pem_file(key_file)
+        .with_context(|| format!("Load private key {} failed", key_file.display()))?;
+
+    let provider = Arc::new(ring::default_provider());
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()?;
+    let builder = match auth_clients {
+        TlsAuthClients::No => builder.with_no_client_auth(),
+        TlsAuthClients::Yes | TlsAuthClients::Optional => {
+            let ca_cert_file =
+                ca_cert_file.context("tls-ca-cert-file is required to authenticate clients")?;
+            let mut roots = RootCertStore::empty();
+            for cert in load_certs(ca_cert_file)? {
+                roots.add(cert)?;
+            }
+            let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider);
+            let verifier = match auth_clients {
+                TlsAuthClients::Optional => verifier.allow_unauthenticated(),
+                _ => verifier,
+            };
+            builder.with_client_cert_verifier(verifier.build()?)
+        }
+    };
+    let config = builder
+        .with_single_cert(certs, key)
+        .context("Invalid tls certificate or key")?;
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .with_context(|| format!("Load certificates {} failed", path.display()))?;
+    if certs.is_empty() {
+        anyhow::bail!("No certificate found in {}", path.display());
+    }
+    Ok(certs)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, path::PathBuf, sync::Arc};
+
+    use rcgen::{BasicConstraints, Certificate, CertificateParams, IsCa, KeyPair};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpStream,
+    };
+    use tokio_rustls::{
+        TlsConnector,
+        rustls::{
+            ClientConfig, RootCertStore,
+            crypto::ring,
+            pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer, ServerName},
+        },
+    };
+
+    use crate::{
+        command::registry::do_register,
+        config::TlsAuthClients,
+        server::{Listener, bind_tcp_listeners, serve},
+        storage::database::Databases,
+        tls::build_acceptor,
+    };
+
+    struct Pki {
+        ca: Certificate,
+        client: Certificate,
+        client_key: KeyPair,
+        dir: PathBuf,
+    }
+
+    /// A CA with a server certificate for `localhost` and a client certificate
+    fn pki(name: &str) -> Pki {
+        let dir = std::env::temp_dir().join(format!("rudis-tls-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut ca_params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let ca_key = KeyPair::generate().unwrap();
+        let ca = ca_params.self_signed(&ca_key).unwrap();
+        let server_key = KeyPair::generate().unwrap();
+        let server = CertificateParams::new(vec!["localhost".to_string()])
+            .unwrap()
+            .signed_by(&server_key, &ca, &ca_key)
+            .unwrap();
+        let client_key = KeyPair::generate().unwrap();
+        let client = CertificateParams::new(vec!["client".to_string()])
+            .unwrap()
+            .signed_by(&client_key, &ca, &ca_key)
+            .unwrap();
+
+        fs::write(dir.join("ca.crt"), ca.pem()).unwrap();
+        fs::write(dir.join("server.crt"), server.pem()).unwrap();
+        fs::write(dir.join("server.key"), server_key.serialize_pem()).unwrap();
+        Pki {
+            ca,
+            client,
+            client_key,
+            dir,
+        }
+    }
+
+    async fn start(pki: &Pki, auth_clients: TlsAuthClients) -> String {
+        do_register().await;
+        let acceptor = build_acceptor(
+            &pki.dir.join("server.crt"),
+            &pki.dir.join("server.key"),
+            Some(&pki.dir.join("ca.crt")),
+            auth_clients,
+        )
+        .unwrap();
+        let tcp = bind_tcp_listeners(&["127.0.0.1".to_string()], 0)
+            .unwrap()
+            .pop()
+            .unwrap();
+        let listener = Listener::Tls(tcp, acceptor);
+        let addr = listener.local_addr();
+        tokio::spawn(serve(listener, Arc::new(Databases::new(1))));
+        addr
+    }
+
+    /// Send `PING` over TLS, returns the reply or `None` when the server drops the client
+    async fn ping(pki: &Pki, addr: &str, with_cert: bool) -> Option<Vec<u8>> {
+        let mut roots = RootCertStore::empty();
+        roots.add(pki.ca.der().clone()).unwrap();
+        let builder = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots);
+        let config = if with_cert {
+            let key = PrivatePkcs8KeyDer::from(pki.client_key.serialize_der());
+            builder
+                .with_client_auth_cert(vec![pki.client.der().clone()], PrivateKeyDer::Pkcs8(key))
+                .unwrap()
+        } else {
+            builder.with_no_client_auth()
+        };
+
+        let socket = TcpStream::connect(addr).await.unwrap();
+        let server_name = ServerName::try_from("localhost").unwrap();
+        let mut stream = TlsConnector::from(Arc::new(config))
+            .connect(server_name, socket)
+            .await
+            .ok()?;
+        stream.write_all(b"PING\r\n").await.ok()?;
+        let mut buf = [0u8; 7];
+        stream.read_exact(&mut buf).await.ok()?;
+        Some(buf.to_vec())
+    }
+
+    #[tokio::test]
+    async fn test_tls_auth_clients() {
+        let pki = pki("auth");
+        let addr = start(&pki, TlsAuthClients::Yes).await;
+        assert_eq!(ping(&pki, &addr, true).await.unwrap(), b"+PONG\r\n");
+        assert!(ping(&pki, &addr, false).await.is_none());
+
+        let addr = start(&pki, TlsAuthClients::Optional).await;
+        assert_eq!(ping(&pki, &addr, false).await.unwrap(), b"+PONG\r\n");
+        assert_eq!(ping(&pki, &addr, true).await.unwrap(), b"+PONG\r\n");
+        fs::remove_dir_all(&pki.dir).unwrap();
+    }
+
+    #[test]
+    fn test_build_acceptor_err() {
+        let pki = pki("err");
+        let cert = pki.dir.join("server.crt");
+        let key = pki.dir.join("server.key");
+        assert!(build_acceptor(&cert, &key, None, TlsAuthClients::No).is_ok());
+        // clients can't be verified without a CA
+        assert!(build_acceptor(&cert, &key, None, TlsAuthClients::Yes).is_err());
+        assert!(build_acceptor(&key, &key, None, TlsAuthClients::No).is_err());
+        assert!(build_acceptor(&cert, &pki.dir.join("missing"), None, TlsAuthClients::No).is_err());
+        fs::remove_dir_all(&pki.dir).unwrap();
+    }
+}
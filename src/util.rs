@@ -0,0 +1,184 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Recursion limit of `string_match`, protects against abusive patterns like `*a*a*a*...`
+const MAX_NESTING: usize = 1000;
+
+/// Glob-style matching of `KEYS` and `SCAN MATCH`, `stringmatchlen` of redis.
+///
+/// * `*` matches any sequence, `?` matches one byte
+/// * `[abc]`, `[^abc]` and `[a-z]` match one byte of a class, `[` without `]` ends the class
+///   at the end of the pattern
+/// * `\` escapes the next byte, inside a class as well
+pub fn string_match(pattern: &[u8], string: &[u8], nocase: bool) -> bool {
+    let mut skip_longer_matches = false;
+    string_match_impl(pattern, string, nocase, &mut skip_longer_matches, 0)
+}
+
+fn string_match_impl(
+    mut pattern: &[u8],
+    mut string: &[u8],
+    nocase: bool,
+    skip_longer_matches: &mut bool,
+    nesting: usize,
+) -> bool {
+    if nesting > MAX_NESTING {
+        return false;
+    }
+    let eq = |a: u8, b: u8| {
+        if nocase {
+            a.eq_ignore_ascii_case(&b)
+        } else {
+            a == b
+        }
+    };
+
+    while !pattern.is_empty() && !string.is_empty() {
+        match pattern[0] {
+            b'*' => {
+                while pattern.len() > 1 && pattern[1] == b'*' {
+                    pattern = &pattern[1..];
+                }
+                if pattern.len() == 1 {
+                    return true;
+                }
+                while !string.is_empty() {
+                    if string_match_impl(
+                        &pattern[1..],
+                        string,
+                        nocase,
+                        skip_longer_matches,
+                        nesting + 1,
+                    ) {
+                        return true;
+                    }
+                    if *skip_longer_matches {
+                        return false;
+                    }
+                    string = &string[1..];
+                }
+                // the rest of the pattern matches nowhere in the rest of the string, so
+                // matching a longer substring with an earlier `*` can't succeed either
+                *skip_longer_matches = true;
+                return false;
+            }
+            b'?' => {}
+            b'[' => {
+                pattern = &pattern[1..];
+                let not = pattern.first() == Some(&b'^');
+                if not {
+                    pattern = &pattern[1..];
+                }
+                let c = string[0];
+                let mut matched = false;
+                loop {
+                    match *pattern {
+                        // an unclosed class takes the rest of the pattern
+                        [] => break,
+                        [b']', ..] => break,
+                        [b'\\', escaped, ..] => {
+                            pattern = &pattern[1..];
+                            matched |= escaped == c;
+                        }
+                        [start, b'-', end, ..] => {
+                            let (mut start, mut end, mut c) = (start, end, c);
+                            if start > end {
+                                std::mem::swap(&mut start, &mut end);
+                            }
+                            if nocase {
+                                start = start.to_ascii_lowercase();
+                                end = end.to_ascii_lowercase();
+                                c = c.to_ascii_lowercase();
+                            }
+                            pattern = &pattern[2..];
+                            matched |= start <= c && c <= end;
+                        }
+                        [other, ..] => matched |= eq(other, c),
+                    }
+                    pattern = &pattern[1..];
+                }
+                if matched == not {
+                    return false;
+                }
+            }
+            b'\\' if pattern.len() >= 2 => {
+                pattern = &pattern[1..];
+                if !eq(pattern[0], string[0]) {
+                    return false;
+                }
+            }
+            other => {
+                if !eq(other, string[0]) {
+                    return false;
+                }
+            }
+        }
+        // the closing `]` of a class, or the byte matched by the other cases
+        pattern = pattern.get(1..).unwrap_or_default();
+        string = &string[1..];
+    }
+    if string.is_empty() {
+        while pattern.first() == Some(&b'*') {
+            pattern = &pattern[1..];
+        }
+    }
+    pattern.is_empty() && string.is_empty()
+}
+
+/// Milliseconds since the unix epoch, negative before it
+pub fn unix_millis(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(elapsed) => elapsed.as_millis() as i64,
+        Err(err) => -(err.duration().as_millis() as i64),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::util::string_match;
+
+    fn matches(pattern: &str, string: &str) -> bool {
+        string_match(pattern.as_bytes(), string.as_bytes(), false)
+    }
+
+    #[test]
+    fn test_string_match() {
+        assert!(matches("*", ""));
+        assert!(matches("*", "key"));
+        assert!(matches("h?llo", "hello"));
+        assert!(!matches("h?llo", "hllo"));
+        assert!(matches("h*llo", "hllo"));
+        assert!(matches("h*llo", "heeeello"));
+        assert!(matches("h[ae]llo", "hallo"));
+        assert!(!matches("h[ae]llo", "hillo"));
+        assert!(matches("h[^e]llo", "hallo"));
+        assert!(!matches("h[^e]llo", "hello"));
+        assert!(matches("h[a-b]llo", "hbllo"));
+        assert!(matches("h[b-a]llo", "hallo"));
+        assert!(!matches("h[a-b]llo", "hcllo"));
+        assert!(matches("user:*:name", "user:1000:name"));
+        assert!(!matches("user:*:name", "user:1000:age"));
+        assert!(matches("a*b*c", "axxbyyc"));
+        assert!(!matches("a*b*c", "axxbyy"));
+    }
+
+    #[test]
+    fn test_string_match_escape_and_edge_cases() {
+        assert!(matches(r"h\*llo", "h*llo"));
+        assert!(!matches(r"h\*llo", "hello"));
+        assert!(matches(r"[\]]", "]"));
+        assert!(matches(r"[\-a]", "-"));
+        // a trailing backslash matches itself
+        assert!(matches(r"a\", r"a\"));
+        // an unclosed class ends at the end of the pattern
+        assert!(matches("a[bc", "ab"));
+        assert!(!matches("a[bc", "abc"));
+        assert!(!matches("", "a"));
+        assert!(matches("", ""));
+        assert!(string_match(b"HELLO", b"hello", true));
+        assert!(string_match(b"[A-C]x", b"bx", true));
+        assert!(!string_match(b"HELLO", b"hello", false));
+        // abusive patterns fail fast instead of backtracking exponentially
+        let pattern = "*a".repeat(50) + "b";
+        assert!(!matches(&pattern, &"a".repeat(100)));
+    }
+}